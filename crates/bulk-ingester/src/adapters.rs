@@ -118,7 +118,7 @@ pub mod builtin {
         }
 
         fn supported_types(&self) -> Vec<&str> {
-            vec!["txt", "md", "json", "csv", "xml"]
+            vec!["txt", "md", "json", "csv", "jsonl", "ndjson", "xml"]
         }
 
         async fn validate_config(&self, config: &JsonValue) -> Result<()> {
@@ -142,21 +142,45 @@ pub mod builtin {
                 .unwrap_or("auto");
             let separator = config.get("separator").and_then(|s| s.as_str());
 
+            // Structured formats go through their parser
+            let mapping = config
+                .get("mapping")
+                .map(|m| serde_json::from_value(m.clone()))
+                .transpose()?
+                .unwrap_or_default();
+            if let Some(parser) = crate::formats::parser_for(format, Path::new(path), mapping) {
+                return Ok(Box::new(crate::formats::ParsedStream::open(
+                    path,
+                    parser.as_ref(),
+                )?));
+            }
+
             Ok(Box::new(FileStream::new(path, format, separator).await?))
         }
 
         fn info(&self) -> AdapterInfo {
             AdapterInfo {
                 name: "file".to_string(),
-                description: "High-performance file reader for txt, md, json, csv, xml".to_string(),
+                description:
+                    "High-performance file reader for txt, md, json, csv, jsonl, ndjson, xml"
+                        .to_string(),
                 version: "1.0.0".to_string(),
                 supported_types: vec!["txt".to_string(), "md".to_string(), "json".to_string()],
                 config_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
                         "path": {"type": "string", "description": "File path"},
-                        "format": {"type": "string", "enum": ["auto", "wikipedia", "lines", "json"], "default": "auto"},
-                        "separator": {"type": "string", "description": "Custom separator pattern"}
+                        "format": {"type": "string", "enum": ["auto", "wikipedia", "lines", "json", "csv", "jsonl", "ndjson"], "default": "auto"},
+                        "separator": {"type": "string", "description": "Custom separator pattern"},
+                        "mapping": {
+                            "type": "object",
+                            "description": "Field mapping for csv/jsonl/ndjson",
+                            "properties": {
+                                "content_fields": {"type": "array", "items": {"type": "string"}, "default": ["content"]},
+                                "metadata_fields": {"type": "array", "items": {"type": "string"}, "description": "Empty keeps every non-content field"},
+                                "separator": {"type": "string", "default": "\n"}
+                            }
+                        }
                     },
                     "required": ["path"]
                 }),
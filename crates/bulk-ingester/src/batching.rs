@@ -0,0 +1,123 @@
+//! Batch sizing under memory pressure
+//!
+//! A batch size tuned for short records can exhaust memory once a source
+//! switches to large documents. `AdaptiveBatchSize` compares resident
+//! memory plus the bytes buffered in the current batch against the
+//! configured limit, cutting batches early and shrinking the target size
+//! when that total nears the limit, and growing it back towards the
+//! configured size once there is room again.
+//!
+//! Resident memory is re-read while a batch fills, not only between
+//! batches, so memory taken by other jobs or a burst of large records cuts
+//! the current batch instead of waiting for it to reach its target size.
+
+use tracing::info;
+
+/// Items added to a batch between resident memory samples
+const RESAMPLE_ITEMS: usize = 64;
+
+/// Resident set size of this process, where the platform reports it
+pub fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+pub struct AdaptiveBatchSize {
+    max: usize,
+    current: usize,
+    limit_bytes: u64,
+    probe: fn() -> Option<u64>,
+    /// Resident memory at the last sample
+    resident: u64,
+    /// Size of the current batch at the last sample
+    sampled_bytes: u64,
+}
+
+impl AdaptiveBatchSize {
+    /// Start at `max` items per batch, within `memory_limit_mb`
+    pub fn new(max: usize, memory_limit_mb: usize) -> Self {
+        let mut sizer = Self {
+            max: max.max(1),
+            current: max.max(1),
+            limit_bytes: memory_limit_mb as u64 * 1024 * 1024,
+            probe: resident_bytes,
+            resident: 0,
+            sampled_bytes: 0,
+        };
+        sizer.sample();
+        sizer
+    }
+
+    /// Measure resident memory with `probe` instead of the process RSS
+    pub fn with_probe(mut self, probe: fn() -> Option<u64>) -> Self {
+        self.probe = probe;
+        self.sample();
+        self
+    }
+
+    /// Effective batch size
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    fn sample(&mut self) {
+        self.resident = (self.probe)().unwrap_or(0);
+        self.sampled_bytes = 0;
+    }
+
+    /// Shrink above 80% of the limit
+    fn high_watermark(&self) -> u64 {
+        self.limit_bytes / 10 * 8
+    }
+
+    /// Grow below 50% of the limit
+    fn low_watermark(&self) -> u64 {
+        self.limit_bytes / 2
+    }
+
+    /// Whether a batch of `len` items holding `bytes` should be sent now
+    ///
+    /// Re-samples resident memory every `RESAMPLE_ITEMS` items, or sooner
+    /// once the batch has grown by 1/64 of the limit since the last sample.
+    pub fn should_flush(&mut self, len: usize, bytes: u64) -> bool {
+        if len >= self.current {
+            return true;
+        }
+        let unsampled = bytes.saturating_sub(self.sampled_bytes);
+        if len > 0 && (len.is_multiple_of(RESAMPLE_ITEMS) || unsampled >= self.limit_bytes / 64) {
+            self.sample();
+            self.sampled_bytes = bytes;
+        }
+        self.resident + bytes >= self.high_watermark()
+    }
+
+    /// Resize from the average item size of a batch just sent
+    pub fn record_batch(&mut self, len: usize, bytes: u64) {
+        self.sample();
+        if len == 0 {
+            return;
+        }
+        let per_item = (bytes / len as u64).max(1);
+        let headroom = self.high_watermark().saturating_sub(self.resident);
+        let fits = (headroom / per_item).clamp(1, self.max as u64) as usize;
+        let projected = self.resident + per_item * self.current as u64;
+
+        let previous = self.current;
+        if projected >= self.high_watermark() {
+            self.current = fits;
+        } else if projected < self.low_watermark() {
+            self.current = (self.current * 2).min(fits);
+        }
+        if self.current != previous {
+            info!(
+                "Batch size {} -> {} ({} MB resident, {} bytes per item)",
+                previous,
+                self.current,
+                self.resident / (1024 * 1024),
+                per_item
+            );
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Job checkpoints for resumable ingestion
+//!
+//! After every committed batch the job records how many source records it
+//! has consumed, and before sending a batch it records where that batch
+//! ends. A restarted job skips the committed records and replays the batch
+//! that was in flight with the same boundaries, so a crash at 80% of a large
+//! file costs one batch. A batch the storage server rejects is never
+//! recorded as committed: the job stops there and retries it when resumed.
+
+use crate::JobProgress;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Progress of a job as of its last committed batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCheckpoint {
+    pub job_id: String,
+    /// Source records consumed up to and including the last committed batch
+    pub next_offset: u64,
+    /// End offset of the batch sent after `next_offset`, if one was in flight
+    #[serde(default)]
+    pub in_flight_end: Option<u64>,
+    /// Progress as of `next_offset`
+    pub progress: JobProgress,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Dedup key of the batch starting at `offset`
+///
+/// Derived from the job and the source offset rather than generated, so a
+/// batch replayed after a crash carries the same key as the original send
+/// and the storage server answers it from its idempotency cache.
+///
+/// That cache is in memory and remembers a key for 10 minutes
+/// (`DEFAULT_IDEMPOTENCY_TTL` in sutra-storage). A job resumed later than
+/// that, or after the storage server restarted, learns the replayed batch
+/// a second time.
+pub fn batch_key(job_id: &str, offset: u64) -> String {
+    format!("{}:{}", job_id, offset)
+}
+
+/// One JSON checkpoint file per job under a directory
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn path(&self, job_id: &str) -> PathBuf {
+        let name: String = job_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+
+    pub fn load(&self, job_id: &str) -> Result<Option<JobCheckpoint>> {
+        match std::fs::read(self.path(job_id)) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the checkpoint via a temp file and rename, so a crash mid-write
+    /// leaves the previous checkpoint intact
+    pub fn save(&self, checkpoint: &JobCheckpoint) -> Result<()> {
+        let path = self.path(&checkpoint.job_id);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(checkpoint)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn clear(&self, job_id: &str) -> Result<()> {
+        match std::fs::remove_file(self.path(job_id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
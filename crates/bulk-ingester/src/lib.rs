@@ -9,7 +9,10 @@
 //! - TCP binary protocol for storage
 
 pub mod adapters;
+pub mod batching;
+pub mod checkpoint;
 pub mod core;
+pub mod formats;
 pub mod metrics;
 pub mod plugins;
 pub mod server;
@@ -36,6 +39,9 @@ pub struct BulkIngester {
     job_sender: mpsc::UnboundedSender<JobEvent>,
     job_receiver: mpsc::UnboundedReceiver<JobEvent>,
 
+    /// Per-job resume points, when `checkpoint_dir` is set
+    checkpoints: Option<checkpoint::CheckpointStore>,
+
     /// Configuration
     config: IngesterConfig,
 }
@@ -49,6 +55,12 @@ pub struct IngesterConfig {
     pub plugin_dir: String,
     pub compression_enabled: bool,
     pub metrics_enabled: bool,
+    /// Directory for job checkpoints; jobs restart from zero when unset
+    #[serde(default)]
+    pub checkpoint_dir: Option<String>,
+    /// Format for file jobs that do not name one ("csv", "jsonl", ...)
+    #[serde(default)]
+    pub default_format: Option<String>,
 }
 
 impl Default for IngesterConfig {
@@ -61,6 +73,8 @@ impl Default for IngesterConfig {
             plugin_dir: "./plugins".to_string(),
             compression_enabled: true,
             metrics_enabled: true,
+            checkpoint_dir: None,
+            default_format: None,
         }
     }
 }
@@ -95,6 +109,12 @@ pub struct JobProgress {
     pub concepts_created: u64,
     pub bytes_processed: u64,
     pub current_rate: f64, // items per second
+    /// Records skipped because they could not be parsed
+    #[serde(default)]
+    pub malformed_items: u64,
+    /// Items per batch as currently sized for memory pressure
+    #[serde(default)]
+    pub effective_batch_size: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -117,6 +137,12 @@ impl BulkIngester {
         let mut plugin_registry = plugins::PluginRegistry::new();
         plugin_registry.load_plugins(&config.plugin_dir).await?;
 
+        let checkpoints = config
+            .checkpoint_dir
+            .as_deref()
+            .map(checkpoint::CheckpointStore::new)
+            .transpose()?;
+
         let (job_sender, job_receiver) = mpsc::unbounded_channel();
 
         Ok(Self {
@@ -125,6 +151,7 @@ impl BulkIngester {
             active_jobs: HashMap::new(),
             job_sender,
             job_receiver,
+            checkpoints,
             config,
         })
     }
@@ -144,7 +171,7 @@ impl BulkIngester {
             tokio::select! {
                 // Handle job events
                 Some(event) = self.job_receiver.recv() => {
-                    self.handle_job_event(event).await?;
+                    self.handle_job_event(event);
                 }
 
                 // Handle shutdown signal
@@ -159,14 +186,36 @@ impl BulkIngester {
     }
 
     /// Submit new ingestion job
-    pub async fn submit_job(&mut self, job: IngestionJob) -> Result<String> {
+    ///
+    /// A job submitted under the id of an interrupted one resumes from that
+    /// job's checkpoint (with `checkpoint_dir` set) instead of starting over.
+    pub fn submit_job(&mut self, mut job: IngestionJob) -> Result<String> {
         info!("Submitting ingestion job: {}", job.id);
 
+        // A format given with the job wins over the configured default
+        if let (Some(format), Some(source)) = (
+            &self.config.default_format,
+            job.source_config.as_object_mut(),
+        ) {
+            source
+                .entry("format")
+                .or_insert_with(|| serde_json::json!(format));
+        }
+
         // Validate adapter exists
         if !self.plugin_registry.has_adapter(&job.adapter_name) {
             return Err(anyhow::anyhow!("Adapter '{}' not found", job.adapter_name));
         }
 
+        self.apply_job_events();
+        if self
+            .active_jobs
+            .get(&job.id)
+            .is_some_and(|j| matches!(j.status, JobStatus::Running))
+        {
+            return Err(anyhow::anyhow!("Job {} is already running", job.id));
+        }
+
         // Check concurrent job limit
         let running_jobs = self
             .active_jobs
@@ -186,72 +235,77 @@ impl BulkIngester {
         self.active_jobs.insert(job_id.clone(), job);
 
         // Start processing
-        self.start_job_processing(&job_id).await?;
+        self.start_job_processing(&job_id)?;
 
         Ok(job_id)
     }
 
-    async fn start_job_processing(&mut self, job_id: &str) -> Result<()> {
+    /// Run `job_id` in the background on its own copy of the storage client
+    ///
+    /// The task reports through the job event channel and ends with a
+    /// `Completed` or `Failed` event.
+    fn start_job_processing(&mut self, job_id: &str) -> Result<()> {
         let job = self
             .active_jobs
             .get_mut(job_id)
             .ok_or_else(|| anyhow::anyhow!("Job {} not found", job_id))?;
 
         // Get adapter
-        if !self.plugin_registry.has_adapter(&job.adapter_name) {
-            return Err(anyhow::anyhow!("Adapter '{}' not found", job.adapter_name));
-        }
-
-        // Check if mock mode is allowed
-        let allow_mock =
-            std::env::var("SUTRA_ALLOW_MOCK_MODE").unwrap_or_else(|_| "0".to_string()) == "1";
-
-        if !allow_mock {
-            // PRODUCTION: Job processing not yet fully integrated
-            return Err(anyhow::anyhow!(
-                "Bulk ingestion job processing is not yet fully implemented.\n\
-                 \n\
-                 Current status:\n\
-                 - Adapter system: ✅ Implemented\n\
-                 - Storage client: ✅ Implemented\n\
-                 - Job queue: ⚠️  Mock implementation only\n\
-                 \n\
-                 TODO: Replace mock job processing with real implementation\n\
-                 See: process_job_with_adapter() for reference implementation\n\
-                 \n\
-                 For testing ONLY, set SUTRA_ALLOW_MOCK_MODE=1 to enable mock processing.\n\
-                 WARNING: Mock mode just sleeps and reports success!"
-            ));
-        }
+        let adapter = self
+            .plugin_registry
+            .get_adapter(&job.adapter_name)
+            .ok_or_else(|| anyhow::anyhow!("Adapter '{}' not found", job.adapter_name))?;
 
-        // MOCK MODE: Simple synchronous processing to avoid lifetime issues
-        // In production, this would use process_job_with_adapter() with proper job queue
         job.status = JobStatus::Running;
-
-        warn!("⚠️  MOCK JOB PROCESSING: Job will not actually process data!");
-        warn!("⚠️  Set SUTRA_ALLOW_MOCK_MODE=0 to disable mock mode");
-        info!("Started mock processing for job: {}", job_id);
-
-        let job_id_clone = job_id.to_string();
+        job.completed_at = None;
+        job.error = None;
+        info!("Started processing for job: {}", job_id);
+
+        let job = job.clone();
+        let storage_client = self.storage_client.clone();
+        let job_sender = self.job_sender.clone();
+        let checkpoints = self.checkpoints.clone();
+        let batch_size =
+            batching::AdaptiveBatchSize::new(self.config.batch_size, self.config.memory_limit_mb);
         tokio::spawn(async move {
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-            warn!(
-                "⚠️  Mock job {} completed (no actual work done)",
-                job_id_clone
-            );
+            let job_id = job.id.clone();
+            let result = Self::process_job_with_adapter(
+                job,
+                adapter.as_ref(),
+                storage_client,
+                job_sender.clone(),
+                batch_size,
+                checkpoints.as_ref(),
+            )
+            .await;
+            let event = match result {
+                Ok(progress) => JobEvent::Completed(job_id, progress),
+                Err(err) => {
+                    warn!("Job {} failed: {}", job_id, err);
+                    JobEvent::Failed(job_id, err.to_string())
+                }
+            };
+            let _ = job_sender.send(event);
         });
 
         Ok(())
     }
 
     // Real job processing with adapters and performance optimization
-    #[allow(dead_code)]
-    async fn process_job_with_adapter(
+    //
+    // Batches are cut by the adaptive sizer rather than a fixed count. With a
+    // checkpoint store the job resumes after its last committed batch,
+    // replays an in-flight batch with its original boundaries, and removes
+    // the checkpoint once it finishes. A batch that fails then ends the job,
+    // leaving the checkpoint on it; without a store its records are counted
+    // as failed and the job goes on.
+    async fn process_job_with_adapter<S: storage::ConceptSink>(
         job: IngestionJob,
         adapter: &(dyn adapters::IngestionAdapter + Send + Sync),
-        mut storage_client: storage::TcpStorageClient,
+        mut storage_client: S,
         job_sender: mpsc::UnboundedSender<JobEvent>,
-        batch_size: usize,
+        mut batch_size: batching::AdaptiveBatchSize,
+        checkpoints: Option<&checkpoint::CheckpointStore>,
     ) -> Result<JobProgress> {
         info!(
             "Processing job: {} with adapter: {}",
@@ -264,17 +318,46 @@ impl BulkIngester {
 
         // Create data stream from adapter
         let mut data_stream = adapter.create_stream(&job.source_config).await?;
+        let total_items = data_stream.estimate_total().await?;
 
-        let mut progress = JobProgress {
-            total_items: data_stream.estimate_total().await?,
-            processed_items: 0,
-            failed_items: 0,
-            concepts_created: 0,
-            bytes_processed: 0,
-            current_rate: 0.0,
+        let resume_from = match checkpoints {
+            Some(store) => store.load(&job.id)?,
+            None => None,
+        };
+        // Source records consumed so far, committed or not
+        let mut offset = 0u64;
+        // Batch to cut at exactly this offset, replaying one from before a restart
+        let mut replay_end = None;
+        let mut progress = match resume_from {
+            Some(checkpoint) => {
+                info!(
+                    "Resuming job {} after record {}",
+                    job.id, checkpoint.next_offset
+                );
+                while offset < checkpoint.next_offset && data_stream.next().await.is_some() {
+                    offset += 1;
+                }
+                replay_end = checkpoint.in_flight_end;
+                checkpoint.progress
+            }
+            None => JobProgress {
+                total_items,
+                processed_items: 0,
+                failed_items: 0,
+                concepts_created: 0,
+                bytes_processed: 0,
+                current_rate: 0.0,
+                malformed_items: 0,
+                effective_batch_size: 0,
+            },
         };
+        progress.effective_batch_size = batch_size.current();
+        // Progress as of the last committed batch, which is what a checkpoint holds
+        let mut settled = progress.clone();
 
-        let mut batch = Vec::with_capacity(batch_size);
+        let mut batch = Vec::with_capacity(batch_size.current());
+        let mut batch_bytes = 0u64;
+        let mut batch_start = offset;
         let mut last_progress_report = std::time::Instant::now();
         let start_time = std::time::Instant::now();
 
@@ -284,62 +367,79 @@ impl BulkIngester {
         );
 
         // Process data stream in optimized batches
-        while let Some(item_result) = data_stream.next().await {
-            match item_result {
-                Ok(item) => {
-                    progress.bytes_processed += item.size_bytes();
-                    batch.push(item);
-
-                    // Process batch when full or at end
-                    if batch.len() >= batch_size {
-                        match Self::process_batch_optimized(&mut storage_client, &batch).await {
-                            Ok(concepts) => {
-                                progress.concepts_created += concepts;
-                                progress.processed_items += batch.len() as u64;
-                                info!(
-                                    "Processed batch of {} items, total: {}",
-                                    batch.len(),
-                                    progress.processed_items
-                                );
-                            }
-                            Err(err) => {
-                                warn!("Batch processing failed: {}", err);
-                                progress.failed_items += batch.len() as u64;
-                            }
-                        }
-
-                        batch.clear();
-
-                        // Report progress every 5 seconds for performance
-                        if last_progress_report.elapsed() > std::time::Duration::from_secs(5) {
-                            progress.current_rate = progress.processed_items as f64
-                                / start_time.elapsed().as_secs_f64();
-                            let _ = job_sender
-                                .send(JobEvent::Progress(job.id.clone(), progress.clone()));
-                            last_progress_report = std::time::Instant::now();
-                        }
+        loop {
+            let next = data_stream.next().await;
+            let exhausted = next.is_none();
+            if let Some(item_result) = next {
+                offset += 1;
+                match item_result {
+                    Ok(item) => {
+                        let size = item.size_bytes();
+                        progress.bytes_processed += size;
+                        batch_bytes += size;
+                        batch.push(item);
+                    }
+                    Err(err) if err.is::<formats::MalformedRecord>() => {
+                        warn!("Skipping {}", err);
+                        progress.malformed_items += 1;
+                    }
+                    Err(err) => {
+                        warn!("Item processing error: {}", err);
+                        progress.failed_items += 1;
                     }
-                }
-                Err(err) => {
-                    warn!("Item processing error: {}", err);
-                    progress.failed_items += 1;
                 }
             }
-        }
 
-        // Process final batch
-        if !batch.is_empty() {
-            match Self::process_batch_optimized(&mut storage_client, &batch).await {
-                Ok(concepts) => {
-                    progress.concepts_created += concepts;
-                    progress.processed_items += batch.len() as u64;
-                    info!("Processed final batch of {} items", batch.len());
+            // Process batch when full, when memory is tight, or at end
+            let cut = match replay_end {
+                Some(end) => offset >= end,
+                None => batch_size.should_flush(batch.len(), batch_bytes),
+            };
+            if !batch.is_empty() && (cut || exhausted) {
+                Self::save_checkpoint(checkpoints, &job.id, batch_start, Some(offset), &settled);
+                let committed = Self::commit_batch(
+                    &mut storage_client,
+                    &job.id,
+                    batch_start,
+                    &batch,
+                    &mut progress,
+                )
+                .await;
+                batch_size.record_batch(batch.len(), batch_bytes);
+                progress.effective_batch_size = batch_size.current();
+                if committed {
+                    settled = progress.clone();
+                    Self::save_checkpoint(checkpoints, &job.id, offset, None, &settled);
+                } else if checkpoints.is_some() {
+                    // Only a committed batch settles: stop with the checkpoint
+                    // on this batch so resubmitting the job retries it
+                    return Err(anyhow::anyhow!(
+                        "Batch of records {}..{} failed; resubmit job {} to retry it",
+                        batch_start,
+                        offset,
+                        job.id
+                    ));
                 }
-                Err(err) => {
-                    warn!("Final batch processing failed: {}", err);
-                    progress.failed_items += batch.len() as u64;
+                batch.clear();
+                batch_bytes = 0;
+                batch_start = offset;
+                replay_end = None;
+
+                // Report progress every 5 seconds for performance
+                if last_progress_report.elapsed() > std::time::Duration::from_secs(5) {
+                    progress.current_rate =
+                        progress.processed_items as f64 / start_time.elapsed().as_secs_f64();
+                    let _ = job_sender.send(JobEvent::Progress(job.id.clone(), progress.clone()));
+                    last_progress_report = std::time::Instant::now();
                 }
             }
+            if exhausted {
+                break;
+            }
+        }
+
+        if let Some(store) = checkpoints {
+            store.clear(&job.id)?;
         }
 
         // Final progress calculation
@@ -354,11 +454,65 @@ impl BulkIngester {
         Ok(progress)
     }
 
+    /// Send `batch`, which starts at source record `start`; false if it failed
+    ///
+    /// The dedup key is derived from `start`, so if the job dies between the
+    /// commit and the checkpoint, the replayed batch is not learned twice.
+    async fn commit_batch<S: storage::ConceptSink>(
+        storage_client: &mut S,
+        job_id: &str,
+        start: u64,
+        batch: &[adapters::DataItem],
+        progress: &mut JobProgress,
+    ) -> bool {
+        let key = checkpoint::batch_key(job_id, start);
+        match Self::process_batch_optimized(storage_client, batch, &key).await {
+            Ok(concepts) => {
+                progress.concepts_created += concepts;
+                progress.processed_items += batch.len() as u64;
+                info!(
+                    "Processed batch of {} items, total: {}",
+                    batch.len(),
+                    progress.processed_items
+                );
+                true
+            }
+            Err(err) => {
+                warn!("Batch processing failed: {}", err);
+                progress.failed_items += batch.len() as u64;
+                false
+            }
+        }
+    }
+
+    fn save_checkpoint(
+        checkpoints: Option<&checkpoint::CheckpointStore>,
+        job_id: &str,
+        next_offset: u64,
+        in_flight_end: Option<u64>,
+        progress: &JobProgress,
+    ) {
+        let Some(store) = checkpoints else {
+            return;
+        };
+        let checkpoint = checkpoint::JobCheckpoint {
+            job_id: job_id.to_string(),
+            next_offset,
+            in_flight_end,
+            progress: progress.clone(),
+            updated_at: chrono::Utc::now(),
+        };
+        // A stale checkpoint only replays deduplicated batches
+        if let Err(err) = store.save(&checkpoint) {
+            warn!("Failed to checkpoint job {}: {}", job_id, err);
+        }
+    }
+
     // High-performance batch processing with optimized memory usage
-    #[allow(dead_code)]
-    async fn process_batch_optimized(
-        storage_client: &mut storage::TcpStorageClient,
+    async fn process_batch_optimized<S: storage::ConceptSink>(
+        storage_client: &mut S,
         batch: &[adapters::DataItem],
+        dedup_key: &str,
     ) -> Result<u64> {
         #[allow(unused_assignments)]
         let mut concepts_created = 0u64;
@@ -374,7 +528,7 @@ impl BulkIngester {
             .collect();
 
         // Process batch via storage client
-        match storage_client.batch_learn_concepts(concepts).await {
+        match storage_client.learn_batch(concepts, dedup_key).await {
             Ok(concept_ids) => {
                 concepts_created = concept_ids.len() as u64;
                 info!("Created {} concepts in batch", concepts_created);
@@ -398,7 +552,14 @@ impl BulkIngester {
         Ok(10) // Mock result
     }
 
-    async fn handle_job_event(&mut self, event: JobEvent) -> Result<()> {
+    /// Apply the events jobs have reported since the last call
+    pub fn apply_job_events(&mut self) {
+        while let Ok(event) = self.job_receiver.try_recv() {
+            self.handle_job_event(event);
+        }
+    }
+
+    fn handle_job_event(&mut self, event: JobEvent) {
         match event {
             JobEvent::Started(job_id) => {
                 if let Some(job) = self.active_jobs.get_mut(&job_id) {
@@ -425,8 +586,6 @@ impl BulkIngester {
                 }
             }
         }
-
-        Ok(())
     }
 
     /// Get job status
@@ -439,6 +598,14 @@ impl BulkIngester {
         self.active_jobs.values().collect()
     }
 
+    /// Last checkpoint of `job_id`, if it has one to resume from
+    pub fn job_checkpoint(&self, job_id: &str) -> Result<Option<checkpoint::JobCheckpoint>> {
+        match &self.checkpoints {
+            Some(store) => store.load(job_id),
+            None => Ok(None),
+        }
+    }
+
     /// Expose storage server address (for stateless handlers)
     pub fn storage_server_address(&self) -> String {
         self.config.storage_server.clone()
@@ -461,6 +628,10 @@ impl BulkIngester {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adapters::{AdapterInfo, DataItem, DataStream, IngestionAdapter};
+    use async_trait::async_trait;
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
 
     #[tokio::test]
     async fn test_ingester_creation() {
@@ -468,4 +639,424 @@ mod tests {
         // Test would need mock storage server
         // let ingester = BulkIngester::new(config).await.unwrap();
     }
+
+    /// Adapter streaming `record-0`, `record-1`, ..., each padded by the
+    /// given number of bytes
+    struct RecordAdapter(Vec<usize>);
+
+    struct RecordStream {
+        next: usize,
+        padding: Vec<usize>,
+    }
+
+    #[async_trait]
+    impl DataStream for RecordStream {
+        async fn next(&mut self) -> Option<Result<DataItem>> {
+            let padding = *self.padding.get(self.next)?;
+            self.next += 1;
+            Some(Ok(DataItem {
+                content: format!("record-{}{}", self.next - 1, " ".repeat(padding)),
+                metadata: HashMap::new(),
+                embedding: None,
+                source_id: format!("item_{}", self.next),
+                item_type: "record".to_string(),
+            }))
+        }
+
+        async fn estimate_total(&self) -> Result<Option<u64>> {
+            Ok(Some(self.padding.len() as u64))
+        }
+
+        fn position(&self) -> u64 {
+            self.next as u64
+        }
+    }
+
+    #[async_trait]
+    impl IngestionAdapter for RecordAdapter {
+        fn name(&self) -> &str {
+            "records"
+        }
+
+        fn supported_types(&self) -> Vec<&str> {
+            vec!["test"]
+        }
+
+        async fn validate_config(&self, _config: &serde_json::Value) -> Result<()> {
+            Ok(())
+        }
+
+        async fn create_stream(&self, _config: &serde_json::Value) -> Result<Box<dyn DataStream>> {
+            Ok(Box::new(RecordStream {
+                next: 0,
+                padding: self.0.clone(),
+            }))
+        }
+
+        fn info(&self) -> AdapterInfo {
+            AdapterInfo {
+                name: "records".to_string(),
+                description: "Test records".to_string(),
+                version: "1.0.0".to_string(),
+                supported_types: vec!["test".to_string()],
+                config_schema: serde_json::json!({}),
+            }
+        }
+    }
+
+    /// Storage stand-in that deduplicates on the batch key like the server's
+    /// idempotency cache
+    #[derive(Default)]
+    struct FakeStorage {
+        learned: Arc<Mutex<Vec<String>>>,
+        keys: Arc<Mutex<HashSet<String>>>,
+        /// Item count and content bytes of every batch received
+        batches: Arc<Mutex<Vec<(usize, usize)>>>,
+        /// Commit this many batches, then hang before acknowledging the next
+        /// one as if the process died mid-request
+        die_after: Option<usize>,
+        /// Commit this many batches, then reject the next one
+        reject_after: Option<usize>,
+    }
+
+    #[async_trait]
+    impl storage::ConceptSink for FakeStorage {
+        async fn learn_batch(
+            &mut self,
+            concepts: Vec<storage::Concept>,
+            dedup_key: &str,
+        ) -> Result<Vec<String>> {
+            let bytes = concepts.iter().map(|c| c.content.len()).sum();
+            self.batches.lock().unwrap().push((concepts.len(), bytes));
+            match &mut self.reject_after {
+                Some(0) => return Err(anyhow::anyhow!("storage unavailable")),
+                Some(remaining) => *remaining -= 1,
+                None => {}
+            }
+            let ids: Vec<String> = concepts
+                .iter()
+                .map(|c| c.content.trim_end().to_string())
+                .collect();
+            if self.keys.lock().unwrap().insert(dedup_key.to_string()) {
+                self.learned.lock().unwrap().extend(ids.clone());
+            }
+            match &mut self.die_after {
+                Some(0) => std::future::pending().await,
+                Some(remaining) => *remaining -= 1,
+                None => {}
+            }
+            Ok(ids)
+        }
+    }
+
+    /// Batches of `size` items with no memory pressure
+    fn fixed_batches(size: usize) -> batching::AdaptiveBatchSize {
+        batching::AdaptiveBatchSize::new(size, 4096).with_probe(|| Some(0))
+    }
+
+    fn job(id: &str) -> IngestionJob {
+        IngestionJob {
+            id: id.to_string(),
+            source_type: "test".to_string(),
+            source_config: serde_json::json!({}),
+            adapter_name: "records".to_string(),
+            status: JobStatus::Pending,
+            progress: JobProgress {
+                total_items: None,
+                processed_items: 0,
+                failed_items: 0,
+                concepts_created: 0,
+                bytes_processed: 0,
+                current_rate: 0.0,
+                malformed_items: 0,
+                effective_batch_size: 0,
+            },
+            started_at: chrono::Utc::now(),
+            completed_at: None,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_killed_job_resumes_and_learns_each_record_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = checkpoint::CheckpointStore::new(dir.path()).unwrap();
+        let adapter = RecordAdapter(vec![0; 25]);
+        let (sender, _receiver) = mpsc::unbounded_channel();
+
+        // The third batch reaches storage but the job dies before checkpointing it
+        let first = FakeStorage {
+            die_after: Some(2),
+            ..Default::default()
+        };
+        let learned = first.learned.clone();
+        let keys = first.keys.clone();
+        let run = BulkIngester::process_job_with_adapter(
+            job("job-1"),
+            &adapter,
+            first,
+            sender.clone(),
+            fixed_batches(5),
+            Some(&store),
+        );
+        let killed = tokio::time::timeout(std::time::Duration::from_millis(200), run).await;
+        assert!(killed.is_err());
+        assert_eq!(learned.lock().unwrap().len(), 15);
+        let checkpoint = store.load("job-1").unwrap().unwrap();
+        assert_eq!(checkpoint.next_offset, 10);
+        assert_eq!(checkpoint.in_flight_end, Some(15));
+        assert_eq!(checkpoint.progress.processed_items, 10);
+
+        // Restart: replays the third batch under the same key, then carries on
+        let second = FakeStorage {
+            learned: learned.clone(),
+            keys: keys.clone(),
+            ..Default::default()
+        };
+        let progress = BulkIngester::process_job_with_adapter(
+            job("job-1"),
+            &adapter,
+            second,
+            sender,
+            fixed_batches(5),
+            Some(&store),
+        )
+        .await
+        .unwrap();
+
+        let expected: Vec<String> = (0..25).map(|i| format!("record-{}", i)).collect();
+        assert_eq!(*learned.lock().unwrap(), expected);
+        assert_eq!(keys.lock().unwrap().len(), 5);
+        assert!(keys
+            .lock()
+            .unwrap()
+            .contains(&checkpoint::batch_key("job-1", 10)));
+        assert_eq!(progress.processed_items, 25);
+        assert!(store.load("job-1").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_not_checkpointed_and_retried_on_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = checkpoint::CheckpointStore::new(dir.path()).unwrap();
+        let adapter = RecordAdapter(vec![0; 25]);
+        let (sender, _receiver) = mpsc::unbounded_channel();
+
+        // The second batch is rejected: the job stops on it
+        let first = FakeStorage {
+            reject_after: Some(1),
+            ..Default::default()
+        };
+        let learned = first.learned.clone();
+        let result = BulkIngester::process_job_with_adapter(
+            job("job-2"),
+            &adapter,
+            first,
+            sender.clone(),
+            fixed_batches(10),
+            Some(&store),
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(learned.lock().unwrap().len(), 10);
+        let checkpoint = store.load("job-2").unwrap().unwrap();
+        assert_eq!(checkpoint.next_offset, 10);
+        assert_eq!(checkpoint.in_flight_end, Some(20));
+        assert_eq!(checkpoint.progress.processed_items, 10);
+        assert_eq!(checkpoint.progress.failed_items, 0);
+
+        // Resuming retries the rejected batch and finishes the rest
+        let second = FakeStorage::default();
+        let retried = second.learned.clone();
+        let progress = BulkIngester::process_job_with_adapter(
+            job("job-2"),
+            &adapter,
+            second,
+            sender,
+            fixed_batches(10),
+            Some(&store),
+        )
+        .await
+        .unwrap();
+        let retried = retried.lock().unwrap();
+        assert_eq!(retried.first().map(String::as_str), Some("record-10"));
+        assert_eq!(retried.len(), 15);
+        assert_eq!(progress.processed_items, 25);
+        assert_eq!(progress.failed_items, 0);
+    }
+
+    #[test]
+    fn test_batch_is_cut_when_memory_grows_mid_batch() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static RESIDENT: AtomicU64 = AtomicU64::new(0);
+        const MB: u64 = 1024 * 1024;
+
+        let mut sizer = batching::AdaptiveBatchSize::new(1000, 100)
+            .with_probe(|| Some(RESIDENT.load(Ordering::Relaxed)));
+        assert!(!sizer.should_flush(10, 0));
+
+        // Another job takes memory while this batch is still small
+        RESIDENT.store(90 * MB, Ordering::Relaxed);
+        let cut = (1..1000).find(|&len| sizer.should_flush(len, 0));
+        assert!(cut.is_some_and(|len| len <= 64), "{:?}", cut);
+
+        // Buffered bytes count on top of the last sample
+        RESIDENT.store(10 * MB, Ordering::Relaxed);
+        let mut sizer = sizer.with_probe(|| Some(RESIDENT.load(Ordering::Relaxed)));
+        assert!(!sizer.should_flush(1, 50 * MB));
+        assert!(sizer.should_flush(2, 75 * MB));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_rows_are_skipped_and_counted_in_job_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "id,content\n1,alpha\n2,too,many\n3,gamma\n").unwrap();
+
+        let mut file_job = job("csv-job");
+        file_job.source_config = serde_json::json!({ "path": path.to_str().unwrap() });
+        let storage = FakeStorage::default();
+        let learned = storage.learned.clone();
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let progress = BulkIngester::process_job_with_adapter(
+            file_job,
+            &adapters::builtin::FileAdapter::new(),
+            storage,
+            sender,
+            fixed_batches(10),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*learned.lock().unwrap(), vec!["alpha", "gamma"]);
+        assert_eq!(progress.processed_items, 2);
+        assert_eq!(progress.malformed_items, 1);
+        assert_eq!(progress.failed_items, 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_size_shrinks_when_records_grow_and_recovers() {
+        const KB: usize = 1024;
+        // 1 KB records, then 100 KB records, then 1 KB records again
+        let padding: Vec<usize> = [(200, KB), (100, 100 * KB), (400, KB)]
+            .into_iter()
+            .flat_map(|(count, size)| std::iter::repeat_n(size, count))
+            .collect();
+        let adapter = RecordAdapter(padding.clone());
+        let storage = FakeStorage::default();
+        let learned = storage.learned.clone();
+        let batches = storage.batches.clone();
+        let (sender, _receiver) = mpsc::unbounded_channel();
+
+        // 100 items per batch would hold 10 MB of large records against a 1 MB limit
+        let batch_size = batching::AdaptiveBatchSize::new(100, 1).with_probe(|| Some(0));
+        let progress = BulkIngester::process_job_with_adapter(
+            job("sized"),
+            &adapter,
+            storage,
+            sender,
+            batch_size,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let batches = batches.lock().unwrap();
+        assert!(batches[..2].iter().all(|(len, _)| *len == 100));
+        // Large records are cut into batches that stay under the limit
+        let shrunk = batches.iter().position(|(len, _)| *len <= 9);
+        assert!(shrunk.is_some(), "{:?}", batches);
+        assert!(batches.iter().all(|(_, bytes)| *bytes < 1024 * KB));
+
+        // Back to full batches once the records are small again
+        assert!(batches[shrunk.unwrap()..]
+            .iter()
+            .any(|(len, _)| *len == 100));
+        assert_eq!(progress.effective_batch_size, 100);
+        assert_eq!(progress.processed_items, padding.len() as u64);
+        assert_eq!(learned.lock().unwrap().len(), padding.len());
+    }
+
+    /// Ingester with checkpoints under `dir`, against a listener standing in
+    /// for the storage server (jobs here never send it a batch)
+    async fn local_ingester(dir: &std::path::Path) -> (BulkIngester, tokio::net::TcpListener) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ingester = BulkIngester::new(IngesterConfig {
+            storage_server: listener.local_addr().unwrap().to_string(),
+            plugin_dir: dir.join("plugins").to_string_lossy().into_owned(),
+            checkpoint_dir: Some(dir.join("checkpoints").to_string_lossy().into_owned()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        (ingester, listener)
+    }
+
+    /// File job reading `path`
+    fn file_job(id: &str, path: &std::path::Path) -> IngestionJob {
+        let mut job = job(id);
+        job.adapter_name = "file".to_string();
+        job.source_config = serde_json::json!({ "path": path });
+        job
+    }
+
+    /// Wait for the submitted job `id` to stop running
+    async fn finished(ingester: &mut BulkIngester, id: &str) -> IngestionJob {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            ingester.apply_job_events();
+            let job = ingester.get_job(id).unwrap();
+            if !matches!(job.status, JobStatus::Running) {
+                return job.clone();
+            }
+            assert!(std::time::Instant::now() < deadline, "job never finished");
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submitted_job_resumes_from_checkpoint_under_its_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("empty.txt");
+        std::fs::write(&source, "").unwrap();
+        let (mut ingester, _listener) = local_ingester(dir.path()).await;
+
+        // An earlier run of "job-9" got 7 records in before it was interrupted
+        let mut interrupted = job("job-9").progress;
+        interrupted.processed_items = 7;
+        checkpoint::CheckpointStore::new(dir.path().join("checkpoints"))
+            .unwrap()
+            .save(&checkpoint::JobCheckpoint {
+                job_id: "job-9".to_string(),
+                next_offset: 0,
+                in_flight_end: None,
+                progress: interrupted,
+                updated_at: chrono::Utc::now(),
+            })
+            .unwrap();
+
+        let resumed = file_job("job-9", &source);
+        assert_eq!(ingester.submit_job(resumed.clone()).unwrap(), "job-9");
+        assert!(ingester.submit_job(resumed).is_err(), "already running");
+
+        let job = finished(&mut ingester, "job-9").await;
+        assert!(matches!(job.status, JobStatus::Completed), "{:?}", job);
+        assert_eq!(job.progress.processed_items, 7);
+        assert!(ingester.job_checkpoint("job-9").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submitted_job_reports_malformed_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("rows.csv");
+        std::fs::write(&source, "id,content\n1,a,b\n2,\"never closed\n").unwrap();
+        let (mut ingester, _listener) = local_ingester(dir.path()).await;
+
+        ingester.submit_job(file_job("csv-9", &source)).unwrap();
+        let job = finished(&mut ingester, "csv-9").await;
+        assert!(matches!(job.status, JobStatus::Completed), "{:?}", job);
+        assert_eq!(job.progress.malformed_items, 2);
+        assert_eq!(job.progress.processed_items, 0);
+    }
 }
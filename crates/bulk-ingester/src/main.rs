@@ -29,6 +29,15 @@ struct Args {
     /// Memory limit in MB
     #[arg(long, default_value = "4096")]
     memory_limit_mb: usize,
+
+    /// Directory for job checkpoints (enables resuming interrupted jobs)
+    #[arg(long)]
+    checkpoint_dir: Option<String>,
+
+    /// Format for file jobs that do not set one (csv, jsonl, ndjson);
+    /// otherwise chosen by file extension
+    #[arg(long)]
+    format: Option<String>,
 }
 
 #[tokio::main]
@@ -52,6 +61,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         plugin_dir: args.plugin_dir,
         compression_enabled: true,
         metrics_enabled: true,
+        checkpoint_dir: args.checkpoint_dir,
+        default_format: args.format,
     };
 
     // Initialize bulk ingester
@@ -4,10 +4,11 @@ use crate::adapters::{builtin::FileAdapter, IngestionAdapter};
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use tracing::{info, warn};
 
 pub struct PluginRegistry {
-    adapters: HashMap<String, Box<dyn IngestionAdapter + Send + Sync>>,
+    adapters: HashMap<String, Arc<dyn IngestionAdapter + Send + Sync>>,
 }
 
 impl Default for PluginRegistry {
@@ -32,7 +33,7 @@ impl PluginRegistry {
         // Register built-in file adapter
         let file_adapter = FileAdapter::new();
         self.adapters
-            .insert("file".to_string(), Box::new(file_adapter));
+            .insert("file".to_string(), Arc::new(file_adapter));
 
         info!("Registered built-in adapters: file");
     }
@@ -79,7 +80,8 @@ impl PluginRegistry {
 
                 match self.load_python_adapter(&path) {
                     Ok(adapter) => {
-                        self.adapters.insert(plugin_name.to_string(), adapter);
+                        self.adapters
+                            .insert(plugin_name.to_string(), Arc::from(adapter));
                         info!("Successfully loaded Python adapter: {}", plugin_name);
                     }
                     Err(e) => {
@@ -144,8 +146,8 @@ impl PluginRegistry {
         self.adapters.contains_key(name)
     }
 
-    pub fn get_adapter(&self, name: &str) -> Option<&(dyn IngestionAdapter + Send + Sync)> {
-        self.adapters.get(name).map(|adapter| adapter.as_ref())
+    pub fn get_adapter(&self, name: &str) -> Option<Arc<dyn IngestionAdapter + Send + Sync>> {
+        self.adapters.get(name).cloned()
     }
 
     pub fn list_adapters(&self) -> Vec<&str> {
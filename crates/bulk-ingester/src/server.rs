@@ -1,6 +1,6 @@
 //! FastAPI-like server for job management
 
-use crate::BulkIngester;
+use crate::{BulkIngester, IngestionJob, JobProgress, JobStatus};
 use axum::{
     extract::{Json, Path, State},
     http::StatusCode,
@@ -20,6 +20,10 @@ pub struct CreateJobRequest {
     pub source_type: String,
     pub source_config: Value,
     pub adapter_name: String,
+    /// Id of an interrupted job to resume from its checkpoint; a new id is
+    /// generated when unset
+    #[serde(default)]
+    pub job_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -65,29 +69,59 @@ async fn health_check() -> impl IntoResponse {
 }
 
 async fn create_job(
-    State(_ingester): State<SharedIngester>,
+    State(ingester): State<SharedIngester>,
     Json(request): Json<CreateJobRequest>,
 ) -> impl IntoResponse {
-    // Simplified implementation for testing
-    let job_id = uuid::Uuid::new_v4().to_string();
-
-    // Log the request
-    println!(
-        "Received job request: source_type={}, adapter={}, config={:?}",
-        request.source_type, request.adapter_name, request.source_config
-    );
-
-    // Return immediate success response
-    let response = JobResponse {
-        id: job_id.clone(),
-        status: "submitted".to_string(),
-        progress: json!({
-            "processed_items": 0,
-            "total_items": null
-        }),
+    let job = IngestionJob {
+        id: request
+            .job_id
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        source_type: request.source_type,
+        source_config: request.source_config,
+        adapter_name: request.adapter_name,
+        status: JobStatus::Pending,
+        progress: JobProgress {
+            total_items: None,
+            processed_items: 0,
+            failed_items: 0,
+            concepts_created: 0,
+            bytes_processed: 0,
+            current_rate: 0.0,
+            malformed_items: 0,
+            effective_batch_size: 0,
+        },
+        started_at: chrono::Utc::now(),
+        completed_at: None,
+        error: None,
     };
 
-    ResponseJson(response)
+    let mut ing = match ingester.lock() {
+        Ok(ing) => ing,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    match ing.submit_job(job) {
+        Ok(job_id) => {
+            // A resumed job starts from its checkpoint's progress
+            let processed_items = match ing.job_checkpoint(&job_id) {
+                Ok(Some(checkpoint)) => checkpoint.progress.processed_items,
+                _ => 0,
+            };
+            ResponseJson(JobResponse {
+                id: job_id,
+                status: "running".to_string(),
+                progress: json!({
+                    "processed_items": processed_items,
+                    "total_items": null
+                }),
+            })
+            .into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
 }
 
 async fn get_job(
@@ -95,32 +129,38 @@ async fn get_job(
     Path(job_id): Path<String>,
 ) -> impl IntoResponse {
     match ingester.lock() {
-        Ok(ing) => match ing.get_job(&job_id) {
-            Some(job) => Json(json!({
-                "id": job.id,
-                "status": format!("{:?}", job.status).to_lowercase(),
-                "progress": {
-                    "processed_items": job.progress.processed_items,
-                    "total_items": job.progress.total_items,
-                    "failed_items": job.progress.failed_items,
-                    "concepts_created": job.progress.concepts_created,
-                    "bytes_processed": job.progress.bytes_processed,
-                    "current_rate": job.progress.current_rate
-                },
-                "started_at": job.started_at,
-                "completed_at": job.completed_at,
-                "error": job.error
-            }))
-            .into_response(),
-            None => StatusCode::NOT_FOUND.into_response(),
-        },
+        Ok(mut ing) => {
+            ing.apply_job_events();
+            match ing.get_job(&job_id) {
+                Some(job) => Json(json!({
+                    "id": job.id,
+                    "status": format!("{:?}", job.status).to_lowercase(),
+                    "progress": {
+                        "processed_items": job.progress.processed_items,
+                        "total_items": job.progress.total_items,
+                        "failed_items": job.progress.failed_items,
+                        "malformed_items": job.progress.malformed_items,
+                        "effective_batch_size": job.progress.effective_batch_size,
+                        "concepts_created": job.progress.concepts_created,
+                        "bytes_processed": job.progress.bytes_processed,
+                        "current_rate": job.progress.current_rate
+                    },
+                    "started_at": job.started_at,
+                    "completed_at": job.completed_at,
+                    "error": job.error
+                }))
+                .into_response(),
+                None => StatusCode::NOT_FOUND.into_response(),
+            }
+        }
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
     }
 }
 
 async fn list_jobs(State(ingester): State<SharedIngester>) -> impl IntoResponse {
     match ingester.lock() {
-        Ok(ing) => {
+        Ok(mut ing) => {
+            ing.apply_job_events();
             let jobs: Vec<Value> = ing
                 .list_jobs()
                 .iter()
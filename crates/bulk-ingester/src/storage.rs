@@ -2,6 +2,7 @@
 //! Uses unified learning API (embeddings + associations handled by storage)
 
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
@@ -42,6 +43,15 @@ impl Default for LearnOptions {
     }
 }
 
+/// Destination for batches of concepts
+#[async_trait]
+pub trait ConceptSink: Send {
+    /// Learn `concepts`; a repeat with the same `dedup_key` must not learn
+    /// them a second time
+    async fn learn_batch(&mut self, concepts: Vec<Concept>, dedup_key: &str)
+        -> Result<Vec<String>>;
+}
+
 #[derive(Debug, Clone)]
 pub struct TcpStorageClient {
     server_address: String,
@@ -81,8 +91,10 @@ enum StorageRequest {
         options: LearnOptionsWire,
     },
     LearnBatch {
+        namespace: Option<String>,
         contents: Vec<String>,
         options: LearnOptionsWire,
+        idempotency_key: Option<String>,
     },
     GetStats,
     Flush,
@@ -195,9 +207,17 @@ impl TcpStorageClient {
     /// Batch learn concepts using unified learning API
     /// Storage server handles: embedding generation + association extraction + storage
     pub async fn batch_learn_concepts(&mut self, concepts: Vec<Concept>) -> Result<Vec<String>> {
+        self.batch_learn(concepts, None).await
+    }
+
+    async fn batch_learn(
+        &mut self,
+        concepts: Vec<Concept>,
+        idempotency_key: Option<String>,
+    ) -> Result<Vec<String>> {
         if let Some(_client) = &self.client {
             // Real TCP storage communication with unified API
-            self.batch_learn_real_v2(concepts).await
+            self.batch_learn_real_v2(concepts, idempotency_key).await
         } else {
             // Mock mode for testing
             self.batch_learn_mock(concepts).await
@@ -205,7 +225,11 @@ impl TcpStorageClient {
     }
 
     /// Real TCP batch learning using unified API (v2)
-    async fn batch_learn_real_v2(&mut self, concepts: Vec<Concept>) -> Result<Vec<String>> {
+    async fn batch_learn_real_v2(
+        &mut self,
+        concepts: Vec<Concept>,
+        idempotency_key: Option<String>,
+    ) -> Result<Vec<String>> {
         info!(
             "Learning {} concepts via unified TCP API (embeddings + associations)",
             concepts.len()
@@ -224,8 +248,10 @@ impl TcpStorageClient {
 
         // Build request
         let request = StorageRequest::LearnBatch {
+            namespace: None,
             contents,
             options: options_wire,
+            idempotency_key,
         };
         let bytes = rmp_serde::to_vec(&request)?;
 
@@ -304,3 +330,17 @@ impl TcpStorageClient {
         }
     }
 }
+
+#[async_trait]
+impl ConceptSink for TcpStorageClient {
+    /// Sends `dedup_key` as the request's idempotency key, so the server
+    /// answers a replayed batch from its cache instead of learning it again
+    async fn learn_batch(
+        &mut self,
+        concepts: Vec<Concept>,
+        dedup_key: &str,
+    ) -> Result<Vec<String>> {
+        self.batch_learn(concepts, Some(dedup_key.to_string()))
+            .await
+    }
+}
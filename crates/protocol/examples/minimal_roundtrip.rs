@@ -1,7 +1,9 @@
 use std::error::Error;
 use tokio::net::TcpListener;
 
-use sutra_protocol::{recv_message, send_message, StorageMessage, StorageResponse};
+use sutra_protocol::{
+    recv_message, send_message, Client, ClientConfig, StorageMessage, StorageResponse,
+};
 
 fn main() -> Result<(), Box<dyn Error>> {
     let runtime = tokio::runtime::Builder::new_current_thread()
@@ -22,6 +24,7 @@ fn main() -> Result<(), Box<dyn Error>> {
                     healthy: true,
                     status: "ok".to_string(),
                     uptime_seconds: 1,
+                    max_message_size: 16 * 1024 * 1024,
                 },
             )
             .await?;
@@ -29,11 +32,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         });
 
         // Client side.
-        let mut client = tokio::net::TcpStream::connect(addr).await?;
-        send_message(&mut client, &StorageMessage::HealthCheck).await?;
-        let resp: StorageResponse = recv_message(&mut client).await?;
+        let mut client = Client::connect(ClientConfig::new(addr.to_string())).await?;
+        let healthy = client.health_check().await?;
 
-        println!("Received response: {:?}", resp);
+        println!("Server healthy: {}", healthy);
         server.await??;
         Ok::<(), Box<dyn Error>>(())
     })?;
@@ -0,0 +1,704 @@
+//! Async client for the storage protocol
+//!
+//! `Client` owns one connection to a storage server and exposes typed
+//! methods over `StorageMessage`/`StorageResponse`. A request that fails
+//! with an I/O error (connection reset, timeout) drops the connection and
+//! reconnects with exponential backoff. Idempotent requests are then sent
+//! again, up to `max_retries` times; anything else fails with the I/O error,
+//! since the first attempt may have been applied with only its response
+//! lost. The typed write methods attach a fresh idempotency key to every
+//! write, which the server uses to answer a repeat from its cache, so they
+//! are retried too. The server keeps keys for a limited time (ten minutes
+//! by default), far longer than a retry budget.
+//!
+//! With [`ClientConfig::with_handshake`] the client asks the server for its
+//! maximum message size on every connect. Requests larger than the known
+//! limit fail locally with `ProtocolError::MessageTooLarge` instead of being
+//! rejected by the server.
+//!
+//! With [`ClientConfig::with_version_handshake`] every connection starts with
+//! the version frame exchange of [`crate::handshake`], and requests are only
+//! sent once a version has been agreed; servers that predate it fail with
+//! `ProtocolError::VersionMismatch` instead of misreading newer messages.
+//!
+//! [`Client::subscribe`] asks the server to push change notifications on the
+//! client's connection. Notifications that arrive while waiting for a reply
+//! are queued and handed out by [`Client::next_notification`]; subscriptions
+//! are renewed after a reconnect, but changes made while disconnected are
+//! not replayed.
+//!
+//! `ClientPool` hands out clients for concurrent use.
+
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep, timeout};
+use tracing::{debug, warn};
+
+use crate::{
+    handshake, recv_message_with_limit, request_with_limit, request_with_options, ChangeKind,
+    ConceptMetadata, ConceptSummary, ProtocolError, Result, StorageMessage, StorageResponse,
+    VectorMatch, DEFAULT_MAX_MESSAGE_SIZE, MAX_MESSAGE_SIZE_CEILING, PROTOCOL_VERSION,
+};
+
+/// Connection and retry settings
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Server address (`host:port`)
+    pub addr: String,
+    /// Namespace for every request; the server's default if `None`
+    pub namespace: Option<String>,
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    /// Reconnect attempts per request before giving up
+    pub max_retries: u32,
+    /// Delay before the first reconnect; doubled on each further attempt
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Message size limit until the server advertises its own
+    pub max_message_size: u32,
+    /// Send a `Handshake` on connect to learn the server's limits
+    pub handshake: bool,
+    /// Exchange version frames on connect and refuse to talk without one
+    pub version_handshake: bool,
+    /// zstd-compress large requests (the storage server needs `false`)
+    pub compression: bool,
+}
+
+impl ClientConfig {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            namespace: None,
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(30),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            handshake: false,
+            version_handshake: false,
+            compression: false,
+        }
+    }
+
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    pub fn with_retries(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_message_size(mut self, bytes: u32) -> Self {
+        self.max_message_size = bytes.min(MAX_MESSAGE_SIZE_CEILING);
+        self
+    }
+
+    /// Learn the server's message size limit with a handshake on connect
+    pub fn with_handshake(mut self) -> Self {
+        self.handshake = true;
+        self
+    }
+
+    /// Require a protocol version handshake before any request
+    pub fn with_version_handshake(mut self) -> Self {
+        self.version_handshake = true;
+        self
+    }
+
+    /// Compress large requests, for peers built on this crate
+    pub fn with_compression(mut self) -> Self {
+        self.compression = true;
+        self
+    }
+}
+
+/// Concept returned by [`Client::query_concept`]
+#[derive(Debug, Clone)]
+pub struct ConceptRecord {
+    pub concept_id: String,
+    pub content: String,
+    pub strength: f32,
+    pub confidence: f32,
+    pub metadata: ConceptMetadata,
+}
+
+/// Change pushed by a subscription, from [`Client::next_notification`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConceptChange {
+    pub concept_id: String,
+    pub change_kind: ChangeKind,
+}
+
+/// Storage client over a single reconnecting TCP connection
+pub struct Client {
+    config: ClientConfig,
+    stream: Option<TcpStream>,
+    max_message_size: u32,
+    /// Version agreed on the current connection
+    protocol_version: Option<u32>,
+    /// Patterns to subscribe to again after a reconnect
+    subscriptions: Vec<String>,
+    /// Notifications received while waiting for replies
+    notifications: VecDeque<ConceptChange>,
+    /// Set while a frame is on the wire; still set if that future was dropped
+    /// mid-exchange, leaving the stream out of step
+    in_flight: bool,
+}
+
+impl Client {
+    /// Connect to `config.addr`, retrying with backoff
+    pub async fn connect(config: ClientConfig) -> Result<Self> {
+        let mut client = Self {
+            max_message_size: config.max_message_size,
+            config,
+            stream: None,
+            protocol_version: None,
+            subscriptions: Vec::new(),
+            notifications: VecDeque::new(),
+            in_flight: false,
+        };
+        client.reconnect().await?;
+        Ok(client)
+    }
+
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Largest message this client will send, as advertised by the server
+    /// when a handshake was made
+    pub fn max_message_size(&self) -> u32 {
+        self.max_message_size
+    }
+
+    /// Protocol version agreed with the server, if a version handshake was made
+    pub fn protocol_version(&self) -> Option<u32> {
+        self.protocol_version
+    }
+
+    /// Send a raw message, reconnecting on I/O errors and retrying if the
+    /// message is idempotent (see [`StorageMessage::is_idempotent`]).
+    /// `StorageResponse::Error` is returned as `ProtocolError::ServerError`.
+    pub async fn call(&mut self, message: &StorageMessage) -> Result<StorageResponse> {
+        let size = rmp_serde::to_vec_named(message)?.len();
+        if size > self.max_message_size as usize {
+            return Err(ProtocolError::MessageTooLarge(
+                size,
+                self.max_message_size as usize,
+            ));
+        }
+
+        self.drop_interrupted_stream();
+        let mut attempt = 0;
+        loop {
+            if self.stream.is_none() {
+                self.reconnect().await?;
+            }
+            if self.config.version_handshake && self.protocol_version.is_none() {
+                return Err(ProtocolError::VersionMismatch(0, PROTOCOL_VERSION));
+            }
+            let stream = self.stream.as_mut().expect("connected above");
+
+            self.in_flight = true;
+            let result = exchange(
+                stream,
+                message,
+                &self.config,
+                self.max_message_size,
+                &mut self.notifications,
+            )
+            .await;
+            self.in_flight = false;
+            match result {
+                Ok(StorageResponse::Error { message }) => {
+                    return Err(ProtocolError::ServerError(message))
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if attempt < self.config.max_retries && message.is_idempotent() => {
+                    warn!("Request to {} failed ({}), retrying", self.config.addr, e);
+                    self.stream = None;
+                    sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.stream = None;
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+
+    pub async fn learn_concept(
+        &mut self,
+        concept_id: impl Into<String>,
+        content: impl Into<String>,
+        embedding: Vec<f32>,
+        strength: f32,
+        confidence: f32,
+    ) -> Result<u64> {
+        let message = StorageMessage::LearnConcept {
+            namespace: self.config.namespace.clone(),
+            concept_id: concept_id.into(),
+            content: content.into(),
+            embedding,
+            strength,
+            confidence,
+            idempotency_key: Some(idempotency_key()),
+        };
+        match self.call(&message).await? {
+            StorageResponse::LearnConceptOk { sequence } => Ok(sequence),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn learn_association(
+        &mut self,
+        source_id: impl Into<String>,
+        target_id: impl Into<String>,
+        assoc_type: u32,
+        confidence: f32,
+    ) -> Result<u64> {
+        let message = StorageMessage::LearnAssociation {
+            namespace: self.config.namespace.clone(),
+            source_id: source_id.into(),
+            target_id: target_id.into(),
+            assoc_type,
+            confidence,
+            idempotency_key: Some(idempotency_key()),
+        };
+        match self.call(&message).await? {
+            StorageResponse::LearnAssociationOk { sequence } => Ok(sequence),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn query_concept(
+        &mut self,
+        concept_id: impl Into<String>,
+    ) -> Result<Option<ConceptRecord>> {
+        let message = StorageMessage::QueryConcept {
+            namespace: self.config.namespace.clone(),
+            concept_id: concept_id.into(),
+            include_vector: false,
+        };
+        match self.call(&message).await? {
+            StorageResponse::QueryConceptOk { found: false, .. } => Ok(None),
+            StorageResponse::QueryConceptOk {
+                concept_id,
+                content,
+                strength,
+                confidence,
+                attributes,
+                ..
+            } => Ok(Some(ConceptRecord {
+                concept_id,
+                content,
+                strength,
+                confidence,
+                metadata: attributes,
+            })),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn get_neighbors(&mut self, concept_id: impl Into<String>) -> Result<Vec<String>> {
+        let message = StorageMessage::GetNeighbors {
+            namespace: self.config.namespace.clone(),
+            concept_id: concept_id.into(),
+        };
+        match self.call(&message).await? {
+            StorageResponse::GetNeighborsOk { neighbor_ids } => Ok(neighbor_ids),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Shortest path between two concepts, if one exists within `max_depth`
+    pub async fn find_path(
+        &mut self,
+        start_id: impl Into<String>,
+        end_id: impl Into<String>,
+        max_depth: u32,
+    ) -> Result<Option<Vec<String>>> {
+        let message = StorageMessage::FindPath {
+            namespace: self.config.namespace.clone(),
+            start_id: start_id.into(),
+            end_id: end_id.into(),
+            max_depth,
+            deadline_ms: None,
+        };
+        match self.call(&message).await? {
+            StorageResponse::FindPathOk { found, path, .. } => Ok(found.then_some(path)),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn vector_search(
+        &mut self,
+        query_vector: Vec<f32>,
+        k: u32,
+        ef_search: u32,
+    ) -> Result<Vec<VectorMatch>> {
+        let message = StorageMessage::VectorSearch {
+            namespace: self.config.namespace.clone(),
+            query_vector,
+            k,
+            ef_search,
+            deadline_ms: None,
+        };
+        match self.call(&message).await? {
+            StorageResponse::VectorSearchOk { results, .. } => Ok(results
+                .into_iter()
+                .map(|(concept_id, similarity)| VectorMatch {
+                    concept_id,
+                    similarity,
+                })
+                .collect()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn query_by_metadata(
+        &mut self,
+        attributes: std::collections::HashMap<String, String>,
+        limit: u32,
+    ) -> Result<Vec<ConceptSummary>> {
+        let message = StorageMessage::QueryByMetadata {
+            namespace: self.config.namespace.clone(),
+            attributes,
+            limit,
+        };
+        match self.call(&message).await? {
+            StorageResponse::QueryByMetadataOk { concepts } => Ok(concepts),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        match self.call(&StorageMessage::Flush).await? {
+            StorageResponse::FlushOk => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn health_check(&mut self) -> Result<bool> {
+        match self.call(&StorageMessage::HealthCheck).await? {
+            StorageResponse::HealthCheckOk { healthy, .. } => Ok(healthy),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Receive change notifications for concept IDs matching `pattern`
+    /// (see [`crate::pattern_matches`])
+    pub async fn subscribe(&mut self, pattern: impl Into<String>) -> Result<String> {
+        let pattern = pattern.into();
+        let message = StorageMessage::Subscribe {
+            pattern: pattern.clone(),
+        };
+        match self.call(&message).await? {
+            StorageResponse::SubscribeOk { subscription_id } => {
+                if !self.subscriptions.contains(&pattern) {
+                    self.subscriptions.push(pattern);
+                }
+                Ok(subscription_id)
+            }
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Next pushed notification, waiting for one if none is queued
+    ///
+    /// Must not be called with a request in flight on this client.
+    pub async fn next_notification(&mut self) -> Result<ConceptChange> {
+        if let Some(change) = self.notifications.pop_front() {
+            return Ok(change);
+        }
+        self.drop_interrupted_stream();
+        if self.stream.is_none() {
+            self.reconnect().await?;
+        }
+        let stream = self.stream.as_mut().expect("connected above");
+        self.in_flight = true;
+        let result = recv_message_with_limit(stream, self.max_message_size).await;
+        self.in_flight = false;
+        match result {
+            Ok(StorageResponse::Notification {
+                concept_id,
+                change_kind,
+            }) => Ok(ConceptChange {
+                concept_id,
+                change_kind,
+            }),
+            Ok(other) => Err(unexpected(other)),
+            Err(e) => {
+                self.stream = None;
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn reconnect(&mut self) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match timeout(
+                self.config.connect_timeout,
+                TcpStream::connect(&self.config.addr),
+            )
+            .await
+            {
+                Ok(Ok(mut stream)) => {
+                    stream.set_nodelay(true)?;
+                    debug!("Connected to {}", self.config.addr);
+                    self.protocol_version = None;
+                    if self.config.version_handshake {
+                        let version = handshake(&mut stream).await?;
+                        debug!("{} speaks protocol v{}", self.config.addr, version);
+                        self.protocol_version = Some(version);
+                    }
+                    if self.config.handshake {
+                        self.handshake(&mut stream).await?;
+                    }
+                    self.resubscribe(&mut stream).await?;
+                    self.stream = Some(stream);
+                    return Ok(());
+                }
+                Ok(Err(e)) if attempt >= self.config.max_retries => return Err(e.into()),
+                Err(_) if attempt >= self.config.max_retries => return Err(ProtocolError::Timeout),
+                _ => {
+                    sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Agree on the protocol version and adopt the server's message size limit
+    ///
+    /// Servers that predate the handshake answer with an error; the
+    /// configured limit is kept for them.
+    async fn handshake(&mut self, stream: &mut TcpStream) -> Result<()> {
+        let message = StorageMessage::Handshake {
+            protocol_version: PROTOCOL_VERSION,
+        };
+        let response = request_with_limit(
+            stream,
+            &message,
+            self.config.request_timeout,
+            self.config.max_message_size,
+        )
+        .await?;
+        match response {
+            StorageResponse::HandshakeOk {
+                protocol_version,
+                max_message_size,
+            } => {
+                if protocol_version != PROTOCOL_VERSION {
+                    return Err(ProtocolError::VersionMismatch(
+                        protocol_version,
+                        PROTOCOL_VERSION,
+                    ));
+                }
+                self.max_message_size = max_message_size.clamp(1, MAX_MESSAGE_SIZE_CEILING);
+                debug!(
+                    "{} accepts messages up to {} bytes",
+                    self.config.addr, self.max_message_size
+                );
+                Ok(())
+            }
+            StorageResponse::Error { message } => {
+                debug!(
+                    "Handshake not supported by {}: {}",
+                    self.config.addr, message
+                );
+                Ok(())
+            }
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Renew this client's subscriptions on a new connection
+    async fn resubscribe(&mut self, stream: &mut TcpStream) -> Result<()> {
+        for pattern in &self.subscriptions {
+            let message = StorageMessage::Subscribe {
+                pattern: pattern.clone(),
+            };
+            match exchange(
+                stream,
+                &message,
+                &self.config,
+                self.max_message_size,
+                &mut self.notifications,
+            )
+            .await?
+            {
+                StorageResponse::SubscribeOk { .. } => {}
+                StorageResponse::Error { message } => {
+                    return Err(ProtocolError::ServerError(message))
+                }
+                other => return Err(unexpected(other)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a call was cancelled mid-exchange, leaving the stream unusable
+    fn is_interrupted(&self) -> bool {
+        self.in_flight
+    }
+
+    /// Close a stream a cancelled call left with a partial request or an
+    /// unread reply; the next call reconnects
+    fn drop_interrupted_stream(&mut self) {
+        if self.in_flight {
+            debug!("Dropping interrupted connection to {}", self.config.addr);
+            self.stream = None;
+            self.in_flight = false;
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.config
+            .initial_backoff
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.config.max_backoff)
+    }
+}
+
+/// Send `message` and return its reply, queueing notifications read first
+async fn exchange(
+    stream: &mut TcpStream,
+    message: &StorageMessage,
+    config: &ClientConfig,
+    max_message_size: u32,
+    notifications: &mut VecDeque<ConceptChange>,
+) -> io::Result<StorageResponse> {
+    let mut response = request_with_options(
+        stream,
+        message,
+        config.request_timeout,
+        max_message_size,
+        config.compression,
+    )
+    .await?;
+    while let StorageResponse::Notification {
+        concept_id,
+        change_kind,
+    } = response
+    {
+        notifications.push_back(ConceptChange {
+            concept_id,
+            change_kind,
+        });
+        response = timeout(
+            config.request_timeout,
+            recv_message_with_limit(stream, max_message_size),
+        )
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "Request timeout"))??;
+    }
+    Ok(response)
+}
+
+/// Key unique to one write: a per-process random prefix and a counter
+fn idempotency_key() -> String {
+    static PREFIX: std::sync::OnceLock<u64> = std::sync::OnceLock::new();
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let prefix = PREFIX.get_or_init(|| RandomState::new().build_hasher().finish());
+    format!(
+        "{:016x}-{}",
+        prefix,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn unexpected(response: StorageResponse) -> ProtocolError {
+    ProtocolError::ClientError(format!("Unexpected response: {:?}", response))
+}
+
+/// Fixed-size pool of clients for concurrent use
+///
+/// Connections are opened lazily and returned to the pool when the
+/// [`PooledClient`] guard is dropped.
+pub struct ClientPool {
+    config: ClientConfig,
+    idle: Arc<Mutex<Vec<Client>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl ClientPool {
+    pub fn new(config: ClientConfig, size: usize) -> Self {
+        Self {
+            config,
+            idle: Arc::new(Mutex::new(Vec::new())),
+            permits: Arc::new(Semaphore::new(size.max(1))),
+        }
+    }
+
+    /// Wait for a free slot and return a connected client
+    pub async fn get(&self) -> Result<PooledClient> {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .map_err(|_| ProtocolError::ConnectionClosed)?;
+
+        let idle = self.idle.lock().expect("pool lock poisoned").pop();
+        let client = match idle {
+            Some(client) => client,
+            None => Client::connect(self.config.clone()).await?,
+        };
+
+        Ok(PooledClient {
+            client: Some(client),
+            idle: Arc::clone(&self.idle),
+            _permit: permit,
+        })
+    }
+}
+
+/// Client checked out of a [`ClientPool`]
+pub struct PooledClient {
+    client: Option<Client>,
+    idle: Arc<Mutex<Vec<Client>>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().expect("client present until drop")
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        self.client.as_mut().expect("client present until drop")
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        // A client whose call was cancelled mid-exchange is not reused
+        if let Some(client) = self.client.take().filter(|c| !c.is_interrupted()) {
+            if let Ok(mut idle) = self.idle.lock() {
+                idle.push(client);
+            }
+        }
+    }
+}
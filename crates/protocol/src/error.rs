@@ -23,6 +23,9 @@ pub enum ProtocolError {
     #[error("Protocol version mismatch: got {0}, expected {1}")]
     VersionMismatch(u32, u32),
 
+    #[error("Handshake failed: {0}")]
+    HandshakeFailed(String),
+
     #[error("Server error: {0}")]
     ServerError(String),
 
@@ -35,8 +38,8 @@ pub enum ProtocolError {
 
 pub type Result<T> = std::result::Result<T, ProtocolError>;
 
-impl From<bincode::Error> for ProtocolError {
-    fn from(e: bincode::Error) -> Self {
+impl From<rmp_serde::encode::Error> for ProtocolError {
+    fn from(e: rmp_serde::encode::Error) -> Self {
         ProtocolError::Serialization(e.to_string())
     }
 }
@@ -1,29 +1,89 @@
 //! Sutra Custom Binary Protocol
 //!
-//! High-performance binary protocol for all internal Sutra communication.
-//! Replaces gRPC with 10-50× lower latency and 3-4× less bandwidth.
+//! Client side of the storage server's binary protocol. `StorageMessage`
+//! and `StorageResponse` mirror the subset of the server's
+//! `StorageRequest`/`StorageResponse` that this crate speaks, field for
+//! field, so they decode on either end.
 //!
 //! Message Format:
 //! ```text
-//! [4 bytes: message length][N bytes: bincode-serialized payload]
+//! [4 bytes: message length, big-endian][N bytes: MessagePack payload]
+//! ```
+//!
+//! Payloads are MessagePack with named fields, as the storage server reads
+//! and writes them. The top bit of the length marks a zstd-compressed
+//! payload; the remaining 31 bits are the length on the wire. Compression
+//! is opt-in (the `*_with_options` helpers, `ClientConfig::with_compression`):
+//! payloads over [`COMPRESSION_THRESHOLD`] bytes are then compressed if that
+//! makes them smaller. The storage server does not read compressed frames,
+//! so only peers built on this crate should enable it.
+//! Size limits always apply to the uncompressed payload.
+//!
+//! Each request gets exactly one reply, in request order. A `Handshake`
+//! request negotiates the protocol version and returns the server's request
+//! size limit.
+//!
+//! After a `Subscribe` the server may also push
+//! [`StorageResponse::Notification`] frames at any point between replies.
+//! Notifications are never replies, so a client tells them apart by variant
+//! alone: every other frame answers the oldest outstanding request.
+//!
+//! Peers may open a connection with [`handshake`], which exchanges a fixed
+//! frame before any message:
+//! ```text
+//! [8 bytes: b"SUTRAPRO"][4 bytes: protocol version, little-endian]
 //! ```
 
+pub mod client;
 pub mod error;
 
 use serde::{Deserialize, Serialize};
 use std::io::{self, ErrorKind};
 use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
 
+pub use client::{Client, ClientConfig, ClientPool, ConceptChange, ConceptRecord, PooledClient};
 pub use error::{ProtocolError, Result};
 
 /// Protocol version for compatibility checking
 pub const PROTOCOL_VERSION: u32 = 1;
 
-/// Maximum message size (16MB) - prevents DoS
-const MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+/// Oldest protocol version this build can still speak
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Magic bytes opening the version handshake frame
+pub const HANDSHAKE_MAGIC: [u8; 8] = *b"SUTRAPRO";
+
+/// How long [`handshake`] waits for the peer's frame
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Default maximum message size (16MB) - prevents DoS
+pub const DEFAULT_MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Largest maximum message size a server may configure or advertise (1GB)
+pub const MAX_MESSAGE_SIZE_CEILING: u32 = 1024 * 1024 * 1024;
+
+/// Length-prefix bit marking a zstd-compressed payload
+pub const COMPRESSED_FLAG: u32 = 1 << 31;
+
+/// Serialized payloads larger than this are sent compressed
+pub const COMPRESSION_THRESHOLD: usize = 512;
+
+/// zstd level for compressed payloads (favours speed)
+const COMPRESSION_LEVEL: i32 = 1;
+
+/// Check a configured maximum message size against [`MAX_MESSAGE_SIZE_CEILING`]
+pub fn validate_max_message_size(bytes: u32) -> Result<u32> {
+    if bytes == 0 || bytes > MAX_MESSAGE_SIZE_CEILING {
+        return Err(ProtocolError::ValidationError(format!(
+            "max message size must be between 1 and {} bytes, got {}",
+            MAX_MESSAGE_SIZE_CEILING, bytes
+        )));
+    }
+    Ok(bytes)
+}
 
 // ============================================================================
 // Core Data Types
@@ -39,45 +99,102 @@ pub type ConceptMetadata = std::collections::HashMap<String, String>;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StorageMessage {
     LearnConcept {
+        /// Server's default namespace if `None`
+        namespace: Option<String>,
         concept_id: String,
         content: String,
         embedding: Vec<f32>,
         strength: f32,
         confidence: f32,
-        /// Optional metadata for concept classification
-        metadata: Option<ConceptMetadata>,
+        /// Retry-safe key: the server answers a repeat from its cache
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
     LearnAssociation {
+        namespace: Option<String>,
         source_id: String,
         target_id: String,
         assoc_type: u32,
         confidence: f32,
+        /// Retry-safe key: the server answers a repeat from its cache
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
     QueryConcept {
+        namespace: Option<String>,
         concept_id: String,
+        /// Also return the stored embedding
+        #[serde(default)]
+        include_vector: bool,
     },
     GetNeighbors {
+        namespace: Option<String>,
         concept_id: String,
     },
     FindPath {
+        namespace: Option<String>,
         start_id: String,
         end_id: String,
         max_depth: u32,
+        #[serde(default)]
+        deadline_ms: Option<u64>,
     },
     VectorSearch {
+        namespace: Option<String>,
         query_vector: Vec<f32>,
         k: u32,
         ef_search: u32,
+        #[serde(default)]
+        deadline_ms: Option<u64>,
     },
-    /// Query concepts by metadata filters
+    /// Concepts whose attributes match every `key = value` pair
     QueryByMetadata {
-        tags: Vec<String>,
+        namespace: Option<String>,
         attributes: std::collections::HashMap<String, String>,
         limit: u32,
     },
-    GetStats,
+    GetStats {
+        namespace: Option<String>,
+    },
     Flush,
     HealthCheck,
+    /// Exchange protocol version and limits (answered with `HandshakeOk`)
+    Handshake {
+        protocol_version: u32,
+    },
+    /// Push a `Notification` on this connection for every change to a
+    /// concept whose ID matches `pattern` (see [`pattern_matches`]), in any
+    /// namespace. Sent as the server's `SubscribeChanges`, which is distinct
+    /// from its callback-based autonomy `Subscribe`.
+    #[serde(rename = "SubscribeChanges")]
+    Subscribe {
+        pattern: String,
+    },
+}
+
+impl StorageMessage {
+    /// Whether sending this message twice has the same effect as sending it
+    /// once: reads, and writes that carry an idempotency key
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            StorageMessage::LearnConcept {
+                idempotency_key, ..
+            }
+            | StorageMessage::LearnAssociation {
+                idempotency_key, ..
+            } => idempotency_key.is_some(),
+            StorageMessage::Subscribe { .. } => false,
+            StorageMessage::QueryConcept { .. }
+            | StorageMessage::GetNeighbors { .. }
+            | StorageMessage::FindPath { .. }
+            | StorageMessage::VectorSearch { .. }
+            | StorageMessage::QueryByMetadata { .. }
+            | StorageMessage::GetStats { .. }
+            | StorageMessage::Flush
+            | StorageMessage::HealthCheck
+            | StorageMessage::Handshake { .. } => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,7 +211,10 @@ pub enum StorageResponse {
         content: String,
         strength: f32,
         confidence: f32,
-        metadata: Option<ConceptMetadata>,
+        #[serde(default)]
+        attributes: ConceptMetadata,
+        #[serde(default)]
+        vector: Option<Vec<f32>>,
     },
     GetNeighborsOk {
         neighbor_ids: Vec<String>,
@@ -102,9 +222,14 @@ pub enum StorageResponse {
     FindPathOk {
         found: bool,
         path: Vec<String>,
+        #[serde(default)]
+        deadline_exceeded: bool,
     },
     VectorSearchOk {
-        results: Vec<VectorMatch>,
+        /// `(concept_id, similarity)`, best first
+        results: Vec<(String, f32)>,
+        #[serde(default)]
+        deadline_exceeded: bool,
     },
     QueryByMetadataOk {
         concepts: Vec<ConceptSummary>,
@@ -123,13 +248,57 @@ pub enum StorageResponse {
         healthy: bool,
         status: String,
         uptime_seconds: u64,
+        /// Largest request frame the server accepts, in bytes
+        #[serde(default)]
+        max_message_size: u64,
     },
     Error {
         message: String,
     },
+    HandshakeOk {
+        protocol_version: u32,
+        /// Largest message the server accepts, in bytes
+        max_message_size: u32,
+    },
+    SubscribeOk {
+        subscription_id: String,
+    },
+    /// Unsolicited change event for a subscribed pattern (never a reply)
+    Notification {
+        concept_id: String,
+        change_kind: ChangeKind,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl StorageResponse {
+    /// Whether this frame was pushed by a subscription rather than
+    /// answering a request
+    pub fn is_notification(&self) -> bool {
+        matches!(self, StorageResponse::Notification { .. })
+    }
+}
+
+/// What happened to a concept, in a `Notification`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeKind {
+    /// Concept learned or overwritten
+    Learned,
+    /// Association added from this concept
+    Associated,
+}
+
+/// Whether `concept_id` matches a `Subscribe` pattern
+///
+/// `*` matches every concept, a trailing `*` matches by prefix, and any
+/// other pattern must equal the ID.
+pub fn pattern_matches(pattern: &str, concept_id: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => concept_id.starts_with(prefix),
+        None => pattern == concept_id,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VectorMatch {
     pub concept_id: String,
     pub similarity: f32,
@@ -138,11 +307,12 @@ pub struct VectorMatch {
 /// Concept summary for metadata queries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConceptSummary {
+    #[serde(rename = "id")]
     pub concept_id: String,
     pub content_preview: String, // First 200 chars
-    pub metadata: ConceptMetadata,
     pub created: u64,
-    pub last_accessed: u64,
+    #[serde(rename = "attributes")]
+    pub metadata: ConceptMetadata,
 }
 
 // ============================================================================
@@ -150,38 +320,102 @@ pub struct ConceptSummary {
 // ============================================================================
 
 /// Send a message over TCP with length prefix
-pub async fn send_message<T: Serialize>(stream: &mut TcpStream, message: &T) -> io::Result<()> {
+///
+/// The send and receive helpers work on any async stream, so a subscribed
+/// connection can be split into read and write halves.
+pub async fn send_message<T: Serialize>(
+    stream: &mut (impl AsyncWrite + Unpin),
+    message: &T,
+) -> io::Result<()> {
+    send_message_with_limit(stream, message, DEFAULT_MAX_MESSAGE_SIZE).await
+}
+
+/// Send a message uncompressed, refusing payloads larger than `max_message_size`
+pub async fn send_message_with_limit<T: Serialize>(
+    stream: &mut (impl AsyncWrite + Unpin),
+    message: &T,
+    max_message_size: u32,
+) -> io::Result<()> {
+    send_message_with_options(stream, message, max_message_size, false).await
+}
+
+/// Send a message, compressing large payloads only if `compress` is set
+///
+/// The storage server and peers that predate compression need
+/// `compress: false`.
+pub async fn send_message_with_options<T: Serialize>(
+    stream: &mut (impl AsyncWrite + Unpin),
+    message: &T,
+    max_message_size: u32,
+    compress: bool,
+) -> io::Result<()> {
+    write_frame(stream, message, max_message_size, compress).await?;
+
+    // Ensure data is sent
+    stream.flush().await
+}
+
+/// Write one length-prefixed frame without flushing
+async fn write_frame<W: AsyncWrite + Unpin, T: Serialize>(
+    stream: &mut W,
+    message: &T,
+    max_message_size: u32,
+    compress: bool,
+) -> io::Result<()> {
     // Serialize message
-    let bytes =
-        bincode::serialize(message).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    let mut bytes = encode(message)?;
 
-    // Check size limit
-    if bytes.len() > MAX_MESSAGE_SIZE as usize {
+    // Check size limit (uncompressed)
+    if bytes.len() > max_message_size as usize {
         return Err(io::Error::new(
             ErrorKind::InvalidData,
             format!("Message too large: {} bytes", bytes.len()),
         ));
     }
 
+    let mut prefix = bytes.len() as u32;
+    if compress && bytes.len() > COMPRESSION_THRESHOLD {
+        let compressed = zstd::bulk::compress(&bytes, COMPRESSION_LEVEL)?;
+        if compressed.len() < bytes.len() {
+            prefix = compressed.len() as u32 | COMPRESSED_FLAG;
+            bytes = compressed;
+        }
+    }
+
     // Send length prefix (4 bytes, big-endian)
-    stream.write_u32(bytes.len() as u32).await?;
+    stream.write_u32(prefix).await?;
 
     // Send payload
-    stream.write_all(&bytes).await?;
+    stream.write_all(&bytes).await
+}
 
-    // Ensure data is sent
-    stream.flush().await?;
+/// Receive a message from TCP with length prefix
+pub async fn recv_message<T: for<'de> Deserialize<'de>>(
+    stream: &mut (impl AsyncRead + Unpin),
+) -> io::Result<T> {
+    recv_message_with_limit(stream, DEFAULT_MAX_MESSAGE_SIZE).await
+}
 
-    Ok(())
+/// Receive a message, refusing payloads larger than `max_message_size`
+pub async fn recv_message_with_limit<T: for<'de> Deserialize<'de>>(
+    stream: &mut (impl AsyncRead + Unpin),
+    max_message_size: u32,
+) -> io::Result<T> {
+    read_frame(stream, max_message_size).await
 }
 
-/// Receive a message from TCP with length prefix
-pub async fn recv_message<T: for<'de> Deserialize<'de>>(stream: &mut TcpStream) -> io::Result<T> {
+/// Read and decode one length-prefixed frame
+async fn read_frame<R: AsyncRead + Unpin, T: for<'de> Deserialize<'de>>(
+    stream: &mut R,
+    max_message_size: u32,
+) -> io::Result<T> {
     // Read length prefix
-    let len = stream.read_u32().await?;
+    let prefix = stream.read_u32().await?;
+    let compressed = prefix & COMPRESSED_FLAG != 0;
+    let len = prefix & !COMPRESSED_FLAG;
 
     // Check size limit
-    if len > MAX_MESSAGE_SIZE {
+    if len > max_message_size {
         return Err(io::Error::new(
             ErrorKind::InvalidData,
             format!("Message too large: {} bytes", len),
@@ -192,8 +426,76 @@ pub async fn recv_message<T: for<'de> Deserialize<'de>>(stream: &mut TcpStream)
     let mut buf = vec![0u8; len as usize];
     stream.read_exact(&mut buf).await?;
 
+    // Decompress, never past the limit
+    if compressed {
+        buf = zstd::bulk::decompress(&buf, max_message_size as usize).map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Message too large or corrupt compressed payload: {}", e),
+            )
+        })?;
+    }
+
     // Deserialize
-    bincode::deserialize(&buf).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    rmp_serde::from_slice(&buf).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// MessagePack payload of `message`, with named fields as the server expects
+pub fn encode<T: Serialize>(message: &T) -> io::Result<Vec<u8>> {
+    rmp_serde::to_vec_named(message).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Exchange version frames with the peer and return the version both speak
+///
+/// Both sides send their frame first, so the same call works on either end
+/// of the connection. A peer that predates the handshake never sends a
+/// frame (or hangs up on ours); that is reported as
+/// `VersionMismatch(0, PROTOCOL_VERSION)` after [`HANDSHAKE_TIMEOUT`]
+/// instead of blocking forever.
+pub async fn handshake(stream: &mut TcpStream) -> Result<u32> {
+    handshake_with_timeout(stream, HANDSHAKE_TIMEOUT).await
+}
+
+/// [`handshake`] with a custom wait for the peer's frame
+pub async fn handshake_with_timeout(stream: &mut TcpStream, read_timeout: Duration) -> Result<u32> {
+    let mut frame = [0u8; 12];
+    frame[..8].copy_from_slice(&HANDSHAKE_MAGIC);
+    frame[8..].copy_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+    stream.write_all(&frame).await?;
+    stream.flush().await?;
+
+    let mut peer = [0u8; 12];
+    match timeout(read_timeout, stream.read_exact(&mut peer)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e))
+            if matches!(
+                e.kind(),
+                ErrorKind::UnexpectedEof
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+            ) =>
+        {
+            return Err(ProtocolError::VersionMismatch(0, PROTOCOL_VERSION))
+        }
+        Ok(Err(e)) => return Err(e.into()),
+        Err(_) => return Err(ProtocolError::VersionMismatch(0, PROTOCOL_VERSION)),
+    }
+
+    if peer[..8] != HANDSHAKE_MAGIC {
+        return Err(ProtocolError::HandshakeFailed(format!(
+            "unexpected magic bytes {:?}",
+            String::from_utf8_lossy(&peer[..8])
+        )));
+    }
+    let peer_version = u32::from_le_bytes(peer[8..].try_into().expect("4-byte slice"));
+    let version = peer_version.min(PROTOCOL_VERSION);
+    if version < MIN_PROTOCOL_VERSION {
+        return Err(ProtocolError::VersionMismatch(
+            peer_version,
+            PROTOCOL_VERSION,
+        ));
+    }
+    Ok(version)
 }
 
 /// Helper for request-response pattern
@@ -210,15 +512,102 @@ pub async fn request_with_timeout<Req: Serialize, Resp: for<'de> Deserialize<'de
     stream: &mut TcpStream,
     request: &Req,
     timeout_duration: Duration,
+) -> io::Result<Resp> {
+    request_with_limit(stream, request, timeout_duration, DEFAULT_MAX_MESSAGE_SIZE).await
+}
+
+/// Helper for request-response with timeout and a message size limit
+pub async fn request_with_limit<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+    stream: &mut TcpStream,
+    request: &Req,
+    timeout_duration: Duration,
+    max_message_size: u32,
+) -> io::Result<Resp> {
+    request_with_options(stream, request, timeout_duration, max_message_size, false).await
+}
+
+/// [`request_with_limit`], compressing large requests only if `compress` is set
+pub async fn request_with_options<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+    stream: &mut TcpStream,
+    request: &Req,
+    timeout_duration: Duration,
+    max_message_size: u32,
+    compress: bool,
 ) -> io::Result<Resp> {
     timeout(timeout_duration, async {
-        send_message(stream, request).await?;
-        recv_message(stream).await
+        send_message_with_options(stream, request, max_message_size, compress).await?;
+        recv_message_with_limit(stream, max_message_size).await
     })
     .await
     .map_err(|_| io::Error::new(ErrorKind::TimedOut, "Request timeout"))?
 }
 
+/// Read frames until the peer closes the connection
+///
+/// Hands every decoded frame to `on_frame`, stopping early when it returns
+/// false. Meant for subscribed connections, where notifications arrive
+/// without a request.
+pub async fn recv_stream<R, T, F>(stream: &mut R, mut on_frame: F) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    T: for<'de> Deserialize<'de>,
+    F: FnMut(T) -> bool,
+{
+    loop {
+        match read_frame(stream, DEFAULT_MAX_MESSAGE_SIZE).await {
+            Ok(frame) => {
+                if !on_frame(frame) {
+                    return Ok(());
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Send all `requests` back-to-back and read their responses in order
+///
+/// Saves a round trip per request; the server must answer requests on a
+/// connection sequentially, as the storage server does. Responses are read
+/// while requests are still being written, so large batches cannot stall on
+/// full socket buffers.
+pub async fn request_batch<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+    stream: &mut TcpStream,
+    requests: &[Req],
+) -> io::Result<Vec<Resp>> {
+    request_batch_with_limit(stream, requests, DEFAULT_MAX_MESSAGE_SIZE, false).await
+}
+
+/// [`request_batch`] with a message size limit and compression setting
+pub async fn request_batch_with_limit<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+    stream: &mut TcpStream,
+    requests: &[Req],
+    max_message_size: u32,
+    compress: bool,
+) -> io::Result<Vec<Resp>> {
+    let (mut reader, writer) = stream.split();
+    let mut writer = tokio::io::BufWriter::new(writer);
+
+    let send = async {
+        for request in requests {
+            write_frame(&mut writer, request, max_message_size, compress).await?;
+        }
+        writer.flush().await
+    };
+    let receive = async {
+        let mut responses = Vec::with_capacity(requests.len());
+        for _ in 0..requests.len() {
+            responses.push(read_frame::<_, Resp>(&mut reader, max_message_size).await?);
+        }
+        Ok::<_, io::Error>(responses)
+    };
+
+    // A failed write abandons the read side rather than waiting forever
+    let ((), responses) = tokio::try_join!(send, receive)?;
+    Ok(responses)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -248,12 +637,13 @@ mod tests {
         // Client
         let mut client = TcpStream::connect(addr).await.unwrap();
         let req = StorageMessage::LearnConcept {
+            namespace: None,
             concept_id: "test".to_string(),
             content: "content".to_string(),
             embedding: vec![0.1, 0.2, 0.3],
             strength: 1.0,
             confidence: 0.9,
-            metadata: None,
+            idempotency_key: None,
         };
 
         send_message(&mut client, &req).await.unwrap();
@@ -265,20 +655,183 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_handshake_negotiates_version() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            handshake(&mut socket).await
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        assert_eq!(handshake(&mut client).await.unwrap(), PROTOCOL_VERSION);
+        assert_eq!(server.await.unwrap().unwrap(), PROTOCOL_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_handshake_detects_silent_and_foreign_peers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            // Predates the handshake: waits for a length-prefixed message
+            let (silent, _) = listener.accept().await.unwrap();
+            // Speaks something else entirely
+            let (mut foreign, _) = listener.accept().await.unwrap();
+            foreign.write_all(b"HTTP/1.1 400").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(silent);
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let started = std::time::Instant::now();
+        let result = handshake_with_timeout(&mut client, Duration::from_millis(100)).await;
+        assert!(matches!(
+            result,
+            Err(ProtocolError::VersionMismatch(0, PROTOCOL_VERSION))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let result = handshake_with_timeout(&mut client, Duration::from_secs(2)).await;
+        assert!(matches!(result, Err(ProtocolError::HandshakeFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_large_payload_is_compressed_on_the_wire() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // 10KB embedding, as a 2560-dim model would produce
+        let embedding: Vec<f32> = (0..2560).map(|i| (i % 16) as f32 / 16.0).collect();
+        let req = StorageMessage::LearnConcept {
+            namespace: None,
+            concept_id: "big".to_string(),
+            content: "content".to_string(),
+            embedding: embedding.clone(),
+            strength: 1.0,
+            confidence: 0.9,
+            idempotency_key: None,
+        };
+        let uncompressed = encode(&req).unwrap().len();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let decoded: StorageMessage = recv_message(&mut socket).await.unwrap();
+
+            // Second copy read raw to see what crossed the wire
+            let prefix = socket.read_u32().await.unwrap();
+            (decoded, prefix)
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        send_message_with_options(&mut client, &req, DEFAULT_MAX_MESSAGE_SIZE, true)
+            .await
+            .unwrap();
+        send_message_with_options(&mut client, &req, DEFAULT_MAX_MESSAGE_SIZE, true)
+            .await
+            .unwrap();
+
+        let (decoded, prefix) = server.await.unwrap();
+        match decoded {
+            StorageMessage::LearnConcept { embedding: e, .. } => assert_eq!(e, embedding),
+            _ => panic!("Unexpected message type"),
+        }
+        assert_ne!(prefix & COMPRESSED_FLAG, 0);
+        assert!(((prefix & !COMPRESSED_FLAG) as usize) < uncompressed / 2);
+    }
+
+    #[tokio::test]
+    async fn test_default_helpers_send_uncompressed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // A 1536-dim embedding, well over the compression threshold
+        let req = StorageMessage::LearnConcept {
+            namespace: None,
+            concept_id: "big".to_string(),
+            content: "content".to_string(),
+            embedding: vec![0.5; 1536],
+            strength: 1.0,
+            confidence: 0.9,
+            idempotency_key: None,
+        };
+        let uncompressed = encode(&req).unwrap().len();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut prefixes = Vec::new();
+            for _ in 0..2 {
+                let prefix = socket.read_u32().await.unwrap();
+                let mut payload = vec![0u8; prefix as usize];
+                socket.read_exact(&mut payload).await.unwrap();
+                prefixes.push(prefix);
+            }
+            prefixes
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        send_message(&mut client, &req).await.unwrap();
+        send_message_with_limit(&mut client, &req, DEFAULT_MAX_MESSAGE_SIZE)
+            .await
+            .unwrap();
+
+        assert_eq!(server.await.unwrap(), vec![uncompressed as u32; 2]);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_payload_checked_against_uncompressed_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            recv_message_with_limit::<StorageMessage>(&mut socket, 4096).await
+        });
+
+        // Compresses far below 4KB, but is 40KB once expanded
+        let req = StorageMessage::VectorSearch {
+            namespace: None,
+            query_vector: vec![0.0; 10_000],
+            k: 10,
+            ef_search: 50,
+            deadline_ms: None,
+        };
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        send_message_with_options(&mut client, &req, DEFAULT_MAX_MESSAGE_SIZE, true)
+            .await
+            .unwrap();
+
+        let err = server.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("too large"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("*", "anything"));
+        assert!(pattern_matches("user:*", "user:42"));
+        assert!(!pattern_matches("user:*", "team:42"));
+        assert!(pattern_matches("user:42", "user:42"));
+        assert!(!pattern_matches("user:4", "user:42"));
+    }
+
     #[test]
     fn test_message_size() {
         let msg = StorageMessage::LearnConcept {
+            namespace: None,
             concept_id: "test".to_string(),
             content: "content".to_string(),
             embedding: vec![],
             strength: 1.0,
             confidence: 0.9,
-            metadata: None,
+            idempotency_key: None,
         };
 
-        let bytes = bincode::serialize(&msg).unwrap();
-        println!("StorageMessage size: {} bytes", bytes.len());
-        assert!(bytes.len() < 100); // Should be tiny
+        // Field names are on the wire, but the payload stays small
+        let bytes = encode(&msg).unwrap();
+        assert!(bytes.len() < 160, "{} bytes", bytes.len());
     }
 
     #[test]
@@ -288,25 +841,25 @@ mod tests {
             ("origin".to_string(), "unit-test".to_string()),
         ]);
 
-        let msg = StorageMessage::LearnConcept {
+        let response = StorageResponse::QueryConceptOk {
+            found: true,
             concept_id: "msg-001".to_string(),
             content: "Hello world".to_string(),
-            embedding: vec![0.1, 0.2, 0.3],
             strength: 1.0,
             confidence: 0.95,
-            metadata: Some(metadata),
+            attributes: metadata,
+            vector: None,
         };
 
         // Serialize and check size
-        let bytes = bincode::serialize(&msg).unwrap();
+        let bytes = encode(&response).unwrap();
         assert!(bytes.len() < 1024); // Should be reasonable
 
         // Deserialize and verify
-        let decoded: StorageMessage = bincode::deserialize(&bytes).unwrap();
+        let decoded: StorageResponse = rmp_serde::from_slice(&bytes).unwrap();
         match decoded {
-            StorageMessage::LearnConcept {
-                metadata: Some(meta),
-                ..
+            StorageResponse::QueryConceptOk {
+                attributes: meta, ..
             } => {
                 assert_eq!(meta.get("type"), Some(&"message".to_string()));
                 assert_eq!(meta.get("origin"), Some(&"unit-test".to_string()));
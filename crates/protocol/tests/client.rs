@@ -0,0 +1,613 @@
+//! Drives an in-process storage server through `Client` and `ClientPool`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+
+use sutra_protocol::{
+    handshake, pattern_matches, recv_message, recv_message_with_limit, recv_stream, request_batch,
+    send_message, ChangeKind, Client, ClientConfig, ClientPool, ConceptChange, ProtocolError,
+    StorageMessage, StorageResponse, DEFAULT_MAX_MESSAGE_SIZE, PROTOCOL_VERSION,
+};
+
+#[derive(Default)]
+struct MemoryStore {
+    concepts: Mutex<HashMap<String, (String, Vec<f32>)>>,
+    edges: Mutex<HashMap<String, Vec<String>>>,
+    sequence: AtomicU64,
+}
+
+impl MemoryStore {
+    fn handle(&self, message: StorageMessage) -> StorageResponse {
+        match message {
+            StorageMessage::LearnConcept {
+                concept_id,
+                content,
+                embedding,
+                ..
+            } => {
+                self.concepts
+                    .lock()
+                    .unwrap()
+                    .insert(concept_id, (content, embedding));
+                StorageResponse::LearnConceptOk {
+                    sequence: self.sequence.fetch_add(1, Ordering::SeqCst),
+                }
+            }
+            StorageMessage::LearnAssociation {
+                source_id,
+                target_id,
+                ..
+            } => {
+                self.edges
+                    .lock()
+                    .unwrap()
+                    .entry(source_id)
+                    .or_default()
+                    .push(target_id);
+                StorageResponse::LearnAssociationOk {
+                    sequence: self.sequence.fetch_add(1, Ordering::SeqCst),
+                }
+            }
+            StorageMessage::QueryConcept { concept_id, .. } => {
+                match self.concepts.lock().unwrap().get(&concept_id) {
+                    Some((content, _)) => StorageResponse::QueryConceptOk {
+                        found: true,
+                        concept_id,
+                        content: content.clone(),
+                        strength: 1.0,
+                        confidence: 1.0,
+                        attributes: HashMap::new(),
+                        vector: None,
+                    },
+                    None => StorageResponse::QueryConceptOk {
+                        found: false,
+                        concept_id,
+                        content: String::new(),
+                        strength: 0.0,
+                        confidence: 0.0,
+                        attributes: HashMap::new(),
+                        vector: None,
+                    },
+                }
+            }
+            StorageMessage::GetNeighbors { concept_id, .. } => StorageResponse::GetNeighborsOk {
+                neighbor_ids: self
+                    .edges
+                    .lock()
+                    .unwrap()
+                    .get(&concept_id)
+                    .cloned()
+                    .unwrap_or_default(),
+            },
+            StorageMessage::VectorSearch {
+                query_vector, k, ..
+            } => {
+                let mut results: Vec<(String, f32)> = self
+                    .concepts
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(id, (_, v))| {
+                        let similarity = v.iter().zip(&query_vector).map(|(a, b)| a * b).sum();
+                        (id.clone(), similarity)
+                    })
+                    .collect();
+                results.sort_by(|a, b| b.1.total_cmp(&a.1));
+                results.truncate(k as usize);
+                StorageResponse::VectorSearchOk {
+                    results,
+                    deadline_exceeded: false,
+                }
+            }
+            StorageMessage::HealthCheck => StorageResponse::HealthCheckOk {
+                healthy: true,
+                status: "ok".to_string(),
+                uptime_seconds: 0,
+                max_message_size: 0,
+            },
+            other => StorageResponse::Error {
+                message: format!("unsupported: {:?}", other),
+            },
+        }
+    }
+}
+
+/// Serve `store`; each connection is closed after `requests_per_conn` requests
+async fn spawn_server(store: Arc<MemoryStore>, requests_per_conn: usize) -> String {
+    spawn_server_with_limit(store, requests_per_conn, DEFAULT_MAX_MESSAGE_SIZE).await
+}
+
+/// Like `spawn_server`, accepting messages up to `max_message_size` bytes
+async fn spawn_server_with_limit(
+    store: Arc<MemoryStore>,
+    requests_per_conn: usize,
+    max_message_size: u32,
+) -> String {
+    spawn_server_with(store, requests_per_conn, max_message_size, false).await
+}
+
+/// Like `spawn_server_with_limit`, optionally expecting a version handshake
+async fn spawn_server_with(
+    store: Arc<MemoryStore>,
+    requests_per_conn: usize,
+    max_message_size: u32,
+    version_handshake: bool,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                if version_handshake && handshake(&mut socket).await.is_err() {
+                    return;
+                }
+                for _ in 0..requests_per_conn {
+                    let message: StorageMessage =
+                        match recv_message_with_limit(&mut socket, max_message_size).await {
+                            Ok(message) => message,
+                            Err(_) => return,
+                        };
+                    let response = match message {
+                        StorageMessage::Handshake { .. } => StorageResponse::HandshakeOk {
+                            protocol_version: PROTOCOL_VERSION,
+                            max_message_size,
+                        },
+                        message => store.handle(message),
+                    };
+                    if send_message(&mut socket, &response).await.is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    addr
+}
+
+/// Serve `store` with subscriptions: every learn is pushed to the
+/// connections subscribed to a matching pattern
+async fn spawn_streaming_server(store: Arc<MemoryStore>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let (events, _) = broadcast::channel::<String>(1024);
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            let store = Arc::clone(&store);
+            let events = events.clone();
+            tokio::spawn(async move {
+                let (mut reader, mut writer) = socket.into_split();
+                let (requests_tx, mut requests) = mpsc::unbounded_channel::<StorageMessage>();
+                tokio::spawn(async move {
+                    let _ =
+                        recv_stream(&mut reader, |message| requests_tx.send(message).is_ok()).await;
+                });
+
+                let mut changes = events.subscribe();
+                let mut patterns: Vec<String> = Vec::new();
+                loop {
+                    let response = tokio::select! {
+                        message = requests.recv() => match message {
+                            Some(StorageMessage::Subscribe { pattern }) => {
+                                patterns.push(pattern);
+                                StorageResponse::SubscribeOk {
+                                    subscription_id: patterns.len().to_string(),
+                                }
+                            }
+                            Some(message) => {
+                                let learned = match &message {
+                                    StorageMessage::LearnConcept { concept_id, .. } => {
+                                        Some(concept_id.clone())
+                                    }
+                                    _ => None,
+                                };
+                                let response = store.handle(message);
+                                if let Some(concept_id) = learned {
+                                    let _ = events.send(concept_id);
+                                }
+                                response
+                            }
+                            None => return,
+                        },
+                        Ok(concept_id) = changes.recv() => {
+                            if !patterns.iter().any(|p| pattern_matches(p, &concept_id)) {
+                                continue;
+                            }
+                            StorageResponse::Notification {
+                                concept_id,
+                                change_kind: ChangeKind::Learned,
+                            }
+                        }
+                    };
+                    if send_message(&mut writer, &response).await.is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_client_typed_roundtrip() {
+    let store = Arc::new(MemoryStore::default());
+    let addr = spawn_server(Arc::clone(&store), usize::MAX).await;
+    let mut client = Client::connect(ClientConfig::new(addr)).await.unwrap();
+
+    assert!(client.health_check().await.unwrap());
+    client
+        .learn_concept("a", "alpha", vec![1.0, 0.0], 1.0, 0.9)
+        .await
+        .unwrap();
+    client
+        .learn_concept("b", "beta", vec![0.0, 1.0], 1.0, 0.9)
+        .await
+        .unwrap();
+    client.learn_association("a", "b", 0, 0.8).await.unwrap();
+
+    let concept = client.query_concept("a").await.unwrap().unwrap();
+    assert_eq!(concept.content, "alpha");
+    assert!(client.query_concept("missing").await.unwrap().is_none());
+    assert_eq!(client.get_neighbors("a").await.unwrap(), vec!["b"]);
+
+    let results = client.vector_search(vec![0.0, 1.0], 1, 16).await.unwrap();
+    assert_eq!(results[0].concept_id, "b");
+
+    // Server-side errors surface as ServerError, not as a retry
+    match client.flush().await {
+        Err(ProtocolError::ServerError(message)) => assert!(message.contains("unsupported")),
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_client_reconnects_after_connection_loss() {
+    let store = Arc::new(MemoryStore::default());
+    // The server hangs up after every request
+    let addr = spawn_server(Arc::clone(&store), 1).await;
+    let config = ClientConfig::new(addr).with_retries(3, Duration::from_millis(5));
+    let mut client = Client::connect(config).await.unwrap();
+
+    for i in 0..5 {
+        client
+            .learn_concept(format!("c{}", i), "content", vec![], 1.0, 1.0)
+            .await
+            .unwrap();
+    }
+    assert_eq!(store.concepts.lock().unwrap().len(), 5);
+}
+
+/// Server that drops the first connection after reading one request,
+/// without replying, and answers normally afterwards. Returns the address and
+/// every request received.
+async fn spawn_lossy_server() -> (String, Arc<Mutex<Vec<StorageMessage>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&seen);
+    tokio::spawn(async move {
+        let store = MemoryStore::default();
+        let mut first = true;
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            while let Ok(message) = recv_message::<StorageMessage>(&mut socket).await {
+                received.lock().unwrap().push(message.clone());
+                if std::mem::take(&mut first) {
+                    break;
+                }
+                if send_message(&mut socket, &store.handle(message))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+    });
+    (addr, seen)
+}
+
+#[tokio::test]
+async fn test_client_retries_only_idempotent_requests() {
+    let key = |message: &StorageMessage| match message {
+        StorageMessage::LearnAssociation {
+            idempotency_key, ..
+        } => idempotency_key.clone(),
+        other => panic!("Unexpected message: {:?}", other),
+    };
+
+    // Typed writes carry a key, so the lost attempt is sent again unchanged
+    let (addr, seen) = spawn_lossy_server().await;
+    let config = ClientConfig::new(addr).with_retries(3, Duration::from_millis(5));
+    let mut client = Client::connect(config).await.unwrap();
+    client.learn_association("a", "b", 0, 0.8).await.unwrap();
+    {
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert!(key(&seen[0]).is_some());
+        assert_eq!(key(&seen[0]), key(&seen[1]));
+    }
+
+    // Without a key the write may already have been applied: not retried
+    let (addr, seen) = spawn_lossy_server().await;
+    let mut client =
+        Client::connect(ClientConfig::new(addr).with_retries(3, Duration::from_millis(5)))
+            .await
+            .unwrap();
+    let unkeyed = StorageMessage::LearnAssociation {
+        namespace: None,
+        source_id: "a".to_string(),
+        target_id: "b".to_string(),
+        assoc_type: 0,
+        confidence: 0.8,
+        idempotency_key: None,
+    };
+    assert!(matches!(
+        client.call(&unkeyed).await,
+        Err(ProtocolError::Io(_))
+    ));
+    assert_eq!(seen.lock().unwrap().len(), 1);
+
+    // The connection is re-established for the next request
+    assert!(client.health_check().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_client_pool_concurrent_use() {
+    let store = Arc::new(MemoryStore::default());
+    let addr = spawn_server(Arc::clone(&store), usize::MAX).await;
+    let pool = Arc::new(ClientPool::new(ClientConfig::new(addr), 4));
+
+    let mut tasks = Vec::new();
+    for i in 0..32 {
+        let pool = Arc::clone(&pool);
+        tasks.push(tokio::spawn(async move {
+            let mut client = pool.get().await.unwrap();
+            client
+                .learn_concept(format!("p{}", i), "pooled", vec![], 1.0, 1.0)
+                .await
+                .unwrap()
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(store.concepts.lock().unwrap().len(), 32);
+
+    // Unreachable servers fail after the retry budget instead of hanging
+    let dead = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let dead_addr = dead.local_addr().unwrap().to_string();
+    drop(dead);
+    let config = ClientConfig::new(dead_addr).with_retries(1, Duration::from_millis(1));
+    assert!(Client::connect(config).await.is_err());
+}
+
+#[tokio::test]
+async fn test_client_pool_drops_interrupted_connections() {
+    // Replies to queries for "slow" only after a delay
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let store = Arc::new(MemoryStore::default());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                while let Ok(message) = recv_message::<StorageMessage>(&mut socket).await {
+                    if matches!(&message, StorageMessage::QueryConcept { concept_id, .. } if concept_id == "slow")
+                    {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                    }
+                    if send_message(&mut socket, &store.handle(message))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    let pool = ClientPool::new(ClientConfig::new(addr), 1);
+    {
+        let mut client = pool.get().await.unwrap();
+        client
+            .learn_concept("fast", "answered at once", vec![], 1.0, 1.0)
+            .await
+            .unwrap();
+        let slow = tokio::time::timeout(Duration::from_millis(20), client.query_concept("slow"));
+        assert!(slow.await.is_err());
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // The next borrower gets its own reply, not the abandoned one
+    let mut client = pool.get().await.unwrap();
+    let record = client.query_concept("fast").await.unwrap().unwrap();
+    assert_eq!(record.concept_id, "fast");
+    assert_eq!(record.content, "answered at once");
+}
+
+#[tokio::test]
+async fn test_client_prechecks_server_message_limit() {
+    let store = Arc::new(MemoryStore::default());
+    let addr = spawn_server_with_limit(Arc::clone(&store), usize::MAX, 1024).await;
+    let config = ClientConfig::new(addr).with_handshake();
+    let mut client = Client::connect(config).await.unwrap();
+    assert_eq!(client.max_message_size(), 1024);
+
+    // Above the server's limit: refused locally, nothing reaches the server
+    match client
+        .learn_concept("big", "too large", vec![0.5; 512], 1.0, 0.9)
+        .await
+    {
+        Err(ProtocolError::MessageTooLarge(size, 1024)) => assert!(size > 1024),
+        other => panic!("Unexpected result: {:?}", other),
+    }
+    assert!(store.concepts.lock().unwrap().is_empty());
+
+    // Below the limit: accepted on the same connection
+    client
+        .learn_concept("small", "fits", vec![0.5; 16], 1.0, 0.9)
+        .await
+        .unwrap();
+    assert!(store.concepts.lock().unwrap().contains_key("small"));
+}
+
+#[tokio::test]
+async fn test_client_version_handshake() {
+    let store = Arc::new(MemoryStore::default());
+    let addr = spawn_server_with(
+        Arc::clone(&store),
+        usize::MAX,
+        DEFAULT_MAX_MESSAGE_SIZE,
+        true,
+    )
+    .await;
+    let config = ClientConfig::new(addr).with_version_handshake();
+    let mut client = Client::connect(config).await.unwrap();
+    assert_eq!(client.protocol_version(), Some(PROTOCOL_VERSION));
+    client
+        .learn_concept("v", "versioned", vec![], 1.0, 0.9)
+        .await
+        .unwrap();
+    assert!(store.concepts.lock().unwrap().contains_key("v"));
+
+    // A server without the handshake is detected instead of misreading the frame
+    let old = spawn_server(Arc::clone(&store), usize::MAX).await;
+    let config = ClientConfig::new(old).with_version_handshake();
+    match Client::connect(config).await {
+        Err(ProtocolError::VersionMismatch(0, PROTOCOL_VERSION)) => {}
+        other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn test_request_batch_preserves_order() {
+    let store = Arc::new(MemoryStore::default());
+    let addr = spawn_server(Arc::clone(&store), usize::MAX).await;
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+
+    // Sizes from a few bytes to several KB, so some frames are compressed
+    let content = |i: usize| format!("{}:{}", i, "x".repeat((i * 131) % 7000));
+    let learns: Vec<StorageMessage> = (0..100)
+        .map(|i| StorageMessage::LearnConcept {
+            namespace: None,
+            concept_id: format!("c{}", i),
+            content: content(i),
+            embedding: vec![],
+            strength: 1.0,
+            confidence: 1.0,
+            idempotency_key: None,
+        })
+        .collect();
+    let responses: Vec<StorageResponse> = request_batch(&mut stream, &learns).await.unwrap();
+    assert_eq!(responses.len(), 100);
+
+    // Queried in reverse so the order can't match by accident
+    let queries: Vec<StorageMessage> = (0..100)
+        .rev()
+        .map(|i| StorageMessage::QueryConcept {
+            namespace: None,
+            concept_id: format!("c{}", i),
+            include_vector: false,
+        })
+        .collect();
+    let responses: Vec<StorageResponse> = request_batch(&mut stream, &queries).await.unwrap();
+    assert_eq!(responses.len(), 100);
+    for (response, i) in responses.into_iter().zip((0..100).rev()) {
+        match response {
+            StorageResponse::QueryConceptOk {
+                found: true,
+                concept_id,
+                content: c,
+                ..
+            } => {
+                assert_eq!(concept_id, format!("c{}", i));
+                assert_eq!(c, content(i));
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    // The connection is still in sync for ordinary requests afterwards
+    let health: Vec<StorageResponse> = request_batch(&mut stream, &[StorageMessage::HealthCheck])
+        .await
+        .unwrap();
+    assert!(matches!(
+        health[0],
+        StorageResponse::HealthCheckOk { healthy: true, .. }
+    ));
+}
+
+async fn next_notification(client: &mut Client) -> ConceptChange {
+    tokio::time::timeout(Duration::from_secs(5), client.next_notification())
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_subscription_notifies_other_connections() {
+    let store = Arc::new(MemoryStore::default());
+    let addr = spawn_streaming_server(Arc::clone(&store)).await;
+
+    let mut subscriber = Client::connect(ClientConfig::new(&addr)).await.unwrap();
+    subscriber.subscribe("user:*").await.unwrap();
+
+    // Raw connection consuming the stream with recv_stream
+    let mut raw = TcpStream::connect(&addr).await.unwrap();
+    send_message(
+        &mut raw,
+        &StorageMessage::Subscribe {
+            pattern: "*".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let mut writer = Client::connect(ClientConfig::new(&addr)).await.unwrap();
+    for id in ["user:1", "team:1", "user:2"] {
+        writer
+            .learn_concept(id, "content", vec![], 1.0, 0.9)
+            .await
+            .unwrap();
+    }
+
+    // Notifications arriving around a reply are queued, not taken for it
+    assert!(subscriber.health_check().await.unwrap());
+    let learned = |id: &str| ConceptChange {
+        concept_id: id.to_string(),
+        change_kind: ChangeKind::Learned,
+    };
+    assert_eq!(next_notification(&mut subscriber).await, learned("user:1"));
+    assert_eq!(next_notification(&mut subscriber).await, learned("user:2"));
+
+    let mut frames = Vec::new();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        recv_stream(&mut raw, |frame: StorageResponse| {
+            frames.push(frame);
+            frames.len() < 4
+        }),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(matches!(frames[0], StorageResponse::SubscribeOk { .. }));
+    let ids: Vec<&str> = frames[1..]
+        .iter()
+        .map(|frame| match frame {
+            StorageResponse::Notification { concept_id, .. } => concept_id.as_str(),
+            other => panic!("Unexpected frame: {:?}", other),
+        })
+        .collect();
+    assert_eq!(ids, ["user:1", "team:1", "user:2"]);
+}
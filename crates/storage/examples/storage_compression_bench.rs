@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+/// Storage Compression Benchmark
+///
+/// Flushes the same text-heavy concepts to an uncompressed and a zstd-compressed
+/// `storage.dat`, then compares file size, flush time and startup (load) time.
+use sutra_storage::{ConceptId, ConcurrentConfig, ConcurrentMemory, StorageCompression};
+use tempfile::TempDir;
+
+const CONCEPTS: usize = 50_000;
+const DIMENSION: usize = 64;
+
+fn main() {
+    println!("=== Storage Compression Benchmark ===\n");
+
+    let contents: Vec<String> = (0..CONCEPTS)
+        .map(|i| {
+            format!(
+                "Concept {}: patients with condition {} were treated with protocol {} and \
+                 showed improvement after {} days of observation.",
+                i,
+                i % 97,
+                i % 13,
+                i % 30
+            )
+        })
+        .collect();
+
+    for compression in [StorageCompression::None, StorageCompression::Zstd(3)] {
+        let dir = TempDir::new().expect("temp dir");
+        let config = ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            vector_dimension: DIMENSION,
+            storage_compression: compression,
+            ..Default::default()
+        };
+
+        let memory = ConcurrentMemory::new(config.clone());
+        for (i, content) in contents.iter().enumerate() {
+            let vector = (0..DIMENSION)
+                .map(|d| ((i * 31 + d * 7) % 101) as f32 / 101.0)
+                .collect();
+            memory
+                .learn_concept(
+                    ConceptId::from_string(content),
+                    content.as_bytes().to_vec(),
+                    Some(vector),
+                    1.0,
+                    0.9,
+                    HashMap::new(),
+                )
+                .expect("learn");
+        }
+        while memory.get_snapshot().concept_count < CONCEPTS {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let start = Instant::now();
+        memory.flush().expect("flush");
+        let flush_time = start.elapsed();
+        drop(memory);
+
+        let file_size = std::fs::metadata(dir.path().join("storage.dat"))
+            .expect("metadata")
+            .len();
+
+        let start = Instant::now();
+        let memory = ConcurrentMemory::new(config);
+        let load_time = start.elapsed();
+        assert_eq!(memory.get_snapshot().concept_count, CONCEPTS);
+
+        println!("{:?}:", compression);
+        println!("  storage.dat: {:.2} MB", file_size as f64 / 1_048_576.0);
+        println!("  Flush:       {:?}", flush_time);
+        println!("  Load:        {:?}", load_time);
+    }
+}
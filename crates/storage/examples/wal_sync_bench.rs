@@ -0,0 +1,54 @@
+/// WAL Sync Policy Benchmark
+///
+/// Appends the same concept records to a WAL under each sync policy and
+/// compares append throughput and latency.
+use std::time::{Duration, Instant};
+use sutra_storage::{ConceptId, Operation, SyncPolicy, WriteAheadLog};
+use tempfile::TempDir;
+
+const APPENDS: usize = 5_000;
+
+fn main() {
+    println!("=== WAL Sync Policy Benchmark ===\n");
+
+    let policies = [
+        SyncPolicy::Always,
+        SyncPolicy::EverySync(Duration::from_millis(10)),
+        SyncPolicy::EverySync(Duration::from_millis(100)),
+        SyncPolicy::Never,
+    ];
+
+    for policy in policies {
+        let dir = TempDir::new().expect("temp dir");
+        let mut wal =
+            WriteAheadLog::create_with_policy(dir.path().join("wal.log"), policy).expect("wal");
+
+        let mut latencies = Vec::with_capacity(APPENDS);
+        let start = Instant::now();
+        for i in 0..APPENDS {
+            let append = Instant::now();
+            wal.append(Operation::WriteConcept {
+                concept_id: ConceptId::from_string(&format!("concept-{}", i)),
+                content_len: 128,
+                vector_len: 384,
+                created: i as u64,
+                modified: i as u64,
+            })
+            .expect("append");
+            latencies.push(append.elapsed());
+        }
+        wal.sync().expect("sync");
+        let total = start.elapsed();
+
+        latencies.sort();
+        let percentile = |p: usize| latencies[(latencies.len() * p / 100).min(latencies.len() - 1)];
+
+        println!("{:?}:", policy);
+        println!(
+            "  Throughput: {:.0} appends/sec",
+            APPENDS as f64 / total.as_secs_f64()
+        );
+        println!("  p50:        {:?}", percentile(50));
+        println!("  p99:        {:?}", percentile(99));
+    }
+}
@@ -0,0 +1,37 @@
+//! Concept access statistics kept off the write path
+//!
+//! Reads vastly outnumber writes, so recording one through the write log
+//! would bump concept versions, show up as modifications in snapshot diffs
+//! and count against write backpressure. Accesses accumulate here instead
+//! and the reconciler folds them into the next snapshot in one pass.
+
+use crate::types::ConceptId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Accesses to one concept since the last fold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingAccess {
+    pub count: u32,
+    /// Timestamp of the latest access (microseconds)
+    pub last: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct AccessTracker {
+    pending: Mutex<HashMap<ConceptId, PendingAccess>>,
+}
+
+impl AccessTracker {
+    pub fn record(&self, id: ConceptId, timestamp: u64) {
+        let mut pending = self.pending.lock();
+        let entry = pending.entry(id).or_default();
+        entry.count = entry.count.saturating_add(1);
+        entry.last = entry.last.max(timestamp);
+    }
+
+    /// Take everything recorded since the last call
+    pub fn drain(&self) -> HashMap<ConceptId, PendingAccess> {
+        std::mem::take(&mut *self.pending.lock())
+    }
+}
@@ -11,7 +11,9 @@
 /// - Predictive queue depth forecasting
 /// - Self-healing interval adjustment
 /// - Hooks for telemetry and monitoring
+use crate::access_tracker::AccessTracker;
 use crate::read_view::{ConceptNode, GraphSnapshot, ReadView};
+use crate::replication::{ReplicationLog, ReplicationOp};
 use crate::write_log::{WriteEntry, WriteLog};
 use std::collections::VecDeque;
 use std::path::PathBuf;
@@ -20,6 +22,47 @@ use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
+/// How the reconciler resolves two writes to the same concept
+///
+/// Consistency implications:
+/// - `LastWriteWins` applies entries in log order, so readers always see the
+///   most recent write. This is the default and matches the WAL replay order.
+/// - `HighestConfidence` / `HighestStrength` may keep an older version when a
+///   newer write scores lower. The read plane then diverges from the raw log
+///   order, and a re-learn with a lower score is silently discarded. Ties go
+///   to the newer write.
+///
+/// Keyword and vector indexes follow the version the policy keeps. Recovery
+/// reloads the reconciled snapshot from `storage.dat`, and WAL replay does not
+/// re-apply concept writes, so a restart cannot resurrect a losing version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum ConflictPolicy {
+    /// The newest write replaces the existing concept
+    #[default]
+    LastWriteWins,
+    /// The version with the higher confidence is kept
+    HighestConfidence,
+    /// The version with the higher strength is kept
+    HighestStrength,
+}
+
+impl ConflictPolicy {
+    /// Returns true if the incoming write should replace the existing concept
+    fn incoming_wins(&self, existing: &ConceptNode, strength: f32, confidence: f32) -> bool {
+        match self {
+            Self::LastWriteWins => true,
+            Self::HighestConfidence => confidence >= existing.confidence,
+            Self::HighestStrength => strength >= existing.strength,
+        }
+    }
+}
+
+/// Called after each snapshot swap with every concept a write stored
+///
+/// Lets search indexes follow the version the [`ConflictPolicy`] kept rather
+/// than whichever write arrived last.
+pub type ConceptIndexer = Arc<dyn Fn(&ConceptNode) + Send + Sync>;
+
 /// Adaptive reconciler configuration
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AdaptiveReconcilerConfig {
@@ -49,6 +92,10 @@ pub struct AdaptiveReconcilerConfig {
 
     /// Look-back window for trend analysis (number of reconciliation cycles)
     pub trend_window_size: usize,
+
+    /// Resolution rule when a write targets an already-present concept
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
 }
 
 impl Default for AdaptiveReconcilerConfig {
@@ -63,6 +110,7 @@ impl Default for AdaptiveReconcilerConfig {
             queue_warning_threshold: 0.70, // Warn at 70% capacity
             ema_alpha: 0.3,
             trend_window_size: 50,
+            conflict_policy: ConflictPolicy::LastWriteWins,
         }
     }
 }
@@ -163,6 +211,11 @@ pub struct AdaptiveReconcilerStats {
     pub backpressure_events: u64,
     pub interval_adjustments: u64,
 
+    // Conflict resolution metrics
+    pub conflict_policy: ConflictPolicy,
+    pub conflicts_resolved: u64,
+    pub conflicts_kept_existing: u64,
+
     // 🔥 NEW: Health indicators
     pub health_score: f64, // 0.0-1.0
     pub recommendation: String,
@@ -323,12 +376,23 @@ pub struct AdaptiveReconciler {
     entries_processed: Arc<AtomicU64>,
     disk_flushes: Arc<AtomicU64>,
     interval_adjustments: Arc<AtomicU64>,
+    conflicts_resolved: Arc<AtomicU64>,
+    conflicts_kept_existing: Arc<AtomicU64>,
 
     /// Current interval (dynamically adjusted)
     current_interval_ms: Arc<AtomicU64>,
 
     /// Trend analyzer (shared with reconciliation thread)
     trend_analyzer: Arc<Mutex<TrendAnalyzer>>,
+
+    /// Applied writes are published here for read replicas
+    replication_log: Option<Arc<ReplicationLog>>,
+
+    /// Reads recorded since the last cycle
+    access_tracker: Option<Arc<AccessTracker>>,
+
+    /// Told about each concept version that won its write
+    indexer: Option<ConceptIndexer>,
 }
 
 impl AdaptiveReconciler {
@@ -356,10 +420,33 @@ impl AdaptiveReconciler {
             entries_processed: Arc::new(AtomicU64::new(0)),
             disk_flushes: Arc::new(AtomicU64::new(0)),
             interval_adjustments: Arc::new(AtomicU64::new(0)),
+            conflicts_resolved: Arc::new(AtomicU64::new(0)),
+            conflicts_kept_existing: Arc::new(AtomicU64::new(0)),
             trend_analyzer,
+            replication_log: None,
+            access_tracker: None,
+            indexer: None,
         }
     }
 
+    /// Fold reads recorded in `tracker` into each snapshot (set before `start`)
+    pub fn with_access_tracker(mut self, tracker: Arc<AccessTracker>) -> Self {
+        self.access_tracker = Some(tracker);
+        self
+    }
+
+    /// Publish every applied write to `log` (set before `start`)
+    pub fn with_replication_log(mut self, log: Arc<ReplicationLog>) -> Self {
+        self.replication_log = Some(log);
+        self
+    }
+
+    /// Hand every stored concept to `indexer` (set before `start`)
+    pub fn with_indexer(mut self, indexer: ConceptIndexer) -> Self {
+        self.indexer = Some(indexer);
+        self
+    }
+
     /// Start adaptive reconciliation thread
     pub fn start(&mut self) {
         if self.running.load(Ordering::Relaxed) {
@@ -376,8 +463,13 @@ impl AdaptiveReconciler {
         let entries_processed = Arc::clone(&self.entries_processed);
         let disk_flushes = Arc::clone(&self.disk_flushes);
         let interval_adjustments = Arc::clone(&self.interval_adjustments);
+        let conflicts_resolved = Arc::clone(&self.conflicts_resolved);
+        let conflicts_kept_existing = Arc::clone(&self.conflicts_kept_existing);
         let current_interval_ms = Arc::clone(&self.current_interval_ms);
         let trend_analyzer = Arc::clone(&self.trend_analyzer);
+        let replication_log = self.replication_log.clone();
+        let access_tracker = self.access_tracker.clone();
+        let indexer = self.indexer.clone();
 
         let handle = thread::spawn(move || {
             adaptive_reconcile_loop(
@@ -389,8 +481,13 @@ impl AdaptiveReconciler {
                 entries_processed,
                 disk_flushes,
                 interval_adjustments,
+                conflicts_resolved,
+                conflicts_kept_existing,
                 current_interval_ms,
                 trend_analyzer,
+                replication_log,
+                access_tracker,
+                indexer,
             );
         });
 
@@ -461,6 +558,9 @@ impl AdaptiveReconciler {
             predicted_queue_depth,
             backpressure_events: write_stats.dropped,
             interval_adjustments: self.interval_adjustments.load(Ordering::Relaxed),
+            conflict_policy: self.config.conflict_policy,
+            conflicts_resolved: self.conflicts_resolved.load(Ordering::Relaxed),
+            conflicts_kept_existing: self.conflicts_kept_existing.load(Ordering::Relaxed),
             health_score,
             recommendation,
         }
@@ -484,8 +584,13 @@ fn adaptive_reconcile_loop(
     entries_processed: Arc<AtomicU64>,
     _disk_flushes: Arc<AtomicU64>,
     interval_adjustments: Arc<AtomicU64>,
+    conflicts_resolved: Arc<AtomicU64>,
+    conflicts_kept_existing: Arc<AtomicU64>,
     current_interval_ms: Arc<AtomicU64>,
     trend_analyzer: Arc<Mutex<TrendAnalyzer>>,
+    replication_log: Option<Arc<ReplicationLog>>,
+    access_tracker: Option<Arc<AccessTracker>>,
+    indexer: Option<ConceptIndexer>,
 ) {
     let _storage_version = 0u32; // Reserved for future use
     let mut cycle_count = 0u64;
@@ -501,23 +606,57 @@ fn adaptive_reconcile_loop(
         // Drain write log
         let batch = write_log.drain_batch(config.max_batch_size);
         let batch_size = batch.len();
+        let accesses = access_tracker
+            .as_ref()
+            .map(|tracker| tracker.drain())
+            .unwrap_or_default();
 
-        if !batch.is_empty() {
+        if !batch.is_empty() || !accesses.is_empty() {
             // Load current snapshot
             let current_snapshot = read_view.load();
 
             // Clone snapshot (structural sharing via im::HashMap)
             let mut new_snapshot = GraphSnapshot {
                 concepts: current_snapshot.concepts.clone(),
+                attribute_index: current_snapshot.attribute_index.clone(),
                 sequence: current_snapshot.sequence + 1,
                 timestamp: current_timestamp_us(),
                 concept_count: current_snapshot.concept_count,
                 edge_count: current_snapshot.edge_count,
             };
 
-            // Apply batch
-            for entry in &batch {
-                apply_entry(&mut new_snapshot, entry);
+            // Apply batch (atomic entries land in this same snapshot)
+            let mut replicated = Vec::new();
+            let mut stored = std::collections::HashSet::new();
+            for entry in batch.iter().flat_map(WriteEntry::entries) {
+                let outcome = apply_entry(&mut new_snapshot, entry, config.conflict_policy);
+                match outcome {
+                    ApplyOutcome::Applied => {}
+                    ApplyOutcome::ConflictReplaced => {
+                        conflicts_resolved.fetch_add(1, Ordering::Relaxed);
+                    }
+                    ApplyOutcome::ConflictKeptExisting => {
+                        conflicts_resolved.fetch_add(1, Ordering::Relaxed);
+                        conflicts_kept_existing.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                if replication_log.is_some() && outcome != ApplyOutcome::ConflictKeptExisting {
+                    replicated.extend(ReplicationOp::from_entry(entry));
+                }
+                if let WriteEntry::AddConcept { id, .. } = entry {
+                    if indexer.is_some() && outcome != ApplyOutcome::ConflictKeptExisting {
+                        stored.insert(*id);
+                    }
+                }
+            }
+
+            // Reads only touch access statistics, never the concept version
+            for (id, access) in accesses {
+                if let Some(mut node) = new_snapshot.concepts.get(&id).cloned() {
+                    node.access_count = node.access_count.saturating_add(access.count);
+                    node.last_accessed = node.last_accessed.max(access.last);
+                    new_snapshot.concepts.insert(id, node);
+                }
             }
 
             // Update stats
@@ -525,6 +664,24 @@ fn adaptive_reconcile_loop(
 
             // Atomic swap
             read_view.store(new_snapshot);
+            write_log.mark_applied(&batch);
+
+            // Index the surviving version of each stored concept, skipping any
+            // a later entry in the batch deleted
+            if let Some(ref indexer) = indexer {
+                let snapshot = read_view.load();
+                for id in stored {
+                    if let Some(node) = snapshot.concepts.get(&id) {
+                        indexer(node);
+                    }
+                }
+            }
+
+            // Publish only after the swap, so a replica that snapshots after
+            // reading the log sequence sees every record below it
+            if let Some(ref log) = replication_log {
+                log.publish(replicated);
+            }
 
             // Update metrics
             reconciliations.fetch_add(1, Ordering::Relaxed);
@@ -596,8 +753,23 @@ fn adaptive_reconcile_loop(
     }
 }
 
+/// Result of applying a write entry to the snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApplyOutcome {
+    /// No conflict with existing state
+    Applied,
+    /// Concept already existed; the incoming write replaced it
+    ConflictReplaced,
+    /// Concept already existed; the policy kept the existing version
+    ConflictKeptExisting,
+}
+
 /// Apply a single write entry to the snapshot
-fn apply_entry(snapshot: &mut GraphSnapshot, entry: &WriteEntry) {
+fn apply_entry(
+    snapshot: &mut GraphSnapshot,
+    entry: &WriteEntry,
+    policy: ConflictPolicy,
+) -> ApplyOutcome {
     match entry {
         WriteEntry::AddConcept {
             id,
@@ -609,6 +781,14 @@ fn apply_entry(snapshot: &mut GraphSnapshot, entry: &WriteEntry) {
             attributes,
             semantic,
         } => {
+            let mut outcome = ApplyOutcome::Applied;
+            if let Some(existing) = snapshot.concepts.get(id) {
+                if !policy.incoming_wins(existing, *strength, *confidence) {
+                    return ApplyOutcome::ConflictKeptExisting;
+                }
+                outcome = ApplyOutcome::ConflictReplaced;
+            }
+
             let mut node = if let Some(semantic_meta) = semantic.clone() {
                 ConceptNode::with_semantic(
                     *id,
@@ -630,25 +810,45 @@ fn apply_entry(snapshot: &mut GraphSnapshot, entry: &WriteEntry) {
                 )
             };
             node.attributes = attributes.clone();
-            snapshot.concepts.insert(*id, node);
+            snapshot.insert_concept(node);
+            return outcome;
         }
 
         WriteEntry::AddAssociation { record } => {
             if let Some(mut source_node) = snapshot.concepts.get(&record.source_id).cloned() {
                 source_node.add_edge(record.target_id, *record);
-                snapshot.concepts.insert(record.source_id, source_node);
+                snapshot.update_concept(source_node);
             }
 
             if let Some(mut target_node) = snapshot.concepts.get(&record.target_id).cloned() {
                 target_node.add_edge(record.source_id, *record);
-                snapshot.concepts.insert(record.target_id, target_node);
+                snapshot.update_concept(target_node);
             }
         }
 
         WriteEntry::UpdateStrength { id, strength } => {
             if let Some(mut node) = snapshot.concepts.get(id).cloned() {
                 node.strength = *strength;
-                snapshot.concepts.insert(*id, node);
+                snapshot.update_concept(node);
+            }
+        }
+
+        WriteEntry::UpdateConcept {
+            id,
+            strength,
+            confidence,
+            attributes,
+        } => {
+            if let Some(mut node) = snapshot.concepts.get(id).cloned() {
+                if let Some(strength) = strength {
+                    node.strength = *strength;
+                }
+                if let Some(confidence) = confidence {
+                    node.confidence = *confidence;
+                }
+                node.attributes
+                    .extend(attributes.iter().map(|(k, v)| (k.clone(), v.clone())));
+                snapshot.insert_concept(node);
             }
         }
 
@@ -656,32 +856,32 @@ fn apply_entry(snapshot: &mut GraphSnapshot, entry: &WriteEntry) {
             if let Some(mut node) = snapshot.concepts.get(id).cloned() {
                 node.last_accessed = *timestamp;
                 node.access_count += 1;
-                snapshot.concepts.insert(*id, node);
+                snapshot.update_concept(node);
             }
         }
 
         WriteEntry::DeleteConcept { id, timestamp: _ } => {
             // Remove concept and all its edges
-            if snapshot.concepts.contains_key(id) {
-                snapshot.concepts.remove(id);
-
-                // Remove all edges pointing to this concept from other concepts
-                // Since im::HashMap is immutable-friendly, we need to clone and update
-                let mut updated_concepts = im::HashMap::new();
-                for (other_id, other_node) in snapshot.concepts.iter() {
-                    if other_id != id {
-                        let mut node_clone = other_node.clone();
-                        node_clone.neighbors.retain(|neighbor| neighbor != id);
-                        updated_concepts.insert(*other_id, node_clone);
-                    }
+            if snapshot.remove_concept(id).is_some() {
+                // Remove all edges pointing to this concept from other concepts,
+                // touching only the concepts that had one
+                let referencing: Vec<ConceptNode> = snapshot
+                    .concepts
+                    .values()
+                    .filter(|node| node.neighbors.contains(id))
+                    .cloned()
+                    .collect();
+                for mut node in referencing {
+                    node.neighbors.retain(|neighbor| neighbor != id);
+                    snapshot.update_concept(node);
                 }
-                snapshot.concepts = updated_concepts;
             }
         }
 
         WriteEntry::Clear => {
             // Reset everything
             snapshot.concepts.clear();
+            snapshot.attribute_index.clear();
             snapshot.concept_count = 0;
             snapshot.edge_count = 0;
         }
@@ -689,7 +889,15 @@ fn apply_entry(snapshot: &mut GraphSnapshot, entry: &WriteEntry) {
         WriteEntry::BatchMarker { .. } => {
             // Marker only, no action
         }
+
+        WriteEntry::Atomic { entries } => {
+            for entry in entries {
+                apply_entry(snapshot, entry, policy);
+            }
+        }
     }
+
+    ApplyOutcome::Applied
 }
 
 /// Get current timestamp in microseconds
@@ -757,6 +965,44 @@ mod tests {
         assert!(score < 0.2);
     }
 
+    #[test]
+    fn test_conflict_policy_picks_expected_winner() {
+        let id = ConceptId([7; 16]);
+        let concept = |content: &[u8], strength: f32, confidence: f32| WriteEntry::AddConcept {
+            id,
+            content: content.to_vec().into_boxed_slice(),
+            vector: None,
+            strength,
+            confidence,
+            timestamp: 1,
+            attributes: std::collections::HashMap::new(),
+            semantic: None,
+        };
+
+        // First write: high confidence, low strength. Second: the reverse.
+        let first = concept(b"first", 0.2, 0.9);
+        let second = concept(b"second", 0.8, 0.4);
+
+        let cases = [
+            (ConflictPolicy::LastWriteWins, &b"second"[..]),
+            (ConflictPolicy::HighestConfidence, &b"first"[..]),
+            (ConflictPolicy::HighestStrength, &b"second"[..]),
+        ];
+
+        for (policy, expected) in cases {
+            let mut snapshot = GraphSnapshot::new(0);
+            assert_eq!(
+                apply_entry(&mut snapshot, &first, policy),
+                ApplyOutcome::Applied
+            );
+            let outcome = apply_entry(&mut snapshot, &second, policy);
+            assert_ne!(outcome, ApplyOutcome::Applied, "{:?}", policy);
+
+            let node = snapshot.concepts.get(&id).unwrap();
+            assert_eq!(&node.content[..], expected, "{:?}", policy);
+        }
+    }
+
     #[test]
     fn test_adaptive_reconciler_stats() {
         let dir = TempDir::new().unwrap();
@@ -32,6 +32,26 @@ pub enum Role {
     Service,
 }
 
+/// What a key may do regardless of its roles
+///
+/// Scopes are ordered: each one includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Scope {
+    /// Queries and searches only
+    ReadOnly,
+    /// Also learn, update and delete data
+    ReadWrite,
+    /// Also maintenance such as flushing and reindexing
+    Admin,
+}
+
+impl Scope {
+    /// Whether a key with this scope may make a request needing `required`
+    pub fn allows(self, required: Scope) -> bool {
+        self >= required
+    }
+}
+
 /// Authentication claims
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -45,6 +65,9 @@ pub struct Claims {
     pub roles: Vec<Role>,
     /// Optional: Allowed operations
     pub permissions: Option<Vec<String>>,
+    /// Scope of the key; derived from `roles` when absent
+    #[serde(default)]
+    pub scope: Option<Scope>,
 }
 
 impl Claims {
@@ -57,6 +80,19 @@ impl Claims {
         now > self.exp
     }
 
+    /// The key's scope, or the widest one its roles imply
+    pub fn scope(&self) -> Scope {
+        self.scope.unwrap_or_else(|| {
+            if self.has_role(&Role::Admin) {
+                Scope::Admin
+            } else if self.has_role(&Role::Writer) || self.has_role(&Role::Service) {
+                Scope::ReadWrite
+            } else {
+                Scope::ReadOnly
+            }
+        })
+    }
+
     /// Check if claims have specific role
     pub fn has_role(&self, role: &Role) -> bool {
         self.roles.contains(role)
@@ -205,6 +241,20 @@ impl AuthManager {
 
     /// Generate authentication token
     pub fn generate_token(&self, subject: &str, roles: Vec<Role>) -> Result<String> {
+        self.issue_token(subject, roles, None)
+    }
+
+    /// Generate a token limited to `scope`, whatever its roles allow
+    pub fn generate_scoped_token(
+        &self,
+        subject: &str,
+        roles: Vec<Role>,
+        scope: Scope,
+    ) -> Result<String> {
+        self.issue_token(subject, roles, Some(scope))
+    }
+
+    fn issue_token(&self, subject: &str, roles: Vec<Role>, scope: Option<Scope>) -> Result<String> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -216,6 +266,7 @@ impl AuthManager {
             exp: now + self.token_ttl,
             roles,
             permissions: None,
+            scope,
         };
 
         match &self.method {
@@ -410,6 +461,7 @@ mod tests {
             exp: u64::MAX,
             roles: vec![Role::Admin],
             permissions: None,
+            scope: None,
         };
 
         assert!(admin_claims.can_perform("read"));
@@ -422,6 +474,7 @@ mod tests {
             exp: u64::MAX,
             roles: vec![Role::Reader],
             permissions: None,
+            scope: None,
         };
 
         assert!(reader_claims.can_perform("read"));
@@ -429,6 +482,29 @@ mod tests {
         assert!(!reader_claims.can_perform("delete"));
     }
 
+    #[test]
+    fn test_scope_survives_token_round_trip() {
+        let manager = AuthManager::new_jwt_hs256(
+            "test-secret-key-with-sufficient-length-32chars".to_string(),
+            3600,
+        );
+
+        let token = manager
+            .generate_scoped_token("dashboard", vec![Role::Writer], Scope::ReadOnly)
+            .unwrap();
+        let claims = manager.validate_token(&token).unwrap();
+        assert_eq!(claims.scope(), Scope::ReadOnly);
+        assert!(claims.scope().allows(Scope::ReadOnly));
+        assert!(!claims.scope().allows(Scope::ReadWrite));
+
+        // Unscoped tokens get the scope their roles imply
+        let token = manager
+            .generate_token("ingest", vec![Role::Writer])
+            .unwrap();
+        let claims = manager.validate_token(&token).unwrap();
+        assert_eq!(claims.scope(), Scope::ReadWrite);
+    }
+
     #[test]
     fn test_token_revocation() {
         let manager = AuthManager::new_hmac(
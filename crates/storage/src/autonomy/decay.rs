@@ -1,8 +1,8 @@
 //! Knowledge Decay
 //!
-//! Background loop that applies exponential strength decay to concepts based on
-//! time since last access. Reinforces frequently accessed concepts and prunes
-//! concepts that fall below a threshold.
+//! Background loop that decays concept strength based on time since last
+//! access, along an exponential, linear or power-law curve. Reinforces
+//! frequently accessed concepts and prunes concepts that fall below a threshold.
 
 use crate::concurrent_memory::ConcurrentMemory;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -10,6 +10,42 @@ use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+/// Shape of strength decay over time since last access
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DecayCurve {
+    /// `strength * exp(-rate * t)`
+    #[default]
+    Exponential,
+    /// `strength * (1 - rate * t)`, reaching zero at `t = 1 / rate`
+    Linear,
+    /// `strength * (1 + rate * t)^-exponent`; heavy-tailed, so old
+    /// concepts fade much slower than under exponential decay
+    PowerLaw { exponent: f64 },
+}
+
+impl DecayCurve {
+    /// Share of strength left after `seconds` at `rate`
+    pub fn factor(&self, rate: f64, seconds: f64) -> f64 {
+        match *self {
+            DecayCurve::Exponential => (-rate * seconds).exp(),
+            DecayCurve::Linear => (1.0 - rate * seconds).max(0.0),
+            DecayCurve::PowerLaw { exponent } => (1.0 + rate * seconds).powf(-exponent),
+        }
+    }
+
+    /// Share of strength left going from `from` to `to` seconds since access
+    ///
+    /// Applying each step in turn gives the same strength as `factor(to)`
+    /// applied once, however many steps the time is cut into.
+    pub fn step(&self, rate: f64, from: f64, to: f64) -> f64 {
+        let before = self.factor(rate, from);
+        if before <= 0.0 {
+            return 0.0;
+        }
+        self.factor(rate, to) / before
+    }
+}
+
 /// Configuration for the decay loop
 #[derive(Debug, Clone)]
 pub struct DecayConfig {
@@ -17,7 +53,9 @@ pub struct DecayConfig {
     pub enabled: bool,
     /// Interval between decay cycles
     pub interval: Duration,
-    /// Exponential decay rate (per second)
+    /// How strength falls off with time since last access
+    pub curve: DecayCurve,
+    /// Decay rate (per second) fed to the curve
     pub decay_rate: f64,
     /// Reinforcement bonus multiplier for access count
     pub reinforcement_bonus: f64,
@@ -32,6 +70,7 @@ impl Default for DecayConfig {
         Self {
             enabled: true,
             interval: Duration::from_secs(5),
+            curve: DecayCurve::Exponential,
             decay_rate: 0.0001, // Very slow decay
             reinforcement_bonus: 0.01,
             prune_threshold: 0.01,
@@ -75,19 +114,42 @@ impl Drop for DecayLoop {
     }
 }
 
+/// Strength after the time since access grew from `from` to `to` seconds,
+/// plus the access-count reinforcement
+///
+/// Only the step between the two ages is applied, so a concept decays along
+/// its curve instead of compounding the whole age into every cycle.
+/// Reinforcement is added on top of whichever curve is configured, so
+/// frequently used concepts hold up the same way under every curve.
+fn decayed_strength(
+    config: &DecayConfig,
+    strength: f32,
+    access_count: u32,
+    from: f64,
+    to: f64,
+) -> f32 {
+    let decayed = strength as f64 * config.curve.step(config.decay_rate, from, to);
+    let reinforcement = config.reinforcement_bonus * (1.0 + access_count as f64).ln();
+    (decayed + reinforcement).clamp(0.0, 1.0) as f32
+}
+
 fn decay_loop(config: DecayConfig, storage: Arc<ConcurrentMemory>, running: Arc<AtomicBool>) {
     log::info!(
-        "Decay loop started (interval={:?}, rate={}, prune={})",
+        "Decay loop started (interval={:?}, curve={:?}, rate={}, prune={})",
         config.interval,
+        config.curve,
         config.decay_rate,
         config.prune_threshold
     );
 
+    let mut last_cycle = std::time::Instant::now();
     while running.load(Ordering::Relaxed) {
         thread::sleep(config.interval);
         if !running.load(Ordering::Relaxed) {
             break;
         }
+        let elapsed = last_cycle.elapsed().as_secs_f64();
+        last_cycle = std::time::Instant::now();
 
         let snapshot = storage.get_snapshot();
         let now_us = std::time::SystemTime::now()
@@ -119,15 +181,13 @@ fn decay_loop(config: DecayConfig, storage: Arc<ConcurrentMemory>, running: Arc<
                 0.0
             };
 
-            // Exponential decay: new_strength = strength * exp(-decay_rate * seconds)
-            let decayed =
-                concept.strength as f64 * (-config.decay_rate * seconds_since_access).exp();
-
-            // Reinforcement: bonus based on access count
-            let reinforcement =
-                config.reinforcement_bonus * (1.0 + concept.access_count as f64).ln();
-
-            let new_strength = (decayed + reinforcement).clamp(0.0, 1.0) as f32;
+            let new_strength = decayed_strength(
+                &config,
+                concept.strength,
+                concept.access_count,
+                (seconds_since_access - elapsed).max(0.0),
+                seconds_since_access,
+            );
 
             if new_strength < config.prune_threshold {
                 let _ = storage.delete_concept(concept.id);
@@ -150,3 +210,80 @@ fn decay_loop(config: DecayConfig, storage: Arc<ConcurrentMemory>, running: Arc<
 
     log::info!("Decay loop stopped");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_law_keeps_old_concepts_stronger() {
+        let config = |curve| DecayConfig {
+            curve,
+            reinforcement_bonus: 0.0,
+            ..Default::default()
+        };
+        let exponential = config(DecayCurve::Exponential);
+        let linear = config(DecayCurve::Linear);
+        let power_law = config(DecayCurve::PowerLaw { exponent: 1.0 });
+
+        // A day without access at the default rate
+        let day = 86_400.0;
+        let exp_strength = decayed_strength(&exponential, 0.9, 0, 0.0, day);
+        let power_strength = decayed_strength(&power_law, 0.9, 0, 0.0, day);
+        assert!(
+            power_strength > exp_strength,
+            "power law {} <= exponential {}",
+            power_strength,
+            exp_strength
+        );
+        assert!(power_strength < 0.9);
+        assert_eq!(decayed_strength(&linear, 0.9, 0, 0.0, day), 0.0);
+        assert!((decayed_strength(&linear, 0.9, 0, 0.0, 1_000.0) - 0.81).abs() < 1e-6);
+
+        // Access reinforcement applies whatever the curve
+        for config in [exponential, linear, power_law] {
+            let config = DecayConfig {
+                reinforcement_bonus: DecayConfig::default().reinforcement_bonus,
+                ..config
+            };
+            assert!(
+                decayed_strength(&config, 0.5, 10, 0.0, 0.0) > 0.5,
+                "{:?}",
+                config.curve
+            );
+        }
+    }
+
+    #[test]
+    fn test_decay_does_not_compound_across_cycles() {
+        for curve in [
+            DecayCurve::Exponential,
+            DecayCurve::Linear,
+            DecayCurve::PowerLaw { exponent: 1.0 },
+        ] {
+            let config = DecayConfig {
+                curve,
+                reinforcement_bonus: 0.0,
+                ..Default::default()
+            };
+            // Ten one-minute cycles for a concept last accessed an hour ago
+            let (hour, minute) = (3_600.0, 60.0);
+            let mut strength = 0.9;
+            for cycle in 1..=10 {
+                let age = hour + cycle as f64 * minute;
+                strength = decayed_strength(&config, strength, 0, age - minute, age);
+            }
+            let once = decayed_strength(&config, 0.9, 0, hour, hour + 10.0 * minute);
+            assert!(
+                (strength - once).abs() < 1e-5,
+                "{:?}: {} after ten cycles, {} at once",
+                curve,
+                strength,
+                once
+            );
+            let expected = 0.9 * curve.factor(config.decay_rate, hour + 10.0 * minute)
+                / curve.factor(config.decay_rate, hour);
+            assert!((strength as f64 - expected).abs() < 1e-5);
+        }
+    }
+}
@@ -5,7 +5,13 @@
 
 use crate::concurrent_memory::ConcurrentMemory;
 use crate::types::ConceptId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Source for feedback from a caller the server cannot identify
+pub const ANONYMOUS_SOURCE: &str = "anonymous";
 
 /// Configuration for feedback processing
 #[derive(Debug, Clone)]
@@ -16,6 +22,19 @@ pub struct FeedbackConfig {
     pub reject_penalty: f32,
     /// Maximum proportional ranking boost
     pub max_ranking_boost: f32,
+    /// How quickly repeated signals from one source lose effect: a source's
+    /// net vote `n` is worth `1 + repeat_diminishing * ln(n)` signals
+    pub repeat_diminishing: f32,
+    /// Largest strength change one source can cause on one concept
+    pub source_cap: f32,
+    /// Extra weight per agreeing source: with `k` other sources already
+    /// voting the same way a signal is scaled by `1 + agreement_bonus * ln(1 + k)`
+    pub agreement_bonus: f32,
+    /// A source's vote on a concept is forgotten after this long without a
+    /// new signal; its past effect on strength stays
+    pub vote_ttl: Duration,
+    /// Most (concept, source) votes tracked; the stalest are forgotten first
+    pub max_tracked_votes: usize,
 }
 
 impl Default for FeedbackConfig {
@@ -24,22 +43,98 @@ impl Default for FeedbackConfig {
             accept_boost: 0.1,
             reject_penalty: 0.05,
             max_ranking_boost: 0.15,
+            repeat_diminishing: 1.0,
+            source_cap: 0.3,
+            agreement_bonus: 0.5,
+            vote_ttl: Duration::from_secs(24 * 3600),
+            max_tracked_votes: 100_000,
         }
     }
 }
 
+/// One source's standing on one concept
+#[derive(Debug, Clone, Copy)]
+struct SourceVote {
+    /// Accepts minus rejects
+    net: i32,
+    /// Strength change this source has caused so far
+    applied: f32,
+    /// When the source last signalled on this concept
+    last_seen: Instant,
+}
+
+impl SourceVote {
+    fn new(now: Instant) -> Self {
+        Self {
+            net: 0,
+            applied: 0.0,
+            last_seen: now,
+        }
+    }
+}
+
+/// Votes by concept and source
+#[derive(Default)]
+struct Votes {
+    by_concept: HashMap<ConceptId, HashMap<String, SourceVote>>,
+    /// Total (concept, source) entries in `by_concept`
+    tracked: usize,
+}
+
+impl Votes {
+    /// Forget expired votes, then the stalest ones until at most `max` remain
+    fn prune(&mut self, now: Instant, ttl: Duration, max: usize) {
+        let mut tracked = 0;
+        self.by_concept.retain(|_, sources| {
+            sources.retain(|_, vote| now.duration_since(vote.last_seen) < ttl);
+            tracked += sources.len();
+            !sources.is_empty()
+        });
+        self.tracked = tracked;
+        if self.tracked <= max {
+            return;
+        }
+
+        let mut seen: Vec<Instant> = self
+            .by_concept
+            .values()
+            .flat_map(|sources| sources.values().map(|vote| vote.last_seen))
+            .collect();
+        let excess = self.tracked - max;
+        let (_, &mut cutoff, _) = seen.select_nth_unstable(excess - 1);
+        // Ties at the cutoff may take a few more than `excess` with them
+        self.by_concept.retain(|_, sources| {
+            sources.retain(|_, vote| vote.last_seen > cutoff);
+            !sources.is_empty()
+        });
+        self.tracked = self.by_concept.values().map(HashMap::len).sum();
+    }
+}
+
 /// Processes feedback signals to adjust concept strengths
+///
+/// Each source's effect on a concept follows its net vote rather than the
+/// raw signal count, so a source that accepts and then rejects a concept
+/// leaves its strength where it started, and repeating the same signal
+/// only nudges it further, up to `source_cap`. Votes are kept for
+/// `vote_ttl` and at most `max_tracked_votes` of them at once.
 pub struct FeedbackProcessor {
     config: FeedbackConfig,
+    votes: Mutex<Votes>,
 }
 
 impl FeedbackProcessor {
     pub fn new(config: FeedbackConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            votes: Mutex::new(Votes::default()),
+        }
     }
 
     /// Process feedback for a set of query results.
     ///
+    /// - `source`: who gave the feedback (the caller's identity or address);
+    ///   repeats from one source diminish
     /// - `result_concept_ids`: IDs of concepts returned in a query result
     /// - `accepted`: parallel bool vec indicating whether each result was accepted
     /// - `ranking`: optional ranking order (lower = better) for accepted results
@@ -48,12 +143,15 @@ impl FeedbackProcessor {
     pub fn process(
         &self,
         storage: &Arc<ConcurrentMemory>,
+        source: &str,
         result_concept_ids: &[String],
         accepted: &[bool],
         ranking: Option<&[u32]>,
     ) -> usize {
         let mut adjustments = 0;
         let snapshot = storage.get_snapshot();
+        let now = Instant::now();
+        let mut votes = self.votes.lock();
 
         for (i, id_str) in result_concept_ids.iter().enumerate() {
             let concept_id = ConceptId::from_string(id_str);
@@ -65,7 +163,8 @@ impl FeedbackProcessor {
 
             let is_accepted = accepted.get(i).copied().unwrap_or(false);
 
-            if is_accepted {
+            // Weight of a single signal
+            let unit = if is_accepted {
                 // Base boost for accepted results
                 let mut boost = self.config.accept_boost;
 
@@ -80,25 +179,217 @@ impl FeedbackProcessor {
                         }
                     }
                 }
+                boost
+            } else {
+                self.config.reject_penalty
+            };
 
-                let new_strength = (current_strength + boost).min(1.0);
-                if (new_strength - current_strength).abs() > 0.001 {
-                    let _ = storage.update_strength(concept_id, new_strength);
-                    adjustments += 1;
-                }
+            let sources = votes.by_concept.entry(concept_id).or_default();
+            let mut vote = match sources.get(source) {
+                Some(vote) if now.duration_since(vote.last_seen) < self.config.vote_ttl => *vote,
+                _ => SourceVote::new(now),
+            };
+            vote.net += if is_accepted { 1 } else { -1 };
+            vote.last_seen = now;
+
+            let agreeing = sources
+                .iter()
+                .filter(|(other, v)| {
+                    other.as_str() != source && v.net.signum() == vote.net.signum()
+                })
+                .count();
+            let target = self.source_contribution(vote.net, unit, agreeing);
+
+            let new_strength = (current_strength + target - vote.applied).clamp(0.0, 1.0);
+            if (new_strength - current_strength).abs() > 0.001 {
+                let _ = storage.update_strength(concept_id, new_strength);
+                vote.applied += new_strength - current_strength;
+                adjustments += 1;
+            }
+            if sources.insert(source.to_string(), vote).is_none() {
+                votes.tracked += 1;
+            }
 
+            if is_accepted {
                 // Record access for accepted results
-                let _ = storage.record_access(concept_id);
-            } else {
-                // Penalize rejected results
-                let new_strength = (current_strength - self.config.reject_penalty).max(0.0);
-                if (new_strength - current_strength).abs() > 0.001 {
-                    let _ = storage.update_strength(concept_id, new_strength);
-                    adjustments += 1;
-                }
+                storage.record_access(concept_id);
             }
         }
 
+        if votes.tracked > self.config.max_tracked_votes {
+            votes.prune(now, self.config.vote_ttl, self.config.max_tracked_votes);
+        }
         adjustments
     }
+
+    /// Total strength change a source with net vote `net` should have caused
+    fn source_contribution(&self, net: i32, unit: f32, agreeing: usize) -> f32 {
+        if net == 0 {
+            return 0.0;
+        }
+        let repeats = 1.0 + self.config.repeat_diminishing * (net.unsigned_abs() as f32).ln();
+        let agreement = 1.0 + self.config.agreement_bonus * (1.0 + agreeing as f32).ln();
+        let magnitude = (unit * repeats * agreement).min(self.config.source_cap);
+        magnitude.copysign(net as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent_memory::ConcurrentConfig;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn storage_with_concept(dir: &TempDir, strength: f32) -> (Arc<ConcurrentMemory>, String) {
+        let storage = Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        }));
+        let id = ConceptId::from_string("f00d");
+        storage
+            .learn_concept(id, b"answer".to_vec(), None, strength, 0.9, HashMap::new())
+            .unwrap();
+        while storage.query_concept(&id).is_none() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        (storage, id.to_hex())
+    }
+
+    /// Wait for the write of `expected` to land and return it
+    fn strength(storage: &ConcurrentMemory, id: &str, expected: f32) -> f32 {
+        let id = ConceptId::from_string(id);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let current = storage.get_snapshot().get_concept(&id).unwrap().strength;
+            if (current - expected).abs() < 1e-4 || Instant::now() > deadline {
+                return current;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Send one signal and wait for it to apply, returning the new strength
+    ///
+    /// The processor reads strength from the snapshot, so each update must be
+    /// visible before the next signal.
+    fn signal(
+        processor: &FeedbackProcessor,
+        storage: &Arc<ConcurrentMemory>,
+        source: &str,
+        id: &str,
+        accept: bool,
+    ) -> f32 {
+        processor.process(storage, source, &[id.to_string()], &[accept], None);
+        let applied: f32 = processor.votes.lock().by_concept[&ConceptId::from_string(id)]
+            .values()
+            .map(|v| v.applied)
+            .sum();
+        strength(storage, id, START + applied)
+    }
+
+    const START: f32 = 0.2;
+
+    #[test]
+    fn test_single_source_saturates() {
+        let dir = TempDir::new().unwrap();
+        let (storage, id) = storage_with_concept(&dir, START);
+        let processor = FeedbackProcessor::new(FeedbackConfig::default());
+
+        let mut current = START;
+        let mut gains = Vec::new();
+        for _ in 0..30 {
+            let next = signal(&processor, &storage, "alice", &id, true);
+            gains.push(next - current);
+            current = next;
+        }
+
+        assert!((gains[0] - 0.1).abs() < 1e-4);
+        assert!(gains.windows(2).all(|w| w[1] <= w[0] + 1e-4));
+        assert!(current - START <= 0.3 + 1e-4, "capped, got {}", current);
+        assert!(gains[29].abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_alternating_signals_cancel_out() {
+        let dir = TempDir::new().unwrap();
+        let (storage, id) = storage_with_concept(&dir, START);
+        let processor = FeedbackProcessor::new(FeedbackConfig::default());
+
+        let mut current = START;
+        for i in 0..20 {
+            current = signal(&processor, &storage, "bob", &id, i % 2 == 0);
+            assert!(
+                (current - START).abs() <= 0.1 + 1e-4,
+                "swung to {}",
+                current
+            );
+        }
+        assert!((current - START).abs() < 1e-4, "drifted to {}", current);
+    }
+
+    #[test]
+    fn test_agreeing_sources_outweigh_one_repeating_source() {
+        let dir = TempDir::new().unwrap();
+        let (storage, id) = storage_with_concept(&dir, START);
+        let processor = FeedbackProcessor::new(FeedbackConfig::default());
+        let mut single = START;
+        for _ in 0..3 {
+            single = signal(&processor, &storage, "carol", &id, true);
+        }
+
+        let dir = TempDir::new().unwrap();
+        let (storage, id) = storage_with_concept(&dir, START);
+        let processor = FeedbackProcessor::new(FeedbackConfig::default());
+        let mut diverse = START;
+        for source in ["dave", "erin", "frank"] {
+            diverse = signal(&processor, &storage, source, &id, true);
+        }
+
+        assert!(
+            diverse - START > 3.0 * 0.1,
+            "three sources should beat three plain boosts, got {}",
+            diverse - START
+        );
+        assert!(diverse - START > 1.5 * (single - START));
+    }
+
+    #[test]
+    fn test_votes_expire_and_are_capped() {
+        let dir = TempDir::new().unwrap();
+        let (storage, id) = storage_with_concept(&dir, START);
+        let processor = FeedbackProcessor::new(FeedbackConfig {
+            max_tracked_votes: 10,
+            vote_ttl: Duration::from_millis(200),
+            ..Default::default()
+        });
+
+        for i in 0..25 {
+            processor.process(
+                &storage,
+                &format!("peer{}", i),
+                std::slice::from_ref(&id),
+                &[true],
+                None,
+            );
+            assert!(processor.votes.lock().tracked <= 10);
+        }
+        let votes = processor.votes.lock();
+        let sources = &votes.by_concept[&ConceptId::from_string(&id)];
+        assert_eq!(votes.tracked, sources.len());
+        // The newest voters are the ones remembered
+        assert!(sources.contains_key("peer24"));
+        assert!(!sources.contains_key("peer0"));
+        drop(votes);
+
+        // An expired vote starts over instead of counting as a repeat
+        let vote = |processor: &FeedbackProcessor| {
+            processor.votes.lock().by_concept[&ConceptId::from_string(&id)]["peer24"]
+        };
+        processor.process(&storage, "peer24", std::slice::from_ref(&id), &[true], None);
+        assert_eq!(vote(&processor).net, 2);
+        std::thread::sleep(Duration::from_millis(250));
+        processor.process(&storage, "peer24", std::slice::from_ref(&id), &[true], None);
+        assert_eq!(vote(&processor).net, 1);
+    }
 }
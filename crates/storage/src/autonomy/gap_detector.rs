@@ -1,11 +1,13 @@
 //! Gap Detection
 //!
 //! Identifies knowledge gaps: isolated concepts, near-miss pairs (similar but
-//! not connected), and incomplete causal chains. Stores gaps as concepts and
-//! optionally notifies through the subscription system.
+//! not connected), and incomplete causal chains. The background loop stores
+//! gaps as concepts and optionally notifies through the subscription system;
+//! `detect_gaps` runs the same analysis on demand for the `GetGaps` request.
 
 use super::subscriptions::SubscriptionManager;
 use crate::concurrent_memory::ConcurrentMemory;
+use crate::read_view::ConceptNode;
 use crate::semantic::{DomainContext, SemanticMetadata, SemanticType};
 use crate::types::ConceptId;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -43,6 +45,76 @@ impl Default for GapDetectorConfig {
     }
 }
 
+/// Kind of knowledge gap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GapKind {
+    /// Concept with fewer neighbors than the isolation threshold
+    Isolated,
+    /// Similar concepts that are not connected
+    NearMiss,
+    /// Causal concept with no connections
+    IncompleteChain,
+}
+
+impl GapKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GapKind::Isolated => "isolated",
+            GapKind::NearMiss => "near_miss",
+            GapKind::IncompleteChain => "incomplete_chain",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "isolated" => Some(GapKind::Isolated),
+            "near_miss" => Some(GapKind::NearMiss),
+            "incomplete_chain" => Some(GapKind::IncompleteChain),
+            _ => None,
+        }
+    }
+}
+
+/// A detected knowledge gap
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapReport {
+    pub kind: GapKind,
+    /// Concepts involved: one for isolated/incomplete_chain, two for near_miss
+    pub concept_ids: Vec<ConceptId>,
+    /// 0.0-1.0, higher is more severe
+    pub severity: f32,
+}
+
+impl GapReport {
+    /// Content stored for the gap concept
+    fn describe(&self, concept: &ConceptNode) -> String {
+        let snippet = || {
+            String::from_utf8_lossy(&concept.content)
+                .chars()
+                .take(100)
+                .collect::<String>()
+        };
+        match self.kind {
+            GapKind::Isolated => format!(
+                "Knowledge gap: isolated concept '{}' (id={})",
+                snippet(),
+                concept.id.to_hex()
+            ),
+            GapKind::NearMiss => format!(
+                "Knowledge gap: near-miss pair {} <-> {} (similarity={:.3})",
+                self.concept_ids[0].to_hex(),
+                self.concept_ids[1].to_hex(),
+                self.severity
+            ),
+            GapKind::IncompleteChain => format!(
+                "Knowledge gap: causal leaf node '{}' (id={}) has no connections",
+                snippet(),
+                concept.id.to_hex()
+            ),
+        }
+    }
+}
+
 /// Background gap detection loop handle
 pub struct GapDetectorLoop {
     running: Arc<AtomicBool>,
@@ -114,68 +186,19 @@ fn gap_detection_loop(
                 break;
             }
 
-            // Skip system-generated concepts
-            if concept.attributes.contains_key("sutra:source") {
+            if is_system_concept(concept) {
                 continue;
             }
 
             processed += 1;
 
-            // Detect isolated concepts
-            if concept.neighbors.len() < config.isolation_threshold {
-                let content = format!(
-                    "Knowledge gap: isolated concept '{}' (id={})",
-                    String::from_utf8_lossy(&concept.content)
-                        .chars()
-                        .take(100)
-                        .collect::<String>(),
-                    concept.id.to_hex()
-                );
-
-                store_gap(&storage, &content, &subscriptions);
-                isolated += 1;
-            }
-
-            // Detect near-miss pairs via vector search
-            if let Some(ref vector) = concept.vector {
-                let results = storage.vector_search(vector, 5, 50);
-
-                for (neighbor_id, similarity) in results {
-                    if neighbor_id == concept.id {
-                        continue;
-                    }
-
-                    if similarity >= config.near_miss_low
-                        && similarity < config.near_miss_high
-                        && !concept.neighbors.contains(&neighbor_id)
-                    {
-                        let content = format!(
-                            "Knowledge gap: near-miss pair {} <-> {} (similarity={:.3})",
-                            concept.id.to_hex(),
-                            neighbor_id.to_hex(),
-                            similarity
-                        );
-
-                        store_gap(&storage, &content, &subscriptions);
-                        near_misses += 1;
-                    }
-                }
-            }
-
-            // Detect incomplete causal chains
-            if let Some(ref semantic) = concept.semantic {
-                if semantic.semantic_type == SemanticType::Causal && concept.neighbors.is_empty() {
-                    let content = format!(
-                        "Knowledge gap: causal leaf node '{}' (id={}) has no connections",
-                        String::from_utf8_lossy(&concept.content)
-                            .chars()
-                            .take(100)
-                            .collect::<String>(),
-                        concept.id.to_hex()
-                    );
-
-                    store_gap(&storage, &content, &subscriptions);
+            for gap in concept_gaps(&storage, &config, concept, &[]) {
+                match gap.kind {
+                    GapKind::Isolated => isolated += 1,
+                    GapKind::NearMiss => near_misses += 1,
+                    GapKind::IncompleteChain => {}
                 }
+                store_gap(&storage, &gap.describe(concept), &subscriptions);
             }
         }
 
@@ -192,6 +215,91 @@ fn gap_detection_loop(
     log::info!("Gap detector loop stopped");
 }
 
+/// Gaps are not reported for concepts the autonomy engine wrote itself
+fn is_system_concept(concept: &ConceptNode) -> bool {
+    concept.attributes.contains_key("sutra:source")
+}
+
+/// Analyze up to `config.sample_size` concepts and return their gaps
+///
+/// An empty `kinds` reports every kind. Reports are ordered most severe
+/// first and truncated to `limit`.
+pub fn detect_gaps(
+    storage: &ConcurrentMemory,
+    config: &GapDetectorConfig,
+    kinds: &[GapKind],
+    limit: usize,
+) -> Vec<GapReport> {
+    let snapshot = storage.get_snapshot();
+    let mut gaps: Vec<GapReport> = snapshot
+        .concepts
+        .values()
+        .filter(|concept| !is_system_concept(concept))
+        .take(config.sample_size)
+        .flat_map(|concept| concept_gaps(storage, config, concept, kinds))
+        .collect();
+
+    gaps.sort_by(|a, b| b.severity.total_cmp(&a.severity));
+    gaps.truncate(limit);
+    gaps
+}
+
+/// Gaps involving a single concept
+fn concept_gaps(
+    storage: &ConcurrentMemory,
+    config: &GapDetectorConfig,
+    concept: &ConceptNode,
+    kinds: &[GapKind],
+) -> Vec<GapReport> {
+    let wanted = |kind: GapKind| kinds.is_empty() || kinds.contains(&kind);
+    let mut gaps = Vec::new();
+
+    // Severity is the fraction of expected neighbors that are missing
+    if wanted(GapKind::Isolated) && concept.neighbors.len() < config.isolation_threshold {
+        let missing = config.isolation_threshold - concept.neighbors.len();
+        gaps.push(GapReport {
+            kind: GapKind::Isolated,
+            concept_ids: vec![concept.id],
+            severity: missing as f32 / config.isolation_threshold as f32,
+        });
+    }
+
+    // Similar but unconnected pairs; the closer the pair, the more likely a
+    // link is missing, so severity is the similarity itself
+    if wanted(GapKind::NearMiss) {
+        if let Some(ref vector) = concept.vector {
+            for (neighbor_id, similarity) in storage.vector_search(vector, 5, 50) {
+                if neighbor_id != concept.id
+                    && similarity >= config.near_miss_low
+                    && similarity < config.near_miss_high
+                    && !concept.neighbors.contains(&neighbor_id)
+                {
+                    gaps.push(GapReport {
+                        kind: GapKind::NearMiss,
+                        concept_ids: vec![concept.id, neighbor_id],
+                        severity: similarity,
+                    });
+                }
+            }
+        }
+    }
+
+    // Causal concepts with nothing on either side of them
+    if wanted(GapKind::IncompleteChain) {
+        if let Some(ref semantic) = concept.semantic {
+            if semantic.semantic_type == SemanticType::Causal && concept.neighbors.is_empty() {
+                gaps.push(GapReport {
+                    kind: GapKind::IncompleteChain,
+                    concept_ids: vec![concept.id],
+                    severity: semantic.classification_confidence,
+                });
+            }
+        }
+    }
+
+    gaps
+}
+
 fn store_gap(
     storage: &Arc<ConcurrentMemory>,
     content: &str,
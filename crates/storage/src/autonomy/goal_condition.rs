@@ -0,0 +1,571 @@
+//! Goal Condition Expressions
+//!
+//! A small expression language for goal conditions:
+//!
+//! ```text
+//! concept_count > 1000 and reconciler_health < 0.5
+//! concept("a1b2").strength <= 0.2 or (association_count >= 50 and hnsw_vectors != 0)
+//! concept("a1b2").status == "done"
+//! ```
+//!
+//! Operands are numbers, quoted strings, engine stats (see [`STAT_NAMES`]) and
+//! concept fields. `concept("<id>").strength`, `.confidence`, `.access_count`
+//! and `.neighbors` (edge count) read the concept itself; any other field name
+//! reads that attribute. `and` binds tighter than `or`. Parentheses nest at
+//! most [`MAX_NESTING`] deep and a condition holds at most [`MAX_COMPARISONS`]
+//! comparisons, so parsing and evaluation never recurse without bound.
+
+use crate::concurrent_memory::ConcurrentMemory;
+use crate::read_view::GraphSnapshot;
+use crate::types::ConceptId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Engine stats a condition can reference
+pub const STAT_NAMES: &[&str] = &[
+    "concept_count",
+    "association_count",
+    "write_log_pending",
+    "write_log_dropped",
+    "reconciler_health",
+    "hnsw_vectors",
+];
+
+/// Deepest parenthesis nesting a condition may use
+pub const MAX_NESTING: usize = 16;
+
+/// Most comparisons one condition may hold
+pub const MAX_COMPARISONS: usize = 64;
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+}
+
+/// One side of a comparison
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Operand {
+    Number(f64),
+    Text(String),
+    /// One of [`STAT_NAMES`]
+    Stat(String),
+    /// A concept field or attribute
+    Concept {
+        concept_id: String,
+        field: String,
+    },
+}
+
+/// Parsed condition expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConditionExpr {
+    And(Box<ConditionExpr>, Box<ConditionExpr>),
+    Or(Box<ConditionExpr>, Box<ConditionExpr>),
+    Compare {
+        left: Operand,
+        op: CompareOp,
+        right: Operand,
+    },
+}
+
+/// Syntax error in a condition expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionError {
+    /// Byte offset of the offending token
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ConditionError {}
+
+/// Current values of [`STAT_NAMES`] for `storage`
+pub fn engine_stats(storage: &ConcurrentMemory) -> HashMap<&'static str, f64> {
+    let stats = storage.stats();
+    HashMap::from([
+        ("concept_count", stats.snapshot.concept_count as f64),
+        ("association_count", stats.snapshot.edge_count as f64),
+        ("write_log_pending", stats.write_log.pending as f64),
+        ("write_log_dropped", stats.write_log.dropped as f64),
+        ("reconciler_health", stats.reconciler.health_score),
+        ("hnsw_vectors", storage.hnsw_stats().indexed_vectors as f64),
+    ])
+}
+
+impl ConditionExpr {
+    pub fn parse(input: &str) -> Result<Self, ConditionError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: input.len(),
+            depth: 0,
+            comparisons: 0,
+        };
+        let expr = parser.or_expr()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((at, token)) => Err(ConditionError {
+                position: *at,
+                message: format!("Unexpected {}", token.describe()),
+            }),
+        }
+    }
+
+    /// Evaluate against a snapshot and the stats from [`engine_stats`]
+    ///
+    /// A comparison involving a missing concept or attribute is false.
+    pub fn evaluate(&self, snapshot: &GraphSnapshot, stats: &HashMap<&'static str, f64>) -> bool {
+        match self {
+            ConditionExpr::And(a, b) => a.evaluate(snapshot, stats) && b.evaluate(snapshot, stats),
+            ConditionExpr::Or(a, b) => a.evaluate(snapshot, stats) || b.evaluate(snapshot, stats),
+            ConditionExpr::Compare { left, op, right } => {
+                match (
+                    resolve(left, snapshot, stats),
+                    resolve(right, snapshot, stats),
+                ) {
+                    (Some(l), Some(r)) => compare(&l, *op, &r),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Text(s) => s.trim().parse().ok(),
+        }
+    }
+}
+
+fn resolve(
+    operand: &Operand,
+    snapshot: &GraphSnapshot,
+    stats: &HashMap<&'static str, f64>,
+) -> Option<Value> {
+    match operand {
+        Operand::Number(n) => Some(Value::Number(*n)),
+        Operand::Text(s) => Some(Value::Text(s.clone())),
+        Operand::Stat(name) => stats.get(name.as_str()).copied().map(Value::Number),
+        Operand::Concept { concept_id, field } => {
+            let concept = snapshot.get_concept(&ConceptId::from_string(concept_id))?;
+            match field.as_str() {
+                "strength" => Some(Value::Number(concept.strength as f64)),
+                "confidence" => Some(Value::Number(concept.confidence as f64)),
+                "access_count" => Some(Value::Number(concept.access_count as f64)),
+                "neighbors" => Some(Value::Number(concept.neighbors.len() as f64)),
+                attribute => concept.attributes.get(attribute).cloned().map(Value::Text),
+            }
+        }
+    }
+}
+
+/// Numeric comparison when both sides are numbers, string comparison otherwise
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    let ordering = match (left.as_number(), right.as_number()) {
+        (Some(l), Some(r)) => l.partial_cmp(&r),
+        _ => match (left, right) {
+            (Value::Text(l), Value::Text(r)) => Some(l.cmp(r)),
+            _ => None,
+        },
+    };
+    let Some(ordering) = ordering else {
+        return op == CompareOp::Ne;
+    };
+    match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::Ne => ordering.is_ne(),
+        CompareOp::Gt => ordering.is_gt(),
+        CompareOp::Lt => ordering.is_lt(),
+        CompareOp::Ge => ordering.is_ge(),
+        CompareOp::Le => ordering.is_le(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Ident(String),
+    Op(CompareOp),
+    And,
+    Or,
+    LParen,
+    RParen,
+    Dot,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(n) => format!("number {}", n),
+            Token::Text(s) => format!("string \"{}\"", s),
+            Token::Ident(s) => format!("'{}'", s),
+            Token::Op(_) => "comparison operator".to_string(),
+            Token::And => "'and'".to_string(),
+            Token::Or => "'or'".to_string(),
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+            Token::Dot => "'.'".to_string(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ConditionError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let error = |message: String| ConditionError {
+            position: start,
+            message,
+        };
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token = match c {
+            b'(' => {
+                i += 1;
+                Token::LParen
+            }
+            b')' => {
+                i += 1;
+                Token::RParen
+            }
+            b'.' => {
+                i += 1;
+                Token::Dot
+            }
+            b'<' | b'>' | b'=' | b'!' => {
+                let two = bytes.get(i + 1) == Some(&b'=');
+                let op = match (c, two) {
+                    (b'<', true) => CompareOp::Le,
+                    (b'<', false) => CompareOp::Lt,
+                    (b'>', true) => CompareOp::Ge,
+                    (b'>', false) => CompareOp::Gt,
+                    (b'=', true) => CompareOp::Eq,
+                    (b'!', true) => CompareOp::Ne,
+                    _ => return Err(error(format!("Unknown operator '{}'", c as char))),
+                };
+                i += if two { 2 } else { 1 };
+                Token::Op(op)
+            }
+            b'"' | b'\'' => {
+                let end = input[i + 1..]
+                    .find(c as char)
+                    .ok_or_else(|| error("Unterminated string".to_string()))?;
+                let text = input[i + 1..i + 1 + end].to_string();
+                i += end + 2;
+                Token::Text(text)
+            }
+            b'0'..=b'9' | b'-' => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                let literal = &input[start..i];
+                Token::Number(
+                    literal
+                        .parse()
+                        .map_err(|_| error(format!("Invalid number '{}'", literal)))?,
+                )
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'_' | b':'))
+                {
+                    i += 1;
+                }
+                let word = &input[start..i];
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    _ => Token::Ident(word.to_string()),
+                }
+            }
+            _ => {
+                let ch = input[i..].chars().next().unwrap_or('?');
+                return Err(error(format!("Unexpected character '{}'", ch)));
+            }
+        };
+        tokens.push((start, token));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Input length, reported for errors at end of input
+    end: usize,
+    /// Open parentheses around the current position
+    depth: usize,
+    /// Comparisons parsed so far
+    comparisons: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(usize, Token)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<(usize, Token), ConditionError> {
+        let token = self.tokens.get(self.pos).cloned().ok_or(ConditionError {
+            position: self.end,
+            message: format!("Expected {}, found end of condition", expected),
+        })?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), ConditionError> {
+        let (at, found) = self.next(expected)?;
+        if found == token {
+            Ok(())
+        } else {
+            Err(ConditionError {
+                position: at,
+                message: format!("Expected {}, found {}", expected, found.describe()),
+            })
+        }
+    }
+
+    fn or_expr(&mut self) -> Result<ConditionExpr, ConditionError> {
+        let mut expr = self.and_expr()?;
+        while matches!(self.peek(), Some((_, Token::Or))) {
+            self.pos += 1;
+            expr = ConditionExpr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<ConditionExpr, ConditionError> {
+        let mut expr = self.primary()?;
+        while matches!(self.peek(), Some((_, Token::And))) {
+            self.pos += 1;
+            expr = ConditionExpr::And(Box::new(expr), Box::new(self.primary()?));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<ConditionExpr, ConditionError> {
+        if let Some(&(at, Token::LParen)) = self.peek() {
+            if self.depth == MAX_NESTING {
+                return Err(ConditionError {
+                    position: at,
+                    message: format!("Parentheses nested deeper than {}", MAX_NESTING),
+                });
+            }
+            self.pos += 1;
+            self.depth += 1;
+            let expr = self.or_expr()?;
+            self.expect(Token::RParen, "')'")?;
+            self.depth -= 1;
+            return Ok(expr);
+        }
+
+        if self.comparisons == MAX_COMPARISONS {
+            return Err(ConditionError {
+                position: self.peek().map_or(self.end, |(at, _)| *at),
+                message: format!("More than {} comparisons", MAX_COMPARISONS),
+            });
+        }
+        self.comparisons += 1;
+        let left = self.operand()?;
+        let op = match self.next("comparison operator")? {
+            (_, Token::Op(op)) => op,
+            (at, found) => {
+                return Err(ConditionError {
+                    position: at,
+                    message: format!("Expected comparison operator, found {}", found.describe()),
+                })
+            }
+        };
+        let right = self.operand()?;
+        Ok(ConditionExpr::Compare { left, op, right })
+    }
+
+    fn operand(&mut self) -> Result<Operand, ConditionError> {
+        match self.next("operand")? {
+            (_, Token::Number(n)) => Ok(Operand::Number(n)),
+            (_, Token::Text(s)) => Ok(Operand::Text(s)),
+            (_, Token::Ident(name)) if name.eq_ignore_ascii_case("concept") => {
+                self.expect(Token::LParen, "'(' after 'concept'")?;
+                let concept_id = match self.next("quoted concept id")? {
+                    (_, Token::Text(id)) => id,
+                    (at, found) => {
+                        return Err(ConditionError {
+                            position: at,
+                            message: format!(
+                                "Expected quoted concept id, found {}",
+                                found.describe()
+                            ),
+                        })
+                    }
+                };
+                self.expect(Token::RParen, "')'")?;
+                self.expect(Token::Dot, "'.' and a field name")?;
+                match self.next("field name")? {
+                    (_, Token::Ident(field)) => Ok(Operand::Concept { concept_id, field }),
+                    (at, found) => Err(ConditionError {
+                        position: at,
+                        message: format!("Expected field name, found {}", found.describe()),
+                    }),
+                }
+            }
+            (at, Token::Ident(name)) => {
+                if STAT_NAMES.contains(&name.as_str()) {
+                    Ok(Operand::Stat(name))
+                } else {
+                    Err(ConditionError {
+                        position: at,
+                        message: format!(
+                            "Unknown stat '{}' (expected one of {})",
+                            name,
+                            STAT_NAMES.join(", ")
+                        ),
+                    })
+                }
+            }
+            (at, found) => Err(ConditionError {
+                position: at,
+                message: format!("Expected operand, found {}", found.describe()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent_memory::ConcurrentConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compound_condition_needs_both_clauses() {
+        let dir = TempDir::new().unwrap();
+        let storage = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let snapshot = storage.get_snapshot();
+
+        let expr =
+            ConditionExpr::parse("concept_count > 1000 and reconciler_health < 0.5").unwrap();
+        let stats = |concepts: f64, health: f64| {
+            HashMap::from([("concept_count", concepts), ("reconciler_health", health)])
+        };
+        assert!(expr.evaluate(&snapshot, &stats(2000.0, 0.3)));
+        assert!(!expr.evaluate(&snapshot, &stats(2000.0, 0.9)));
+        assert!(!expr.evaluate(&snapshot, &stats(10.0, 0.3)));
+
+        let either =
+            ConditionExpr::parse("concept_count > 1000 or reconciler_health < 0.5").unwrap();
+        assert!(either.evaluate(&snapshot, &stats(10.0, 0.3)));
+        assert!(!either.evaluate(&snapshot, &stats(10.0, 0.9)));
+    }
+
+    #[test]
+    fn test_concept_fields_and_attributes() {
+        let dir = TempDir::new().unwrap();
+        let storage = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let id = ConceptId::from_string("a1");
+        let attributes = HashMap::from([
+            ("priority".to_string(), "5".to_string()),
+            ("status".to_string(), "open".to_string()),
+        ]);
+        storage
+            .learn_concept(id, b"task".to_vec(), None, 0.4, 0.9, attributes)
+            .unwrap();
+        while storage.query_concept(&id).is_none() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let snapshot = storage.get_snapshot();
+        let stats = engine_stats(&storage);
+
+        let holds = |s: &str| ConditionExpr::parse(s).unwrap().evaluate(&snapshot, &stats);
+        assert!(holds(
+            r#"concept("a1").priority >= 5 and concept("a1").strength < 0.5"#
+        ));
+        assert!(holds(
+            r#"concept("a1").status == "open" and concept_count == 1"#
+        ));
+        assert!(holds(r#"concept("a1").status != 'done'"#));
+        assert!(!holds(r#"concept("a1").priority > 5"#));
+        // Missing concepts and attributes never satisfy a comparison
+        assert!(!holds(r#"concept("ff").strength < 1"#));
+        assert!(!holds(r#"concept("a1").owner != "x""#));
+        assert!(holds(
+            r#"concept("ff").strength < 1 or (concept("a1").neighbors == 0 and association_count <= 0)"#
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        let err = ConditionExpr::parse("concept_count > 1000 and").unwrap_err();
+        assert_eq!(err.position, 24);
+        assert!(err.message.contains("end of condition"), "{}", err);
+
+        let err = ConditionExpr::parse("concepts > 10").unwrap_err();
+        assert_eq!(err.position, 0);
+        assert!(err.message.contains("Unknown stat"), "{}", err);
+
+        assert!(ConditionExpr::parse("concept_count >> 10").is_err());
+        assert!(ConditionExpr::parse("concept_count = 10").is_err());
+        assert!(ConditionExpr::parse("(concept_count > 10").is_err());
+        assert!(ConditionExpr::parse(r#"concept("a1).strength > 1"#).is_err());
+        assert!(ConditionExpr::parse("concept_count > 10 hnsw_vectors").is_err());
+    }
+
+    #[test]
+    fn test_nesting_and_length_are_bounded() {
+        let nested = |depth: usize| {
+            format!(
+                "{}concept_count > 1{}",
+                "(".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+        assert!(ConditionExpr::parse(&nested(MAX_NESTING)).is_ok());
+        let err = ConditionExpr::parse(&nested(MAX_NESTING + 1)).unwrap_err();
+        assert_eq!(err.position, MAX_NESTING);
+        assert!(err.message.contains("nested"), "{}", err);
+        // Far too deep to recurse into: rejected at the limit, not by overflowing the stack
+        assert!(ConditionExpr::parse(&nested(100_000)).is_err());
+
+        let chain = |clauses: usize| vec!["concept_count > 1"; clauses].join(" and ");
+        assert!(ConditionExpr::parse(&chain(MAX_COMPARISONS)).is_ok());
+        let err = ConditionExpr::parse(&chain(MAX_COMPARISONS + 1)).unwrap_err();
+        assert!(err.message.contains("comparisons"), "{}", err);
+    }
+}
@@ -4,10 +4,12 @@
 //! is serialized as JSON in `attributes["sutra:goal_data"]`.
 //! A background evaluator loop checks goal conditions and triggers actions.
 
+use super::goal_condition::{self, ConditionError, ConditionExpr};
 use crate::concurrent_memory::ConcurrentMemory;
 use crate::semantic::{DomainContext, SemanticMetadata, SemanticType};
 use crate::types::ConceptId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
@@ -42,6 +44,8 @@ pub enum GoalCondition {
     StrengthBelow { concept_id: String, threshold: f32 },
     /// Total association count exceeds threshold
     AssociationCountAbove(usize),
+    /// Comparison expression over stats and concept fields
+    Expression(ConditionExpr),
 }
 
 /// Action to take when a goal condition is met
@@ -81,6 +85,67 @@ pub struct GoalData {
     pub triggered_at: Option<i64>,
 }
 
+impl GoalData {
+    /// Parse a condition string, rejecting malformed expressions
+    ///
+    /// A condition starting with `when:` or holding a comparison operator is
+    /// parsed as a [`ConditionExpr`], so `x != y` is an error rather than a
+    /// content match. "count above N" / "associations above N" and plain
+    /// content matches keep their original meaning.
+    pub fn validate_condition(condition: &str) -> Result<GoalCondition, ConditionError> {
+        let lower = condition.to_lowercase();
+        let start = expression_start(condition).or_else(|| has_comparison(condition).then_some(0));
+        if let Some(start) = start {
+            // Report positions within the whole condition string
+            ConditionExpr::parse(&condition[start..])
+                .map(GoalCondition::Expression)
+                .map_err(|e| ConditionError {
+                    position: start + e.position,
+                    ..e
+                })
+        } else if lower.starts_with("count above") || lower.starts_with("concepts above") {
+            let num = lower
+                .split_whitespace()
+                .last()
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or(100);
+            Ok(GoalCondition::ConceptCountAbove(num))
+        } else if lower.starts_with("associations above") {
+            let num = lower
+                .split_whitespace()
+                .last()
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or(50);
+            Ok(GoalCondition::AssociationCountAbove(num))
+        } else {
+            // Default: treat as content match
+            Ok(GoalCondition::ConceptExists {
+                content_contains: condition.to_string(),
+            })
+        }
+    }
+}
+
+/// Prefix marking a condition as a comparison expression
+const EXPRESSION_MARKER: &str = "when:";
+
+/// Byte offset of the expression after a leading `when:`, if there is one
+fn expression_start(condition: &str) -> Option<usize> {
+    let marker = condition.len() - condition.trim_start().len();
+    let start = marker + EXPRESSION_MARKER.len();
+    condition
+        .get(marker..start)
+        .filter(|prefix| prefix.eq_ignore_ascii_case(EXPRESSION_MARKER))
+        .map(|_| start)
+}
+
+/// Whether `condition` uses one of the expression comparison operators
+fn has_comparison(condition: &str) -> bool {
+    ["<", ">", "==", "!="]
+        .iter()
+        .any(|op| condition.contains(op))
+}
+
 /// Summary info about a goal (for listing)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoalSummary {
@@ -139,8 +204,8 @@ pub fn create_goal(
         .unwrap()
         .as_secs() as i64;
 
-    // Parse condition from string
-    let condition = parse_condition(condition_str);
+    let condition = GoalData::validate_condition(condition_str)
+        .map_err(|e| format!("Invalid goal condition: {}", e))?;
     let action = parse_action(action_str);
 
     let goal_data = GoalData {
@@ -228,7 +293,7 @@ pub fn list_goals(storage: &Arc<ConcurrentMemory>, namespace: Option<&str>) -> V
     }
 
     // Sort by priority descending
-    goals.sort_by(|a, b| b.priority.cmp(&a.priority));
+    goals.sort_by_key(|g| std::cmp::Reverse(g.priority));
     goals
 }
 
@@ -241,30 +306,6 @@ pub fn cancel_goal(storage: &Arc<ConcurrentMemory>, goal_id: &str) -> Result<(),
         .map_err(|e| format!("Failed to cancel goal: {:?}", e))
 }
 
-fn parse_condition(s: &str) -> GoalCondition {
-    let lower = s.to_lowercase();
-    if lower.starts_with("count above") || lower.starts_with("concepts above") {
-        let num = lower
-            .split_whitespace()
-            .last()
-            .and_then(|n| n.parse::<usize>().ok())
-            .unwrap_or(100);
-        GoalCondition::ConceptCountAbove(num)
-    } else if lower.starts_with("associations above") {
-        let num = lower
-            .split_whitespace()
-            .last()
-            .and_then(|n| n.parse::<usize>().ok())
-            .unwrap_or(50);
-        GoalCondition::AssociationCountAbove(num)
-    } else {
-        // Default: treat as content match
-        GoalCondition::ConceptExists {
-            content_contains: s.to_string(),
-        }
-    }
-}
-
 fn parse_action(s: &str) -> GoalAction {
     let lower = s.to_lowercase();
     if lower.starts_with("learn:") || lower.starts_with("learn ") {
@@ -299,6 +340,7 @@ fn goal_evaluator_loop(
         }
 
         let snapshot = storage.get_snapshot();
+        let stats = goal_condition::engine_stats(&storage);
 
         // Find all active goals
         for concept in snapshot.concepts.values() {
@@ -325,7 +367,7 @@ fn goal_evaluator_loop(
             };
 
             // Evaluate condition
-            let condition_met = evaluate_condition(&data.condition, &snapshot);
+            let condition_met = evaluate_condition(&data.condition, &snapshot, &stats);
 
             if condition_met {
                 log::info!(
@@ -370,7 +412,8 @@ fn goal_evaluator_loop(
 
 fn evaluate_condition(
     condition: &GoalCondition,
-    snapshot: &Arc<crate::read_view::GraphSnapshot>,
+    snapshot: &crate::read_view::GraphSnapshot,
+    stats: &HashMap<&'static str, f64>,
 ) -> bool {
     match condition {
         GoalCondition::ConceptExists { content_contains } => {
@@ -392,6 +435,7 @@ fn evaluate_condition(
                 .is_some_and(|c| c.strength < *threshold)
         }
         GoalCondition::AssociationCountAbove(threshold) => snapshot.edge_count > *threshold,
+        GoalCondition::Expression(expr) => expr.evaluate(snapshot, stats),
     }
 }
 
@@ -429,3 +473,61 @@ fn execute_action(action: &GoalAction, storage: &Arc<ConcurrentMemory>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent_memory::ConcurrentConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_malformed_condition_is_rejected_at_creation() {
+        assert!(matches!(
+            GoalData::validate_condition("when: concept_count > 1000 and reconciler_health < 0.5"),
+            Ok(GoalCondition::Expression(ConditionExpr::And(..)))
+        ));
+        // A comparison is an expression even without the marker
+        assert!(matches!(
+            GoalData::validate_condition("concept_count > 1000"),
+            Ok(GoalCondition::Expression(ConditionExpr::Compare { .. }))
+        ));
+        assert_eq!(
+            GoalData::validate_condition("x != y").unwrap_err().position,
+            0
+        );
+        assert!(matches!(
+            GoalData::validate_condition("key=value"),
+            Ok(GoalCondition::ConceptExists { .. })
+        ));
+        let condition = "  WHEN: concept_count >";
+        let err = GoalData::validate_condition(condition).unwrap_err();
+        assert_eq!(err.position, condition.len());
+        assert!(matches!(
+            GoalData::validate_condition("count above 10"),
+            Ok(GoalCondition::ConceptCountAbove(10))
+        ));
+        assert!(matches!(
+            GoalData::validate_condition("rust"),
+            Ok(GoalCondition::ConceptExists { .. })
+        ));
+
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        }));
+        let err = create_goal(
+            &storage,
+            None,
+            "broken",
+            "when: concept_count > and reconciler_health < 0.5",
+            "notify: never",
+            1,
+        )
+        .unwrap_err();
+        assert!(err.starts_with("Invalid goal condition"), "{}", err);
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(storage.get_snapshot().concept_count, 0);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Makes Sutra Engine self-directed through 7 features:
 //! - Knowledge decay (exponential strength decay + reinforcement)
-//! - Self-monitoring (health stats stored as concepts)
+//! - Self-monitoring (health stats stored as concepts in `__metrics__`)
 //! - Background reasoning (association discovery + contradiction detection)
 //! - Goal system (condition/action evaluation)
 //! - Subscriptions (push notifications on concept changes)
@@ -12,17 +12,21 @@
 pub mod decay;
 pub mod feedback;
 pub mod gap_detector;
+pub mod goal_condition;
 pub mod goals;
 pub mod reasoning;
 pub mod self_monitor;
 pub mod subscriptions;
 
-pub use decay::{DecayConfig, DecayLoop};
+pub use decay::{DecayConfig, DecayCurve, DecayLoop};
 pub use feedback::{FeedbackConfig, FeedbackProcessor};
-pub use gap_detector::{GapDetectorConfig, GapDetectorLoop};
+pub use gap_detector::{detect_gaps, GapDetectorConfig, GapDetectorLoop, GapKind, GapReport};
+pub use goal_condition::{ConditionError, ConditionExpr};
 pub use goals::{GoalData, GoalEvaluatorConfig, GoalEvaluatorLoop, GoalSummary};
 pub use reasoning::{ReasoningConfig, ReasoningLoop};
-pub use self_monitor::{SelfMonitorConfig, SelfMonitorLoop};
+pub use self_monitor::{
+    query_metric, MetricPoint, SelfMonitorConfig, SelfMonitorLoop, METRICS_NAMESPACE,
+};
 pub use subscriptions::{SubscriptionConfig, SubscriptionInfo, SubscriptionManager};
 
 use crate::concurrent_memory::ConcurrentMemory;
@@ -104,6 +108,7 @@ impl AutonomyConfig {
 pub struct AutonomyManager {
     config: AutonomyConfig,
     storage: Arc<ConcurrentMemory>,
+    metrics_storage: Arc<ConcurrentMemory>,
     decay_loop: Option<DecayLoop>,
     self_monitor_loop: Option<SelfMonitorLoop>,
     reasoning_loop: Option<ReasoningLoop>,
@@ -120,6 +125,7 @@ impl AutonomyManager {
 
         Self {
             config,
+            metrics_storage: Arc::clone(&storage),
             storage,
             decay_loop: None,
             self_monitor_loop: None,
@@ -131,6 +137,12 @@ impl AutonomyManager {
         }
     }
 
+    /// Store self-monitor samples in `metrics` instead of the monitored storage
+    pub fn with_metrics_storage(mut self, metrics: Arc<ConcurrentMemory>) -> Self {
+        self.metrics_storage = metrics;
+        self
+    }
+
     /// Start all enabled background loops
     pub fn start(&mut self) {
         if !self.config.enabled {
@@ -148,9 +160,10 @@ impl AutonomyManager {
         }
 
         if self.config.self_monitor.enabled {
-            self.self_monitor_loop = Some(SelfMonitorLoop::start(
+            self.self_monitor_loop = Some(SelfMonitorLoop::start_with_metrics(
                 self.config.self_monitor.clone(),
                 Arc::clone(&self.storage),
+                Arc::clone(&self.metrics_storage),
             ));
         }
 
@@ -224,11 +237,21 @@ impl AutonomyManager {
         &self.feedback_processor
     }
 
+    /// Get the gap detector's thresholds (for on-demand gap queries)
+    pub fn gap_detector_config(&self) -> &GapDetectorConfig {
+        &self.config.gap_detector
+    }
+
     /// Get the storage reference (for goal/subscription operations)
     pub fn storage(&self) -> &Arc<ConcurrentMemory> {
         &self.storage
     }
 
+    /// Get the self-monitor time series for `name` between `start` and `end` (ms)
+    pub fn query_metric(&self, name: &str, start: i64, end: i64) -> Vec<MetricPoint> {
+        query_metric(&self.metrics_storage, name, start, end)
+    }
+
     /// Get autonomy stats as a JSON string
     pub fn stats(&self) -> String {
         let snapshot = self.storage.get_snapshot();
@@ -2,18 +2,27 @@
 //!
 //! Background loop that periodically captures engine health stats and stores
 //! them as concepts. Maintains a bounded history by pruning old health snapshots.
+//!
+//! Each tick writes one concept per metric into the metrics storage (normally
+//! the `__metrics__` namespace). Every sample carries the same attribute schema:
+//!
+//! - `metric` — metric name (e.g. `write_log_pending`)
+//! - `ts` — sample time in milliseconds since the Unix epoch
+//! - `value` — sample value formatted as a float
+//!
+//! Use [`query_metric`] to read a time series back for charting.
 
 use crate::concurrent_memory::ConcurrentMemory;
-use crate::semantic::{
-    DomainContext, SemanticMetadata, SemanticType, TemporalBounds, TemporalRelation,
-};
 use crate::types::ConceptId;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+/// Namespace that holds self-monitor samples
+pub const METRICS_NAMESPACE: &str = "__metrics__";
+
 /// Configuration for self-monitoring
 #[derive(Debug, Clone)]
 pub struct SelfMonitorConfig {
@@ -38,6 +47,14 @@ impl Default for SelfMonitorConfig {
     }
 }
 
+/// A single point of a metric time series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricPoint {
+    /// Sample time in milliseconds since the Unix epoch
+    pub ts: i64,
+    pub value: f64,
+}
+
 /// Background self-monitoring loop handle
 pub struct SelfMonitorLoop {
     running: Arc<AtomicBool>,
@@ -45,12 +62,23 @@ pub struct SelfMonitorLoop {
 }
 
 impl SelfMonitorLoop {
+    /// Start monitoring `storage`, writing samples into the same storage
     pub fn start(config: SelfMonitorConfig, storage: Arc<ConcurrentMemory>) -> Self {
+        let metrics = Arc::clone(&storage);
+        Self::start_with_metrics(config, storage, metrics)
+    }
+
+    /// Start monitoring `storage`, writing samples into `metrics`
+    pub fn start_with_metrics(
+        config: SelfMonitorConfig,
+        storage: Arc<ConcurrentMemory>,
+        metrics: Arc<ConcurrentMemory>,
+    ) -> Self {
         let running = Arc::new(AtomicBool::new(true));
         let running_clone = Arc::clone(&running);
 
         let handle = thread::spawn(move || {
-            monitor_loop(config, storage, running_clone);
+            monitor_loop(config, storage, metrics, running_clone);
         });
 
         Self {
@@ -73,9 +101,80 @@ impl Drop for SelfMonitorLoop {
     }
 }
 
+/// Read back the samples of `name` with `start <= ts <= end`, oldest first
+pub fn query_metric(
+    metrics: &ConcurrentMemory,
+    name: &str,
+    start: i64,
+    end: i64,
+) -> Vec<MetricPoint> {
+    let snapshot = metrics.get_snapshot();
+    let mut points: Vec<MetricPoint> = snapshot
+        .concepts
+        .values()
+        .filter(|c| c.attributes.get("metric").is_some_and(|m| m == name))
+        .filter_map(|c| {
+            let ts = c.attributes.get("ts")?.parse::<i64>().ok()?;
+            let value = c.attributes.get("value")?.parse::<f64>().ok()?;
+            Some(MetricPoint { ts, value })
+        })
+        .filter(|p| p.ts >= start && p.ts <= end)
+        .collect();
+
+    points.sort_by_key(|p| p.ts);
+    points
+}
+
+/// Capture one health sample of `storage` and store it in `metrics`.
+///
+/// Returns the IDs of the concepts written for this sample.
+fn record_sample(
+    storage: &ConcurrentMemory,
+    metrics: &ConcurrentMemory,
+    strength: f32,
+) -> Vec<ConceptId> {
+    let stats = storage.stats();
+    let hnsw_stats = storage.hnsw_stats();
+
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+
+    let samples: [(&str, f64); 8] = [
+        ("concepts", stats.snapshot.concept_count as f64),
+        ("edges", stats.snapshot.edge_count as f64),
+        ("write_log_pending", stats.write_log.pending as f64),
+        ("write_log_written", stats.write_log.written as f64),
+        ("write_log_dropped", stats.write_log.dropped as f64),
+        ("reconciliations", stats.reconciler.reconciliations as f64),
+        ("reconciler_health", stats.reconciler.health_score),
+        ("hnsw_vectors", hnsw_stats.indexed_vectors as f64),
+    ];
+
+    let mut ids = Vec::with_capacity(samples.len());
+    for (metric, value) in samples {
+        let concept_id = ConceptId::from_string(&format!("sutra:metric:{}:{}", metric, ts));
+        let content = format!("Engine metric {}={} at {}", metric, value, ts).into_bytes();
+
+        let mut attributes = HashMap::new();
+        attributes.insert("sutra:source".to_string(), "self_monitor".to_string());
+        attributes.insert("metric".to_string(), metric.to_string());
+        attributes.insert("ts".to_string(), ts.to_string());
+        attributes.insert("value".to_string(), value.to_string());
+
+        match metrics.learn_concept(concept_id, content, None, strength, 1.0, attributes) {
+            Ok(_) => ids.push(concept_id),
+            Err(e) => log::warn!("Self-monitor failed to store metric {}: {:?}", metric, e),
+        }
+    }
+    ids
+}
+
 fn monitor_loop(
     config: SelfMonitorConfig,
     storage: Arc<ConcurrentMemory>,
+    metrics: Arc<ConcurrentMemory>,
     running: Arc<AtomicBool>,
 ) {
     log::info!(
@@ -84,7 +183,7 @@ fn monitor_loop(
         config.max_history
     );
 
-    let mut emitted_ids: VecDeque<ConceptId> = VecDeque::new();
+    let mut emitted: VecDeque<Vec<ConceptId>> = VecDeque::new();
 
     while running.load(Ordering::Relaxed) {
         thread::sleep(config.interval);
@@ -92,67 +191,79 @@ fn monitor_loop(
             break;
         }
 
-        let stats = storage.stats();
-        let hnsw_stats = storage.hnsw_stats();
-
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
-        // Build health snapshot as JSON
-        let health = serde_json::json!({
-            "concepts": stats.snapshot.concept_count,
-            "edges": stats.snapshot.edge_count,
-            "pending_writes": stats.write_log.pending,
-            "written": stats.write_log.written,
-            "dropped": stats.write_log.dropped,
-            "reconciliations": stats.reconciler.reconciliations,
-            "reconciler_health": stats.reconciler.health_score,
-            "hnsw_vectors": hnsw_stats.indexed_vectors,
-            "hnsw_dimension": hnsw_stats.dimension,
-            "hnsw_ready": hnsw_stats.index_ready,
-            "timestamp": now,
-        });
+        let ids = record_sample(&storage, &metrics, config.health_concept_strength);
+        if ids.is_empty() {
+            continue;
+        }
+        emitted.push_back(ids);
 
-        let content = format!("Engine health: {}", health);
-        let content_bytes = content.into_bytes();
-
-        // Create concept ID from content + timestamp for uniqueness
-        let id_source = format!("sutra:health:{}", now);
-        let concept_id = ConceptId::from_string(&id_source);
-
-        let mut semantic = SemanticMetadata::new(SemanticType::Event);
-        semantic.domain_context = DomainContext::Technical;
-        semantic.temporal_bounds = Some(TemporalBounds::new(
-            Some(now),
-            Some(now),
-            TemporalRelation::At,
-        ));
-
-        match storage.learn_concept_with_semantic(
-            concept_id,
-            content_bytes,
-            None,
-            config.health_concept_strength,
-            1.0,
-            semantic,
-        ) {
-            Ok(_) => {
-                emitted_ids.push_back(concept_id);
-
-                // Prune oldest if over limit
-                while emitted_ids.len() > config.max_history {
-                    if let Some(old_id) = emitted_ids.pop_front() {
-                        let _ = storage.delete_concept(old_id);
-                    }
+        // Prune oldest samples if over limit
+        while emitted.len() > config.max_history {
+            if let Some(old_ids) = emitted.pop_front() {
+                for id in old_ids {
+                    let _ = metrics.delete_concept(id);
                 }
             }
-            Err(e) => {
-                log::warn!("Self-monitor failed to store health concept: {:?}", e);
-            }
         }
     }
 
     log::info!("Self-monitor loop stopped");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent_memory::ConcurrentConfig;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_monitor_tick_writes_queryable_metrics() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().join("default"),
+            ..Default::default()
+        }));
+        let metrics = Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().join(METRICS_NAMESPACE),
+            ..Default::default()
+        }));
+
+        let id = ConceptId::from_string("monitored");
+        storage
+            .learn_concept(id, b"monitored".to_vec(), None, 1.0, 0.9, HashMap::new())
+            .unwrap();
+        while storage.query_concept(&id).is_none() {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut monitor = SelfMonitorLoop::start_with_metrics(
+            SelfMonitorConfig {
+                interval: Duration::from_millis(20),
+                ..Default::default()
+            },
+            Arc::clone(&storage),
+            Arc::clone(&metrics),
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        // hnsw_vectors is written last in a tick, so once it is visible the
+        // whole sample is
+        while query_metric(&metrics, "hnsw_vectors", 0, i64::MAX).is_empty() {
+            assert!(Instant::now() < deadline, "no monitor tick observed");
+            thread::sleep(Duration::from_millis(20));
+        }
+        monitor.stop();
+
+        let series = query_metric(&metrics, "concepts", 0, i64::MAX);
+        assert!(!series.is_empty());
+        assert_eq!(series[0].value, 1.0);
+        assert!(series.windows(2).all(|w| w[0].ts <= w[1].ts));
+        assert!(!query_metric(&metrics, "write_log_pending", 0, i64::MAX).is_empty());
+        assert_eq!(query_metric(&metrics, "edges", 0, i64::MAX)[0].value, 0.0);
+        assert!(query_metric(&metrics, "concepts", 0, series[0].ts - 1).is_empty());
+
+        // Samples land in the metrics storage, not the monitored one
+        assert_eq!(storage.get_snapshot().concept_count, 1);
+    }
+}
@@ -12,11 +12,15 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sutra_storage::auth::AuthManager;
+use sutra_storage::replication::ReplicaConfig;
 use sutra_storage::secure_tcp_server::SecureStorageServer;
-use sutra_storage::tcp_server::{ShardedStorageServer, StorageServer};
+use sutra_storage::tcp_server::{
+    validate_max_message_size, ShardedStorageServer, StorageServer, DEFAULT_MAX_MESSAGE_SIZE,
+};
 use sutra_storage::{
-    AdaptiveReconcilerConfig, AutonomyConfig, ConcurrentConfig, ConcurrentMemory, ShardConfig,
-    ShardedStorage,
+    AdaptiveReconcilerConfig, AutonomyConfig, ConcurrentConfig, ConcurrentMemory,
+    NamespaceEvictionConfig, NamespaceQuota, QuotaMode, RateLimiterConfig, ShardConfig,
+    ShardedStorage, StorageCompression, SyncPolicy, VectorMetric,
 };
 use tracing::{error, info, warn};
 
@@ -71,6 +75,130 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse::<u32>()
         .unwrap_or(16);
 
+    // WAL durability: "always" (fsync per write), "every:<ms>", or "never"
+    let wal_sync_policy = env::var("SUTRA_WAL_SYNC")
+        .ok()
+        .and_then(|s| s.parse::<SyncPolicy>().ok())
+        .unwrap_or_default();
+
+    // Replication: records kept for replicas to pull (0 = disabled), and the
+    // primary to follow when this node is a read replica
+    let replication_log_capacity = env::var("SUTRA_REPLICATION_LOG_CAPACITY")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse::<usize>()
+        .unwrap_or(0);
+    // HNSW reindex: rebuild once this share of indexed vectors is deleted (0 = disabled)
+    let reindex_tombstone_ratio = env::var("SUTRA_REINDEX_TOMBSTONE_RATIO")
+        .unwrap_or_else(|_| "0.25".to_string())
+        .parse::<f32>()
+        .unwrap_or(0.25)
+        .clamp(0.0, 1.0);
+    // Dedicated thread pool for CPU-heavy storage work (0 = rayon's global pool)
+    let storage_threads = env::var("SUTRA_STORAGE_THREADS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse::<usize>()
+        .unwrap_or(0);
+    // Refuse new writes while the reconciler is far behind, instead of queueing them
+    let reject_on_backpressure = env::var("SUTRA_REJECT_ON_BACKPRESSURE")
+        .unwrap_or_else(|_| "false".to_string())
+        .to_lowercase()
+        == "true";
+    // HNSW similarity: "cosine" (default) or "dot" (pair with normalization)
+    let vector_metric = match env::var("SUTRA_VECTOR_METRIC")
+        .unwrap_or_default()
+        .to_lowercase()
+        .as_str()
+    {
+        "dot" => VectorMetric::DotProduct,
+        _ => VectorMetric::Cosine,
+    };
+    let normalize_vectors = env::var("SUTRA_NORMALIZE_VECTORS")
+        .unwrap_or_else(|_| "false".to_string())
+        .to_lowercase()
+        == "true";
+    // Rewrite storage.dat once this share of it is deleted (0 = only on flush)
+    let compaction_dead_fraction = env::var("SUTRA_COMPACTION_DEAD_FRACTION")
+        .ok()
+        .and_then(|s| s.parse::<f32>().ok())
+        .unwrap_or(0.0)
+        .clamp(0.0, 1.0);
+    // storage.dat codec written on flush: "none" (default), "zstd" or "zstd:<level>"
+    let storage_compression = env::var("SUTRA_STORAGE_COMPRESSION")
+        .ok()
+        .and_then(|s| s.parse::<StorageCompression>().ok())
+        .unwrap_or_default();
+    let replica_of = env::var("SUTRA_REPLICA_OF")
+        .ok()
+        .and_then(|s| s.parse::<SocketAddr>().ok());
+
+    // Namespace eviction: max open namespaces and idle seconds (0 = disabled)
+    let namespace_eviction = NamespaceEvictionConfig {
+        max_open: env::var("SUTRA_MAX_OPEN_NAMESPACES")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(0),
+        idle_timeout: env::var("SUTRA_NAMESPACE_IDLE_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|&secs| secs > 0)
+            .map(std::time::Duration::from_secs),
+    };
+
+    // Per-namespace size limits (0 = unlimited); "evict" drops LRU concepts instead of refusing
+    let namespace_quota = NamespaceQuota {
+        max_concepts: env::var("SUTRA_NAMESPACE_MAX_CONCEPTS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(0),
+        max_bytes: env::var("SUTRA_NAMESPACE_MAX_BYTES")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0),
+        mode: match env::var("SUTRA_NAMESPACE_QUOTA_MODE")
+            .unwrap_or_else(|_| "reject".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "evict" => QuotaMode::EvictLru,
+            _ => QuotaMode::Reject,
+        },
+    };
+
+    // Largest accepted request frame in bytes
+    let max_message_size = match env::var("SUTRA_MAX_MESSAGE_SIZE") {
+        Ok(s) => s
+            .parse::<u32>()
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| validate_max_message_size(bytes).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Invalid SUTRA_MAX_MESSAGE_SIZE '{}': {}", s, e))?
+            as usize,
+        Err(_) => DEFAULT_MAX_MESSAGE_SIZE,
+    };
+
+    // Per-client requests per second (0 = unlimited); writes default to a quarter
+    let peer_read_rps = env::var("SUTRA_PEER_RATE_LIMIT_RPS")
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(0);
+    let peer_write_rps = env::var("SUTRA_PEER_WRITE_RATE_LIMIT_RPS")
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(peer_read_rps / 4)
+        .max(1);
+    let peer_bucket = |rps: u32| RateLimiterConfig {
+        requests_per_second: rps,
+        burst_capacity: rps.saturating_mul(2),
+        ..Default::default()
+    };
+    let peer_rate_limits =
+        (peer_read_rps > 0).then(|| (peer_bucket(peer_read_rps), peer_bucket(peer_write_rps)));
+
+    // Seconds to wait for in-flight requests on shutdown
+    let drain_timeout_secs = env::var("SUTRA_DRAIN_TIMEOUT_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse::<u64>()
+        .unwrap_or(30);
+
     // Autonomy engine configuration
     let autonomy_enabled = env::var("SUTRA_AUTONOMY")
         .unwrap_or_else(|_| "true".to_string())
@@ -109,6 +237,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     info!("  Memory threshold: {} writes", memory_threshold);
     info!("  Vector dimension: {}", vector_dimension);
+    info!("  WAL sync policy: {:?}", wal_sync_policy);
+    info!("  Drain timeout: {}s", drain_timeout_secs);
+    info!("  Max message size: {} bytes", max_message_size);
+    if peer_rate_limits.is_some() {
+        info!(
+            "  Per-client rate limit: {} reads/s, {} writes/s",
+            peer_read_rps, peer_write_rps
+        );
+    } else {
+        info!("  Per-client rate limit: unlimited");
+    }
+    info!("  Replication log capacity: {}", replication_log_capacity);
+    info!("  Reindex tombstone ratio: {}", reindex_tombstone_ratio);
+    if storage_threads > 0 {
+        info!("  Storage threads: {}", storage_threads);
+    } else {
+        info!("  Storage threads: rayon global pool");
+    }
+    info!("  Reject on backpressure: {}", reject_on_backpressure);
+    info!(
+        "  Vector metric: {:?} (normalize: {})",
+        vector_metric, normalize_vectors
+    );
+    info!("  Storage compression: {:?}", storage_compression);
+    info!("  Compaction dead fraction: {}", compaction_dead_fraction);
+    info!(
+        "  Namespace eviction: max open {}, idle timeout {:?}",
+        namespace_eviction.max_open, namespace_eviction.idle_timeout
+    );
+    if let Some(primary) = replica_of {
+        info!("  Read replica of: {}", primary);
+    }
     if storage_mode == "sharded" {
         info!("  Number of shards: {}", num_shards);
     }
@@ -158,6 +318,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 memory_threshold,
                 vector_dimension,
                 adaptive_reconciler_config: adaptive_config.clone(),
+                wal_sync_policy,
+                replication_log_capacity,
+                reindex_tombstone_ratio,
+                storage_threads,
+                reject_on_backpressure,
+                vector_metric,
+                normalize_vectors,
+                compaction_dead_fraction,
+                storage_compression,
             };
 
             let config = ShardConfig {
@@ -191,7 +360,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 warn!("   For production security, use single storage mode with TLS + HMAC");
             }
 
-            let server = Arc::new(ShardedStorageServer::new(sharded_storage).await);
+            let mut server = ShardedStorageServer::new(sharded_storage)
+                .await
+                .with_drain_timeout(std::time::Duration::from_secs(drain_timeout_secs))
+                .with_namespace_eviction(namespace_eviction)
+                .with_namespace_quota(namespace_quota)
+                .with_max_message_size(max_message_size);
+            if let Some((reads, writes)) = peer_rate_limits {
+                server = server.with_rate_limits(reads, writes);
+            }
+            let server = Arc::new(server);
 
             info!("🚀 Starting SHARDED TCP server on {}", addr);
 
@@ -216,6 +394,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 memory_threshold,
                 vector_dimension,
                 adaptive_reconciler_config: adaptive_config,
+                wal_sync_policy,
+                replication_log_capacity,
+                reindex_tombstone_ratio,
+                storage_threads,
+                reject_on_backpressure,
+                vector_metric,
+                normalize_vectors,
+                compaction_dead_fraction,
+                storage_compression,
             };
 
             let storage = ConcurrentMemory::new(config);
@@ -229,8 +416,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Create server (secure or insecure based on mode)
             if secure_mode {
                 // Wrap with secure server
-                let insecure_server =
-                    StorageServer::new_with_autonomy(storage, autonomy_config).await;
+                let mut insecure_server =
+                    StorageServer::new_with_autonomy(storage, autonomy_config)
+                        .await
+                        .with_drain_timeout(std::time::Duration::from_secs(drain_timeout_secs))
+                        .with_namespace_eviction(namespace_eviction)
+                        .with_namespace_quota(namespace_quota)
+                        .with_max_message_size(max_message_size);
+                if let Some(primary) = replica_of {
+                    insecure_server = insecure_server.with_replica(ReplicaConfig::new(primary));
+                }
+                if let Some((reads, writes)) = peer_rate_limits {
+                    insecure_server = insecure_server.with_rate_limits(reads, writes);
+                }
                 let secure_server = SecureStorageServer::new(insecure_server, auth_manager)
                     .await
                     .map_err(|e| format!("Failed to create secure server: {}", e))?;
@@ -245,8 +443,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             } else {
                 // Use insecure server directly
-                let server =
-                    Arc::new(StorageServer::new_with_autonomy(storage, autonomy_config).await);
+                let mut server = StorageServer::new_with_autonomy(storage, autonomy_config)
+                    .await
+                    .with_drain_timeout(std::time::Duration::from_secs(drain_timeout_secs))
+                    .with_namespace_eviction(namespace_eviction)
+                    .with_namespace_quota(namespace_quota)
+                    .with_max_message_size(max_message_size);
+                if let Some(primary) = replica_of {
+                    server = server.with_replica(ReplicaConfig::new(primary));
+                }
+                if let Some((reads, writes)) = peer_rate_limits {
+                    server = server.with_rate_limits(reads, writes);
+                }
+                let server = Arc::new(server);
 
                 info!(
                     "🚀 Starting SINGLE TCP server on {} (DEVELOPMENT MODE - NO SECURITY)",
@@ -1,5 +1,7 @@
+use crate::access_tracker::AccessTracker;
 use crate::adaptive_reconciler::{
-    AdaptiveReconciler, AdaptiveReconcilerConfig, AdaptiveReconcilerStats,
+    AdaptiveReconciler, AdaptiveReconcilerConfig, AdaptiveReconcilerStats, ConceptIndexer,
+    ConflictPolicy,
 };
 /// Concurrent Memory - Production-grade burst-tolerant storage engine
 ///
@@ -110,16 +112,23 @@ use crate::adaptive_reconciler::{
 /// - `Arc<AtomicPtr>` for lock-free snapshot swapping
 /// - `crossbeam::queue::ArrayQueue` for bounded write log
 /// - `usearch::Index` for HNSW vector index (mmap-backed)
-use crate::hnsw_container::{HnswConfig as HnswContainerConfig, HnswContainer};
+use crate::hnsw_container::{HnswConfig as HnswContainerConfig, HnswContainer, RebuildReport};
+use crate::lexical_index::LexicalIndex;
+use crate::namespace_manager::{NamespaceQuota, QuotaMode, QuotaUsage};
 use crate::parallel_paths::{ParallelPathFinder, PathResult};
-use crate::read_view::{ConceptNode, ReadView};
+use crate::read_view::{ConceptNode, DeadlineExceeded, ReadView};
+use crate::replication::{ReplicationLog, ReplicationOp};
+use crate::storage_pool::StoragePool;
+use crate::transaction::{TransactionCoordinator, TxnError, TxnOperation};
 use crate::types::{AssociationRecord, AssociationType, ConceptId};
-use crate::wal::{Operation, WriteAheadLog};
-use crate::write_log::{WriteLog, WriteLogError, WriteLogStats};
+use crate::vectors::VectorMetric;
+use crate::wal::{spawn_sync_timer, Operation, SyncPolicy, WriteAheadLog};
+use crate::write_log::{WriteEntry, WriteLog, WriteLogError, WriteLogStats};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -137,6 +146,131 @@ pub struct ConcurrentConfig {
 
     /// Adaptive reconciler configuration (AI-native self-optimizing)
     pub adaptive_reconciler_config: AdaptiveReconcilerConfig,
+
+    /// When WAL appends are fsynced (see [`SyncPolicy`] for guarantees)
+    #[serde(default)]
+    pub wal_sync_policy: SyncPolicy,
+
+    /// Applied writes retained for read replicas (0 disables replication)
+    #[serde(default)]
+    pub replication_log_capacity: usize,
+
+    /// Rebuild the HNSW index in the background once this share of its
+    /// vectors belongs to deleted concepts (0 disables)
+    #[serde(default = "default_reindex_tombstone_ratio")]
+    pub reindex_tombstone_ratio: f32,
+
+    /// Threads for CPU-heavy work: path finding and HNSW rebuilds
+    /// (0 uses rayon's global pool)
+    #[serde(default)]
+    pub storage_threads: usize,
+
+    /// Refuse new concepts and associations with
+    /// [`WriteLogError::Backpressure`] while [`WritePressure::High`]
+    #[serde(default)]
+    pub reject_on_backpressure: bool,
+
+    /// Similarity the HNSW index ranks by
+    #[serde(default)]
+    pub vector_metric: VectorMetric,
+
+    /// L2-normalize vectors before indexing and queries before searching
+    #[serde(default)]
+    pub normalize_vectors: bool,
+
+    /// Rewrite `storage.dat` early once this share of the concepts it holds
+    /// has been deleted (0 disables; see [`ConcurrentMemory::compact_if_needed`])
+    #[serde(default)]
+    pub compaction_dead_fraction: f32,
+
+    /// Codec for `storage.dat` written by [`ConcurrentMemory::flush`]
+    ///
+    /// Files record their codec, so either setting loads both kinds.
+    #[serde(default)]
+    pub storage_compression: StorageCompression,
+}
+
+/// Compression of the `storage.dat` body (everything after the header)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum StorageCompression {
+    #[default]
+    None,
+    /// zstd at the given level (1-22; higher is smaller and slower to flush)
+    Zstd(i32),
+}
+
+impl StorageCompression {
+    /// Codec id stored at byte 24 of the `storage.dat` header
+    fn codec(self) -> u8 {
+        match self {
+            StorageCompression::None => 0,
+            StorageCompression::Zstd(_) => 1,
+        }
+    }
+}
+
+impl std::str::FromStr for StorageCompression {
+    type Err = String;
+
+    /// Parses `none`, `zstd` (level 3) or `zstd:<level>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" | "off" => Ok(StorageCompression::None),
+            "zstd" => Ok(StorageCompression::Zstd(3)),
+            other => match other.strip_prefix("zstd:").map(str::parse::<i32>) {
+                Some(Ok(level)) if (1..=22).contains(&level) => Ok(StorageCompression::Zstd(level)),
+                _ => Err(format!(
+                    "invalid storage compression '{}': expected none, zstd or zstd:<1-22>",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Writer for the `storage.dat` body, compressing it if configured
+enum SnapshotWriter<W: std::io::Write> {
+    Plain(W),
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+impl<W: std::io::Write> SnapshotWriter<W> {
+    fn new(inner: W, compression: StorageCompression) -> std::io::Result<Self> {
+        Ok(match compression {
+            StorageCompression::None => SnapshotWriter::Plain(inner),
+            StorageCompression::Zstd(level) => {
+                SnapshotWriter::Zstd(zstd::stream::write::Encoder::new(inner, level)?)
+            }
+        })
+    }
+
+    /// Complete the body and return the underlying writer
+    fn finish(self) -> std::io::Result<W> {
+        match self {
+            SnapshotWriter::Plain(inner) => Ok(inner),
+            SnapshotWriter::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for SnapshotWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            SnapshotWriter::Plain(inner) => inner.write(buf),
+            SnapshotWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            SnapshotWriter::Plain(inner) => inner.flush(),
+            SnapshotWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+fn default_reindex_tombstone_ratio() -> f32 {
+    0.25
 }
 
 impl Default for ConcurrentConfig {
@@ -146,6 +280,48 @@ impl Default for ConcurrentConfig {
             memory_threshold: 50_000,
             vector_dimension: 768, // Default: nomic-embed-text-v1.5 dimension
             adaptive_reconciler_config: AdaptiveReconcilerConfig::default(),
+            wal_sync_policy: SyncPolicy::default(),
+            replication_log_capacity: 0,
+            reindex_tombstone_ratio: default_reindex_tombstone_ratio(),
+            storage_threads: 0,
+            reject_on_backpressure: false,
+            vector_metric: VectorMetric::Cosine,
+            normalize_vectors: false,
+            compaction_dead_fraction: 0.0,
+            storage_compression: StorageCompression::None,
+        }
+    }
+}
+
+/// How far the reconciler is behind writers
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum WritePressure {
+    Low,
+    Medium,
+    High,
+}
+
+impl WritePressure {
+    /// Share of write log capacity pending at which pressure is Medium/High
+    const MEDIUM_UTILIZATION: f64 = 0.5;
+    const HIGH_UTILIZATION: f64 = 0.8;
+    /// Reconciler health at or below which pressure is Medium/High
+    const MEDIUM_HEALTH: f64 = 0.5;
+    const HIGH_HEALTH: f64 = 0.2;
+
+    /// Classify from the pending share of the write log and reconciler health
+    ///
+    /// Either signal alone can raise pressure: utilization reacts at once to
+    /// a burst, health (smoothed over recent cycles) to a sustained backlog.
+    pub fn assess(utilization: f64, health_score: f64) -> Self {
+        if utilization >= Self::HIGH_UTILIZATION || health_score <= Self::HIGH_HEALTH {
+            WritePressure::High
+        } else if utilization >= Self::MEDIUM_UTILIZATION || health_score <= Self::MEDIUM_HEALTH {
+            WritePressure::Medium
+        } else {
+            WritePressure::Low
         }
     }
 }
@@ -188,6 +364,24 @@ impl ConcurrentConfig {
             }
         }
 
+        if self.wal_sync_policy == SyncPolicy::EverySync(std::time::Duration::ZERO) {
+            anyhow::bail!("wal_sync_policy interval must be > 0");
+        }
+
+        if !(0.0..=1.0).contains(&self.reindex_tombstone_ratio) {
+            anyhow::bail!(
+                "reindex_tombstone_ratio must be within [0, 1], got {}",
+                self.reindex_tombstone_ratio
+            );
+        }
+
+        if !(0.0..=1.0).contains(&self.compaction_dead_fraction) {
+            anyhow::bail!(
+                "compaction_dead_fraction must be within [0, 1], got {}",
+                self.compaction_dead_fraction
+            );
+        }
+
         // Adaptive reconciler config validation
         self.adaptive_reconciler_config.validate()?;
 
@@ -215,11 +409,151 @@ pub struct ConcurrentMemory {
     /// Parallel pathfinder (4-8× query speedup with Rayon)
     parallel_pathfinder: Arc<ParallelPathFinder>,
 
+    /// Pool for CPU-heavy work, shared with other namespaces
+    pool: StoragePool,
+
     /// Write-Ahead Log for durability
     wal: Arc<Mutex<WriteAheadLog>>,
 
     /// Configuration
     config: ConcurrentConfig,
+
+    /// Reads awaiting the next reconciliation
+    access_tracker: Arc<AccessTracker>,
+
+    /// Access-count ranking, rebuilt lazily when the snapshot sequence changes
+    access_ranking: parking_lot::Mutex<Option<(u64, Arc<Vec<AccessRank>>)>>,
+
+    /// Applied writes for read replicas (primary side)
+    replication_log: Option<Arc<ReplicationLog>>,
+
+    /// Tracks in-flight atomic writes
+    transactions: TransactionCoordinator,
+
+    /// Size limit and per-concept byte accounting, if a quota is set
+    quota: parking_lot::Mutex<Option<QuotaState>>,
+
+    /// BM25 keyword index over concept content
+    lexical_index: Arc<RwLock<LexicalIndex>>,
+
+    /// Concepts in `storage.dat` when it was last written or loaded
+    flushed_concepts: AtomicUsize,
+
+    /// Deletes since `storage.dat` was last written
+    deleted_since_flush: AtomicUsize,
+
+    /// `WriteLog::settled_deletes` when `storage.dat` was last written
+    settled_deletes_at_flush: AtomicU64,
+
+    /// Set while [`ConcurrentMemory::compact_if_needed`] is rewriting `storage.dat`
+    compacting: AtomicBool,
+}
+
+/// Point `hnsw` and `vectors` at `id`'s current vector, or drop it for `None`
+///
+/// The container keeps the first vector inserted under an id, so a changed
+/// vector is removed (tombstoned) before the new one goes in.
+fn index_concept_vector(
+    vectors: &RwLock<HashMap<ConceptId, Vec<f32>>>,
+    hnsw: &HnswContainer,
+    id: ConceptId,
+    vector: Option<Vec<f32>>,
+) {
+    let previous = vectors.read().get(&id).cloned();
+    if previous.is_some() && previous != vector {
+        hnsw.remove(&id);
+    }
+    match vector {
+        Some(vector) => {
+            if let Err(e) = hnsw.insert(id, vector.clone()) {
+                log::warn!("⚠️ Failed to insert into HNSW container: {}", e);
+            }
+            vectors.write().insert(id, vector);
+        }
+        None => {
+            vectors.write().remove(&id);
+        }
+    }
+}
+
+/// Bookkeeping behind [`ConcurrentMemory::set_quota`]
+///
+/// Updated when writes are accepted rather than when they are reconciled, so
+/// a burst of writes cannot overshoot the quota before the snapshot catches up.
+struct QuotaState {
+    quota: NamespaceQuota,
+    sizes: HashMap<ConceptId, u64>,
+    bytes: u64,
+    evicted: u64,
+}
+
+/// Quota change made for writes that are not logged yet
+///
+/// [`ConcurrentMemory::settle_admission`] deletes the victims once the writes
+/// are logged, or undoes the change if logging them failed.
+#[must_use]
+struct QuotaAdmission {
+    /// Each written concept with its previous and new size
+    incoming: Vec<(ConceptId, Option<u64>, u64)>,
+    /// Concepts evicted to make room, with their sizes
+    victims: Vec<(ConceptId, u64)>,
+}
+
+/// WAL record for a replicated write, if it is one the WAL tracks
+fn replicated_operation(entry: &WriteEntry) -> Option<Operation> {
+    match entry {
+        WriteEntry::AddConcept {
+            id,
+            content,
+            vector,
+            timestamp,
+            ..
+        } => Some(Operation::WriteConcept {
+            concept_id: *id,
+            content_len: content.len() as u32,
+            vector_len: vector.as_ref().map(|v| v.len() as u32).unwrap_or(0),
+            created: *timestamp,
+            modified: current_timestamp_us(),
+        }),
+        WriteEntry::AddAssociation { record } => {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            Hash::hash(&record.source_id, &mut hasher);
+            Hash::hash(&record.target_id, &mut hasher);
+            Some(Operation::WriteAssociation {
+                source: record.source_id,
+                target: record.target_id,
+                association_id: hasher.finish(),
+                strength: record.confidence,
+                created: record.created,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Bytes a concept counts against a quota
+fn concept_bytes(content: &[u8], vector_len: usize, attributes: &HashMap<String, String>) -> u64 {
+    let attribute_bytes: usize = attributes.iter().map(|(k, v)| k.len() + v.len()).sum();
+    (content.len() + vector_len * std::mem::size_of::<f32>() + attribute_bytes) as u64
+}
+
+/// One write of an atomic batch (see [`ConcurrentMemory::learn_atomic`])
+#[derive(Debug, Clone)]
+pub enum AtomicWrite {
+    Concept {
+        id: ConceptId,
+        content: Vec<u8>,
+        vector: Option<Vec<f32>>,
+        strength: f32,
+        confidence: f32,
+        attributes: HashMap<String, String>,
+    },
+    Association {
+        source: ConceptId,
+        target: ConceptId,
+        assoc_type: AssociationType,
+        confidence: f32,
+    },
 }
 
 impl ConcurrentMemory {
@@ -237,11 +571,16 @@ impl ConcurrentMemory {
         std::fs::create_dir_all(&config.storage_path).ok();
 
         let wal = if wal_path.exists() {
-            WriteAheadLog::open(&wal_path, true).expect("Failed to open WAL")
+            WriteAheadLog::open_with_policy(&wal_path, config.wal_sync_policy)
+                .expect("Failed to open WAL")
         } else {
-            WriteAheadLog::create(&wal_path, true).expect("Failed to create WAL")
+            WriteAheadLog::create_with_policy(&wal_path, config.wal_sync_policy)
+                .expect("Failed to create WAL")
         };
         let wal = Arc::new(Mutex::new(wal));
+        if let SyncPolicy::EverySync(interval) = config.wal_sync_policy {
+            spawn_sync_timer(Arc::downgrade(&wal), interval);
+        }
 
         // CRITICAL FIX: Load existing data from storage.dat if it exists
         let storage_file = config.storage_path.join("storage.dat");
@@ -284,22 +623,14 @@ impl ConcurrentMemory {
             }
         }
 
-        // 🚀 PRODUCTION: Initialize adaptive reconciler (AI-native self-optimizing)
-        let mut reconciler = AdaptiveReconciler::new(
-            config.adaptive_reconciler_config.clone(),
-            Arc::clone(&write_log),
-            Arc::clone(&read_view),
-        );
-
-        // Start reconciler thread immediately
-        reconciler.start();
-
         // 🔥 PRODUCTION: Initialize HNSW container with persistence (100× faster startup)
         let hnsw_config = HnswContainerConfig {
             dimension: config.vector_dimension,
             max_neighbors: 16,
             ef_construction: 200,
             max_elements: 100_000,
+            metric: config.vector_metric,
+            normalize_on_insert: config.normalize_vectors,
         };
         let hnsw_container = Arc::new(HnswContainer::new(
             config.storage_path.join("storage"),
@@ -319,22 +650,80 @@ impl ConcurrentMemory {
             );
         }
 
+        let mut lexical_index = LexicalIndex::default();
+        for node in read_view.load().concepts.values() {
+            lexical_index.insert(node.id, &node.content);
+        }
+        let lexical_index = Arc::new(RwLock::new(lexical_index));
+        let vectors = Arc::new(RwLock::new(vectors));
+        let flushed_concepts = read_view.load().concept_count;
+
+        // 🚀 PRODUCTION: Initialize adaptive reconciler (AI-native self-optimizing)
+        let replication_log = (config.replication_log_capacity > 0)
+            .then(|| Arc::new(ReplicationLog::new(config.replication_log_capacity)));
+        let access_tracker = Arc::new(AccessTracker::default());
+        let mut reconciler = AdaptiveReconciler::new(
+            config.adaptive_reconciler_config.clone(),
+            Arc::clone(&write_log),
+            Arc::clone(&read_view),
+        )
+        .with_access_tracker(Arc::clone(&access_tracker));
+        if let Some(ref log) = replication_log {
+            reconciler = reconciler.with_replication_log(Arc::clone(log));
+        }
+        // A policy other than last-write-wins may keep an older version, so
+        // the indexes follow the reconciler instead of every accepted write
+        if config.adaptive_reconciler_config.conflict_policy != ConflictPolicy::LastWriteWins {
+            let lexical_index = Arc::clone(&lexical_index);
+            let vectors = Arc::clone(&vectors);
+            let hnsw_container = Arc::clone(&hnsw_container);
+            let dimension = config.vector_dimension;
+            let indexer: ConceptIndexer = Arc::new(move |node: &ConceptNode| {
+                lexical_index.write().insert(node.id, &node.content);
+                let vector = node
+                    .vector
+                    .as_deref()
+                    .filter(|v| v.len() == dimension)
+                    .map(<[f32]>::to_vec);
+                index_concept_vector(&vectors, &hnsw_container, node.id, vector);
+            });
+            reconciler = reconciler.with_indexer(indexer);
+        }
+
+        // Start reconciler thread immediately
+        reconciler.start();
+
         // Initialize parallel pathfinder (default decay: 0.85)
-        let parallel_pathfinder = Arc::new(ParallelPathFinder::default());
+        let pool = StoragePool::shared(config.storage_threads);
+        let parallel_pathfinder = Arc::new(ParallelPathFinder::default().with_pool(pool.clone()));
 
         Self {
             write_log,
             read_view,
             reconciler,
-            vectors: Arc::new(RwLock::new(vectors)),
+            vectors,
             hnsw_container,
             parallel_pathfinder,
+            pool,
             wal,
             config,
+            access_tracker,
+            access_ranking: parking_lot::Mutex::new(None),
+            replication_log,
+            transactions: TransactionCoordinator::default(),
+            quota: parking_lot::Mutex::new(None),
+            lexical_index,
+            flushed_concepts: AtomicUsize::new(flushed_concepts),
+            deleted_since_flush: AtomicUsize::new(0),
+            settled_deletes_at_flush: AtomicU64::new(0),
+            compacting: AtomicBool::new(false),
         }
     }
 
     /// Replay WAL entries into WriteLog for crash recovery
+    ///
+    /// Concept entries only carry metadata and are not re-applied, so the
+    /// loaded snapshot, already resolved by the [`ConflictPolicy`], stands.
     fn replay_wal(
         wal: &Arc<Mutex<WriteAheadLog>>,
         _write_log: &Arc<WriteLog>,
@@ -416,11 +805,18 @@ impl ConcurrentMemory {
         let mut edges = HashMap::new();
 
         let file = File::open(storage_file)?;
-        let mut reader = BufReader::new(file);
+        let mut file_reader = BufReader::new(file);
 
         // Read file header (64 bytes)
         let mut header_buffer = vec![0u8; 64];
-        reader.read_exact(&mut header_buffer)?;
+        file_reader.read_exact(&mut header_buffer)?;
+
+        // Byte 24 names the body codec; files written before it existed hold 0
+        let mut reader: Box<dyn Read> = match header_buffer[24] {
+            0 => Box::new(file_reader),
+            1 => Box::new(zstd::stream::read::Decoder::with_buffer(file_reader)?),
+            codec => anyhow::bail!("Unknown storage codec: {}", codec),
+        };
 
         // Parse header
         let magic_bytes = &header_buffer[0..8];
@@ -537,6 +933,7 @@ impl ConcurrentMemory {
                 attributes: std::collections::HashMap::new(), // Added missing field
                 neighbors: Vec::new(),
                 associations: Vec::new(),
+                version: 0,
             };
 
             concepts.insert(id, node);
@@ -716,54 +1113,64 @@ impl ConcurrentMemory {
         confidence: f32,
         attributes: std::collections::HashMap<String, String>,
     ) -> Result<u64, WriteLogError> {
-        // CRITICAL: Write to WAL first for durability (before in-memory structures)
-        {
-            let mut wal = self.wal.lock().unwrap();
-            wal.append(Operation::WriteConcept {
-                concept_id: id,
-                content_len: content.len() as u32,
-                vector_len: vector.as_ref().map(|v| v.len() as u32).unwrap_or(0),
-                created: current_timestamp_us(),
-                modified: current_timestamp_us(),
-            })
-            .map_err(|_| WriteLogError::Disconnected)?;
-        }
+        self.check_backpressure()?;
+        let bytes = concept_bytes(&content, vector.as_ref().map_or(0, Vec::len), &attributes);
+        let admission = self.admit_concepts(&[(id, bytes)], true)?;
+        // Indexed for keyword search only once the write is accepted
+        let tokens = crate::lexical_index::content_tokens(&content);
+
+        let logged = (|| {
+            // CRITICAL: Write to WAL first for durability (before in-memory structures)
+            {
+                let mut wal = self.wal.lock().unwrap();
+                wal.append(Operation::WriteConcept {
+                    concept_id: id,
+                    content_len: content.len() as u32,
+                    vector_len: vector.as_ref().map(|v| v.len() as u32).unwrap_or(0),
+                    created: current_timestamp_us(),
+                    modified: current_timestamp_us(),
+                })
+                .map_err(|_| WriteLogError::Disconnected)?;
+            }
 
-        // Now safe to write to in-memory WriteLog (WAL guarantees durability)
-        let seq = self.write_log.append_concept(
-            id,
-            content,
-            vector.clone(),
-            strength,
-            confidence,
-            attributes,
-        )?;
+            // Now safe to write to in-memory WriteLog (WAL guarantees durability)
+            self.write_log.append_concept(
+                id,
+                content,
+                vector.clone(),
+                strength,
+                confidence,
+                attributes,
+            )
+        })();
+        self.settle_admission(admission, logged.is_ok());
+        let seq = logged?;
 
         // Auto-index vector in HNSW if provided
-        if let Some(vec) = vector {
-            if vec.len() == self.config.vector_dimension {
+        let vector = match vector {
+            Some(vec) if vec.len() == self.config.vector_dimension => {
                 log::info!(
                     "🔍 HNSW: Indexing vector for concept {} (dim={})",
                     id.to_hex(),
                     vec.len()
                 );
-                // Legacy storage (for compatibility)
-                let _ = self.index_vector(id, vec.clone());
-                // 🔥 NEW: Incremental insert into persistent HNSW container
-                if let Err(e) = self.hnsw_container.insert(id, vec) {
-                    log::warn!("⚠️ Failed to insert into HNSW container: {}", e);
-                }
-            } else {
+                Some(vec)
+            }
+            Some(vec) => {
                 log::warn!(
                     "❌ HNSW: Dimension mismatch! Expected {}, got {}. Concept {} NOT indexed.",
                     self.config.vector_dimension,
                     vec.len(),
                     id.to_hex()
                 );
+                None
             }
-        } else {
-            log::debug!("ℹ️  Concept {} stored without embedding", id.to_hex());
-        }
+            None => {
+                log::debug!("ℹ️  Concept {} stored without embedding", id.to_hex());
+                None
+            }
+        };
+        self.index_accepted(id, tokens, vector);
 
         Ok(seq)
     }
@@ -778,36 +1185,43 @@ impl ConcurrentMemory {
         confidence: f32,
         semantic: crate::semantic::SemanticMetadata,
     ) -> Result<u64, WriteLogError> {
-        // Write to WAL first
-        {
-            let mut wal = self.wal.lock().unwrap();
-            wal.append(Operation::WriteConcept {
-                concept_id: id,
-                content_len: content.len() as u32,
-                vector_len: vector.as_ref().map(|v| v.len() as u32).unwrap_or(0),
-                created: current_timestamp_us(),
-                modified: current_timestamp_us(),
-            })
-            .map_err(|_| WriteLogError::Disconnected)?;
-        }
-
-        let seq = self.write_log.append_concept_with_semantic(
-            id,
-            content,
-            vector.clone(),
-            strength,
-            confidence,
-            semantic,
-        )?;
-
-        if let Some(vec) = vector {
-            if vec.len() == self.config.vector_dimension {
-                let _ = self.index_vector(id, vec.clone());
-                if let Err(e) = self.hnsw_container.insert(id, vec) {
-                    log::warn!("⚠️ Failed to insert into HNSW container: {}", e);
-                }
+        self.check_backpressure()?;
+        let bytes = concept_bytes(
+            &content,
+            vector.as_ref().map_or(0, Vec::len),
+            &HashMap::new(),
+        );
+        let admission = self.admit_concepts(&[(id, bytes)], true)?;
+        // Indexed for keyword search only once the write is accepted
+        let tokens = crate::lexical_index::content_tokens(&content);
+
+        let logged = (|| {
+            // Write to WAL first
+            {
+                let mut wal = self.wal.lock().unwrap();
+                wal.append(Operation::WriteConcept {
+                    concept_id: id,
+                    content_len: content.len() as u32,
+                    vector_len: vector.as_ref().map(|v| v.len() as u32).unwrap_or(0),
+                    created: current_timestamp_us(),
+                    modified: current_timestamp_us(),
+                })
+                .map_err(|_| WriteLogError::Disconnected)?;
             }
-        }
+
+            self.write_log.append_concept_with_semantic(
+                id,
+                content,
+                vector.clone(),
+                strength,
+                confidence,
+                semantic,
+            )
+        })();
+        self.settle_admission(admission, logged.is_ok());
+        let seq = logged?;
+        let vector = vector.filter(|vec| vec.len() == self.config.vector_dimension);
+        self.index_accepted(id, tokens, vector);
 
         Ok(seq)
     }
@@ -820,6 +1234,8 @@ impl ConcurrentMemory {
         assoc_type: AssociationType,
         confidence: f32,
     ) -> Result<u64, WriteLogError> {
+        self.check_backpressure()?;
+
         // CRITICAL: Write to WAL first for durability
         {
             let mut wal = self.wal.lock().unwrap();
@@ -844,69 +1260,738 @@ impl ConcurrentMemory {
         self.write_log.append_association(record)
     }
 
-    /// Update concept strength (for temporal decay)
-    pub fn update_strength(&self, id: ConceptId, strength: f32) -> Result<u64, WriteLogError> {
-        self.write_log
-            .append(crate::write_log::WriteEntry::UpdateStrength { id, strength })
+    /// Apply several writes all-or-nothing
+    ///
+    /// Every write is validated before anything is logged: vectors must match
+    /// the configured dimension, and association endpoints must already be in
+    /// the snapshot or be created earlier in the batch. The writes are logged
+    /// as one WAL transaction and reach the reconciler as a single entry, so
+    /// readers see either none or all of them.
+    pub fn learn_atomic(&self, writes: Vec<AtomicWrite>) -> Result<u64, TxnError> {
+        let txn_id = self.transactions.begin(TxnOperation::LocalBatch {
+            shard_id: 0,
+            writes: writes.len(),
+        });
+        let result = self.apply_atomic(txn_id, writes);
+        if result.is_err() {
+            let _ = self.transactions.abort(txn_id);
+        }
+        self.transactions.complete(txn_id);
+        result
     }
 
-    /// Record concept access (for heat tracking)
-    pub fn record_access(&self, id: ConceptId) -> Result<u64, WriteLogError> {
-        let timestamp = current_timestamp_us();
-        self.write_log
-            .append(crate::write_log::WriteEntry::RecordAccess { id, timestamp })
+    fn apply_atomic(&self, txn_id: u64, writes: Vec<AtomicWrite>) -> Result<u64, TxnError> {
+        let snapshot = self.read_view.load();
+        let mut created = std::collections::HashSet::new();
+        for (index, write) in writes.iter().enumerate() {
+            match write {
+                AtomicWrite::Concept { id, vector, .. } => {
+                    if let Some(v) = vector {
+                        if v.len() != self.config.vector_dimension {
+                            return Err(TxnError::Rejected {
+                                index,
+                                reason: format!(
+                                    "vector dimension {} does not match {}",
+                                    v.len(),
+                                    self.config.vector_dimension
+                                ),
+                            });
+                        }
+                    }
+                    created.insert(*id);
+                }
+                AtomicWrite::Association { source, target, .. } => {
+                    for endpoint in [source, target] {
+                        if !created.contains(endpoint) && !snapshot.contains(endpoint) {
+                            return Err(TxnError::Rejected {
+                                index,
+                                reason: format!("concept {} not found", endpoint.to_hex()),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        let sizes: Vec<(ConceptId, u64)> = writes
+            .iter()
+            .filter_map(|write| match write {
+                AtomicWrite::Concept {
+                    id,
+                    content,
+                    vector,
+                    attributes,
+                    ..
+                } => Some((
+                    *id,
+                    concept_bytes(content, vector.as_ref().map_or(0, Vec::len), attributes),
+                )),
+                AtomicWrite::Association { .. } => None,
+            })
+            .collect();
+        let admission = self
+            .admit_concepts(&sizes, false)
+            .map_err(|e| TxnError::Rejected {
+                index: 0,
+                reason: e.to_string(),
+            })?;
+        let result = self.write_atomic(txn_id, writes);
+        self.settle_admission(admission, result.is_ok());
+        result
     }
 
-    /// Delete a concept and all its associations
-    pub fn delete_concept(&self, id: ConceptId) -> Result<u64, WriteLogError> {
-        let timestamp = current_timestamp_us();
-        self.write_log
-            .append(crate::write_log::WriteEntry::DeleteConcept { id, timestamp })
-    }
+    /// Log a validated, admitted atomic batch and apply it to the indexes
+    fn write_atomic(&self, txn_id: u64, writes: Vec<AtomicWrite>) -> Result<u64, TxnError> {
+        self.transactions.mark_prepared(txn_id, 0)?;
 
-    /// Clear all data in this memory instance
-    pub fn clear(&self) -> Result<u64, WriteLogError> {
-        // Clear HNSW index immediately (it's separate from WriteLog)
-        if let Err(e) = self.hnsw_container.clear() {
-            log::warn!("⚠️ Failed to clear HNSW container: {}", e);
+        let mut entries = Vec::with_capacity(writes.len());
+        let mut indexed = Vec::new();
+        for write in writes {
+            let timestamp = current_timestamp_us();
+            match write {
+                AtomicWrite::Concept {
+                    id,
+                    content,
+                    vector,
+                    strength,
+                    confidence,
+                    attributes,
+                } => {
+                    indexed.push((
+                        id,
+                        crate::lexical_index::content_tokens(&content),
+                        vector.clone(),
+                    ));
+                    entries.push(WriteEntry::AddConcept {
+                        id,
+                        content: content.into_boxed_slice(),
+                        vector: vector.map(Vec::into_boxed_slice),
+                        strength,
+                        confidence,
+                        timestamp,
+                        attributes,
+                        semantic: None,
+                    });
+                }
+                AtomicWrite::Association {
+                    source,
+                    target,
+                    assoc_type,
+                    confidence,
+                } => entries.push(WriteEntry::AddAssociation {
+                    record: AssociationRecord::new(source, target, assoc_type, confidence),
+                }),
+            }
         }
 
-        self.write_log.append(crate::write_log::WriteEntry::Clear)
-    }
+        // Hold the WAL for the whole transaction so no other write interleaves
+        let seq = {
+            let mut wal = self.wal.lock().unwrap();
+            let write_failed = |e: anyhow::Error| TxnError::WriteFailed(e.to_string());
+            wal.begin_transaction().map_err(write_failed)?;
+            let logged = entries.iter().try_for_each(|entry| {
+                let operation = match entry {
+                    WriteEntry::AddConcept {
+                        id,
+                        content,
+                        vector,
+                        timestamp,
+                        ..
+                    } => Operation::WriteConcept {
+                        concept_id: *id,
+                        content_len: content.len() as u32,
+                        vector_len: vector.as_ref().map(|v| v.len() as u32).unwrap_or(0),
+                        created: *timestamp,
+                        modified: *timestamp,
+                    },
+                    WriteEntry::AddAssociation { record } => {
+                        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                        Hash::hash(&record.source_id, &mut hasher);
+                        Hash::hash(&record.target_id, &mut hasher);
+                        Operation::WriteAssociation {
+                            source: record.source_id,
+                            target: record.target_id,
+                            association_id: hasher.finish(),
+                            strength: record.confidence,
+                            created: record.created,
+                        }
+                    }
+                    _ => unreachable!("atomic batches only hold concepts and associations"),
+                };
+                wal.append(operation).map(|_| ())
+            });
+            let seq = logged.map_err(write_failed).and_then(|()| {
+                self.write_log
+                    .append(WriteEntry::Atomic { entries })
+                    .map_err(|e| match e {
+                        WriteLogError::Full | WriteLogError::Backpressure => {
+                            TxnError::WriteLogBusy(e)
+                        }
+                        e => TxnError::WriteFailed(format!("{:?}", e)),
+                    })
+            });
+            match seq {
+                Ok(seq) => {
+                    wal.commit_transaction().map_err(write_failed)?;
+                    seq
+                }
+                Err(e) => {
+                    let _ = wal.rollback_transaction();
+                    return Err(e);
+                }
+            }
+        };
+        self.transactions.commit(txn_id)?;
 
-    // ========================
-    // READ API (never blocks)
-    // ========================
+        for (id, tokens, vector) in indexed {
+            self.index_accepted(id, tokens, vector);
+        }
 
-    /// Query a concept by ID
-    pub fn query_concept(&self, id: &ConceptId) -> Option<ConceptNode> {
-        self.read_view.get_concept(id)
+        Ok(seq)
     }
 
-    /// Get neighbors of a concept
-    pub fn query_neighbors(&self, id: &ConceptId) -> Vec<ConceptId> {
-        self.read_view.get_neighbors(id)
-    }
+    /// Apply a write received from a replication primary
+    pub(crate) fn apply_replicated(&self, op: ReplicationOp) -> Result<u64, WriteLogError> {
+        let entry = match op {
+            ReplicationOp::Clear => return self.clear(),
+            op => op.into_entry(),
+        };
 
-    /// Get neighbors with association strengths
-    pub fn query_neighbors_weighted(&self, id: &ConceptId) -> Vec<(ConceptId, f32)> {
-        let snapshot = self.read_view.load();
-        snapshot.get_neighbors_weighted(id)
-    }
+        if let Some(operation) = replicated_operation(&entry) {
+            let mut wal = self.wal.lock().unwrap();
+            wal.append(operation)
+                .map_err(|_| WriteLogError::Disconnected)?;
+        }
 
-    /// Find path between two concepts (BFS - sequential)
-    pub fn find_path(
-        &self,
-        start: ConceptId,
-        end: ConceptId,
-        max_depth: usize,
-    ) -> Option<Vec<ConceptId>> {
-        self.read_view.find_path(start, end, max_depth)
-    }
+        // `Some(tokens)` to index, `None` to drop, once the write is accepted
+        let lexical = match &entry {
+            WriteEntry::AddConcept { id, content, .. } => {
+                Some((*id, Some(crate::lexical_index::content_tokens(content))))
+            }
+            WriteEntry::DeleteConcept { id, .. } => Some((*id, None)),
+            _ => None,
+        };
+        let vector = match &entry {
+            WriteEntry::AddConcept { vector, .. } => vector
+                .as_deref()
+                .filter(|vec| vec.len() == self.config.vector_dimension)
+                .map(<[f32]>::to_vec),
+            _ => None,
+        };
 
-    /// 🚀 NEW: Find multiple paths in parallel (4-8× speedup)
-    pub fn find_paths_parallel(
-        &self,
+        // Counted before the append so the reconciler can't settle it first
+        let deleting = matches!(entry, WriteEntry::DeleteConcept { .. });
+        if deleting {
+            self.deleted_since_flush.fetch_add(1, Ordering::Relaxed);
+        }
+        let seq = match self.write_log.append(entry) {
+            Ok(seq) => seq,
+            Err(e) => {
+                if deleting {
+                    self.deleted_since_flush.fetch_sub(1, Ordering::Relaxed);
+                }
+                return Err(e);
+            }
+        };
+        match lexical {
+            Some((id, Some(tokens))) => self.index_accepted(id, tokens, vector),
+            Some((id, None)) => self.lexical_index.write().remove(&id),
+            None => {}
+        }
+        Ok(seq)
+    }
+
+    /// Replace all data with a primary's snapshot in a single step
+    ///
+    /// The snapshot becomes visible in one reconciliation, so readers see the
+    /// old data until then and never an empty store. Search indexes are
+    /// updated in place rather than cleared.
+    pub(crate) fn replace_replicated(&self, ops: Vec<ReplicationOp>) -> Result<u64, WriteLogError> {
+        let mut entries = vec![WriteEntry::Clear];
+        let mut indexed = Vec::new();
+        let mut kept = std::collections::HashSet::new();
+        for op in ops {
+            let entry = match op {
+                ReplicationOp::Clear => continue,
+                op => op.into_entry(),
+            };
+            if let WriteEntry::AddConcept {
+                id,
+                content,
+                vector,
+                ..
+            } = &entry
+            {
+                let vector = vector
+                    .as_deref()
+                    .filter(|vec| vec.len() == self.config.vector_dimension)
+                    .map(<[f32]>::to_vec);
+                kept.insert(*id);
+                indexed.push((*id, crate::lexical_index::content_tokens(content), vector));
+            }
+            entries.push(entry);
+        }
+
+        {
+            let mut wal = self.wal.lock().unwrap();
+            for operation in entries.iter().filter_map(replicated_operation) {
+                wal.append(operation)
+                    .map_err(|_| WriteLogError::Disconnected)?;
+            }
+        }
+
+        let stale: std::collections::HashSet<ConceptId> = {
+            let snapshot = self.read_view.load();
+            let vectors = self.vectors.read();
+            snapshot
+                .concepts
+                .keys()
+                .chain(vectors.keys())
+                .filter(|id| !kept.contains(*id))
+                .copied()
+                .collect()
+        };
+        let seq = self.write_log.append(WriteEntry::Atomic { entries })?;
+
+        let mut tombstoned = false;
+        for id in stale {
+            self.lexical_index.write().remove(&id);
+            self.vectors.write().remove(&id);
+            tombstoned |= self.hnsw_container.remove(&id);
+        }
+        if tombstoned {
+            self.maybe_reindex_in_background();
+        }
+        for (id, tokens, vector) in indexed {
+            self.index_accepted(id, tokens, vector);
+        }
+        Ok(seq)
+    }
+
+    /// Update concept strength (for temporal decay)
+    pub fn update_strength(&self, id: ConceptId, strength: f32) -> Result<u64, WriteLogError> {
+        self.write_log
+            .append(crate::write_log::WriteEntry::UpdateStrength { id, strength })
+    }
+
+    /// Edit an existing concept's strength, confidence and attributes
+    ///
+    /// `None` leaves a field unchanged and `attributes` is merged into the
+    /// stored ones. Content, vector and associations are kept, unlike
+    /// re-learning the concept. Unknown IDs are ignored when applied.
+    ///
+    /// Merged attributes count against the quota like a re-learn's do.
+    pub fn update_concept(
+        &self,
+        id: ConceptId,
+        strength: Option<f32>,
+        confidence: Option<f32>,
+        attributes: HashMap<String, String>,
+    ) -> Result<u64, WriteLogError> {
+        self.check_backpressure()?;
+        let admission = match self.merged_size(&id, &attributes) {
+            Some(bytes) => self.admit_concepts(&[(id, bytes)], true)?,
+            None => None,
+        };
+
+        let logged = (|| {
+            {
+                let mut wal = self.wal.lock().unwrap();
+                wal.append(Operation::UpdateConcept {
+                    concept_id: id,
+                    modified: current_timestamp_us(),
+                })
+                .map_err(|_| WriteLogError::Disconnected)?;
+            }
+
+            self.write_log
+                .append(crate::write_log::WriteEntry::UpdateConcept {
+                    id,
+                    strength,
+                    confidence,
+                    attributes,
+                })
+        })();
+        self.settle_admission(admission, logged.is_ok());
+        logged
+    }
+
+    /// Quota size of `id` once `attributes` are merged in, if it is counted
+    ///
+    /// A concept still waiting for the reconciler has no stored attributes to
+    /// replace, so every merged one counts in full.
+    fn merged_size(&self, id: &ConceptId, attributes: &HashMap<String, String>) -> Option<u64> {
+        let current = *self.quota.lock().as_ref()?.sizes.get(id)?;
+        let snapshot = self.read_view.load();
+        let existing = snapshot.concepts.get(id).map(|node| &node.attributes);
+        let added: i64 = attributes
+            .iter()
+            .map(|(k, v)| match existing.and_then(|stored| stored.get(k)) {
+                Some(old) => v.len() as i64 - old.len() as i64,
+                None => (k.len() + v.len()) as i64,
+            })
+            .sum();
+        Some(current.saturating_add_signed(added))
+    }
+
+    /// Record concept access (for heat tracking)
+    ///
+    /// Bypasses the write log: the count reaches the snapshot with the next
+    /// reconciliation and leaves the concept version unchanged.
+    pub fn record_access(&self, id: ConceptId) {
+        self.access_tracker.record(id, current_timestamp_us());
+    }
+
+    /// Delete a concept and all its associations
+    pub fn delete_concept(&self, id: ConceptId) -> Result<u64, WriteLogError> {
+        let timestamp = current_timestamp_us();
+        // Counted before the append so the reconciler can't settle it first
+        self.deleted_since_flush.fetch_add(1, Ordering::Relaxed);
+        let seq = self
+            .write_log
+            .append(crate::write_log::WriteEntry::DeleteConcept { id, timestamp })
+            .inspect_err(|_| {
+                self.deleted_since_flush.fetch_sub(1, Ordering::Relaxed);
+            })?;
+        if let Some(state) = self.quota.lock().as_mut() {
+            if let Some(size) = state.sizes.remove(&id) {
+                state.bytes -= size;
+            }
+        }
+
+        self.lexical_index.write().remove(&id);
+
+        // Leaves a tombstone in the HNSW index; rebuild once they pile up
+        self.vectors.write().remove(&id);
+        if self.hnsw_container.remove(&id) {
+            self.maybe_reindex_in_background();
+        }
+        Ok(seq)
+    }
+
+    /// Rebuild the HNSW index without tombstones
+    ///
+    /// Searches keep using the current index until the new one is swapped in.
+    /// Returns `None` if a rebuild is already running.
+    pub fn reindex(&self) -> anyhow::Result<Option<RebuildReport>> {
+        self.pool
+            .install(|| self.hnsw_container.rebuild(&self.vectors))
+    }
+
+    fn maybe_reindex_in_background(&self) {
+        let threshold = self.config.reindex_tombstone_ratio;
+        if threshold <= 0.0
+            || self.hnsw_container.is_rebuilding()
+            || self.hnsw_container.tombstone_ratio() < threshold
+        {
+            return;
+        }
+
+        let container = Arc::clone(&self.hnsw_container);
+        let vectors = Arc::clone(&self.vectors);
+        self.pool.spawn(move || {
+            if let Err(e) = container.rebuild(&vectors) {
+                log::warn!("⚠️ Background HNSW reindex failed: {}", e);
+            }
+        });
+    }
+
+    /// Clear all data in this memory instance
+    pub fn clear(&self) -> Result<u64, WriteLogError> {
+        // Clear HNSW index immediately (it's separate from WriteLog)
+        if let Err(e) = self.hnsw_container.clear() {
+            log::warn!("⚠️ Failed to clear HNSW container: {}", e);
+        }
+        self.vectors.write().clear();
+        self.lexical_index.write().clear();
+        if let Some(state) = self.quota.lock().as_mut() {
+            state.sizes.clear();
+            state.bytes = 0;
+        }
+
+        self.write_log.append(crate::write_log::WriteEntry::Clear)
+    }
+
+    /// Limit how much this memory may hold, or lift the limit with `None`
+    ///
+    /// Usage starts from the current snapshot; writes still waiting for the
+    /// reconciler when the quota is set are not counted.
+    pub fn set_quota(&self, quota: Option<NamespaceQuota>) {
+        let mut guard = self.quota.lock();
+        let Some(quota) = quota else {
+            *guard = None;
+            return;
+        };
+        if let Some(state) = guard.as_mut() {
+            state.quota = quota;
+            return;
+        }
+        let snapshot = self.read_view.load();
+        let sizes: HashMap<ConceptId, u64> = snapshot
+            .concepts
+            .values()
+            .map(|node| {
+                let vector_len = node.vector.as_ref().map_or(0, |v| v.len());
+                (
+                    node.id,
+                    concept_bytes(&node.content, vector_len, &node.attributes),
+                )
+            })
+            .collect();
+        *guard = Some(QuotaState {
+            quota,
+            bytes: sizes.values().sum(),
+            sizes,
+            evicted: 0,
+        });
+    }
+
+    /// Usage against the quota, if one is set
+    pub fn quota_usage(&self) -> Option<QuotaUsage> {
+        self.quota.lock().as_ref().map(|state| QuotaUsage {
+            quota: state.quota,
+            concepts: state.sizes.len(),
+            bytes: state.bytes,
+            evicted: state.evicted,
+        })
+    }
+
+    /// Count concepts about to be written against the quota
+    ///
+    /// Re-learning a concept only counts its change in size. If the writes do
+    /// not fit, they are refused, or with `QuotaMode::EvictLru` and `may_evict`
+    /// the least-recently-accessed reconciled concepts are picked to make
+    /// room. Nothing is deleted until the admission is settled.
+    fn admit_concepts(
+        &self,
+        writes: &[(ConceptId, u64)],
+        may_evict: bool,
+    ) -> Result<Option<QuotaAdmission>, WriteLogError> {
+        let mut guard = self.quota.lock();
+        let Some(state) = guard.as_mut() else {
+            return Ok(None);
+        };
+        let incoming: HashMap<ConceptId, u64> = writes.iter().copied().collect();
+        let mut concepts = state.sizes.len();
+        let mut bytes = state.bytes;
+        for (id, size) in &incoming {
+            match state.sizes.get(id) {
+                Some(old) => bytes = bytes - old + size,
+                None => {
+                    concepts += 1;
+                    bytes += size;
+                }
+            }
+        }
+
+        let mut victims = Vec::new();
+        if !state.quota.allows(concepts, bytes)
+            && may_evict
+            && state.quota.mode == QuotaMode::EvictLru
+        {
+            // Heapify is linear; only the victims actually taken pay log n
+            let snapshot = self.read_view.load();
+            let mut candidates: std::collections::BinaryHeap<_> = snapshot
+                .concepts
+                .values()
+                .filter(|node| {
+                    state.sizes.contains_key(&node.id) && !incoming.contains_key(&node.id)
+                })
+                .map(|node| std::cmp::Reverse((node.last_accessed, node.created, node.id.0)))
+                .collect();
+            while !state.quota.allows(concepts, bytes) {
+                let Some(std::cmp::Reverse((_, _, id))) = candidates.pop() else {
+                    break;
+                };
+                let id = ConceptId(id);
+                let size = state.sizes[&id];
+                concepts -= 1;
+                bytes -= size;
+                victims.push((id, size));
+            }
+        }
+        if !state.quota.allows(concepts, bytes) {
+            return Err(WriteLogError::QuotaExceeded(format!(
+                "{} concepts / {} bytes would exceed max_concepts {} / max_bytes {}",
+                concepts, bytes, state.quota.max_concepts, state.quota.max_bytes
+            )));
+        }
+
+        for (id, _) in &victims {
+            state.sizes.remove(id);
+        }
+        state.evicted += victims.len() as u64;
+        let incoming = incoming
+            .into_iter()
+            .map(|(id, size)| (id, state.sizes.insert(id, size), size))
+            .collect();
+        state.bytes = bytes;
+        Ok(Some(QuotaAdmission { incoming, victims }))
+    }
+
+    /// Finish an admission once its writes were logged, or undo it if not
+    ///
+    /// Victims are only deleted after the write that needed the room went
+    /// through. A victim that cannot be deleted is counted again.
+    fn settle_admission(&self, admission: Option<QuotaAdmission>, logged: bool) {
+        let Some(admission) = admission else {
+            return;
+        };
+        if !logged {
+            let mut guard = self.quota.lock();
+            if let Some(state) = guard.as_mut() {
+                for (id, previous, size) in admission.incoming {
+                    // Leave sizes a later write has replaced alone
+                    if state.sizes.get(&id) != Some(&size) {
+                        continue;
+                    }
+                    state.bytes -= size;
+                    match previous {
+                        Some(previous) => {
+                            state.sizes.insert(id, previous);
+                            state.bytes += previous;
+                        }
+                        None => {
+                            state.sizes.remove(&id);
+                        }
+                    }
+                }
+                for (id, size) in admission.victims {
+                    if state.sizes.insert(id, size).is_none() {
+                        state.bytes += size;
+                    }
+                    state.evicted -= 1;
+                }
+            }
+            return;
+        }
+
+        for (id, size) in admission.victims {
+            log::debug!("Evicting concept {} to stay within quota", id.to_hex());
+            if let Err(e) = self.delete_concept(id) {
+                log::warn!("⚠️ Failed to evict concept {}: {}", id.to_hex(), e);
+                if let Some(state) = self.quota.lock().as_mut() {
+                    if state.sizes.insert(id, size).is_none() {
+                        state.bytes += size;
+                    }
+                    state.evicted -= 1;
+                }
+            }
+        }
+    }
+
+    // ========================
+    // READ API (never blocks)
+    // ========================
+
+    /// Query a concept by ID
+    pub fn query_concept(&self, id: &ConceptId) -> Option<ConceptNode> {
+        self.read_view.get_concept(id)
+    }
+
+    /// Concepts whose attributes match every `key = value` filter, newest first
+    ///
+    /// Uses the snapshot's attribute index, so the cost depends on the size
+    /// of the matching posting lists rather than on the number of concepts.
+    /// Matches are ranked by creation time first, so only the `limit`
+    /// returned are cloned.
+    pub fn query_by_attributes(
+        &self,
+        filters: &HashMap<String, String>,
+        limit: usize,
+    ) -> Vec<ConceptNode> {
+        let snapshot = self.read_view.load();
+        let mut matches: Vec<(u64, ConceptId)> = snapshot
+            .query_by_attributes(filters)
+            .into_iter()
+            .filter_map(|id| snapshot.concepts.get(&id).map(|node| (node.created, id)))
+            .collect();
+        let newest_first = |a: &(u64, ConceptId), b: &(u64, ConceptId)| b.0.cmp(&a.0);
+        if limit < matches.len() {
+            matches.select_nth_unstable_by(limit, newest_first);
+            matches.truncate(limit);
+        }
+        matches.sort_by(newest_first);
+        matches
+            .into_iter()
+            .filter_map(|(_, id)| snapshot.concepts.get(&id).cloned())
+            .collect()
+    }
+
+    /// Up to `limit` concepts, newest first, strictly after `before`
+    ///
+    /// Order is `(created, id)` descending, so the `(created, id)` of the
+    /// last node of one page is the `before` of the next. Concepts written
+    /// between calls sort ahead of any earlier page and never shift it.
+    pub fn list_recent(&self, limit: usize, before: Option<(u64, ConceptId)>) -> Vec<ConceptNode> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        if limit == 0 {
+            return Vec::new();
+        }
+
+        let snapshot = self.read_view.load();
+        // Min-heap of the `limit` largest keys seen so far
+        let mut heap: BinaryHeap<Reverse<(u64, [u8; 16])>> =
+            BinaryHeap::with_capacity(limit.min(snapshot.concepts.len()) + 1);
+        for node in snapshot.concepts.values() {
+            let key = (node.created, node.id.0);
+            if let Some((created, id)) = before {
+                if key >= (created, id.0) {
+                    continue;
+                }
+            }
+            if heap.len() < limit {
+                heap.push(Reverse(key));
+            } else if heap.peek().is_some_and(|Reverse(min)| key > *min) {
+                heap.pop();
+                heap.push(Reverse(key));
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .filter_map(|Reverse((_, id))| snapshot.concepts.get(&ConceptId(id)).cloned())
+            .collect()
+    }
+
+    /// Get neighbors of a concept
+    pub fn query_neighbors(&self, id: &ConceptId) -> Vec<ConceptId> {
+        self.read_view.get_neighbors(id)
+    }
+
+    /// Get neighbors with association strengths
+    pub fn query_neighbors_weighted(&self, id: &ConceptId) -> Vec<(ConceptId, f32)> {
+        let snapshot = self.read_view.load();
+        snapshot.get_neighbors_weighted(id)
+    }
+
+    /// Find path between two concepts (BFS - sequential)
+    pub fn find_path(
+        &self,
+        start: ConceptId,
+        end: ConceptId,
+        max_depth: usize,
+    ) -> Option<Vec<ConceptId>> {
+        self.read_view.find_path(start, end, max_depth)
+    }
+
+    /// [`find_path`](Self::find_path) that stops once `deadline` has passed
+    pub fn find_path_until(
+        &self,
+        start: ConceptId,
+        end: ConceptId,
+        max_depth: usize,
+        deadline: Option<Instant>,
+    ) -> Result<Option<Vec<ConceptId>>, DeadlineExceeded> {
+        self.read_view
+            .load()
+            .find_path_until(start, end, max_depth, deadline)
+    }
+
+    /// 🚀 NEW: Find multiple paths in parallel (4-8× speedup)
+    pub fn find_paths_parallel(
+        &self,
         start: ConceptId,
         end: ConceptId,
         max_depth: usize,
@@ -934,14 +2019,184 @@ impl ConcurrentMemory {
         self.read_view.load().contains(id)
     }
 
+    /// Whether `id` exists or a write adding it is waiting for the reconciler
+    pub fn contains_or_pending(&self, id: &ConceptId) -> bool {
+        self.contains(id) || self.write_log.has_pending_concept(id)
+    }
+
+    /// Most-accessed concepts (access count, then most recent access)
+    pub fn top_accessed(&self, limit: usize) -> Vec<AccessRank> {
+        self.access_ranking().iter().take(limit).copied().collect()
+    }
+
+    /// Least-accessed concepts (access count, then least recent access)
+    pub fn coldest_concepts(&self, limit: usize) -> Vec<AccessRank> {
+        self.access_ranking()
+            .iter()
+            .rev()
+            .take(limit)
+            .copied()
+            .collect()
+    }
+
+    /// Hot-to-cold ranking for the current snapshot.
+    ///
+    /// The sort is done once per snapshot sequence and shared by callers, so
+    /// repeated queries between reconciliations are O(limit).
+    fn access_ranking(&self) -> Arc<Vec<AccessRank>> {
+        let snapshot = self.read_view.load();
+        let mut cached = self.access_ranking.lock();
+
+        if let Some((sequence, ranking)) = cached.as_ref() {
+            if *sequence == snapshot.sequence {
+                return Arc::clone(ranking);
+            }
+        }
+
+        let mut ranking: Vec<AccessRank> = snapshot
+            .concepts
+            .values()
+            .map(|node| AccessRank {
+                id: node.id,
+                access_count: node.access_count,
+                last_accessed: node.last_accessed,
+            })
+            .collect();
+        ranking.sort_by(|a, b| {
+            b.access_count
+                .cmp(&a.access_count)
+                .then(b.last_accessed.cmp(&a.last_accessed))
+        });
+
+        let ranking = Arc::new(ranking);
+        *cached = Some((snapshot.sequence, Arc::clone(&ranking)));
+        ranking
+    }
+
+    /// Dry-run audit of duplicate content in the current snapshot
+    ///
+    /// Exact duplicates share the same content bytes; near duplicates are
+    /// linked by embedding similarity of at least `similarity_threshold`
+    /// (clamped to `[0, 1]`), transitively. Each exact group takes part in the
+    /// near-duplicate pass through its oldest concept only, so no concept is
+    /// counted twice. Nothing is modified.
+    pub fn duplicate_report(&self, similarity_threshold: f32) -> DuplicateReport {
+        const NEAR_DUPLICATE_CANDIDATES: usize = 10;
+
+        let threshold = similarity_threshold.clamp(0.0, 1.0);
+        let snapshot = self.read_view.load();
+        let oldest_first = |ids: &mut Vec<ConceptId>| {
+            ids.sort_by_key(|id| {
+                let created = snapshot.concepts.get(id).map_or(0, |node| node.created);
+                (created, id.to_hex())
+            });
+        };
+
+        let mut by_content: HashMap<&[u8], Vec<ConceptId>> = HashMap::new();
+        for node in snapshot.concepts.values() {
+            by_content.entry(&node.content).or_default().push(node.id);
+        }
+
+        let mut exact = Vec::new();
+        let mut candidates = HashMap::new();
+        for mut ids in by_content.into_values() {
+            oldest_first(&mut ids);
+            candidates.insert(ids[0], ids[0]);
+            if ids.len() > 1 {
+                exact.push(DuplicateGroup {
+                    concept_ids: ids,
+                    similarity: 1.0,
+                });
+            }
+        }
+
+        // Union-find over candidates linked by a similar-enough neighbor
+        fn root(parents: &mut HashMap<ConceptId, ConceptId>, id: ConceptId) -> ConceptId {
+            let mut r = id;
+            while parents[&r] != r {
+                r = parents[&r];
+            }
+            let mut current = id;
+            while current != r {
+                current = parents.insert(current, r).unwrap_or(r);
+            }
+            r
+        }
+        let mut links = Vec::new();
+        for node in snapshot.concepts.values() {
+            let Some(vector) = node.vector.as_ref() else {
+                continue;
+            };
+            if !candidates.contains_key(&node.id) {
+                continue;
+            }
+            for (other, similarity) in
+                self.hnsw_container
+                    .search(vector, NEAR_DUPLICATE_CANDIDATES, 0)
+            {
+                if other != node.id && similarity >= threshold && candidates.contains_key(&other) {
+                    let (a, b) = (root(&mut candidates, node.id), root(&mut candidates, other));
+                    if a != b {
+                        candidates.insert(a, b);
+                    }
+                    links.push((node.id, similarity));
+                }
+            }
+        }
+
+        let mut members: HashMap<ConceptId, Vec<ConceptId>> = HashMap::new();
+        for id in candidates.keys().copied().collect::<Vec<_>>() {
+            let r = root(&mut candidates, id);
+            members.entry(r).or_default().push(id);
+        }
+        let mut lowest: HashMap<ConceptId, f32> = HashMap::new();
+        for (id, similarity) in links {
+            let r = root(&mut candidates, id);
+            let entry = lowest.entry(r).or_insert(1.0);
+            *entry = entry.min(similarity);
+        }
+        let mut near: Vec<DuplicateGroup> = members
+            .into_iter()
+            .filter(|(_, ids)| ids.len() > 1)
+            .map(|(r, mut ids)| {
+                oldest_first(&mut ids);
+                DuplicateGroup {
+                    concept_ids: ids,
+                    similarity: lowest.get(&r).copied().unwrap_or(threshold),
+                }
+            })
+            .collect();
+
+        for groups in [&mut exact, &mut near] {
+            groups.sort_by(|a, b| {
+                b.concept_ids
+                    .len()
+                    .cmp(&a.concept_ids.len())
+                    .then_with(|| a.concept_ids[0].to_hex().cmp(&b.concept_ids[0].to_hex()))
+            });
+        }
+        let reclaimable = exact
+            .iter()
+            .chain(near.iter())
+            .map(|group| group.concept_ids.len() - 1)
+            .sum();
+
+        DuplicateReport {
+            exact,
+            near,
+            reclaimable,
+        }
+    }
+
     /// Get current snapshot stats
     pub fn snapshot_info(&self) -> SnapshotInfo {
-        let (sequence, timestamp, concepts, edges) = self.read_view.snapshot_info();
+        let snapshot = self.read_view.load();
         SnapshotInfo {
-            sequence,
-            timestamp,
-            concept_count: concepts,
-            edge_count: edges,
+            sequence: snapshot.sequence,
+            timestamp: snapshot.timestamp,
+            concept_count: snapshot.concept_count,
+            edge_count: snapshot.edge_count,
+            attribute_index_entries: snapshot.attribute_index_size(),
         }
     }
 
@@ -949,10 +2204,16 @@ impl ConcurrentMemory {
     // VECTOR SEARCH API
     // ========================
 
-    /// Store vector for indexing (internal, called automatically by learn_concept)
-    fn index_vector(&self, concept_id: ConceptId, vector: Vec<f32>) -> Result<(), String> {
-        self.vectors.write().insert(concept_id, vector);
-        Ok(())
+    /// Index an accepted concept write for keyword and vector search
+    ///
+    /// Skipped when the conflict policy may keep an older version: the
+    /// reconciler indexes whichever version it stores instead.
+    fn index_accepted(&self, id: ConceptId, tokens: Vec<String>, vector: Option<Vec<f32>>) {
+        if self.config.adaptive_reconciler_config.conflict_policy != ConflictPolicy::LastWriteWins {
+            return;
+        }
+        self.lexical_index.write().insert_tokens(id, tokens);
+        index_concept_vector(&self.vectors, &self.hnsw_container, id, vector);
     }
 
     /// Vector similarity search (k-NN) - 🔥 NOW USES PERSISTENT HNSW (100× faster!)
@@ -987,6 +2248,28 @@ impl ConcurrentMemory {
         results
     }
 
+    /// [`vector_search`](Self::vector_search) for several queries at once
+    ///
+    /// Results come back in query order. The HNSW index is locked once for
+    /// the batch and queries run in parallel on the storage pool.
+    pub fn vector_search_batch(
+        &self,
+        queries: &[Vec<f32>],
+        k: usize,
+        ef_search: usize,
+    ) -> Vec<Vec<(ConceptId, f32)>> {
+        let start = Instant::now();
+        let results = self
+            .pool
+            .install(|| self.hnsw_container.search_batch(queries, k, ef_search));
+        log::info!(
+            "✅ Batched vector search completed: {} queries in {:.2}ms",
+            queries.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        results
+    }
+
     /// Get HNSW statistics
     pub fn hnsw_stats(&self) -> HnswStats {
         // 🔥 NEW: Get stats from persistent container
@@ -1004,6 +2287,8 @@ impl ConcurrentMemory {
             indexed_vectors: container_stats.num_vectors,
             dimension: container_stats.dimension,
             index_ready: container_stats.initialized,
+            tombstones: container_stats.tombstones,
+            last_reindex_us: container_stats.last_rebuild_us,
         }
     }
 
@@ -1027,40 +2312,11 @@ impl ConcurrentMemory {
     /// Text-based keyword search (for use without embeddings)
     ///
     /// Searches concepts using keyword matching with stop word filtering.
-    /// Returns concepts ranked by number of matching keywords.
-    ///
-    /// This is used when no external embedding service is available.
-    pub fn text_search(&self, query: &str, limit: usize) -> Vec<(ConceptId, String, f32)> {
-        // Common stop words to filter out
-        const STOP_WORDS: &[&str] = &[
-            "what", "is", "the", "a", "an", "of", "in", "to", "for", "on", "with", "by", "at",
-            "from", "as", "are", "was", "were", "be", "been", "being", "have", "has", "had", "do",
-            "does", "did", "will", "would", "could", "should", "may", "might", "can", "this",
-            "that", "these", "those", "it", "its", "my", "your", "his", "her", "their", "our",
-            "which", "who", "whom", "whose", "where", "when", "why", "how", "all", "any", "both",
-            "each", "tell", "me", "about", "please", "give", "show", "explain",
-        ];
-
-        // Extract keywords from query (filter stop words, min length 2)
-        let keywords: Vec<String> = query
-            .to_lowercase()
-            .split(|c: char| !c.is_alphanumeric())
-            .filter(|w| !w.is_empty() && w.len() > 1 && !STOP_WORDS.contains(w))
-            .map(String::from)
-            .collect();
-
-        // If all words filtered out, use original words
-        let keywords = if keywords.is_empty() {
-            query
-                .to_lowercase()
-                .split(|c: char| !c.is_alphanumeric())
-                .filter(|w| !w.is_empty() && w.len() > 1)
-                .map(String::from)
-                .collect()
-        } else {
-            keywords
-        };
-
+    /// Returns concepts ranked by number of matching keywords.
+    ///
+    /// This is used when no external embedding service is available.
+    pub fn text_search(&self, query: &str, limit: usize) -> Vec<(ConceptId, String, f32)> {
+        let keywords = crate::highlight::query_keywords(query);
         if keywords.is_empty() {
             return Vec::new();
         }
@@ -1085,7 +2341,7 @@ impl ConcurrentMemory {
         }
 
         // Sort by score descending
-        scored_results.sort_by(|a, b| b.2.cmp(&a.2));
+        scored_results.sort_by_key(|r| std::cmp::Reverse(r.2));
 
         // Convert score to normalized confidence (0.0 - 1.0)
         let max_score = keywords.len() as f32;
@@ -1096,11 +2352,87 @@ impl ConcurrentMemory {
             .collect()
     }
 
+    /// Rank concepts for `query` by BM25 keyword score blended with `semantic`
+    ///
+    /// `semantic` holds vector search results for the same query. Each
+    /// concept scores `alpha * bm25 + (1 - alpha) * similarity`, with BM25
+    /// normalized so the best keyword match scores 1.0. Concepts whose content
+    /// contains the whole query verbatim (ignoring case) rank above all others,
+    /// however far their embeddings drift from the query's.
+    pub fn hybrid_search(
+        &self,
+        query: &str,
+        semantic: &[(ConceptId, f32)],
+        limit: usize,
+        alpha: f32,
+    ) -> Vec<(ConceptId, f32)> {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let terms = crate::highlight::query_keywords(query);
+        let phrase = query.trim().to_lowercase();
+        let snapshot = self.read_view.load();
+        let index = self.lexical_index.read();
+
+        let lexical = index.search(&terms, usize::MAX);
+        let best = lexical.first().map_or(0.0, |&(_, score)| score);
+        let mut scores: HashMap<ConceptId, f32> = HashMap::new();
+        for &(id, score) in &lexical {
+            scores.insert(id, alpha * score / best);
+        }
+        for &(id, similarity) in semantic {
+            *scores.entry(id).or_default() += (1.0 - alpha) * similarity;
+        }
+
+        let verbatim = |id: &ConceptId| {
+            !phrase.is_empty()
+                && index.contains_all(id, &terms)
+                && snapshot.concepts.get(id).is_some_and(|node| {
+                    std::str::from_utf8(&node.content)
+                        .is_ok_and(|content| content.to_lowercase().contains(&phrase))
+                })
+        };
+        let mut ranked: Vec<(bool, ConceptId, f32)> = scores
+            .into_iter()
+            .map(|(id, score)| (verbatim(&id), id, score))
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.2.total_cmp(&a.2)));
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, id, score)| (id, score))
+            .collect()
+    }
+
+    /// Keyword spans and best-matching snippet for each concept in `ids`
+    ///
+    /// Returns `None` for concepts that no longer exist or whose content is
+    /// not valid UTF-8.
+    pub fn highlight_matches(
+        &self,
+        ids: &[ConceptId],
+        query: &str,
+        snippet_len: usize,
+    ) -> Vec<Option<crate::highlight::TextHighlight>> {
+        let keywords = crate::highlight::query_keywords(query);
+        let snapshot = self.read_view.load();
+        ids.iter()
+            .map(|id| {
+                let node = snapshot.concepts.get(id)?;
+                let content = std::str::from_utf8(&node.content).ok()?;
+                Some(crate::highlight::highlight(content, &keywords, snippet_len))
+            })
+            .collect()
+    }
+
     /// Get read snapshot for external use
     pub fn get_snapshot(&self) -> Arc<crate::read_view::GraphSnapshot> {
         self.read_view.load()
     }
 
+    /// Replication log (present when `replication_log_capacity > 0`)
+    pub fn replication_log(&self) -> Option<&Arc<ReplicationLog>> {
+        self.replication_log.as_ref()
+    }
+
     /// Get configuration
     pub fn config(&self) -> &ConcurrentConfig {
         &self.config
@@ -1126,6 +2458,20 @@ impl ConcurrentMemory {
         self.write_log.stats()
     }
 
+    /// Whether writers should slow down, from write log depth and reconciler health
+    pub fn write_pressure(&self) -> WritePressure {
+        let write_stats = self.write_log.stats();
+        let utilization = write_stats.pending as f64 / write_stats.capacity.max(1) as f64;
+        WritePressure::assess(utilization, self.reconciler.stats().health_score)
+    }
+
+    fn check_backpressure(&self) -> Result<(), WriteLogError> {
+        if self.config.reject_on_backpressure && self.write_pressure() == WritePressure::High {
+            return Err(WriteLogError::Backpressure);
+        }
+        Ok(())
+    }
+
     /// Get adaptive reconciler statistics (AI-native metrics)
     pub fn reconciler_stats(&self) -> AdaptiveReconcilerStats {
         self.reconciler.stats()
@@ -1137,11 +2483,64 @@ impl ConcurrentMemory {
             write_log: self.write_stats(),
             reconciler: self.reconciler_stats(),
             snapshot: self.snapshot_info(),
+            storage_threads: self.pool.num_threads(),
+        }
+    }
+
+    /// Share of the concepts in `storage.dat` deleted since it was written
+    ///
+    /// Deletes of concepts learned after the last flush count too, so this
+    /// errs towards compacting early.
+    pub fn dead_fraction(&self) -> f32 {
+        let flushed = self.flushed_concepts.load(Ordering::Relaxed);
+        if flushed == 0 {
+            return 0.0;
+        }
+        self.deleted_since_flush.load(Ordering::Relaxed) as f32 / flushed as f32
+    }
+
+    /// Whether [`compact_if_needed`](Self::compact_if_needed) would rewrite `storage.dat`
+    pub fn compaction_due(&self) -> bool {
+        let threshold = self.config.compaction_dead_fraction;
+        threshold > 0.0
+            && !self.compacting.load(Ordering::Relaxed)
+            && self.dead_fraction() >= threshold
+    }
+
+    /// Rewrite `storage.dat` without deleted concepts once the
+    /// [`dead_fraction`](Self::dead_fraction) reaches `compaction_dead_fraction`
+    ///
+    /// `flush` always writes the live snapshot, so compaction is a flush
+    /// brought forward. Pending deletes get up to a second to reach the
+    /// snapshot first so the concepts they remove are dropped. Returns
+    /// whether it ran.
+    pub fn compact_if_needed(&self) -> anyhow::Result<bool> {
+        if !self.compaction_due() || self.compacting.swap(true, Ordering::AcqRel) {
+            return Ok(false);
+        }
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(1);
+        while self.write_log.pending_deletes() > 0 && Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(5));
         }
+        let dead_fraction = self.dead_fraction();
+        let result = self.flush();
+        self.compacting.store(false, Ordering::Release);
+        result?;
+
+        log::info!(
+            "🧹 Compacted storage.dat (dead fraction was {:.2})",
+            dead_fraction
+        );
+        Ok(true)
     }
 
     /// Force immediate flush to disk
     pub fn flush(&self) -> anyhow::Result<()> {
+        // Read before the snapshot: every delete settled by now is in it.
+        // Later or still pending deletes stay counted.
+        let settled = self.write_log.settled_deletes();
+
         // Get current snapshot
         let snap = self.read_view.load();
 
@@ -1154,6 +2553,17 @@ impl ConcurrentMemory {
         // Save complete storage snapshot to disk
         let storage_file = self.config.storage_path.join("storage.dat");
         self.save_snapshot_to_disk(&storage_file, &snap)?;
+        let written = settled.saturating_sub(
+            self.settled_deletes_at_flush
+                .fetch_max(settled, Ordering::Relaxed),
+        ) as usize;
+        let _ = self.deleted_since_flush.fetch_update(
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+            |deleted| Some(deleted.saturating_sub(written)),
+        );
+        self.flushed_concepts
+            .store(snap.concept_count, Ordering::Relaxed);
 
         // 🔥 NEW: Save HNSW container if dirty (100× faster next startup!)
         if self.hnsw_container.is_dirty() {
@@ -1234,9 +2644,14 @@ impl ConcurrentMemory {
         header[16..20].copy_from_slice(&(edge_count as u32).to_le_bytes());
         header[20..24].copy_from_slice(&(vector_count as u32).to_le_bytes());
 
-        // Reserved space (40 bytes) for future extensions
+        // Body codec (1 byte)
+        let compression = self.config.storage_compression;
+        header[24] = compression.codec();
+
+        // Reserved space (39 bytes) for future extensions
 
         writer.write_all(&header)?;
+        let mut writer = SnapshotWriter::new(writer, compression)?;
 
         log::info!(
             "📊 Writing {} concepts, {} edges, {} vectors",
@@ -1290,120 +2705,584 @@ impl ConcurrentMemory {
                 // Use concept confidence for edge confidence (4 bytes)
                 edge_data[32..36].copy_from_slice(&concept.confidence.to_le_bytes());
 
-                writer.write_all(&edge_data)?;
-            }
+                writer.write_all(&edge_data)?;
+            }
+        }
+
+        // Write vectors section
+        let vectors = self.vectors.read();
+        for concept in &all_concepts {
+            if let Some(vector) = vectors.get(&concept.id) {
+                // Vector header: concept_id(16) + dimension(4)
+                let mut vector_header = vec![0u8; 20];
+
+                // Concept ID (16 bytes)
+                vector_header[0..16].copy_from_slice(&concept.id.0);
+
+                // Dimension (4 bytes)
+                let dimension = vector.len();
+                vector_header[16..20].copy_from_slice(&(dimension as u32).to_le_bytes());
+
+                writer.write_all(&vector_header)?;
+
+                // Write vector components
+                for component in vector {
+                    writer.write_all(&component.to_le_bytes())?;
+                }
+            }
+        }
+
+        // Write metadata section (attributes + semantic)
+        for concept in &all_concepts {
+            // Concept ID (16 bytes)
+            writer.write_all(&concept.id.0)?;
+
+            let attrs_bytes = serde_json::to_vec(&concept.attributes)?;
+            let attrs_len = attrs_bytes.len() as u32;
+            writer.write_all(&attrs_len.to_le_bytes())?;
+            if attrs_len > 0 {
+                writer.write_all(&attrs_bytes)?;
+            }
+
+            let semantic_bytes = match &concept.semantic {
+                Some(meta) => serde_json::to_vec(meta)?,
+                None => Vec::new(),
+            };
+            let semantic_len = semantic_bytes.len() as u32;
+            writer.write_all(&semantic_len.to_le_bytes())?;
+            if semantic_len > 0 {
+                writer.write_all(&semantic_bytes)?;
+            }
+        }
+
+        // Flush and sync
+        writer.finish()?.flush()?;
+
+        let elapsed = save_start.elapsed();
+        let file_size = std::fs::metadata(storage_file)?.len();
+
+        log::info!(
+            "✅ Snapshot saved: {:.1} MB in {:.2}ms ({} concepts, {} edges, {} vectors)",
+            file_size as f64 / 1024.0 / 1024.0,
+            elapsed.as_secs_f64() * 1000.0,
+            concept_count,
+            edge_count,
+            vector_count
+        );
+
+        Ok(())
+    }
+}
+
+impl Drop for ConcurrentMemory {
+    fn drop(&mut self) {
+        // Reconciler will be dropped and stopped automatically
+    }
+}
+
+/// Access statistics for a single concept
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct AccessRank {
+    pub id: ConceptId,
+    pub access_count: u32,
+    pub last_accessed: u64,
+}
+
+/// Concepts holding the same or nearly the same content
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DuplicateGroup {
+    /// Oldest first; the first concept is the natural one to keep
+    pub concept_ids: Vec<ConceptId>,
+    /// 1.0 for exact duplicates, otherwise the weakest similarity linking the group
+    pub similarity: f32,
+}
+
+/// Result of `ConcurrentMemory::duplicate_report`
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DuplicateReport {
+    /// Groups with identical content bytes
+    pub exact: Vec<DuplicateGroup>,
+    /// Groups linked by embedding similarity
+    pub near: Vec<DuplicateGroup>,
+    /// Concepts that could be removed keeping one per group
+    pub reclaimable: usize,
+}
+
+/// Snapshot metadata
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct SnapshotInfo {
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub concept_count: usize,
+    pub edge_count: usize,
+    /// Entries in the attribute index
+    #[serde(default)]
+    pub attribute_index_entries: usize,
+}
+
+/// Complete system statistics
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConcurrentStats {
+    pub write_log: WriteLogStats,
+    pub reconciler: AdaptiveReconcilerStats,
+    pub snapshot: SnapshotInfo,
+    /// Threads available to parallel storage work
+    #[serde(default)]
+    pub storage_threads: usize,
+}
+
+/// HNSW index statistics
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct HnswStats {
+    pub indexed_vectors: usize,
+    pub dimension: usize,
+    pub index_ready: bool,
+    /// Indexed vectors of deleted concepts, dropped by the next reindex
+    pub tombstones: usize,
+    /// Completion time of the last reindex (Unix µs)
+    pub last_reindex_us: Option<u64>,
+}
+
+impl HnswStats {
+    /// Share of indexed vectors that are tombstones
+    pub fn tombstone_ratio(&self) -> f32 {
+        if self.indexed_vectors == 0 {
+            0.0
+        } else {
+            self.tombstones as f32 / self.indexed_vectors as f32
+        }
+    }
+}
+
+/// Get current timestamp in microseconds
+fn current_timestamp_us() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_access_ranking() {
+        let dir = TempDir::new().unwrap();
+        let config = ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let memory = ConcurrentMemory::new(config);
+
+        for i in 0..5u8 {
+            memory
+                .learn_concept(
+                    ConceptId([i; 16]),
+                    vec![i],
+                    None,
+                    1.0,
+                    0.9,
+                    std::collections::HashMap::new(),
+                )
+                .unwrap();
+        }
+        thread::sleep(Duration::from_millis(100));
+
+        let hot = ConceptId([3; 16]);
+        for _ in 0..10 {
+            memory.record_access(hot);
+        }
+        memory.record_access(ConceptId([1; 16]));
+        thread::sleep(Duration::from_millis(100));
+
+        let top = memory.top_accessed(2);
+        assert_eq!(top[0].id, hot);
+        assert_eq!(top[0].access_count, 10);
+        assert_eq!(top[1].id, ConceptId([1; 16]));
+
+        let cold = memory.coldest_concepts(3);
+        assert_eq!(cold.len(), 3);
+        assert!(cold.iter().all(|r| r.access_count == 0));
+        assert!(!cold.iter().any(|r| r.id == hot));
+    }
+
+    #[test]
+    fn test_record_access_bypasses_write_log_and_version() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let id = ConceptId([7; 16]);
+        memory
+            .learn_concept(id, b"seen".to_vec(), None, 1.0, 0.9, HashMap::new())
+            .unwrap();
+        thread::sleep(Duration::from_millis(100));
+        let version = memory.query_concept(&id).unwrap().version;
+        let written = memory.write_stats().written;
+
+        for _ in 0..3 {
+            memory.record_access(id);
+        }
+        thread::sleep(Duration::from_millis(100));
+
+        let node = memory.query_concept(&id).unwrap();
+        assert_eq!(node.access_count, 3);
+        assert!(node.last_accessed > 0);
+        assert_eq!(node.version, version);
+        assert_eq!(memory.write_stats().written, written);
+    }
+
+    #[test]
+    fn test_flush_drops_deleted_concepts_from_disk() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let settle = |count: usize| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while memory.get_snapshot().concept_count != count {
+                assert!(Instant::now() < deadline, "snapshot never settled");
+                thread::sleep(Duration::from_millis(10));
+            }
+        };
+        let storage_file = dir.path().join("storage.dat");
+
+        for i in 0..100u8 {
+            let content = format!("concept {} ", i).repeat(20).into_bytes();
+            memory
+                .learn_concept(ConceptId([i; 16]), content, None, 1.0, 0.9, HashMap::new())
+                .unwrap();
+        }
+        settle(100);
+        memory.flush().unwrap();
+        let size_before = std::fs::metadata(&storage_file).unwrap().len();
+
+        // storage.dat is rewritten from the snapshot, so deletes need no compaction pass
+        for i in (0..100u8).step_by(2) {
+            memory.delete_concept(ConceptId([i; 16])).unwrap();
+        }
+        settle(50);
+        memory.flush().unwrap();
+        let size_after = std::fs::metadata(&storage_file).unwrap().len();
+        assert!(
+            size_after < size_before * 6 / 10,
+            "{} -> {}",
+            size_before,
+            size_after
+        );
+
+        drop(memory);
+        let reopened = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        assert_eq!(reopened.get_snapshot().concept_count, 50);
+        assert!(reopened.query_concept(&ConceptId([0; 16])).is_none());
+        assert!(reopened.query_concept(&ConceptId([1; 16])).is_some());
+    }
+
+    #[test]
+    fn test_dead_fraction_triggers_compaction() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            compaction_dead_fraction: 0.4,
+            ..Default::default()
+        });
+        let storage_file = dir.path().join("storage.dat");
+
+        for i in 0..100u8 {
+            let content = format!("concept {} ", i).repeat(20).into_bytes();
+            memory
+                .learn_concept(ConceptId([i; 16]), content, None, 1.0, 0.9, HashMap::new())
+                .unwrap();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while memory.get_snapshot().concept_count != 100 {
+            assert!(Instant::now() < deadline, "snapshot never settled");
+            thread::sleep(Duration::from_millis(10));
+        }
+        memory.flush().unwrap();
+        let size_before = std::fs::metadata(&storage_file).unwrap().len();
+
+        // Below the threshold storage.dat is left alone
+        for i in 0..30u8 {
+            memory.delete_concept(ConceptId([i; 16])).unwrap();
         }
+        assert!(!memory.compaction_due());
+        assert!(!memory.compact_if_needed().unwrap());
+        assert_eq!(std::fs::metadata(&storage_file).unwrap().len(), size_before);
 
-        // Write vectors section
-        let vectors = self.vectors.read();
-        for concept in &all_concepts {
-            if let Some(vector) = vectors.get(&concept.id) {
-                // Vector header: concept_id(16) + dimension(4)
-                let mut vector_header = vec![0u8; 20];
+        for i in 30..50u8 {
+            memory.delete_concept(ConceptId([i; 16])).unwrap();
+        }
+        assert!((memory.dead_fraction() - 0.5).abs() < f32::EPSILON);
+        assert!(memory.compaction_due());
+        assert!(memory.compact_if_needed().unwrap());
 
-                // Concept ID (16 bytes)
-                vector_header[0..16].copy_from_slice(&concept.id.0);
+        let size_after = std::fs::metadata(&storage_file).unwrap().len();
+        assert!(
+            size_after < size_before * 6 / 10,
+            "{} -> {}",
+            size_before,
+            size_after
+        );
+        assert_eq!(memory.dead_fraction(), 0.0);
+        assert!(!memory.compaction_due());
+    }
 
-                // Dimension (4 bytes)
-                let dimension = vector.len();
-                vector_header[16..20].copy_from_slice(&(dimension as u32).to_le_bytes());
+    #[test]
+    fn test_compressed_storage_roundtrip() {
+        let contents: Vec<String> = (0..500)
+            .map(|i| format!("Concept {} holds repeated domain text. ", i).repeat(8))
+            .collect();
+        let write = |dir: &TempDir, storage_compression| {
+            let config = ConcurrentConfig {
+                storage_path: dir.path().to_path_buf(),
+                vector_dimension: 4,
+                storage_compression,
+                ..Default::default()
+            };
+            let memory = ConcurrentMemory::new(config.clone());
+            for (i, content) in contents.iter().enumerate() {
+                let mut attributes = HashMap::new();
+                attributes.insert("index".to_string(), i.to_string());
+                memory
+                    .learn_concept(
+                        ConceptId::from_string(content),
+                        content.as_bytes().to_vec(),
+                        Some(vec![i as f32, 1.0, 0.0, 0.0]),
+                        1.0,
+                        0.9,
+                        attributes,
+                    )
+                    .unwrap();
+            }
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while memory.get_snapshot().concept_count < contents.len() {
+                assert!(Instant::now() < deadline, "snapshot never settled");
+                thread::sleep(Duration::from_millis(10));
+            }
+            memory.flush().unwrap();
+            std::fs::metadata(dir.path().join("storage.dat"))
+                .unwrap()
+                .len()
+        };
 
-                writer.write_all(&vector_header)?;
+        let plain_dir = TempDir::new().unwrap();
+        let zstd_dir = TempDir::new().unwrap();
+        let plain_size = write(&plain_dir, StorageCompression::None);
+        let zstd_size = write(&zstd_dir, StorageCompression::Zstd(3));
+        assert!(
+            zstd_size < plain_size / 3,
+            "{} -> {}",
+            plain_size,
+            zstd_size
+        );
 
-                // Write vector components
-                for component in vector {
-                    writer.write_all(&component.to_le_bytes())?;
-                }
+        // The codec comes from the file, whatever the reader is configured with
+        for (dir, storage_compression) in [
+            (&plain_dir, StorageCompression::Zstd(3)),
+            (&zstd_dir, StorageCompression::None),
+        ] {
+            let memory = ConcurrentMemory::new(ConcurrentConfig {
+                storage_path: dir.path().to_path_buf(),
+                vector_dimension: 4,
+                storage_compression,
+                ..Default::default()
+            });
+            assert_eq!(memory.get_snapshot().concept_count, contents.len());
+            for (i, content) in contents.iter().enumerate().step_by(37) {
+                let node = memory
+                    .query_concept(&ConceptId::from_string(content))
+                    .unwrap();
+                assert_eq!(node.content.as_ref(), content.as_bytes());
+                assert_eq!(node.attributes["index"], i.to_string());
+                assert_eq!(node.vector.as_deref().unwrap()[0], i as f32);
             }
         }
+    }
 
-        // Write metadata section (attributes + semantic)
-        for concept in &all_concepts {
-            // Concept ID (16 bytes)
-            writer.write_all(&concept.id.0)?;
-
-            let attrs_bytes = serde_json::to_vec(&concept.attributes)?;
-            let attrs_len = attrs_bytes.len() as u32;
-            writer.write_all(&attrs_len.to_le_bytes())?;
-            if attrs_len > 0 {
-                writer.write_all(&attrs_bytes)?;
-            }
+    #[test]
+    fn test_storage_compression_from_str() {
+        assert_eq!("none".parse(), Ok(StorageCompression::None));
+        assert_eq!("zstd".parse(), Ok(StorageCompression::Zstd(3)));
+        assert_eq!("ZSTD:19".parse(), Ok(StorageCompression::Zstd(19)));
+        assert!("zstd:0".parse::<StorageCompression>().is_err());
+        assert!("lz4".parse::<StorageCompression>().is_err());
+    }
 
-            let semantic_bytes = match &concept.semantic {
-                Some(meta) => serde_json::to_vec(meta)?,
-                None => Vec::new(),
-            };
-            let semantic_len = semantic_bytes.len() as u32;
-            writer.write_all(&semantic_len.to_le_bytes())?;
-            if semantic_len > 0 {
-                writer.write_all(&semantic_bytes)?;
-            }
+    #[test]
+    fn test_quota_admission_rolls_back_unlogged_writes() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        for i in 0..3u8 {
+            memory
+                .learn_concept(
+                    ConceptId([i; 16]),
+                    vec![i; 10],
+                    None,
+                    1.0,
+                    0.9,
+                    HashMap::new(),
+                )
+                .unwrap();
         }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while memory.get_snapshot().concept_count < 3 {
+            assert!(Instant::now() < deadline, "snapshot never settled");
+            thread::sleep(Duration::from_millis(10));
+        }
+        memory.set_quota(Some(NamespaceQuota {
+            max_concepts: 3,
+            max_bytes: 0,
+            mode: QuotaMode::EvictLru,
+        }));
+        let usage = |memory: &ConcurrentMemory| {
+            let usage = memory.quota_usage().unwrap();
+            (usage.concepts, usage.bytes, usage.evicted)
+        };
+        assert_eq!(usage(&memory), (3, 30, 0));
 
-        // Flush and sync
-        writer.flush()?;
-
-        let elapsed = save_start.elapsed();
-        let file_size = std::fs::metadata(storage_file)?.len();
+        // A write that fails to log leaves the quota and its victim untouched
+        let admission = memory
+            .admit_concepts(&[(ConceptId([9; 16]), 50), (ConceptId([1; 16]), 20)], true)
+            .unwrap();
+        assert_eq!(usage(&memory), (3, 80, 1));
+        memory.settle_admission(admission, false);
+        assert_eq!(usage(&memory), (3, 30, 0));
+        assert!(memory.query_concept(&ConceptId([0; 16])).is_some());
+
+        // Once logged, the victim is deleted
+        let admission = memory
+            .admit_concepts(&[(ConceptId([9; 16]), 50)], true)
+            .unwrap();
+        memory.settle_admission(admission, true);
+        assert_eq!(usage(&memory), (3, 70, 1));
+        while memory.query_concept(&ConceptId([0; 16])).is_some() {
+            assert!(Instant::now() < deadline, "victim never deleted");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
 
-        log::info!(
-            "✅ Snapshot saved: {:.1} MB in {:.2}ms ({} concepts, {} edges, {} vectors)",
-            file_size as f64 / 1024.0 / 1024.0,
-            elapsed.as_secs_f64() * 1000.0,
-            concept_count,
-            edge_count,
-            vector_count
-        );
+    #[test]
+    fn test_update_concept_logs_and_counts_quota() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let id = ConceptId([7; 16]);
+        memory
+            .learn_concept(id, vec![0; 10], None, 1.0, 0.9, HashMap::new())
+            .unwrap();
+        // Updatable before the reconciler has stored it
+        assert!(memory.contains_or_pending(&id));
+        assert!(!memory.contains_or_pending(&ConceptId([8; 16])));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while memory.query_concept(&id).is_none() {
+            assert!(Instant::now() < deadline, "snapshot never settled");
+            thread::sleep(Duration::from_millis(10));
+        }
+        memory.set_quota(Some(NamespaceQuota {
+            max_concepts: 0,
+            max_bytes: 20,
+            mode: QuotaMode::Reject,
+        }));
+        let attrs = |value: &str| HashMap::from([("k".to_string(), value.to_string())]);
 
-        Ok(())
-    }
-}
+        memory
+            .update_concept(id, None, None, attrs("vvvv"))
+            .unwrap();
+        assert_eq!(memory.quota_usage().unwrap().bytes, 15);
+        while memory.query_concept(&id).unwrap().attributes.is_empty() {
+            assert!(Instant::now() < deadline, "update never applied");
+            thread::sleep(Duration::from_millis(10));
+        }
+        // Replacing a value only counts the difference
+        memory.update_concept(id, None, None, attrs("v")).unwrap();
+        assert_eq!(memory.quota_usage().unwrap().bytes, 12);
+        assert!(matches!(
+            memory.update_concept(id, None, None, attrs(&"v".repeat(20))),
+            Err(WriteLogError::QuotaExceeded(_))
+        ));
+        assert_eq!(memory.quota_usage().unwrap().bytes, 12);
 
-impl Drop for ConcurrentMemory {
-    fn drop(&mut self) {
-        // Reconciler will be dropped and stopped automatically
+        let logged = WriteAheadLog::replay(dir.path().join("wal.log")).unwrap();
+        let updates = logged
+            .iter()
+            .filter(|entry| {
+                matches!(entry.operation, Operation::UpdateConcept { concept_id, .. } if concept_id == id)
+            })
+            .count();
+        assert_eq!(updates, 2);
     }
-}
-
-/// Snapshot metadata
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
-pub struct SnapshotInfo {
-    pub sequence: u64,
-    pub timestamp: u64,
-    pub concept_count: usize,
-    pub edge_count: usize,
-}
 
-/// Complete system statistics
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ConcurrentStats {
-    pub write_log: WriteLogStats,
-    pub reconciler: AdaptiveReconcilerStats,
-    pub snapshot: SnapshotInfo,
-}
+    #[test]
+    fn test_replace_replicated_never_exposes_empty_store() {
+        let dir = TempDir::new().unwrap();
+        let memory = Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        }));
+        let (old, kept, new) = (ConceptId([1; 16]), ConceptId([2; 16]), ConceptId([3; 16]));
+        for id in [old, kept] {
+            memory
+                .learn_concept(id, b"old".to_vec(), None, 1.0, 0.9, HashMap::new())
+                .unwrap();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while memory.get_snapshot().concept_count < 2 {
+            assert!(Instant::now() < deadline, "snapshot never settled");
+            thread::sleep(Duration::from_millis(10));
+        }
 
-/// HNSW index statistics
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
-pub struct HnswStats {
-    pub indexed_vectors: usize,
-    pub dimension: usize,
-    pub index_ready: bool,
-}
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let memory = Arc::clone(&memory);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    assert!(memory.get_snapshot().concept_count > 0, "store seen empty");
+                }
+            })
+        };
 
-/// Get current timestamp in microseconds
-fn current_timestamp_us() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_micros() as u64
-}
+        let op = |id: ConceptId| ReplicationOp::AddConcept {
+            id,
+            content: b"new".to_vec(),
+            vector: None,
+            strength: 1.0,
+            confidence: 0.9,
+            timestamp: 1,
+            attributes: HashMap::new(),
+            semantic: None,
+        };
+        memory.replace_replicated(vec![op(kept), op(new)]).unwrap();
+        while memory.query_concept(&new).is_none() {
+            assert!(Instant::now() < deadline, "snapshot never swapped in");
+            thread::sleep(Duration::from_millis(10));
+        }
+        done.store(true, Ordering::Relaxed);
+        reader.join().unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::thread;
-    use std::time::Duration;
-    use tempfile::TempDir;
+        assert!(memory.query_concept(&old).is_none());
+        assert_eq!(
+            memory.query_concept(&kept).unwrap().content.as_ref(),
+            b"new"
+        );
+        assert_eq!(memory.get_snapshot().concept_count, 2);
+    }
 
     #[test]
     fn test_basic_operations() {
@@ -1715,6 +3594,218 @@ mod tests {
         assert!(stats.snapshot.concept_count >= 10);
     }
 
+    #[test]
+    fn test_write_pressure_rises_with_backlog() {
+        let dir = TempDir::new().unwrap();
+        // A reconciler that drains one entry per 100ms cannot keep up
+        let config = ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            adaptive_reconciler_config: AdaptiveReconcilerConfig {
+                base_interval_ms: 100,
+                min_interval_ms: 100,
+                max_interval_ms: 100,
+                max_batch_size: 1,
+                ..Default::default()
+            },
+            reject_on_backpressure: true,
+            ..Default::default()
+        };
+        let memory = ConcurrentMemory::new(config);
+        let learn = |i: u8| {
+            memory.learn_concept(ConceptId([i; 16]), vec![i], None, 1.0, 0.9, HashMap::new())
+        };
+
+        assert_eq!(memory.write_pressure(), WritePressure::Low);
+        learn(1).unwrap();
+
+        let capacity = memory.write_stats().capacity;
+        for _ in 0..capacity * 6 / 10 {
+            memory
+                .write_log
+                .append(WriteEntry::RecordAccess {
+                    id: ConceptId([1; 16]),
+                    timestamp: 0,
+                })
+                .unwrap();
+        }
+        assert_eq!(memory.write_pressure(), WritePressure::Medium);
+        learn(2).unwrap();
+
+        for _ in 0..capacity * 3 / 10 {
+            memory
+                .write_log
+                .append(WriteEntry::RecordAccess {
+                    id: ConceptId([1; 16]),
+                    timestamp: 0,
+                })
+                .unwrap();
+        }
+        assert_eq!(memory.write_pressure(), WritePressure::High);
+        assert_eq!(learn(3), Err(WriteLogError::Backpressure));
+        assert_eq!(
+            memory.learn_association(
+                ConceptId([1; 16]),
+                ConceptId([2; 16]),
+                AssociationType::Semantic,
+                0.9
+            ),
+            Err(WriteLogError::Backpressure)
+        );
+        // Deletes still go through, so writers can shed load
+        memory.delete_concept(ConceptId([1; 16])).unwrap();
+
+        // A sustained unhealthy reconciler raises pressure even with room left
+        assert_eq!(WritePressure::assess(0.1, 0.4), WritePressure::Medium);
+        assert_eq!(WritePressure::assess(0.1, 0.1), WritePressure::High);
+        assert_eq!(WritePressure::assess(0.1, 1.0), WritePressure::Low);
+    }
+
+    #[test]
+    fn test_hybrid_search_ranks_exact_match_above_semantic_neighbour() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let exact = ConceptId::from_string("exact");
+        let similar = ConceptId::from_string("similar");
+        let unrelated = ConceptId::from_string("unrelated");
+        for (id, content) in [
+            (
+                exact,
+                "Replacement fan for unit SKU-88213-B ships in two days",
+            ),
+            (similar, "Spare fans and cooling parts for server units"),
+            (unrelated, "Quarterly revenue grew eight percent"),
+        ] {
+            memory
+                .learn_concept(
+                    id,
+                    content.as_bytes().to_vec(),
+                    None,
+                    1.0,
+                    0.9,
+                    HashMap::new(),
+                )
+                .unwrap();
+        }
+        let start = Instant::now();
+        while memory.get_snapshot().concept_count < 3 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        // Embeddings favour the generic concept over the one holding the SKU
+        let semantic = vec![(similar, 0.95), (exact, 0.2), (unrelated, 0.1)];
+        let ranked = memory.hybrid_search("sku-88213-b", &semantic, 3, 0.3);
+        assert_eq!(ranked[0].0, exact);
+        assert_eq!(ranked[1].0, similar);
+
+        // Without a verbatim match, keyword and vector scores are blended
+        let ranked = memory.hybrid_search("cooling fans", &semantic, 3, 0.5);
+        assert_eq!(ranked[0].0, similar);
+
+        // Deleted concepts leave the keyword index at once
+        memory.delete_concept(exact).unwrap();
+        let ranked = memory.hybrid_search("sku-88213-b", &[], 3, 1.0);
+        assert!(ranked.is_empty());
+    }
+
+    #[test]
+    fn test_conflict_policy_decides_indexed_version() {
+        let dir = TempDir::new().unwrap();
+        let config = ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            vector_dimension: 4,
+            adaptive_reconciler_config: AdaptiveReconcilerConfig {
+                conflict_policy: ConflictPolicy::HighestConfidence,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let memory = ConcurrentMemory::new(config.clone());
+        let id = ConceptId::from_string("fruit");
+        let learn = |content: &str, vector: [f32; 4], confidence: f32| {
+            memory
+                .learn_concept(
+                    id,
+                    content.as_bytes().to_vec(),
+                    Some(vector.to_vec()),
+                    1.0,
+                    confidence,
+                    HashMap::new(),
+                )
+                .unwrap();
+            thread::sleep(Duration::from_millis(100));
+        };
+        let top_score = |vector: [f32; 4]| {
+            memory
+                .vector_search(&vector, 1, 50)
+                .first()
+                .map_or(0.0, |(_, score)| *score)
+        };
+
+        learn("apple orchard", [1.0, 0.0, 0.0, 0.0], 0.9);
+        // Lower confidence loses: neither index may serve the discarded version
+        learn("banana plantation", [0.0, 1.0, 0.0, 0.0], 0.1);
+        assert_eq!(
+            memory.query_concept(&id).unwrap().content.as_ref(),
+            b"apple orchard"
+        );
+        assert!(memory.text_search("banana", 5).is_empty());
+        assert_eq!(memory.text_search("apple", 5)[0].0, id);
+        assert!(top_score([1.0, 0.0, 0.0, 0.0]) > 0.99);
+        assert!(top_score([0.0, 1.0, 0.0, 0.0]) < 0.5);
+
+        // Higher confidence wins: both indexes move to the new version
+        learn("cherry grove", [0.0, 0.0, 1.0, 0.0], 0.95);
+        assert!(memory.text_search("apple", 5).is_empty());
+        assert_eq!(memory.text_search("cherry", 5)[0].0, id);
+        assert!(top_score([0.0, 0.0, 1.0, 0.0]) > 0.99);
+        assert!(top_score([1.0, 0.0, 0.0, 0.0]) < 0.5);
+
+        // Recovery loads the reconciled snapshot, so the winner survives a restart
+        memory.flush().unwrap();
+        drop(memory);
+        let memory = ConcurrentMemory::new(config);
+        assert_eq!(
+            memory.query_concept(&id).unwrap().content.as_ref(),
+            b"cherry grove"
+        );
+        assert_eq!(memory.text_search("cherry", 5)[0].0, id);
+    }
+
+    #[test]
+    fn test_relearn_replaces_indexed_vector() {
+        let dir = TempDir::new().unwrap();
+        let memory = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            vector_dimension: 4,
+            ..Default::default()
+        });
+        let id = ConceptId::from_string("moved");
+        for vector in [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]] {
+            memory
+                .learn_concept(
+                    id,
+                    b"moved".to_vec(),
+                    Some(vector.to_vec()),
+                    1.0,
+                    0.9,
+                    HashMap::new(),
+                )
+                .unwrap();
+        }
+
+        let results = memory.vector_search(&[0.0, 1.0, 0.0, 0.0], 1, 50);
+        assert_eq!(results[0].0, id);
+        assert!(
+            results[0].1 > 0.99,
+            "stale vector still indexed: {:?}",
+            results
+        );
+    }
+
     #[test]
     fn test_wal_crash_recovery() {
         let dir = TempDir::new().unwrap();
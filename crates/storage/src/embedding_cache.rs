@@ -0,0 +1,321 @@
+//! Process-wide embedding cache
+//!
+//! Content-hash keyed cache shared (behind an `Arc`) by every `LearningPipeline`
+//! that uses the same embedding model, so identical text learned into several
+//! namespaces is embedded once. Concurrent misses for the same text are
+//! coalesced: one caller runs the provider, the others wait for its result.
+//!
+//! Namespaces listed in `isolated_namespaces` never read from or write to the
+//! cache, for tenants that must not share anything with other namespaces.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+use crate::embedding_provider::EmbeddingProvider;
+
+/// Configuration for the embedding cache
+#[derive(Debug, Clone)]
+pub struct EmbeddingCacheConfig {
+    /// Maximum number of cached embeddings
+    pub max_entries: usize,
+    /// How long an embedding stays valid after it was computed
+    pub ttl: Duration,
+    /// Namespaces that bypass the cache entirely
+    pub isolated_namespaces: HashSet<String>,
+}
+
+impl Default for EmbeddingCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: std::env::var("SUTRA_EMBEDDING_CACHE_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10_000),
+            ttl: Duration::from_secs(
+                std::env::var("SUTRA_EMBEDDING_CACHE_TTL_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3600),
+            ),
+            isolated_namespaces: std::env::var("SUTRA_EMBEDDING_CACHE_ISOLATED")
+                .map(|s| {
+                    s.split(',')
+                        .map(|ns| ns.trim().to_string())
+                        .filter(|ns| !ns.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Cache statistics
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddingCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+/// Cache key: MD5 of the text plus the normalize flag
+type CacheKey = ([u8; 16], bool);
+
+struct Slot {
+    created: Instant,
+    value: OnceCell<Arc<Vec<f32>>>,
+}
+
+/// Thread-safe embedding cache with size bound, TTL and hit/miss accounting
+pub struct EmbeddingCache {
+    config: EmbeddingCacheConfig,
+    slots: Mutex<HashMap<CacheKey, Arc<Slot>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+static SHARED: Lazy<Arc<EmbeddingCache>> =
+    Lazy::new(|| Arc::new(EmbeddingCache::new(EmbeddingCacheConfig::default())));
+
+impl EmbeddingCache {
+    pub fn new(config: EmbeddingCacheConfig) -> Self {
+        Self {
+            config,
+            slots: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Process-wide cache for pipelines using the default embedding provider
+    pub fn shared() -> Arc<EmbeddingCache> {
+        Arc::clone(&SHARED)
+    }
+
+    /// Whether `namespace` has opted out of the cache
+    pub fn is_isolated(&self, namespace: &str) -> bool {
+        self.config.isolated_namespaces.contains(namespace)
+    }
+
+    /// Return the cached embedding for `text`, computing it with `provider` on a miss
+    pub async fn get_or_embed(
+        &self,
+        provider: &dyn EmbeddingProvider,
+        text: &str,
+        normalize: bool,
+    ) -> Result<Vec<f32>> {
+        let key = Self::key(text, normalize);
+        let (slot, hit) = self.slot(key);
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        match slot
+            .value
+            .get_or_try_init(|| async { provider.generate(text, normalize).await.map(Arc::new) })
+            .await
+        {
+            Ok(value) => Ok(value.as_ref().clone()),
+            Err(e) => {
+                self.discard_empty(key, &slot);
+                Err(e)
+            }
+        }
+    }
+
+    /// Batch variant of [`get_or_embed`](Self::get_or_embed)
+    ///
+    /// Texts that are already cached are served from the cache; the rest are
+    /// sent to the provider in a single batch call.
+    pub async fn get_or_embed_batch(
+        &self,
+        provider: &dyn EmbeddingProvider,
+        texts: &[String],
+        normalize: bool,
+    ) -> Vec<Option<Vec<f32>>> {
+        let mut results: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        let mut missing = Vec::new();
+
+        for (i, text) in texts.iter().enumerate() {
+            match self.lookup(Self::key(text, normalize)) {
+                Some(vec) => {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    results[i] = Some(vec.as_ref().clone());
+                }
+                None => {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    missing.push(i);
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return results;
+        }
+
+        let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+        let embedded = provider.generate_batch(&batch, normalize).await;
+        for (&i, vec) in missing.iter().zip(embedded) {
+            if let Some(ref v) = vec {
+                let (slot, _) = self.slot(Self::key(&texts[i], normalize));
+                let _ = slot.value.set(Arc::new(v.clone()));
+            }
+            results[i] = vec;
+        }
+        results
+    }
+
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        EmbeddingCacheStats {
+            entries: self.slots.lock().len(),
+            hits,
+            misses,
+            hit_rate: if total > 0 {
+                hits as f64 / total as f64
+            } else {
+                0.0
+            },
+        }
+    }
+
+    fn key(text: &str, normalize: bool) -> CacheKey {
+        (md5::compute(text).0, normalize)
+    }
+
+    /// Completed, unexpired embedding for `key`, if any
+    fn lookup(&self, key: CacheKey) -> Option<Arc<Vec<f32>>> {
+        let slots = self.slots.lock();
+        slots
+            .get(&key)
+            .filter(|slot| slot.created.elapsed() < self.config.ttl)
+            .and_then(|slot| slot.value.get().cloned())
+    }
+
+    /// Drop `slot` after a failed embedding so it does not hold a cache entry
+    /// with no value; left alone if it was replaced or filled meanwhile
+    fn discard_empty(&self, key: CacheKey, slot: &Arc<Slot>) {
+        let mut slots = self.slots.lock();
+        if let Some(current) = slots.get(&key) {
+            if Arc::ptr_eq(current, slot) && current.value.get().is_none() {
+                slots.remove(&key);
+            }
+        }
+    }
+
+    /// Get the slot for `key`, creating a fresh one if missing or expired.
+    /// Returns whether an existing slot was reused.
+    fn slot(&self, key: CacheKey) -> (Arc<Slot>, bool) {
+        let mut slots = self.slots.lock();
+        if let Some(slot) = slots.get(&key) {
+            if slot.created.elapsed() < self.config.ttl {
+                return (Arc::clone(slot), true);
+            }
+        }
+
+        if slots.len() >= self.config.max_entries {
+            let ttl = self.config.ttl;
+            slots.retain(|_, slot| slot.created.elapsed() < ttl);
+        }
+        if slots.len() >= self.config.max_entries {
+            // Still full: evict the oldest entry
+            if let Some(oldest) = slots
+                .iter()
+                .min_by_key(|(_, slot)| slot.created)
+                .map(|(k, _)| *k)
+            {
+                slots.remove(&oldest);
+            }
+        }
+
+        let slot = Arc::new(Slot {
+            created: Instant::now(),
+            value: OnceCell::new(),
+        });
+        slots.insert(key, Arc::clone(&slot));
+        (slot, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct CountingProvider {
+        calls: AtomicU64,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for CountingProvider {
+        async fn generate(&self, text: &str, _normalize: bool) -> Result<Vec<f32>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if text.is_empty() {
+                anyhow::bail!("nothing to embed");
+            }
+            Ok(vec![text.len() as f32; 4])
+        }
+
+        async fn generate_batch(&self, texts: &[String], normalize: bool) -> Vec<Option<Vec<f32>>> {
+            let mut out = Vec::new();
+            for t in texts {
+                out.push(self.generate(t, normalize).await.ok());
+            }
+            out
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ttl_and_capacity() {
+        let provider = CountingProvider {
+            calls: AtomicU64::new(0),
+        };
+        let cache = EmbeddingCache::new(EmbeddingCacheConfig {
+            max_entries: 2,
+            ttl: Duration::from_millis(200),
+            isolated_namespaces: HashSet::new(),
+        });
+
+        for text in ["a", "bb", "a", "ccc"] {
+            cache.get_or_embed(&provider, text, true).await.unwrap();
+        }
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(cache.stats().hits, 1);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        cache.get_or_embed(&provider, "ccc", true).await.unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_failed_embedding_leaves_no_entry() {
+        let provider = CountingProvider {
+            calls: AtomicU64::new(0),
+        };
+        let cache = EmbeddingCache::new(EmbeddingCacheConfig {
+            max_entries: 10,
+            ttl: Duration::from_secs(60),
+            isolated_namespaces: HashSet::new(),
+        });
+
+        assert!(cache.get_or_embed(&provider, "", true).await.is_err());
+        assert_eq!(cache.stats().entries, 0);
+
+        // The failure is not cached: the next call retries the provider
+        assert!(cache.get_or_embed(&provider, "", true).await.is_err());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
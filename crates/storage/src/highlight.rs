@@ -0,0 +1,215 @@
+//! Query-term highlighting for search results
+//!
+//! Locates query keywords in concept content so clients can highlight
+//! matches without re-scanning, and picks the passage with the most distinct
+//! keyword hits as a snippet. All offsets are byte offsets into the content
+//! and always fall on UTF-8 character boundaries.
+
+/// Default snippet length in bytes
+pub const DEFAULT_SNIPPET_LEN: usize = 160;
+
+/// Longest snippet returned; longer requests are clamped to it
+pub const MAX_SNIPPET_LEN: usize = 4096;
+
+/// Common stop words ignored when extracting query keywords
+const STOP_WORDS: &[&str] = &[
+    "what", "is", "the", "a", "an", "of", "in", "to", "for", "on", "with", "by", "at", "from",
+    "as", "are", "was", "were", "be", "been", "being", "have", "has", "had", "do", "does", "did",
+    "will", "would", "could", "should", "may", "might", "can", "this", "that", "these", "those",
+    "it", "its", "my", "your", "his", "her", "their", "our", "which", "who", "whom", "whose",
+    "where", "when", "why", "how", "all", "any", "both", "each", "tell", "me", "about", "please",
+    "give", "show", "explain",
+];
+
+/// Matched spans and best snippet for one piece of content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextHighlight {
+    /// `[start, end)` byte ranges of keyword matches, in content order
+    pub spans: Vec<(usize, usize)>,
+    /// Byte offset of `snippet` within the content
+    pub snippet_start: usize,
+    pub snippet: String,
+}
+
+/// Lowercased query keywords (stop words and single characters removed)
+///
+/// Falls back to keeping stop words when the query consists only of them.
+pub fn query_keywords(query: &str) -> Vec<String> {
+    let lower = query.to_lowercase();
+    let words = || {
+        lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 1)
+    };
+
+    let keywords: Vec<String> = words()
+        .filter(|w| !STOP_WORDS.contains(w))
+        .map(String::from)
+        .collect();
+    if keywords.is_empty() {
+        words().map(String::from).collect()
+    } else {
+        keywords
+    }
+}
+
+/// Find `keywords` in `content` and choose a snippet of about `snippet_len`
+/// bytes (at most [`MAX_SNIPPET_LEN`])
+///
+/// A word matches when its lowercase form contains a keyword; the span covers
+/// the keyword itself, or the whole word when lowercasing changes its length.
+/// The snippet is the window holding the most distinct keywords (ties go to
+/// more matches, then to the earlier window), starting a little before its
+/// first match when that keeps its last match in. Without matches the
+/// snippet is the start of the content.
+pub fn highlight(content: &str, keywords: &[String], snippet_len: usize) -> TextHighlight {
+    let matches = match_spans(content, keywords);
+    let snippet_len = snippet_len.clamp(1, MAX_SNIPPET_LEN);
+
+    // Slide a window anchored at each match; `end` only moves forward, so
+    // the scan is linear in the number of matches
+    let mut counts = vec![0usize; keywords.len()];
+    let mut distinct = 0;
+    let mut end = 0;
+    let mut best_start = 0;
+    let mut best_end = 0;
+    let mut best_score = (0, 0);
+    for (i, &(anchor, _, _)) in matches.iter().enumerate() {
+        // A match longer than the window leaves it empty
+        end = end.max(i);
+        let window_end = anchor + snippet_len;
+        while end < matches.len() && matches[end].1 <= window_end {
+            let count = &mut counts[matches[end].2];
+            distinct += usize::from(*count == 0);
+            *count += 1;
+            end += 1;
+        }
+
+        let score = (distinct, end - i);
+        if score > best_score {
+            best_score = score;
+            best_start = anchor;
+            best_end = matches[end - 1].1;
+        }
+
+        if end > i {
+            let count = &mut counts[matches[i].2];
+            *count -= 1;
+            distinct -= usize::from(*count == 0);
+        }
+    }
+    let spans: Vec<(usize, usize)> = matches.iter().map(|&(s, e, _)| (s, e)).collect();
+
+    // Lead with some context when the window has room to spare after its
+    // last match
+    let lead = (snippet_len / 4).min((best_start + snippet_len).saturating_sub(best_end));
+    let start = floor_char_boundary(content, best_start.saturating_sub(lead));
+    let end = floor_char_boundary(content, (start + snippet_len).min(content.len()));
+
+    TextHighlight {
+        spans,
+        snippet_start: start,
+        snippet: content[start..end].to_string(),
+    }
+}
+
+/// `[start, end)` of each match with the index of the keyword it matched
+fn match_spans(content: &str, keywords: &[String]) -> Vec<(usize, usize, usize)> {
+    let mut spans = Vec::new();
+    let mut word_start = None;
+
+    for (i, c) in content
+        .char_indices()
+        .chain(std::iter::once((content.len(), ' ')))
+    {
+        match (c.is_alphanumeric(), word_start) {
+            (true, None) => word_start = Some(i),
+            (false, Some(start)) => {
+                word_start = None;
+                let word = &content[start..i];
+                let lower = word.to_lowercase();
+                for (k, kw) in keywords.iter().enumerate() {
+                    if let Some(pos) = lower.find(kw.as_str()) {
+                        if lower.len() == word.len() {
+                            spans.push((start + pos, start + pos + kw.len(), k));
+                        } else {
+                            spans.push((start, i, k));
+                        }
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_bracket_query_terms() {
+        let content = "Rust ownership prevents data races. Borrowing rules in RUST are \
+                       checked at compile time, unlike Python's GIL.";
+        let keywords = query_keywords("what is Rust borrowing?");
+        assert_eq!(keywords, vec!["rust", "borrowing"]);
+
+        let h = highlight(content, &keywords, 40);
+        assert_eq!(h.spans.len(), 3);
+        for &(start, end) in &h.spans {
+            let matched = content[start..end].to_lowercase();
+            assert!(keywords.contains(&matched), "span {:?}", matched);
+        }
+
+        // The window around "Borrowing ... RUST" holds both keywords
+        assert!(h.snippet.contains("Borrowing") && h.snippet.contains("RUST"));
+        assert_eq!(
+            &content[h.snippet_start..h.snippet_start + h.snippet.len()],
+            h.snippet
+        );
+        assert!(h.snippet.len() <= 40);
+    }
+
+    #[test]
+    fn test_multibyte_content() {
+        let content = "Ünïcode café: the CAFÉ serves crème brûlée";
+        let h = highlight(content, &query_keywords("café"), 12);
+        assert_eq!(h.spans.len(), 2);
+        for &(start, end) in &h.spans {
+            assert_eq!(content[start..end].to_lowercase(), "café");
+        }
+        assert!(content.is_char_boundary(h.snippet_start));
+    }
+
+    #[test]
+    fn test_window_prefers_distinct_keywords_and_caps_length() {
+        // Many repeats of one keyword early, both keywords together late
+        let content = format!("{} alpha beta", "alpha ".repeat(50));
+        let keywords = query_keywords("alpha beta");
+        let h = highlight(&content, &keywords, 20);
+        assert_eq!(h.spans.len(), 52);
+        assert!(h.snippet.contains("alpha") && h.snippet.contains("beta"));
+
+        // A keyword longer than the window still yields a valid snippet
+        let h = highlight(
+            "xx supercalifragilistic yy",
+            &query_keywords("supercali"),
+            4,
+        );
+        assert_eq!(h.spans.len(), 1);
+        assert!(h.snippet.len() <= 4);
+
+        let long = "word ".repeat(2 * MAX_SNIPPET_LEN);
+        let h = highlight(&long, &query_keywords("word"), usize::MAX);
+        assert_eq!(h.snippet.len(), MAX_SNIPPET_LEN);
+    }
+}
@@ -12,14 +12,18 @@
 /// - 100× faster startup: <50ms load vs 2-5s rebuild
 use anyhow::{Context, Result};
 use parking_lot::RwLock;
+use rayon::prelude::*;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use usearch::ffi::{IndexOptions, MetricKind, ScalarKind};
 use usearch::Index;
 
 use crate::types::ConceptId;
+use crate::vectors::{l2_normalized, VectorMetric};
 
 /// HNSW container with persistence support (USearch-based)
 pub struct HnswContainer {
@@ -38,9 +42,13 @@ pub struct HnswContainer {
     config: HnswConfig,
     /// Track if index needs saving
     dirty: Arc<RwLock<bool>>,
+    /// Set while `rebuild` runs
+    rebuilding: AtomicBool,
+    /// Completion time of the last rebuild (Unix µs, 0 = never)
+    last_rebuild_us: AtomicU64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct HnswConfig {
     /// Vector dimension
     pub dimension: usize,
@@ -50,6 +58,11 @@ pub struct HnswConfig {
     pub ef_construction: usize,
     /// Max elements hint for capacity planning
     pub max_elements: usize,
+    /// Similarity the index ranks by
+    pub metric: VectorMetric,
+    /// L2-normalize vectors before they are indexed and queries before they
+    /// are searched
+    pub normalize_on_insert: bool,
 }
 
 impl Default for HnswConfig {
@@ -59,6 +72,8 @@ impl Default for HnswConfig {
             max_neighbors: 16,
             ef_construction: 200,
             max_elements: 100_000, // Start with 100K, grows automatically
+            metric: VectorMetric::Cosine,
+            normalize_on_insert: false,
         }
     }
 }
@@ -74,11 +89,17 @@ impl HnswContainer {
             next_id: Arc::new(RwLock::new(0)),
             config,
             dirty: Arc::new(RwLock::new(false)),
+            rebuilding: AtomicBool::new(false),
+            last_rebuild_us: AtomicU64::new(0),
         }
     }
 
     /// Load existing index from disk OR build new one from vectors
     ///
+    /// A persisted index is only used if it was built with this container's
+    /// config and indexes no concept missing from `vectors`; otherwise it is
+    /// rebuilt. Vectors the persisted index lacks are added incrementally.
+    ///
     /// Performance (USearch with true persistence):
     /// - Load from disk: <50ms for 1M vectors (mmap)
     /// - Build new: ~2-5 seconds for 1M vectors
@@ -86,82 +107,173 @@ impl HnswContainer {
         let index_path = self.base_path.with_extension("usearch");
         let metadata_path = self.base_path.with_extension("hnsw.meta");
 
-        let start = Instant::now();
-
         // Try loading from disk first
         if index_path.exists() && metadata_path.exists() {
-            log::info!("Loading HNSW index from {:?}", index_path);
-
-            // Load metadata (ID mappings)
-            self.load_mappings(&metadata_path)?;
-
-            // Load USearch index via mmap (FAST - no rebuild!)
-            let index = Index::new(&IndexOptions {
-                dimensions: self.config.dimension,
-                metric: MetricKind::Cos,
-                quantization: ScalarKind::F32,
-                connectivity: self.config.max_neighbors,
-                expansion_add: self.config.ef_construction,
-                expansion_search: 40,
-                multi: false,
-            })
-            .context("Failed to create USearch index")?;
-
-            index
-                .load(index_path.to_str().unwrap())
-                .context("Failed to load index from disk")?;
-
-            let num_loaded = index.size();
-            let elapsed = start.elapsed();
-
+            match self
+                .load_from(&self.base_path)
+                .and_then(|()| self.catch_up(vectors))
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    log::warn!("⚠️ Persisted HNSW index unusable, rebuilding: {:#}", e);
+                    self.id_mapping.write().clear();
+                    self.reverse_mapping.write().clear();
+                    *self.next_id.write() = 0;
+                }
+            }
+        } else {
             log::info!(
-                "✅ Loaded HNSW index with {} vectors in {:.2}ms",
-                num_loaded,
-                elapsed.as_secs_f64() * 1000.0
+                "No persisted index found, building from {} vectors",
+                vectors.len()
             );
+        }
 
-            // Check if we need to add new vectors incrementally
-            if num_loaded < vectors.len() {
-                let num_missing = vectors.len() - num_loaded;
-                log::info!("Adding {} new vectors incrementally", num_missing);
+        self.build_from_vectors(vectors)
+    }
 
-                // Reserve capacity for new vectors (required by USearch)
-                index
-                    .reserve(num_missing)
-                    .context("Failed to reserve capacity for incremental inserts")?;
-
-                // Collect missing concept IDs first (avoid holding lock during insert)
-                let missing_concepts: Vec<(ConceptId, Vec<f32>)> = {
-                    let reverse_mapping = self.reverse_mapping.read();
-                    vectors
-                        .iter()
-                        .filter(|(concept_id, _)| !reverse_mapping.contains_key(concept_id))
-                        .map(|(id, vec)| (*id, vec.clone()))
-                        .collect()
-                };
+    /// Load an index saved by [`save_to`](Self::save_to) from `base_path`
+    ///
+    /// Fails, leaving the container untouched, if the files are missing or
+    /// unreadable or were written with a different config.
+    pub fn load_from(&self, base_path: &Path) -> Result<()> {
+        let index_path = base_path.with_extension("usearch");
+        let metadata_path = base_path.with_extension("hnsw.meta");
+        let start = Instant::now();
 
-                // Insert missing vectors
-                for (concept_id, vector) in missing_concepts {
-                    self.insert_into_index(&index, concept_id, &vector)?;
-                }
+        log::info!("Loading HNSW index from {:?}", index_path);
 
-                *self.dirty.write() = true;
-            }
+        let data = std::fs::read(&metadata_path).context("Failed to read metadata file")?;
+        let metadata: HnswMetadata =
+            bincode::deserialize(&data).context("Failed to deserialize metadata")?;
+        if metadata.version != HNSW_METADATA_VERSION {
+            anyhow::bail!("Unsupported HNSW metadata version {}", metadata.version);
+        }
+        if metadata.config != self.config {
+            anyhow::bail!(
+                "HNSW index was built with {:?}, expected {:?}",
+                metadata.config,
+                self.config
+            );
+        }
 
-            *self.index.write() = Some(index);
-            return Ok(());
+        // Load USearch index via mmap (FAST - no rebuild!)
+        let index = self.new_index()?;
+        index
+            .load(index_path.to_str().context("Non UTF-8 index path")?)
+            .context("Failed to load index from disk")?;
+        if metadata.id_mapping.len() > index.size()
+            || metadata.id_mapping.keys().any(|&id| id >= metadata.next_id)
+        {
+            anyhow::bail!(
+                "HNSW metadata maps {} vectors but the index holds {}",
+                metadata.id_mapping.len(),
+                index.size()
+            );
         }
 
-        // No persisted index, build new one
+        let reverse_mapping = metadata
+            .id_mapping
+            .iter()
+            .map(|(hnsw_id, concept_id)| (*concept_id, *hnsw_id))
+            .collect();
+        *self.index.write() = Some(index);
+        *self.id_mapping.write() = metadata.id_mapping;
+        *self.reverse_mapping.write() = reverse_mapping;
+        *self.next_id.write() = metadata.next_id;
+        *self.dirty.write() = false;
+
         log::info!(
-            "No persisted index found, building from {} vectors",
-            vectors.len()
+            "✅ Loaded HNSW index with {} vectors in {:.2}ms",
+            self.id_mapping.read().len(),
+            start.elapsed().as_secs_f64() * 1000.0
         );
-        self.build_from_vectors(vectors)?;
+        Ok(())
+    }
+
+    /// Bring a loaded index in line with the concept store's `vectors`
+    ///
+    /// Concepts written after the index was saved are inserted; an index
+    /// holding concepts the store no longer has is out of date and rejected.
+    fn catch_up(&self, vectors: &HashMap<ConceptId, Vec<f32>>) -> Result<()> {
+        let (stale, missing): (usize, Vec<(ConceptId, Vec<f32>)>) = {
+            let reverse_mapping = self.reverse_mapping.read();
+            let stale = reverse_mapping
+                .keys()
+                .filter(|id| !vectors.contains_key(id))
+                .count();
+            let missing = vectors
+                .iter()
+                .filter(|(id, _)| !reverse_mapping.contains_key(id))
+                .map(|(id, vec)| (*id, vec.clone()))
+                .collect();
+            (stale, missing)
+        };
+        if stale > 0 {
+            anyhow::bail!(
+                "index holds {} vectors for concepts the store does not have ({} indexed, {} stored)",
+                stale,
+                self.id_mapping.read().len(),
+                vectors.len()
+            );
+        }
+        if missing.is_empty() {
+            return Ok(());
+        }
 
+        log::info!("Adding {} new vectors incrementally", missing.len());
+        let index_lock = self.index.read();
+        let index = index_lock
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("HNSW index not initialized"))?;
+        // Reserve capacity for new vectors (required by USearch)
+        index
+            .reserve(index.size() + missing.len())
+            .context("Failed to reserve capacity for incremental inserts")?;
+        for (concept_id, vector) in missing {
+            self.insert_into_index(index, concept_id, &vector)?;
+        }
+        *self.dirty.write() = true;
         Ok(())
     }
 
+    /// Empty USearch index with this container's settings
+    fn new_index(&self) -> Result<Index> {
+        Index::new(&IndexOptions {
+            dimensions: self.config.dimension,
+            metric: match self.config.metric {
+                VectorMetric::Cosine => MetricKind::Cos,
+                VectorMetric::DotProduct => MetricKind::IP,
+            },
+            quantization: ScalarKind::F32,
+            connectivity: self.config.max_neighbors,
+            expansion_add: self.config.ef_construction,
+            expansion_search: 40,
+            multi: false,
+        })
+        .context("Failed to create USearch index")
+    }
+
+    /// `vector` as it is indexed or searched: unit length if the config
+    /// normalizes
+    fn prepare<'a>(&self, vector: &'a [f32]) -> Cow<'a, [f32]> {
+        if self.config.normalize_on_insert {
+            Cow::Owned(l2_normalized(vector))
+        } else {
+            Cow::Borrowed(vector)
+        }
+    }
+
+    /// Similarity for a USearch distance under the configured metric
+    ///
+    /// Cosine distance is `1 - cos`, clamped so similarity stays non-negative;
+    /// inner-product distance is `1 - dot`, so the dot product comes back as is.
+    fn similarity(&self, distance: f32) -> f32 {
+        match self.config.metric {
+            VectorMetric::Cosine => 1.0 - distance.min(1.0),
+            VectorMetric::DotProduct => 1.0 - distance,
+        }
+    }
+
     /// Helper to insert a single vector into existing index
     fn insert_into_index(
         &self,
@@ -177,7 +289,7 @@ impl HnswContainer {
 
         // Insert into USearch
         index
-            .add(hnsw_id as u64, vector)
+            .add(hnsw_id as u64, &self.prepare(vector))
             .context("Failed to add vector to index")?;
 
         // Update mappings
@@ -192,16 +304,7 @@ impl HnswContainer {
         let start = Instant::now();
 
         // Create USearch index
-        let index = Index::new(&IndexOptions {
-            dimensions: self.config.dimension,
-            metric: MetricKind::Cos,
-            quantization: ScalarKind::F32,
-            connectivity: self.config.max_neighbors,
-            expansion_add: self.config.ef_construction,
-            expansion_search: 40,
-            multi: false,
-        })
-        .context("Failed to create USearch index")?;
+        let index = self.new_index()?;
 
         if vectors.is_empty() {
             log::info!("No vectors to index, creating empty HNSW");
@@ -226,7 +329,7 @@ impl HnswContainer {
 
             // Insert into USearch
             index
-                .add(hnsw_id as u64, vector)
+                .add(hnsw_id as u64, &self.prepare(vector))
                 .context("Failed to add vector to index")?;
 
             // Update mappings
@@ -289,6 +392,142 @@ impl HnswContainer {
         Ok(())
     }
 
+    /// Drop a concept from search results
+    ///
+    /// Its vector stays in the index as a tombstone until the next `rebuild`.
+    /// Returns false if the concept was not indexed.
+    pub fn remove(&self, concept_id: &ConceptId) -> bool {
+        let Some(hnsw_id) = self.reverse_mapping.write().remove(concept_id) else {
+            return false;
+        };
+        self.id_mapping.write().remove(&hnsw_id);
+        *self.dirty.write() = true;
+        true
+    }
+
+    /// Share of indexed vectors that belong to removed concepts
+    pub fn tombstone_ratio(&self) -> f32 {
+        let stats = self.stats();
+        if stats.num_vectors == 0 {
+            0.0
+        } else {
+            stats.tombstones as f32 / stats.num_vectors as f32
+        }
+    }
+
+    pub fn is_rebuilding(&self) -> bool {
+        self.rebuilding.load(Ordering::Acquire)
+    }
+
+    /// Rebuild the index from the live `vectors`, dropping tombstones
+    ///
+    /// The new index is built while the current one keeps serving searches
+    /// and inserts, then swapped in under the write locks; concepts inserted
+    /// or removed meanwhile are carried over at the swap. Returns `None` if
+    /// another rebuild is already running.
+    pub fn rebuild(
+        &self,
+        vectors: &RwLock<HashMap<ConceptId, Vec<f32>>>,
+    ) -> Result<Option<RebuildReport>> {
+        if self.rebuilding.swap(true, Ordering::AcqRel) {
+            return Ok(None);
+        }
+        let result = self.rebuild_inner(vectors);
+        self.rebuilding.store(false, Ordering::Release);
+        result.map(Some)
+    }
+
+    fn rebuild_inner(
+        &self,
+        vectors: &RwLock<HashMap<ConceptId, Vec<f32>>>,
+    ) -> Result<RebuildReport> {
+        let start = Instant::now();
+        let live: Vec<(ConceptId, Vec<f32>)> = {
+            let reverse_mapping = self.reverse_mapping.read();
+            vectors
+                .read()
+                .iter()
+                .filter(|(id, v)| {
+                    v.len() == self.config.dimension && reverse_mapping.contains_key(*id)
+                })
+                .map(|(id, v)| (*id, v.clone()))
+                .collect()
+        };
+
+        let index = self.new_index()?;
+        index
+            .reserve(live.len().max(1))
+            .context("Failed to reserve index capacity")?;
+        let mut new_ids = HashMap::with_capacity(live.len());
+        let mut new_reverse = HashMap::with_capacity(live.len());
+        for (hnsw_id, (concept_id, vector)) in live.iter().enumerate() {
+            index
+                .add(hnsw_id as u64, &self.prepare(vector))
+                .context("Failed to add vector to index")?;
+            new_ids.insert(hnsw_id, *concept_id);
+            new_reverse.insert(*concept_id, hnsw_id);
+        }
+        let mut new_next_id = live.len();
+
+        // Swap, reconciling with writes made during the build
+        let mut index_lock = self.index.write();
+        let mut next_id = self.next_id.write();
+        let mut id_mapping = self.id_mapping.write();
+        let mut reverse_mapping = self.reverse_mapping.write();
+
+        new_reverse.retain(|concept_id, hnsw_id| {
+            let still_live = reverse_mapping.contains_key(concept_id);
+            if !still_live {
+                new_ids.remove(hnsw_id);
+            }
+            still_live
+        });
+        let vectors = vectors.read();
+        for concept_id in reverse_mapping.keys() {
+            if new_reverse.contains_key(concept_id) {
+                continue;
+            }
+            if let Some(vector) = vectors.get(concept_id) {
+                index
+                    .reserve(index.size() + 1)
+                    .context("Failed to reserve capacity for insert")?;
+                index
+                    .add(new_next_id as u64, &self.prepare(vector))
+                    .context("Failed to add vector to index")?;
+                new_ids.insert(new_next_id, *concept_id);
+                new_reverse.insert(*concept_id, new_next_id);
+                new_next_id += 1;
+            }
+        }
+
+        let tombstones_removed = index_lock
+            .as_ref()
+            .map_or(0, |old| old.size().saturating_sub(id_mapping.len()));
+        let report = RebuildReport {
+            indexed: new_ids.len(),
+            tombstones_removed,
+        };
+        *index_lock = Some(index);
+        *id_mapping = new_ids;
+        *reverse_mapping = new_reverse;
+        *next_id = new_next_id;
+        *self.dirty.write() = true;
+        self.last_rebuild_us.store(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_micros() as u64),
+            Ordering::Relaxed,
+        );
+
+        log::info!(
+            "✅ Rebuilt HNSW index: {} vectors, {} tombstones dropped in {:.2}ms",
+            report.indexed,
+            report.tombstones_removed,
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(report)
+    }
+
     /// Clear the index and mappings
     pub fn clear(&self) -> Result<()> {
         let mut index_lock = self.index.write();
@@ -297,16 +536,9 @@ impl HnswContainer {
         let mut next_id = self.next_id.write();
 
         // Reset USearch index
-        let new_index = Index::new(&IndexOptions {
-            dimensions: self.config.dimension,
-            metric: MetricKind::Cos,
-            quantization: ScalarKind::F32,
-            connectivity: self.config.max_neighbors,
-            expansion_add: self.config.ef_construction,
-            expansion_search: 40,
-            multi: false,
-        })
-        .context("Failed to create new USearch index for clear")?;
+        let new_index = self
+            .new_index()
+            .context("Failed to create new USearch index for clear")?;
 
         *index_lock = Some(new_index);
         id_mapping.clear();
@@ -333,7 +565,7 @@ impl HnswContainer {
         };
 
         // Search with USearch
-        let matches = match index.search(query, k) {
+        let matches = match index.search(&self.prepare(query), k) {
             Ok(m) => m,
             Err(e) => {
                 log::error!("Search failed: {}", e);
@@ -348,11 +580,56 @@ impl HnswContainer {
             .iter()
             .zip(matches.distances.iter())
             .filter_map(|(hnsw_id, distance)| {
-                id_mapping.get(&(*hnsw_id as usize)).map(|concept_id| {
-                    // Convert distance to similarity (cosine distance -> cosine similarity)
-                    let similarity = 1.0 - distance.min(1.0);
-                    (*concept_id, similarity)
-                })
+                id_mapping
+                    .get(&(*hnsw_id as usize))
+                    .map(|concept_id| (*concept_id, self.similarity(*distance)))
+            })
+            .collect()
+    }
+
+    /// [`search`](Self::search) for several queries, in query order
+    ///
+    /// Takes the index and mapping locks once for the whole batch and
+    /// searches the queries in parallel. A query that fails gets no results.
+    pub fn search_batch(
+        &self,
+        queries: &[Vec<f32>],
+        k: usize,
+        _ef_search: usize,
+    ) -> Vec<Vec<(ConceptId, f32)>> {
+        let index_lock = self.index.read();
+        let Some(index) = index_lock.as_ref() else {
+            log::warn!("⚠️  HNSW index not initialized");
+            return vec![Vec::new(); queries.len()];
+        };
+
+        let matches: Vec<_> = queries
+            .par_iter()
+            .map(|query| match index.search(&self.prepare(query), k) {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    log::error!("Search failed: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        let id_mapping = self.id_mapping.read();
+        matches
+            .into_iter()
+            .map(|m| {
+                let Some(m) = m else {
+                    return Vec::new();
+                };
+                m.keys
+                    .iter()
+                    .zip(m.distances.iter())
+                    .filter_map(|(hnsw_id, distance)| {
+                        id_mapping
+                            .get(&(*hnsw_id as usize))
+                            .map(|concept_id| (*concept_id, self.similarity(*distance)))
+                    })
+                    .collect()
             })
             .collect()
     }
@@ -366,9 +643,20 @@ impl HnswContainer {
             return Ok(());
         }
 
+        self.save_to(&self.base_path)?;
+        *self.dirty.write() = false;
+
+        Ok(())
+    }
+
+    /// Write the index, ID mappings and config next to `base_path`
+    ///
+    /// Produces `<base_path>.usearch` and `<base_path>.hnsw.meta`, which
+    /// [`load_from`](Self::load_from) reads back.
+    pub fn save_to(&self, base_path: &Path) -> Result<()> {
         let start = Instant::now();
-        let index_path = self.base_path.with_extension("usearch");
-        let metadata_path = self.base_path.with_extension("hnsw.meta");
+        let index_path = base_path.with_extension("usearch");
+        let metadata_path = base_path.with_extension("hnsw.meta");
 
         log::info!("Saving HNSW index to {:?}", index_path);
 
@@ -384,70 +672,27 @@ impl HnswContainer {
 
         // Save USearch index (single file)
         index
-            .save(index_path.to_str().unwrap())
+            .save(index_path.to_str().context("Non UTF-8 index path")?)
             .context("Failed to save USearch index")?;
 
+        // Mappings are written under the index lock so they match the saved index
+        let metadata = HnswMetadata {
+            id_mapping: self.id_mapping.read().clone(),
+            next_id: *self.next_id.read(),
+            version: HNSW_METADATA_VERSION,
+            config: self.config.clone(),
+        };
         drop(index_lock);
 
-        // Save mappings to metadata file
-        self.save_mappings(&metadata_path)?;
-
-        let elapsed = start.elapsed();
-        let num_vectors = self.id_mapping.read().len();
+        let encoded = bincode::serialize(&metadata).context("Failed to serialize metadata")?;
+        std::fs::write(&metadata_path, encoded).context("Failed to write metadata")?;
 
         log::info!(
             "✅ Saved HNSW index with {} vectors in {:.2}ms",
-            num_vectors,
-            elapsed.as_secs_f64() * 1000.0
+            metadata.id_mapping.len(),
+            start.elapsed().as_secs_f64() * 1000.0
         );
 
-        *self.dirty.write() = false;
-
-        Ok(())
-    }
-
-    /// Save ID mappings to metadata file
-    fn save_mappings(&self, path: &Path) -> Result<()> {
-        use std::io::Write;
-
-        let id_mapping = self.id_mapping.read();
-        let next_id = *self.next_id.read();
-
-        let metadata = HnswMetadata {
-            id_mapping: id_mapping.clone(),
-            next_id,
-            version: 1,
-        };
-
-        let encoded = bincode::serialize(&metadata).context("Failed to serialize metadata")?;
-
-        let mut file = std::fs::File::create(path).context("Failed to create metadata file")?;
-        file.write_all(&encoded)
-            .context("Failed to write metadata")?;
-
-        Ok(())
-    }
-
-    /// Load ID mappings from metadata file
-    fn load_mappings(&self, path: &Path) -> Result<()> {
-        let data = std::fs::read(path).context("Failed to read metadata file")?;
-
-        let metadata: HnswMetadata =
-            bincode::deserialize(&data).context("Failed to deserialize metadata")?;
-
-        // Restore mappings
-        *self.id_mapping.write() = metadata.id_mapping.clone();
-
-        // Build reverse mapping
-        let mut reverse_mapping = self.reverse_mapping.write();
-        reverse_mapping.clear();
-        for (hnsw_id, concept_id) in metadata.id_mapping.iter() {
-            reverse_mapping.insert(*concept_id, *hnsw_id);
-        }
-        drop(reverse_mapping);
-
-        *self.next_id.write() = metadata.next_id;
-
         Ok(())
     }
 
@@ -460,6 +705,8 @@ impl HnswContainer {
     pub fn stats(&self) -> HnswContainerStats {
         let index_lock = self.index.read();
         let num_vectors = index_lock.as_ref().map(|idx| idx.size()).unwrap_or(0);
+        let live = self.id_mapping.read().len();
+        let last_rebuild_us = self.last_rebuild_us.load(Ordering::Relaxed);
 
         HnswContainerStats {
             num_vectors,
@@ -467,16 +714,22 @@ impl HnswContainer {
             max_neighbors: self.config.max_neighbors,
             dirty: *self.dirty.read(),
             initialized: index_lock.is_some(),
+            tombstones: num_vectors.saturating_sub(live),
+            last_rebuild_us: (last_rebuild_us > 0).then_some(last_rebuild_us),
         }
     }
 }
 
+/// Format of the `.hnsw.meta` file; older files are rebuilt rather than read
+const HNSW_METADATA_VERSION: u32 = 3;
+
 /// Metadata for persistence
 #[derive(serde::Serialize, serde::Deserialize)]
 struct HnswMetadata {
     id_mapping: HashMap<usize, ConceptId>,
     next_id: usize,
     version: u32,
+    config: HnswConfig,
 }
 
 /// Statistics for HNSW container
@@ -487,6 +740,19 @@ pub struct HnswContainerStats {
     pub max_neighbors: usize,
     pub dirty: bool,
     pub initialized: bool,
+    /// Vectors of removed concepts still in the index
+    pub tombstones: usize,
+    /// Completion time of the last rebuild (Unix µs)
+    pub last_rebuild_us: Option<u64>,
+}
+
+/// Outcome of [`HnswContainer::rebuild`]
+#[derive(Debug, Clone, Copy)]
+pub struct RebuildReport {
+    /// Vectors in the new index
+    pub indexed: usize,
+    /// Tombstones the old index held
+    pub tombstones_removed: usize,
 }
 
 #[cfg(test)]
@@ -525,37 +791,71 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let base_path = temp_dir.path().join("storage");
         let config = HnswConfig::default();
+        let vectors = test_vectors(0..100);
+        let query: Vec<f32> = (0..768).map(|j| (j % 100) as f32 / 100.0).collect();
 
         // Build and save
-        {
+        let before = {
             let container = HnswContainer::new(&base_path, config.clone());
-
-            let mut vectors = HashMap::new();
-            for i in 0u64..100 {
-                let mut id_bytes = [0u8; 16];
-                id_bytes[0..8].copy_from_slice(&i.to_le_bytes());
-                let concept_id = ConceptId(id_bytes);
-                let vector: Vec<f32> = (0..768).map(|j| ((i + j) % 100) as f32 / 100.0).collect();
-                vectors.insert(concept_id, vector);
-            }
-
             container.load_or_build(&vectors).unwrap();
             container.save().unwrap();
-        }
+            container.search(&query, 10, 50)
+        };
 
         // Load in new instance
         {
             let container = HnswContainer::new(&base_path, config);
-
-            let empty_vectors = HashMap::new();
-            container.load_or_build(&empty_vectors).unwrap();
+            container.load_or_build(&vectors).unwrap();
 
             let stats = container.stats();
             assert_eq!(stats.num_vectors, 100);
             assert!(!stats.dirty);
+            assert_eq!(container.search(&query, 10, 50), before);
         }
     }
 
+    #[test]
+    fn test_load_rebuilds_diverged_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("storage");
+        let config = HnswConfig::default();
+        let mut vectors = test_vectors(0..100);
+
+        let container = HnswContainer::new(&base_path, config.clone());
+        container.load_or_build(&vectors).unwrap();
+        container.save().unwrap();
+
+        // The store lost a concept since the save: rebuilt without it
+        let (&dropped, query) = vectors.iter().next().unwrap();
+        let query = query.clone();
+        vectors.remove(&dropped);
+        let container = HnswContainer::new(&base_path, config.clone());
+        container.load_or_build(&vectors).unwrap();
+        assert_eq!(container.stats().num_vectors, 99);
+        assert!(container.stats().dirty);
+        assert!(container
+            .search(&query, 10, 50)
+            .iter()
+            .all(|(id, _)| *id != dropped));
+
+        // New concepts are added on top of the persisted index
+        let more = test_vectors(0..120);
+        let container = HnswContainer::new(&base_path, config.clone());
+        container.load_or_build(&more).unwrap();
+        assert_eq!(container.stats().num_vectors, 120);
+
+        // An index built with other settings is not reused
+        let other = HnswConfig {
+            max_neighbors: 32,
+            ..config
+        };
+        let container = HnswContainer::new(&base_path, other.clone());
+        assert!(container.load_from(&base_path).is_err());
+        container.load_or_build(&more).unwrap();
+        assert_eq!(container.stats().num_vectors, 120);
+        assert_eq!(container.stats().max_neighbors, other.max_neighbors);
+    }
+
     #[test]
     fn test_incremental_insert() {
         let temp_dir = TempDir::new().unwrap();
@@ -587,4 +887,56 @@ mod tests {
         assert_eq!(stats.num_vectors, 20);
         assert!(stats.dirty);
     }
+
+    #[test]
+    fn test_metric_and_normalization_apply_to_index_and_queries() {
+        let temp_dir = TempDir::new().unwrap();
+        let id = |i: u8| ConceptId([i; 16]);
+        let long = vec![10.0, 0.0, 0.0, 0.0];
+        let aligned = vec![0.6, 0.8, 0.0, 0.0];
+        let query = [1.0, 1.0, 0.0, 0.0];
+
+        let build = |normalize_on_insert: bool| {
+            let container = HnswContainer::new(
+                temp_dir
+                    .path()
+                    .join(format!("storage-{}", normalize_on_insert)),
+                HnswConfig {
+                    dimension: 4,
+                    metric: VectorMetric::DotProduct,
+                    normalize_on_insert,
+                    ..HnswConfig::default()
+                },
+            );
+            // One vector through the bulk build, one through incremental insert
+            container
+                .load_or_build(&HashMap::from([(id(1), long.clone())]))
+                .unwrap();
+            container.insert(id(2), aligned.clone()).unwrap();
+            container.search(&query, 2, 50)
+        };
+
+        // Raw inner product favours the long vector
+        let raw = build(false);
+        assert_eq!(raw[0].0, id(1));
+        assert!((raw[0].1 - 10.0).abs() < 1e-3);
+
+        // Normalized, the dot product is the cosine: direction wins
+        let normalized = build(true);
+        assert_eq!(normalized[0].0, id(2));
+        let cosine = 1.4 / 2f32.sqrt();
+        assert!((normalized[0].1 - cosine).abs() < 1e-3);
+        assert!((normalized[1].1 - 1.0 / 2f32.sqrt()).abs() < 1e-3);
+    }
+
+    fn test_vectors(range: std::ops::Range<u64>) -> HashMap<ConceptId, Vec<f32>> {
+        range
+            .map(|i| {
+                let mut id_bytes = [0u8; 16];
+                id_bytes[0..8].copy_from_slice(&i.to_le_bytes());
+                let vector = (0..768).map(|j| ((i + j) % 100) as f32 / 100.0).collect();
+                (ConceptId(id_bytes), vector)
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,195 @@
+//! Idempotency keys for write requests
+//!
+//! Clients that retry a learn request after a network error cannot tell
+//! whether the first attempt landed. Requests carrying an idempotency key are
+//! run at most once per key: the response is remembered for `ttl`, and a
+//! repeat (or a concurrent duplicate) gets the original response back instead
+//! of applying the write again. Failed attempts are not remembered, so they
+//! can be retried with the same key. Each key remembers a fingerprint of the
+//! request it was first used with; reusing it for a different request is an
+//! error rather than a replay.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Default number of remembered keys
+pub const DEFAULT_IDEMPOTENCY_CAPACITY: usize = 100_000;
+
+/// Default time a key is remembered after its request completed
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(600);
+
+struct Slot<V> {
+    created: Instant,
+    fingerprint: u64,
+    value: OnceCell<V>,
+}
+
+/// A key was reused for a request other than the one it was first used with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyReused;
+
+impl std::fmt::Display for KeyReused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Idempotency key reused for a different request")
+    }
+}
+
+impl std::error::Error for KeyReused {}
+
+/// Bounded TTL cache of responses keyed by idempotency key
+pub struct IdempotencyCache<V> {
+    max_entries: usize,
+    ttl: Duration,
+    slots: Mutex<HashMap<String, Arc<Slot<V>>>>,
+}
+
+impl<V: Clone> Default for IdempotencyCache<V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_CAPACITY, DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+impl<V: Clone> IdempotencyCache<V> {
+    pub fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            max_entries: max_entries.max(1),
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the remembered response for `key`, or run `op` and remember its
+    /// result if it succeeded (`Ok`). Concurrent calls with the same key wait
+    /// for the first one instead of running `op` again.
+    ///
+    /// `fingerprint` identifies the request; a live key remembered with a
+    /// different one fails with [`KeyReused`] without running `op`.
+    pub async fn get_or_run<F, Fut>(
+        &self,
+        key: String,
+        fingerprint: u64,
+        op: F,
+    ) -> Result<V, KeyReused>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, V>>,
+    {
+        let slot = self.slot(key, fingerprint)?;
+        let mut failed = None;
+        let result = slot
+            .value
+            .get_or_try_init(|| async {
+                match op().await {
+                    Ok(v) => Ok(v),
+                    Err(e) => {
+                        failed = Some(e);
+                        Err(())
+                    }
+                }
+            })
+            .await;
+
+        Ok(match result {
+            Ok(v) => v.clone(),
+            // Only the caller that ran `op` sees its error; waiters retry `op`
+            Err(()) => failed.expect("error recorded by the failed attempt"),
+        })
+    }
+
+    /// Number of remembered keys (including expired ones not yet evicted)
+    pub fn len(&self) -> usize {
+        self.slots.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn slot(&self, key: String, fingerprint: u64) -> Result<Arc<Slot<V>>, KeyReused> {
+        let mut slots = self.slots.lock();
+        if let Some(slot) = slots.get(&key) {
+            if slot.created.elapsed() < self.ttl {
+                if slot.fingerprint != fingerprint {
+                    return Err(KeyReused);
+                }
+                return Ok(Arc::clone(slot));
+            }
+        }
+
+        if slots.len() >= self.max_entries {
+            let ttl = self.ttl;
+            slots.retain(|_, slot| slot.created.elapsed() < ttl);
+        }
+        if slots.len() >= self.max_entries {
+            // Still full: forget the oldest key
+            if let Some(oldest) = slots
+                .iter()
+                .min_by_key(|(_, slot)| slot.created)
+                .map(|(k, _)| k.clone())
+            {
+                slots.remove(&oldest);
+            }
+        }
+
+        let slot = Arc::new(Slot {
+            created: Instant::now(),
+            fingerprint,
+            value: OnceCell::new(),
+        });
+        slots.insert(key, Arc::clone(&slot));
+        Ok(slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_failures_are_not_remembered() {
+        let cache: IdempotencyCache<u32> = IdempotencyCache::new(10, Duration::from_secs(60));
+        let runs = AtomicU32::new(0);
+
+        let first = cache
+            .get_or_run("k".to_string(), 1, || async {
+                runs.fetch_add(1, Ordering::SeqCst);
+                Err(0)
+            })
+            .await;
+        assert_eq!(first, Ok(0));
+
+        for _ in 0..2 {
+            let v = cache
+                .get_or_run("k".to_string(), 1, || async {
+                    Ok(runs.fetch_add(1, Ordering::SeqCst) + 1)
+                })
+                .await;
+            assert_eq!(v, Ok(2));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_key_reused_for_different_request_is_rejected() {
+        let cache: IdempotencyCache<u32> = IdempotencyCache::new(10, Duration::from_secs(60));
+        let first = cache
+            .get_or_run("k".to_string(), 1, || async { Ok(7) })
+            .await;
+        assert_eq!(first, Ok(7));
+
+        let reused = cache
+            .get_or_run("k".to_string(), 2, || async { Ok(8) })
+            .await;
+        assert_eq!(reused, Err(KeyReused));
+        // The original request still replays
+        let replay = cache
+            .get_or_run("k".to_string(), 1, || async { Ok(9) })
+            .await;
+        assert_eq!(replay, Ok(7));
+    }
+}
@@ -5,14 +5,21 @@ use anyhow::Result;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use crate::concurrent_memory::ConcurrentMemory;
+use crate::embedding_cache::EmbeddingCache;
 use crate::embedding_client::HttpEmbeddingClient;
 use crate::embedding_provider::EmbeddingProvider;
 use crate::inference::embedding_engine::LocalEmbeddingEngine; // 🔥 NEW
 use crate::semantic::{SemanticAnalyzer, SemanticMetadata};
-use crate::semantic_extractor::SemanticExtractor;
+use crate::semantic_extractor::{SemanticExtractor, SimilarityMapping, DEFAULT_SIMILARITY_FLOOR};
 use crate::storage_trait::LearningStorage;
 use crate::types::ConceptId;
 
+/// Vector candidates per requested result fetched for hybrid search
+const HYBRID_CANDIDATE_FACTOR: usize = 4;
+/// Upper bound on vector candidates fetched for one hybrid search
+const MAX_HYBRID_CANDIDATES: usize = 4096;
+
 #[derive(Debug, Clone)]
 pub struct LearnOptions {
     pub generate_embedding: bool,
@@ -21,8 +28,14 @@ pub struct LearnOptions {
     pub analyze_semantics: bool, // 🔥 NEW: Enable semantic analysis
     pub min_association_confidence: f32,
     pub max_associations_per_concept: usize,
+    /// Sentence/relation similarity below which no association is created
+    pub similarity_floor: f32,
+    /// Converts similarity at or above the floor into association confidence
+    pub similarity_mapping: SimilarityMapping,
     pub strength: f32,
     pub confidence: f32,
+    /// Use the pipeline's embedding cache (off for isolated namespaces)
+    pub use_embedding_cache: bool,
 }
 
 impl Default for LearnOptions {
@@ -40,8 +53,11 @@ impl Default for LearnOptions {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(10),
+            similarity_floor: DEFAULT_SIMILARITY_FLOOR,
+            similarity_mapping: SimilarityMapping::Linear,
             strength: 1.0,
             confidence: 1.0,
+            use_embedding_cache: true,
         }
     }
 }
@@ -50,6 +66,7 @@ pub struct LearningPipeline {
     embedding_client: Arc<dyn EmbeddingProvider>,
     semantic_extractor: SemanticExtractor,
     semantic_analyzer: SemanticAnalyzer, // 🔥 NEW: Semantic understanding
+    embedding_cache: Option<Arc<EmbeddingCache>>,
 }
 
 impl LearningPipeline {
@@ -67,7 +84,10 @@ impl LearningPipeline {
             }
         };
 
-        Self::new_with_provider(provider).await
+        // Pipelines on the default provider share one process-wide cache
+        Ok(Self::new_with_provider(provider)
+            .await?
+            .with_embedding_cache(EmbeddingCache::shared()))
     }
 
     pub async fn new_with_provider(embedding_client: Arc<dyn EmbeddingProvider>) -> Result<Self> {
@@ -81,9 +101,34 @@ impl LearningPipeline {
             embedding_client,
             semantic_extractor,
             semantic_analyzer,
+            embedding_cache: None,
         })
     }
 
+    /// Share `cache` with this pipeline. Only share a cache between pipelines
+    /// whose providers produce the same embeddings for the same text.
+    pub fn with_embedding_cache(mut self, cache: Arc<EmbeddingCache>) -> Self {
+        self.embedding_cache = Some(cache);
+        self
+    }
+
+    pub fn embedding_cache(&self) -> Option<&Arc<EmbeddingCache>> {
+        self.embedding_cache.as_ref()
+    }
+
+    /// Whether learning into `namespace` may use the embedding cache
+    pub fn embedding_cache_enabled_for(&self, namespace: &str) -> bool {
+        self.embedding_cache
+            .as_ref()
+            .is_some_and(|cache| !cache.is_isolated(namespace))
+    }
+
+    fn cache_for(&self, options: &LearnOptions) -> Option<&Arc<EmbeddingCache>> {
+        self.embedding_cache
+            .as_ref()
+            .filter(|_| options.use_embedding_cache)
+    }
+
     /// Analyze semantic metadata for content
     pub fn analyze_semantic(&self, content: &str) -> SemanticMetadata {
         self.semantic_analyzer.analyze(content)
@@ -100,7 +145,15 @@ impl LearningPipeline {
 
         // Step 1: Embedding
         let embedding_opt = if options.generate_embedding {
-            match self.embedding_client.generate(content, true).await {
+            let result = match self.cache_for(options) {
+                Some(cache) => {
+                    cache
+                        .get_or_embed(self.embedding_client.as_ref(), content, true)
+                        .await
+                }
+                None => self.embedding_client.generate(content, true).await,
+            };
+            match result {
                 Ok(vec) => Some(vec),
                 Err(e) => {
                     warn!("Embedding failed, continuing without: {}", e);
@@ -152,7 +205,14 @@ impl LearningPipeline {
 
         // Step 4: Semantic associations (modern approach!)
         if options.extract_associations {
-            let extracted = self.semantic_extractor.extract(content).await?;
+            let extracted = self
+                .semantic_extractor
+                .extract_with(
+                    content,
+                    options.similarity_floor,
+                    options.similarity_mapping,
+                )
+                .await?;
             let mut stored = 0usize;
 
             for assoc in extracted
@@ -194,14 +254,20 @@ impl LearningPipeline {
 
         // Batch embeddings first to reduce overhead
         let embeddings: Vec<Option<Vec<f32>>> = if options.generate_embedding {
-            self.embedding_client.generate_batch(contents, true).await
+            match self.cache_for(options) {
+                Some(cache) => {
+                    cache
+                        .get_or_embed_batch(self.embedding_client.as_ref(), contents, true)
+                        .await
+                }
+                None => self.embedding_client.generate_batch(contents, true).await,
+            }
         } else {
             vec![None; contents.len()]
         };
 
         let mut concept_ids = Vec::with_capacity(contents.len());
-        for (i, (content, embedding_opt)) in contents.iter().zip(embeddings.into_iter()).enumerate()
-        {
+        for (i, (content, embedding_opt)) in contents.iter().zip(embeddings).enumerate() {
             if let Some(ref emb) = embedding_opt {
                 info!("💡 Concept {}: embedding dimension = {}", i, emb.len());
             } else {
@@ -249,7 +315,14 @@ impl LearningPipeline {
 
             // Extract and store semantic associations
             if options.extract_associations {
-                let extracted = self.semantic_extractor.extract(content).await?;
+                let extracted = self
+                    .semantic_extractor
+                    .extract_with(
+                        content,
+                        options.similarity_floor,
+                        options.similarity_mapping,
+                    )
+                    .await?;
                 let mut stored = 0usize;
 
                 for assoc in extracted
@@ -304,4 +377,31 @@ impl LearningPipeline {
 
         Ok(results)
     }
+
+    /// Search blending BM25 keyword scores with vector similarity
+    ///
+    /// `alpha` is the keyword weight (see [`ConcurrentMemory::hybrid_search`]);
+    /// at 1.0 no query embedding is generated. Fails unless `alpha` is a
+    /// finite value in 0.0-1.0.
+    pub async fn hybrid_search(
+        &self,
+        storage: &ConcurrentMemory,
+        query: &str,
+        limit: usize,
+        alpha: f32,
+    ) -> Result<Vec<(ConceptId, f32)>> {
+        if !(0.0..=1.0).contains(&alpha) {
+            anyhow::bail!("alpha must be between 0.0 and 1.0, got {}", alpha);
+        }
+        let semantic = if alpha < 1.0 {
+            let query_vector = self.embedding_client.generate(query, false).await?;
+            let candidates = limit
+                .saturating_mul(HYBRID_CANDIDATE_FACTOR)
+                .min(MAX_HYBRID_CANDIDATES);
+            storage.vector_search(&query_vector, candidates, 128)
+        } else {
+            Vec::new()
+        };
+        Ok(storage.hybrid_search(query, &semantic, limit, alpha))
+    }
 }
@@ -0,0 +1,168 @@
+//! BM25 inverted index over concept content
+//!
+//! Embeddings blur literal tokens such as error codes, SKUs and names, so
+//! vector search alone can miss a concept that contains the exact query.
+//! This index keeps term frequencies per concept, updated as concepts are
+//! learned and deleted, and scores keyword queries with Okapi BM25.
+
+use crate::types::ConceptId;
+use std::collections::HashMap;
+
+/// Term frequency saturation
+const K1: f32 = 1.2;
+/// Document length normalization
+const B: f32 = 0.75;
+
+/// Lowercased alphanumeric tokens of `text`
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Tokens of concept `content`; none if it is not UTF-8
+pub fn content_tokens(content: &[u8]) -> Vec<String> {
+    std::str::from_utf8(content).map_or_else(|_| Vec::new(), tokenize)
+}
+
+/// Inverted index from tokens to the concepts containing them
+#[derive(Debug, Default)]
+pub struct LexicalIndex {
+    /// Token -> concept -> occurrences
+    postings: HashMap<String, HashMap<ConceptId, u32>>,
+    /// Token count and distinct tokens of each indexed concept
+    docs: HashMap<ConceptId, (u32, Vec<String>)>,
+    total_tokens: u64,
+}
+
+impl LexicalIndex {
+    /// Index `content`, replacing any earlier content for `id`
+    ///
+    /// Content that is not UTF-8 only removes the earlier entry.
+    pub fn insert(&mut self, id: ConceptId, content: &[u8]) {
+        self.insert_tokens(id, content_tokens(content));
+    }
+
+    /// Index tokens from [`content_tokens`], replacing any earlier entry for `id`
+    ///
+    /// Lets writers tokenize content before handing it off and index it only
+    /// once the write has been accepted.
+    pub fn insert_tokens(&mut self, id: ConceptId, tokens: Vec<String>) {
+        self.remove(&id);
+        if tokens.is_empty() {
+            return;
+        }
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *counts.entry(token.clone()).or_default() += 1;
+        }
+        let terms = counts.keys().cloned().collect();
+        for (term, count) in counts {
+            self.postings.entry(term).or_default().insert(id, count);
+        }
+        self.total_tokens += tokens.len() as u64;
+        self.docs.insert(id, (tokens.len() as u32, terms));
+    }
+
+    /// Drop `id` from the index
+    pub fn remove(&mut self, id: &ConceptId) {
+        let Some((len, terms)) = self.docs.remove(id) else {
+            return;
+        };
+        self.total_tokens -= len as u64;
+        for term in terms {
+            if let Some(postings) = self.postings.get_mut(&term) {
+                postings.remove(id);
+                if postings.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Whether the content of `id` contains every one of `terms`
+    pub fn contains_all(&self, id: &ConceptId, terms: &[String]) -> bool {
+        terms.iter().all(|term| {
+            self.postings
+                .get(term)
+                .is_some_and(|postings| postings.contains_key(id))
+        })
+    }
+
+    /// BM25 scores of concepts containing any of `terms`, best first
+    pub fn search(&self, terms: &[String], limit: usize) -> Vec<(ConceptId, f32)> {
+        if self.docs.is_empty() {
+            return Vec::new();
+        }
+        let mut terms: Vec<&String> = terms.iter().collect();
+        terms.sort();
+        terms.dedup();
+
+        let doc_count = self.docs.len() as f32;
+        let avg_len = self.total_tokens as f32 / doc_count;
+        let mut scores: HashMap<ConceptId, f32> = HashMap::new();
+        for term in terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for (id, &count) in postings {
+                let len = self.docs[id].0 as f32;
+                let tf = count as f32;
+                *scores.entry(*id).or_default() +=
+                    idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / avg_len));
+            }
+        }
+
+        let mut ranked: Vec<(ConceptId, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<String> {
+        tokenize(query)
+    }
+
+    #[test]
+    fn test_rare_terms_outweigh_common_ones_and_removal_updates_postings() {
+        let mut index = LexicalIndex::default();
+        let code = ConceptId::from_string("code");
+        let generic = ConceptId::from_string("generic");
+        let other = ConceptId::from_string("other");
+        index.insert(code, b"Disk failure: error E4012 on controller");
+        index.insert(generic, b"An error occurred in the error handler");
+        index.insert(other, b"Controllers route requests");
+
+        let ranked = index.search(&terms("error e4012"), 10);
+        assert_eq!(ranked[0].0, code);
+        assert_eq!(ranked.len(), 2);
+        assert!(index.contains_all(&code, &terms("E4012 error")));
+        assert!(!index.contains_all(&generic, &terms("E4012 error")));
+
+        // Re-learning replaces the old tokens; deleting drops them
+        index.insert(code, b"Resolved");
+        assert!(index.search(&terms("e4012"), 10).is_empty());
+        index.remove(&generic);
+        assert!(index.search(&terms("error"), 10).is_empty());
+        let ids: Vec<ConceptId> = index
+            .search(&terms("route"), 10)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, vec![other]);
+    }
+}
@@ -21,24 +21,31 @@ mod wal;
 pub mod semantic;
 
 // Unified learning pipeline modules
+pub mod embedding_cache;
 pub mod embedding_client;
 pub mod embedding_provider;
+pub mod idempotency; // Retry-safe learn requests
 pub mod inference; // 🔥 NEW: Local inference module
 pub mod learning_pipeline;
 pub mod nl_parser; // 🔥 NEW: NL Command Parser
 pub mod semantic_extractor;
 
 // New concurrent memory modules
+mod access_tracker; // Read statistics folded in by the reconciler
 mod adaptive_reconciler; // AI-native adaptive reconciliation
 mod concurrent_memory;
 mod mmap_store;
 mod parallel_paths;
 mod read_view;
+mod storage_pool; // Dedicated rayon pool for CPU-heavy storage work
 mod write_log;
 
 // Scalability modules
+mod highlight; // Query-term spans and snippets for search results
 mod hnsw_container;
+mod lexical_index; // BM25 keyword index for hybrid text search
 mod namespace_manager;
+pub mod replication; // Snapshot + log-shipping read replicas
 mod sharded_storage;
 mod storage_trait;
 mod transaction; // 🔥 NEW: 2PC transaction coordinator for cross-shard atomicity
@@ -52,7 +59,10 @@ mod rate_limiter;
 pub mod tls;
 
 // Re-export rate limiter for auth module (internal use)
-pub use rate_limiter::{RateLimitError, RateLimiter, RateLimiterConfig, RateLimiterStats};
+pub use rate_limiter::{
+    PeerRateLimiter, PeerRateLimiterStats, RateLimitError, RateLimiter, RateLimiterConfig,
+    RateLimiterStats,
+};
 
 // TCP server for distributed architecture
 pub mod secure_tcp_server;
@@ -67,25 +77,38 @@ pub use index::{ConceptLocation, GraphIndex, IndexStats};
 pub use manifest::{Manifest, SegmentMetadata};
 pub use quantization::ProductQuantizer;
 pub use segment::{ConceptIterator, Segment, SegmentStats};
-pub use vectors::{VectorConfig, VectorMetadata, VectorStats, VectorStore};
-pub use wal::{LogEntry, Operation, WriteAheadLog};
+pub use vectors::{
+    VectorConfig, VectorEncoding, VectorMetadata, VectorMetric, VectorStats, VectorStore,
+};
+pub use wal::{LogEntry, Operation, SyncPolicy, WriteAheadLog};
 
 // New concurrent memory exports
 pub use adaptive_reconciler::{
-    AdaptiveReconciler, AdaptiveReconcilerConfig, AdaptiveReconcilerStats,
+    AdaptiveReconciler, AdaptiveReconcilerConfig, AdaptiveReconcilerStats, ConflictPolicy,
 };
 pub use concurrent_memory::{
-    ConcurrentConfig, ConcurrentMemory, ConcurrentStats, HnswStats, SnapshotInfo,
+    AccessRank, AtomicWrite, ConcurrentConfig, ConcurrentMemory, ConcurrentStats, DuplicateGroup,
+    DuplicateReport, HnswStats, SnapshotInfo, StorageCompression, WritePressure,
 };
 pub use mmap_store::{MmapStats, MmapStore};
 pub use parallel_paths::{ParallelPathFinder, PathResult};
-pub use read_view::{ConceptNode, GraphSnapshot, ReadView};
+pub use read_view::{
+    AttributeIndex, ConceptNode, DeadlineExceeded, GraphSnapshot, ReadView, SnapshotDiff,
+};
+pub use storage_pool::StoragePool;
 pub use write_log::{WriteEntry, WriteLog, WriteLogError, WriteLogStats};
 
 // Scalability exports
-pub use hnsw_container::{HnswConfig, HnswContainer, HnswContainerStats};
-pub use namespace_manager::NamespaceManager;
-pub use sharded_storage::{AggregatedStats, ShardConfig, ShardMap, ShardStats, ShardedStorage};
+pub use highlight::{TextHighlight, DEFAULT_SNIPPET_LEN};
+pub use hnsw_container::{HnswConfig, HnswContainer, HnswContainerStats, RebuildReport};
+pub use namespace_manager::{
+    NamespaceEvictionConfig, NamespaceManager, NamespaceQuota, NamespaceStats, QuotaMode,
+    QuotaUsage,
+};
+pub use sharded_storage::{
+    find_path_across_shards, AggregatedStats, CrossShardPath, ShardConfig, ShardMap, ShardStats,
+    ShardedStorage,
+};
 pub use storage_trait::LearningStorage;
 pub use transaction::{
     Transaction, TransactionCoordinator, TxnCoordinatorStats, TxnError, TxnOperation, TxnState,
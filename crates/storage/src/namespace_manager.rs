@@ -1,9 +1,103 @@
 use crate::concurrent_memory::{ConcurrentConfig, ConcurrentMemory};
 use anyhow::{Context, Result};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// When idle namespaces are flushed and closed
+///
+/// A namespace is only evicted while nothing but the manager holds it: the
+/// default namespace, namespaces watched by autonomy features, and namespaces
+/// with a request in flight stay open. Evicted namespaces reopen from disk
+/// on their next access.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NamespaceEvictionConfig {
+    /// Close least-recently-used namespaces beyond this many (0 = unlimited)
+    pub max_open: usize,
+    /// Close namespaces not accessed for this long (checked on access)
+    pub idle_timeout: Option<Duration>,
+}
+
+/// What a write that would exceed a [`NamespaceQuota`] does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuotaMode {
+    /// Refuse the write with `WriteLogError::QuotaExceeded`
+    #[default]
+    Reject,
+    /// Delete the least-recently-accessed concepts until the write fits
+    EvictLru,
+}
+
+/// Size limits for one namespace
+///
+/// Bytes count concept content, vectors (4 bytes per dimension) and
+/// attribute keys and values. Atomic batches never evict; they are refused
+/// if they do not fit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NamespaceQuota {
+    /// Most concepts the namespace may hold (0 = unlimited)
+    pub max_concepts: usize,
+    /// Most bytes the namespace may hold (0 = unlimited)
+    pub max_bytes: u64,
+    pub mode: QuotaMode,
+}
+
+impl NamespaceQuota {
+    /// Whether neither limit is set
+    pub fn is_unlimited(&self) -> bool {
+        self.max_concepts == 0 && self.max_bytes == 0
+    }
+
+    /// Whether `concepts` concepts totalling `bytes` bytes fit
+    pub fn allows(&self, concepts: usize, bytes: u64) -> bool {
+        (self.max_concepts == 0 || concepts <= self.max_concepts)
+            && (self.max_bytes == 0 || bytes <= self.max_bytes)
+    }
+}
+
+/// Current use of a namespace against its quota
+#[derive(Debug, Clone, Copy)]
+pub struct QuotaUsage {
+    pub quota: NamespaceQuota,
+    pub concepts: usize,
+    pub bytes: u64,
+    /// Concepts deleted by `QuotaMode::EvictLru` since the quota was set
+    pub evicted: u64,
+}
+
+/// Open/evicted namespace counts
+#[derive(Debug, Clone, Copy)]
+pub struct NamespaceStats {
+    pub open: usize,
+    pub evicted: u64,
+}
+
+struct OpenNamespace {
+    storage: Arc<ConcurrentMemory>,
+    last_access: Mutex<Instant>,
+}
+
+impl OpenNamespace {
+    fn new(storage: Arc<ConcurrentMemory>) -> Self {
+        Self {
+            storage,
+            last_access: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) -> Arc<ConcurrentMemory> {
+        *self.last_access.lock() = Instant::now();
+        Arc::clone(&self.storage)
+    }
+
+    /// Nobody outside the manager holds the storage
+    fn is_unused(&self) -> bool {
+        Arc::strong_count(&self.storage) == 1
+    }
+}
 
 /// NamespaceManager - Multi-collection separation for Sutra
 ///
@@ -16,7 +110,14 @@ use std::sync::Arc;
 pub struct NamespaceManager {
     base_path: PathBuf,
     config_template: ConcurrentConfig,
-    namespaces: Arc<RwLock<HashMap<String, Arc<ConcurrentMemory>>>>,
+    namespaces: Arc<RwLock<HashMap<String, OpenNamespace>>>,
+    /// Evicted namespaces still being flushed; reopening one reuses it
+    closing: Mutex<HashMap<String, Arc<ConcurrentMemory>>>,
+    eviction: RwLock<NamespaceEvictionConfig>,
+    evicted: AtomicU64,
+    last_idle_sweep: Mutex<Instant>,
+    quotas: RwLock<HashMap<String, NamespaceQuota>>,
+    default_quota: RwLock<Option<NamespaceQuota>>,
 }
 
 impl NamespaceManager {
@@ -30,16 +131,60 @@ impl NamespaceManager {
             base_path,
             config_template,
             namespaces: Arc::new(RwLock::new(HashMap::new())),
+            closing: Mutex::new(HashMap::new()),
+            eviction: RwLock::new(NamespaceEvictionConfig::default()),
+            evicted: AtomicU64::new(0),
+            last_idle_sweep: Mutex::new(Instant::now()),
+            quotas: RwLock::new(HashMap::new()),
+            default_quota: RwLock::new(None),
         })
     }
 
+    /// Limit the size of `name`, or lift its own limit with `None`
+    ///
+    /// Applies at once if the namespace is open, and again whenever it is
+    /// reopened. A namespace without its own quota uses the default quota.
+    pub fn set_quota(&self, name: &str, quota: Option<NamespaceQuota>) {
+        match quota {
+            Some(quota) => self.quotas.write().insert(name.to_string(), quota),
+            None => self.quotas.write().remove(name),
+        };
+        if let Some(ns) = self.namespaces.read().get(name) {
+            ns.storage.set_quota(self.quota_for(name));
+        }
+    }
+
+    /// Quota for namespaces without their own (default: unlimited)
+    pub fn set_default_quota(&self, quota: Option<NamespaceQuota>) {
+        *self.default_quota.write() = quota;
+        for (name, ns) in self.namespaces.read().iter() {
+            ns.storage.set_quota(self.quota_for(name));
+        }
+    }
+
+    fn quota_for(&self, name: &str) -> Option<NamespaceQuota> {
+        self.quotas
+            .read()
+            .get(name)
+            .copied()
+            .or(*self.default_quota.read())
+            .filter(|quota| !quota.is_unlimited())
+    }
+
+    /// Set the eviction policy (default: keep every namespace open)
+    pub fn set_eviction(&self, config: NamespaceEvictionConfig) {
+        *self.eviction.write() = config;
+    }
+
     /// Get or create a namespace
     pub fn get_namespace(&self, name: &str) -> Arc<ConcurrentMemory> {
+        self.maybe_evict_idle();
+
         // FAST PATH: Check if exists
         {
             let namespaces = self.namespaces.read();
-            if let Some(storage) = namespaces.get(name) {
-                return Arc::clone(storage);
+            if let Some(ns) = namespaces.get(name) {
+                return ns.touch();
             }
         }
 
@@ -47,7 +192,13 @@ impl NamespaceManager {
         let mut namespaces = self.namespaces.write();
 
         // Double check in case of race
-        if let Some(storage) = namespaces.get(name) {
+        if let Some(ns) = namespaces.get(name) {
+            return ns.touch();
+        }
+
+        // Still flushing after an eviction: keep using the same instance
+        if let Some(storage) = self.closing.lock().get(name) {
+            namespaces.insert(name.to_string(), OpenNamespace::new(Arc::clone(storage)));
             return Arc::clone(storage);
         }
 
@@ -56,28 +207,60 @@ impl NamespaceManager {
         ns_config.storage_path = ns_path;
 
         let storage = Arc::new(ConcurrentMemory::new(ns_config));
-        namespaces.insert(name.to_string(), Arc::clone(&storage));
+        storage.set_quota(self.quota_for(name));
+        namespaces.insert(name.to_string(), OpenNamespace::new(Arc::clone(&storage)));
 
         log::info!("Created/Loaded namespace: {}", name);
+
+        let mut closed = Vec::new();
+        let max_open = self.eviction.read().max_open;
+        if max_open > 0 && namespaces.len() > max_open {
+            // Least recently used first; namespaces in use are skipped
+            let mut candidates: Vec<(Instant, String)> = namespaces
+                .iter()
+                .filter(|(_, ns)| ns.is_unused())
+                .map(|(name, ns)| (*ns.last_access.lock(), name.clone()))
+                .collect();
+            candidates.sort();
+            for (_, lru) in candidates {
+                if namespaces.len() <= max_open {
+                    break;
+                }
+                closed.extend(self.start_eviction(&mut namespaces, lru));
+            }
+        }
+        drop(namespaces);
+
+        for (name, evicted) in closed {
+            self.finish_eviction(name, evicted);
+        }
         storage
     }
 
     /// Add an existing storage instance as a namespace
     pub fn add_namespace(&self, name: &str, storage: Arc<ConcurrentMemory>) {
+        storage.set_quota(self.quota_for(name));
         let mut namespaces = self.namespaces.write();
-        namespaces.insert(name.to_string(), storage);
+        namespaces.insert(name.to_string(), OpenNamespace::new(storage));
     }
 
-    /// List available namespaces
+    /// List open namespaces
     pub fn list_namespaces(&self) -> Vec<String> {
         self.namespaces.read().keys().cloned().collect()
     }
 
+    pub fn stats(&self) -> NamespaceStats {
+        NamespaceStats {
+            open: self.namespaces.read().len(),
+            evicted: self.evicted.load(Ordering::Relaxed),
+        }
+    }
+
     /// Clear a specific namespace
     pub fn clear_namespace(&self, name: &str) -> Result<()> {
         let storage = {
             let namespaces = self.namespaces.read();
-            namespaces.get(name).cloned()
+            namespaces.get(name).map(OpenNamespace::touch)
         };
 
         if let Some(storage) = storage {
@@ -93,11 +276,234 @@ impl NamespaceManager {
     /// Flush all namespaces
     pub fn flush_all(&self) -> Result<()> {
         let namespaces = self.namespaces.read();
-        for (name, storage) in namespaces.iter() {
-            storage
+        for (name, ns) in namespaces.iter() {
+            ns.storage
                 .flush()
                 .with_context(|| format!("Failed to flush namespace {}", name))?;
         }
         Ok(())
     }
+
+    /// Close unused namespaces idle for longer than the idle timeout
+    ///
+    /// Runs at most every half timeout, so frequent accesses stay cheap.
+    fn maybe_evict_idle(&self) {
+        let Some(idle_timeout) = self.eviction.read().idle_timeout else {
+            return;
+        };
+        {
+            let mut last = self.last_idle_sweep.lock();
+            if last.elapsed() < idle_timeout / 2 {
+                return;
+            }
+            *last = Instant::now();
+        }
+
+        let closed: Vec<_> = {
+            let mut namespaces = self.namespaces.write();
+            let idle: Vec<String> = namespaces
+                .iter()
+                .filter(|(_, ns)| ns.is_unused() && ns.last_access.lock().elapsed() >= idle_timeout)
+                .map(|(name, _)| name.clone())
+                .collect();
+            idle.into_iter()
+                .filter_map(|name| self.start_eviction(&mut namespaces, name))
+                .collect()
+        };
+        for (name, evicted) in closed {
+            self.finish_eviction(name, evicted);
+        }
+    }
+
+    /// Close `name` under the namespaces lock; `finish_eviction` flushes it
+    /// once the lock is released
+    fn start_eviction(
+        &self,
+        namespaces: &mut HashMap<String, OpenNamespace>,
+        name: String,
+    ) -> Option<(String, Arc<ConcurrentMemory>)> {
+        let ns = namespaces.remove(&name)?;
+        self.closing
+            .lock()
+            .insert(name.clone(), Arc::clone(&ns.storage));
+        Some((name, ns.storage))
+    }
+
+    /// Flush an evicted namespace; it is reopened if the flush fails
+    fn finish_eviction(&self, name: String, storage: Arc<ConcurrentMemory>) {
+        let flushed = storage.flush();
+        let mut namespaces = self.namespaces.write();
+        self.closing.lock().remove(&name);
+        match flushed {
+            // Reopened while it was flushing
+            Ok(()) if namespaces.contains_key(&name) => {}
+            Ok(()) => {
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+                log::info!("Evicted idle namespace: {}", name);
+            }
+            Err(e) => {
+                log::warn!("Not evicting namespace {}: flush failed: {}", name, e);
+                namespaces
+                    .entry(name)
+                    .or_insert_with(|| OpenNamespace::new(storage));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ConceptId;
+    use crate::write_log::WriteLogError;
+
+    #[test]
+    fn test_lru_namespace_evicted_and_reopened() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = NamespaceManager::new(
+            dir.path().to_path_buf(),
+            ConcurrentConfig {
+                vector_dimension: 8,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        manager.set_eviction(NamespaceEvictionConfig {
+            max_open: 2,
+            idle_timeout: None,
+        });
+
+        let id = ConceptId::from_string("tenant-a-fact");
+        {
+            let a = manager.get_namespace("tenant-a");
+            a.learn_concept(
+                id,
+                b"belongs to tenant a".to_vec(),
+                None,
+                1.0,
+                0.9,
+                HashMap::new(),
+            )
+            .unwrap();
+            let start = Instant::now();
+            while a.query_concept(&id).is_none() {
+                assert!(start.elapsed() < Duration::from_secs(5));
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+        manager.get_namespace("tenant-b");
+
+        // A held namespace is never evicted, even if it is the LRU one
+        let held_b = manager.get_namespace("tenant-b");
+        let _ = manager.get_namespace("tenant-a");
+        manager.get_namespace("tenant-c");
+        let mut open = manager.list_namespaces();
+        open.sort();
+        assert_eq!(open, vec!["tenant-b", "tenant-c"]);
+        assert_eq!(manager.stats().evicted, 1);
+        drop(held_b);
+
+        // tenant-a reopens from disk; tenant-b is now the LRU namespace
+        let a = manager.get_namespace("tenant-a");
+        let node = a.query_concept(&id).expect("evicted data survives reopen");
+        assert_eq!(node.content.as_ref(), b"belongs to tenant a");
+        let stats = manager.stats();
+        assert_eq!((stats.open, stats.evicted), (2, 2));
+        assert!(!manager.list_namespaces().contains(&"tenant-b".to_string()));
+    }
+
+    #[test]
+    fn test_namespace_reopened_while_flushing_reuses_instance() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = NamespaceManager::new(
+            dir.path().to_path_buf(),
+            ConcurrentConfig {
+                vector_dimension: 8,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let a = manager.get_namespace("tenant-a");
+        drop(a);
+
+        // Evicted but not yet flushed: the namespaces lock is free again
+        let (name, storage) = manager
+            .start_eviction(&mut manager.namespaces.write(), "tenant-a".to_string())
+            .unwrap();
+        assert!(manager.list_namespaces().is_empty());
+
+        let reopened = manager.get_namespace("tenant-a");
+        assert!(Arc::ptr_eq(&reopened, &storage));
+
+        manager.finish_eviction(name, storage);
+        assert_eq!(manager.list_namespaces(), vec!["tenant-a"]);
+        assert_eq!(manager.stats().evicted, 0);
+    }
+
+    fn wait_for(storage: &ConcurrentMemory, id: &ConceptId, present: bool) {
+        let start = Instant::now();
+        while storage.query_concept(id).is_some() != present {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn learn(storage: &ConcurrentMemory, name: &str) -> Result<ConceptId, WriteLogError> {
+        let id = ConceptId::from_string(name);
+        storage.learn_concept(id, name.as_bytes().to_vec(), None, 1.0, 0.9, HashMap::new())?;
+        Ok(id)
+    }
+
+    #[test]
+    fn test_quota_rejects_or_evicts_past_max_concepts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = NamespaceManager::new(
+            dir.path().to_path_buf(),
+            ConcurrentConfig {
+                vector_dimension: 8,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let quota = NamespaceQuota {
+            max_concepts: 3,
+            max_bytes: 0,
+            mode: QuotaMode::Reject,
+        };
+        manager.set_quota("strict", Some(quota));
+        manager.set_quota(
+            "rolling",
+            Some(NamespaceQuota {
+                mode: QuotaMode::EvictLru,
+                ..quota
+            }),
+        );
+
+        let strict = manager.get_namespace("strict");
+        for name in ["s1", "s2", "s3"] {
+            learn(&strict, name).unwrap();
+        }
+        assert!(matches!(
+            learn(&strict, "s4"),
+            Err(WriteLogError::QuotaExceeded(_))
+        ));
+        // Re-learning an existing concept does not add to the count
+        learn(&strict, "s2").unwrap();
+        assert_eq!(strict.quota_usage().unwrap().concepts, 3);
+
+        let rolling = manager.get_namespace("rolling");
+        let mut ids = Vec::new();
+        for name in ["r1", "r2", "r3"] {
+            let id = learn(&rolling, name).unwrap();
+            wait_for(&rolling, &id, true);
+            ids.push(id);
+        }
+        let newest = learn(&rolling, "r4").unwrap();
+        wait_for(&rolling, &newest, true);
+        wait_for(&rolling, &ids[0], false);
+        assert!(rolling.query_concept(&ids[1]).is_some());
+        assert!(rolling.query_concept(&ids[2]).is_some());
+        let usage = rolling.quota_usage().unwrap();
+        assert_eq!((usage.concepts, usage.evicted), (3, 1));
+    }
 }
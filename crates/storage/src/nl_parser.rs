@@ -21,6 +21,7 @@ impl NlParser {
                 namespace: Some("default".to_string()),
                 content: content.to_string(),
                 options: LearnOptionsMsg::default(),
+                idempotency_key: None,
             });
         }
 
@@ -35,6 +36,7 @@ impl NlParser {
             return Some(StorageRequest::QueryConcept {
                 namespace: Some("default".to_string()),
                 concept_id: query.to_string(), // QueryConcept uses query as ID approx
+                include_vector: false,
             });
         }
 
@@ -43,6 +45,7 @@ impl NlParser {
             return Some(StorageRequest::ListRecent {
                 namespace: "default".to_string(),
                 limit: 20,
+                cursor: None,
             });
         }
 
@@ -1,4 +1,5 @@
 use crate::read_view::GraphSnapshot;
+use crate::storage_pool::StoragePool;
 /// Parallel pathfinding for multi-path reasoning
 ///
 /// Design:
@@ -37,12 +38,23 @@ impl PathResult {
 pub struct ParallelPathFinder {
     /// Confidence decay per hop (default: 0.85)
     decay_factor: f32,
+    /// Pool the per-neighbor searches run on
+    pool: StoragePool,
 }
 
 impl ParallelPathFinder {
     /// Create new parallel pathfinder
     pub fn new(decay_factor: f32) -> Self {
-        Self { decay_factor }
+        Self {
+            decay_factor,
+            pool: StoragePool::global(),
+        }
+    }
+
+    /// Run searches on `pool` instead of rayon's global pool
+    pub fn with_pool(mut self, pool: StoragePool) -> Self {
+        self.pool = pool;
+        self
     }
 
     /// Find multiple paths in parallel between start and end concepts
@@ -86,13 +98,15 @@ impl ParallelPathFinder {
         );
 
         // Parallel search from each first-hop neighbor
-        let paths: Vec<PathResult> = first_neighbors
-            .par_iter()
-            .filter_map(|&first_hop| {
-                // BFS from first_hop to end
-                self.bfs_search(snapshot.clone(), start, first_hop, end, max_depth - 1)
-            })
-            .collect();
+        let paths: Vec<PathResult> = self.pool.install(|| {
+            first_neighbors
+                .par_iter()
+                .filter_map(|&first_hop| {
+                    // BFS from first_hop to end
+                    self.bfs_search(snapshot.clone(), start, first_hop, end, max_depth - 1)
+                })
+                .collect()
+        });
 
         // Sort by confidence and limit to max_paths
         let mut sorted_paths = paths;
@@ -308,6 +322,52 @@ mod tests {
         assert!(paths[0].confidence >= paths[1].confidence);
     }
 
+    #[test]
+    fn test_single_thread_pool_finds_same_paths() {
+        let mut snapshot = GraphSnapshot::new(0);
+        let ids: Vec<ConceptId> = (1..=5).map(|i| ConceptId([i; 16])).collect();
+
+        // 1 fans out to 2, 3 and 4, which all reach 5
+        let mut hub = ConceptNode::new(ids[0], vec![1], None, 1.0, 0.9, 1000);
+        for &mid in &ids[1..4] {
+            hub.add_edge(
+                mid,
+                AssociationRecord::new(ids[0], mid, AssociationType::Semantic, 0.9),
+            );
+            let mut node = ConceptNode::new(mid, vec![mid.0[0]], None, 1.0, 0.9, 1000);
+            node.add_edge(
+                ids[4],
+                AssociationRecord::new(mid, ids[4], AssociationType::Semantic, 0.9),
+            );
+            snapshot.concepts.insert(mid, node);
+        }
+        snapshot.concepts.insert(ids[0], hub);
+        snapshot.concepts.insert(
+            ids[4],
+            ConceptNode::new(ids[4], vec![5], None, 1.0, 0.9, 1000),
+        );
+        let snapshot = Arc::new(snapshot);
+
+        let sorted = |paths: Vec<PathResult>| {
+            let mut paths: Vec<Vec<ConceptId>> = paths.into_iter().map(|p| p.path).collect();
+            paths.sort_by_key(|p| p[1].0);
+            paths
+        };
+        let global = ParallelPathFinder::default().find_paths_parallel(
+            snapshot.clone(),
+            ids[0],
+            ids[4],
+            10,
+            10,
+        );
+        let serial = ParallelPathFinder::default()
+            .with_pool(StoragePool::shared(1))
+            .find_paths_parallel(snapshot, ids[0], ids[4], 10, 10);
+
+        assert_eq!(serial.len(), 3);
+        assert_eq!(sorted(serial), sorted(global));
+    }
+
     #[test]
     fn test_parallel_no_path() {
         let mut snapshot = GraphSnapshot::new(0);
@@ -8,6 +8,8 @@
 /// - Automatic cleanup of stale entries
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -226,6 +228,61 @@ impl Default for RateLimiter {
     }
 }
 
+/// Per-client limits for the TCP servers, with separate read and write buckets
+///
+/// Clients are keyed by IP address, so reconnecting does not refill a bucket.
+pub struct PeerRateLimiter {
+    reads: RateLimiter,
+    writes: RateLimiter,
+    rejected: AtomicU64,
+}
+
+impl PeerRateLimiter {
+    /// Limit reads and writes (usually the tighter of the two) separately
+    pub fn new(reads: RateLimiterConfig, writes: RateLimiterConfig) -> Self {
+        Self {
+            reads: RateLimiter::with_config(reads),
+            writes: RateLimiter::with_config(writes),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Take a token from `peer`'s read or write bucket
+    pub fn check(&self, peer: IpAddr, write: bool) -> Result<(), RateLimitError> {
+        self.check_subject(&peer.to_string(), write)
+    }
+
+    /// Take a token from the buckets of an arbitrary client key
+    ///
+    /// Lets authenticated clients be limited by identity rather than address.
+    pub fn check_subject(&self, subject: &str, write: bool) -> Result<(), RateLimitError> {
+        let limiter = if write { &self.writes } else { &self.reads };
+        let result = limiter.check_rate_limit(subject);
+        if result.is_err() {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Get statistics (for monitoring)
+    pub fn stats(&self) -> PeerRateLimiterStats {
+        PeerRateLimiterStats {
+            reads: self.reads.stats(),
+            writes: self.writes.stats(),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Peer rate limiter statistics
+#[derive(Debug, Clone)]
+pub struct PeerRateLimiterStats {
+    pub reads: RateLimiterStats,
+    pub writes: RateLimiterStats,
+    /// Requests refused since startup
+    pub rejected: u64,
+}
+
 /// Rate limiter statistics
 #[derive(Debug, Clone)]
 pub struct RateLimiterStats {
@@ -363,6 +420,30 @@ mod tests {
         assert!(stats.average_tokens > 0.0);
     }
 
+    #[test]
+    fn test_peer_limits_reads_and_writes_separately() {
+        let config = |burst| RateLimiterConfig {
+            requests_per_second: 1,
+            burst_capacity: burst,
+            memory_duration: Duration::from_secs(60),
+        };
+        let limiter = PeerRateLimiter::new(config(5), config(2));
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+
+        assert!(limiter.check(peer, true).is_ok());
+        assert!(limiter.check(peer, true).is_ok());
+        assert!(limiter.check(peer, true).is_err());
+
+        // Writes being throttled leaves reads alone, and other peers too
+        assert!(limiter.check(peer, false).is_ok());
+        assert!(limiter.check("10.0.0.2".parse().unwrap(), true).is_ok());
+
+        let stats = limiter.stats();
+        assert_eq!(stats.rejected, 1);
+        assert_eq!(stats.writes.active_subjects, 2);
+        assert_eq!(stats.writes.throttled_subjects, 1);
+    }
+
     #[test]
     fn test_reset_subject() {
         let config = RateLimiterConfig {
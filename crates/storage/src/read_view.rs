@@ -9,6 +9,7 @@ use crate::semantic::SemanticMetadata;
 use crate::types::{AssociationRecord, ConceptId};
 use arc_swap::ArcSwap;
 use std::sync::Arc;
+use std::time::Instant;
 
 /// In-memory concept with co-located edges and semantic metadata
 #[derive(Debug, Clone)]
@@ -31,6 +32,9 @@ pub struct ConceptNode {
     /// Co-located edges for cache-friendly traversal
     pub neighbors: Vec<ConceptId>,
     pub associations: Vec<AssociationRecord>,
+
+    /// Sequence of the snapshot that last changed this concept
+    pub version: u64,
 }
 
 impl ConceptNode {
@@ -55,6 +59,7 @@ impl ConceptNode {
             attributes: std::collections::HashMap::new(),
             neighbors: Vec::new(),
             associations: Vec::new(),
+            version: 0,
         }
     }
 
@@ -81,6 +86,7 @@ impl ConceptNode {
             attributes: std::collections::HashMap::new(),
             neighbors: Vec::new(),
             associations: Vec::new(),
+            version: 0,
         }
     }
 
@@ -106,6 +112,37 @@ impl ConceptNode {
     }
 }
 
+/// A search stopped because the caller's deadline passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineExceeded;
+
+/// Changes between two snapshots, from [`GraphSnapshot::diff`]
+///
+/// Edges are `(concept, neighbor)` pairs as stored in each concept's
+/// adjacency list, so an association shows up once per endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    pub added: Vec<ConceptId>,
+    /// Present in both snapshots with a different version
+    pub modified: Vec<ConceptId>,
+    pub removed: Vec<ConceptId>,
+    pub added_edges: Vec<(ConceptId, ConceptId)>,
+    pub removed_edges: Vec<(ConceptId, ConceptId)>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.modified.is_empty()
+            && self.removed.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Inverted index from `(attribute_key, value)` to the concepts carrying it
+pub type AttributeIndex = im::HashMap<(String, String), im::HashSet<ConceptId>>;
+
 /// Immutable graph snapshot
 /// CRITICAL: This must be truly immutable - DashMap allows mutation which breaks snapshot semantics
 /// We use im::HashMap for true immutability and zero-contention reads
@@ -114,6 +151,9 @@ pub struct GraphSnapshot {
     /// All concepts indexed by ID (immutable map)
     pub concepts: im::HashMap<ConceptId, ConceptNode>,
 
+    /// Attribute index, kept in sync by `insert_concept`/`remove_concept`
+    pub attribute_index: AttributeIndex,
+
     /// Snapshot metadata
     pub sequence: u64,
     pub timestamp: u64,
@@ -125,6 +165,7 @@ impl GraphSnapshot {
     pub fn new(sequence: u64) -> Self {
         Self {
             concepts: im::HashMap::new(),
+            attribute_index: im::HashMap::new(),
             sequence,
             timestamp: current_timestamp_us(),
             concept_count: 0,
@@ -137,6 +178,90 @@ impl GraphSnapshot {
         self.concepts.get(id).cloned()
     }
 
+    /// Insert or replace a concept, keeping the attribute index in sync
+    ///
+    /// Stamps the concept with this snapshot's sequence as its version.
+    pub fn insert_concept(&mut self, mut node: ConceptNode) {
+        node.version = self.sequence;
+        let id = node.id;
+        if let Some(old) = self.concepts.get(&id) {
+            let old_attributes = old.attributes.clone();
+            self.unindex_attributes(id, &old_attributes);
+        }
+        for (key, value) in &node.attributes {
+            self.attribute_index
+                .entry((key.clone(), value.clone()))
+                .or_default()
+                .insert(id);
+        }
+        self.concepts.insert(id, node);
+    }
+
+    /// Replace a concept whose attributes did not change
+    ///
+    /// Like `insert_concept` but skips re-indexing attributes.
+    pub fn update_concept(&mut self, mut node: ConceptNode) {
+        node.version = self.sequence;
+        self.concepts.insert(node.id, node);
+    }
+
+    /// Remove a concept and its attribute index entries
+    pub fn remove_concept(&mut self, id: &ConceptId) -> Option<ConceptNode> {
+        let node = self.concepts.remove(id)?;
+        self.unindex_attributes(*id, &node.attributes);
+        Some(node)
+    }
+
+    fn unindex_attributes(
+        &mut self,
+        id: ConceptId,
+        attributes: &std::collections::HashMap<String, String>,
+    ) {
+        for (key, value) in attributes {
+            let posting_key = (key.clone(), value.clone());
+            if let Some(ids) = self.attribute_index.get_mut(&posting_key) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.attribute_index.remove(&posting_key);
+                }
+            }
+        }
+    }
+
+    /// Concepts whose attributes contain every `key = value` pair in `filters`
+    ///
+    /// Answered from the attribute index by intersecting posting lists,
+    /// smallest first. An empty filter matches every concept.
+    pub fn query_by_attributes(
+        &self,
+        filters: &std::collections::HashMap<String, String>,
+    ) -> Vec<ConceptId> {
+        if filters.is_empty() {
+            return self.concepts.keys().copied().collect();
+        }
+
+        let mut postings = Vec::with_capacity(filters.len());
+        for (key, value) in filters {
+            match self.attribute_index.get(&(key.clone(), value.clone())) {
+                Some(ids) => postings.push(ids),
+                None => return Vec::new(),
+            }
+        }
+        postings.sort_by_key(|ids| ids.len());
+
+        let (smallest, rest) = postings.split_first().expect("filters is non-empty");
+        smallest
+            .iter()
+            .filter(|id| rest.iter().all(|ids| ids.contains(*id)))
+            .copied()
+            .collect()
+    }
+
+    /// Total number of (attribute, concept) entries in the attribute index
+    pub fn attribute_index_size(&self) -> usize {
+        self.attribute_index.values().map(|ids| ids.len()).sum()
+    }
+
     /// Check if concept exists
     pub fn contains(&self, id: &ConceptId) -> bool {
         self.concepts.contains_key(id)
@@ -165,10 +290,22 @@ impl GraphSnapshot {
         end: ConceptId,
         max_depth: usize,
     ) -> Option<Vec<ConceptId>> {
+        self.find_path_until(start, end, max_depth, None)
+            .unwrap_or_default()
+    }
+
+    /// BFS path search that gives up once `deadline` has passed
+    pub fn find_path_until(
+        &self,
+        start: ConceptId,
+        end: ConceptId,
+        max_depth: usize,
+        deadline: Option<Instant>,
+    ) -> Result<Option<Vec<ConceptId>>, DeadlineExceeded> {
         use std::collections::{HashMap, VecDeque};
 
         if start == end {
-            return Some(vec![start]);
+            return Ok(Some(vec![start]));
         }
 
         let mut queue = VecDeque::new();
@@ -177,11 +314,18 @@ impl GraphSnapshot {
         queue.push_back((start, 0));
         visited.insert(start, None);
 
+        let mut expanded = 0usize;
         while let Some((current, depth)) = queue.pop_front() {
             if depth >= max_depth {
                 continue;
             }
 
+            // Checking the clock on every node would dominate small searches
+            expanded += 1;
+            if expanded.is_multiple_of(256) && deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(DeadlineExceeded);
+            }
+
             if let Some(node) = self.concepts.get(&current) {
                 for &neighbor in &node.neighbors {
                     if let std::collections::hash_map::Entry::Vacant(e) = visited.entry(neighbor) {
@@ -199,7 +343,7 @@ impl GraphSnapshot {
                             }
 
                             path.reverse();
-                            return Some(path);
+                            return Ok(Some(path));
                         }
 
                         queue.push_back((neighbor, depth + 1));
@@ -208,7 +352,56 @@ impl GraphSnapshot {
             }
         }
 
-        None
+        Ok(None)
+    }
+
+    /// What changed since `previous`, an earlier snapshot of the same view
+    ///
+    /// Modifications are found by comparing concept versions, so unchanged
+    /// concepts are never compared field by field. Versions restart when
+    /// storage is reloaded from disk, so only diff snapshots taken by one
+    /// process.
+    pub fn diff(&self, previous: &GraphSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+        if self.concepts.ptr_eq(&previous.concepts) {
+            return diff;
+        }
+
+        for (id, node) in &self.concepts {
+            match previous.concepts.get(id) {
+                None => {
+                    diff.added.push(*id);
+                    diff.added_edges
+                        .extend(node.neighbors.iter().map(|&neighbor| (*id, neighbor)));
+                }
+                Some(old) if old.version != node.version => {
+                    diff.modified.push(*id);
+                    diff.added_edges.extend(
+                        node.neighbors
+                            .iter()
+                            .filter(|neighbor| !old.neighbors.contains(neighbor))
+                            .map(|&neighbor| (*id, neighbor)),
+                    );
+                    diff.removed_edges.extend(
+                        old.neighbors
+                            .iter()
+                            .filter(|neighbor| !node.neighbors.contains(neighbor))
+                            .map(|&neighbor| (*id, neighbor)),
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (id, old) in &previous.concepts {
+            if !self.concepts.contains_key(id) {
+                diff.removed.push(*id);
+                diff.removed_edges
+                    .extend(old.neighbors.iter().map(|&neighbor| (*id, neighbor)));
+            }
+        }
+
+        diff
     }
 
     /// Get all concepts (expensive, for bulk operations)
@@ -277,7 +470,7 @@ impl ReadView {
             }
 
             // Insert into immutable map
-            snapshot.concepts.insert(concept_id, node);
+            snapshot.insert_concept(node);
         }
 
         // Update statistics
@@ -478,4 +671,66 @@ mod tests {
         assert_eq!(neighbors[0].0, id3); // 0.9 strength
         assert_eq!(neighbors[1].0, id2); // 0.5 strength
     }
+
+    #[test]
+    fn test_diff_reports_only_changed_concepts() {
+        use crate::concurrent_memory::{ConcurrentConfig, ConcurrentMemory};
+        use std::collections::HashMap;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let wait = |ready: &dyn Fn(&GraphSnapshot) -> bool| {
+            let deadline = Instant::now() + std::time::Duration::from_secs(5);
+            loop {
+                let snapshot = storage.get_snapshot();
+                if ready(&snapshot) {
+                    return snapshot;
+                }
+                assert!(Instant::now() < deadline, "write never landed");
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        };
+
+        let [a, b, c, d] = [1u8, 2, 3, 4].map(|i| ConceptId([i; 16]));
+        for id in [a, b, c] {
+            storage
+                .learn_concept(id, vec![id.0[0]], None, 0.5, 0.9, HashMap::new())
+                .unwrap();
+        }
+        storage
+            .learn_association(a, b, AssociationType::Semantic, 0.8)
+            .unwrap();
+        let before = wait(&|s| s.contains(&c) && !s.get_neighbors(&b).is_empty());
+
+        storage
+            .learn_concept(d, vec![4], None, 0.5, 0.9, HashMap::new())
+            .unwrap();
+        storage.delete_concept(c).unwrap();
+        let after = wait(&|s| s.contains(&d) && !s.contains(&c));
+
+        let diff = after.diff(&before);
+        assert_eq!(diff.added, vec![d]);
+        assert_eq!(diff.removed, vec![c]);
+        assert!(diff.modified.is_empty(), "{:?}", diff.modified);
+        assert!(diff.added_edges.is_empty() && diff.removed_edges.is_empty());
+        assert!(after.diff(&after).is_empty());
+
+        // Edge and strength changes show up as modifications
+        storage
+            .learn_association(b, d, AssociationType::Semantic, 0.8)
+            .unwrap();
+        storage.update_strength(a, 0.9).unwrap();
+        let later = wait(&|s| !s.get_neighbors(&d).is_empty() && s.concepts[&a].strength > 0.8);
+
+        let diff = later.diff(&after);
+        let modified: std::collections::HashSet<_> = diff.modified.iter().copied().collect();
+        assert_eq!(modified, [a, b, d].into_iter().collect());
+        assert_eq!(diff.modified.len(), 3);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert!(diff.added_edges.contains(&(b, d)) && diff.added_edges.contains(&(d, b)));
+        assert_eq!(diff.added_edges.len(), 2);
+    }
 }
@@ -0,0 +1,736 @@
+//! Snapshot-based read replicas
+//!
+//! A primary keeps a bounded, in-memory [`ReplicationLog`] of every write the
+//! reconciler applies. A replica bootstraps from a full snapshot transfer and
+//! then pulls log records continuously over the TCP protocol, applying them to
+//! its own `ConcurrentMemory`:
+//!
+//! ```text
+//! replica ── ReplicationSnapshot ──▶ primary   (first chunk, sequence N, cursor)
+//! replica ── ReplicationSnapshot{transfer, cursor} ──▶ primary   (next chunk) ...
+//! replica ── ReplicationPull{N}  ──▶ primary   (records N.., primary sequence)
+//! replica ── ReplicationPull{M}  ──▶ primary   ...
+//! ```
+//!
+//! The snapshot is sent in chunks that each fit in a frame: the primary pins
+//! the snapshot for the transfer so every chunk comes from the same state.
+//!
+//! Replicas are eventually consistent. Records are published only after the
+//! snapshot containing them is visible, so a snapshot taken after reading
+//! sequence N already holds every record below N; records at or above N may
+//! also be in it and are simply re-applied (all operations are idempotent when
+//! replayed in order). If a replica falls further behind than the log retains,
+//! the primary answers `ReplicationResyncRequired` and the replica starts over
+//! from a fresh snapshot.
+//!
+//! A replica that reconnects resumes pulling from the last sequence it applied.
+//! When it has to resync, it buffers the whole snapshot and swaps it in as one
+//! write, so reads keep seeing the old data until the new data replaces it.
+
+use crate::concurrent_memory::ConcurrentMemory;
+use crate::read_view::{ConceptNode, GraphSnapshot};
+use crate::semantic::SemanticMetadata;
+use crate::tcp_server::{StorageRequest, StorageResponse, DEFAULT_MAX_MESSAGE_SIZE};
+use crate::types::{AssociationRecord, ConceptId};
+use crate::write_log::WriteEntry;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+
+/// A replicated write, carrying everything needed to re-apply it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReplicationOp {
+    AddConcept {
+        id: ConceptId,
+        content: Vec<u8>,
+        vector: Option<Vec<f32>>,
+        strength: f32,
+        confidence: f32,
+        timestamp: u64,
+        attributes: HashMap<String, String>,
+        semantic: Option<SemanticMetadata>,
+    },
+    AddAssociation {
+        source: ConceptId,
+        target: ConceptId,
+        assoc_type: u8,
+        confidence: f32,
+        weight: f32,
+        created: u64,
+    },
+    UpdateStrength {
+        id: ConceptId,
+        strength: f32,
+    },
+    UpdateConcept {
+        id: ConceptId,
+        strength: Option<f32>,
+        confidence: Option<f32>,
+        attributes: HashMap<String, String>,
+    },
+    DeleteConcept {
+        id: ConceptId,
+    },
+    Clear,
+}
+
+impl ReplicationOp {
+    /// Convert an applied write-log entry. Access tracking and batch markers
+    /// are local bookkeeping and are not replicated.
+    pub(crate) fn from_entry(entry: &WriteEntry) -> Option<Self> {
+        match entry {
+            WriteEntry::AddConcept {
+                id,
+                content,
+                vector,
+                strength,
+                confidence,
+                timestamp,
+                attributes,
+                semantic,
+            } => Some(ReplicationOp::AddConcept {
+                id: *id,
+                content: content.to_vec(),
+                vector: vector.as_ref().map(|v| v.to_vec()),
+                strength: *strength,
+                confidence: *confidence,
+                timestamp: *timestamp,
+                attributes: attributes.clone(),
+                semantic: semantic.clone(),
+            }),
+            WriteEntry::AddAssociation { record } => Some(Self::from_record(record)),
+            WriteEntry::UpdateStrength { id, strength } => Some(ReplicationOp::UpdateStrength {
+                id: *id,
+                strength: *strength,
+            }),
+            WriteEntry::UpdateConcept {
+                id,
+                strength,
+                confidence,
+                attributes,
+            } => Some(ReplicationOp::UpdateConcept {
+                id: *id,
+                strength: *strength,
+                confidence: *confidence,
+                attributes: attributes.clone(),
+            }),
+            WriteEntry::DeleteConcept { id, .. } => Some(ReplicationOp::DeleteConcept { id: *id }),
+            WriteEntry::Clear => Some(ReplicationOp::Clear),
+            WriteEntry::RecordAccess { .. } | WriteEntry::BatchMarker { .. } => None,
+            // The reconciler replicates the contained entries individually
+            WriteEntry::Atomic { .. } => None,
+        }
+    }
+
+    fn from_record(record: &AssociationRecord) -> Self {
+        ReplicationOp::AddAssociation {
+            source: record.source_id,
+            target: record.target_id,
+            assoc_type: record.assoc_type,
+            confidence: record.confidence,
+            weight: record.weight,
+            created: record.created,
+        }
+    }
+
+    /// Rebuild the write-log entry this op was created from
+    pub(crate) fn into_entry(self) -> WriteEntry {
+        match self {
+            ReplicationOp::AddConcept {
+                id,
+                content,
+                vector,
+                strength,
+                confidence,
+                timestamp,
+                attributes,
+                semantic,
+            } => WriteEntry::AddConcept {
+                id,
+                content: content.into_boxed_slice(),
+                vector: vector.map(|v| v.into_boxed_slice()),
+                strength,
+                confidence,
+                timestamp,
+                attributes,
+                semantic,
+            },
+            ReplicationOp::AddAssociation {
+                source,
+                target,
+                assoc_type,
+                confidence,
+                weight,
+                created,
+            } => {
+                let mut record = AssociationRecord::new(
+                    source,
+                    target,
+                    crate::types::AssociationType::from_u8(assoc_type)
+                        .unwrap_or(crate::types::AssociationType::Semantic),
+                    confidence,
+                );
+                record.weight = weight;
+                record.created = created;
+                WriteEntry::AddAssociation { record }
+            }
+            ReplicationOp::UpdateStrength { id, strength } => {
+                WriteEntry::UpdateStrength { id, strength }
+            }
+            ReplicationOp::UpdateConcept {
+                id,
+                strength,
+                confidence,
+                attributes,
+            } => WriteEntry::UpdateConcept {
+                id,
+                strength,
+                confidence,
+                attributes,
+            },
+            ReplicationOp::DeleteConcept { id } => WriteEntry::DeleteConcept {
+                id,
+                timestamp: current_timestamp_us(),
+            },
+            ReplicationOp::Clear => WriteEntry::Clear,
+        }
+    }
+}
+
+/// A replication log record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationRecord {
+    pub sequence: u64,
+    pub op: ReplicationOp,
+}
+
+/// Unused snapshot transfers are dropped after this long
+const TRANSFER_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Concurrent snapshot transfers a primary keeps pinned per namespace
+const MAX_TRANSFERS: usize = 8;
+
+/// A snapshot pinned for a chunked transfer
+///
+/// Positions `0..ids.len()` send each concept, positions
+/// `ids.len()..2 * ids.len()` send the edges of each concept, so every edge
+/// arrives after both of its endpoints.
+struct SnapshotTransfer {
+    snapshot: Arc<GraphSnapshot>,
+    ids: Vec<ConceptId>,
+    sequence: u64,
+    last_used: Instant,
+}
+
+/// One chunk of a snapshot transfer
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotChunk {
+    pub transfer_id: u64,
+    /// First log sequence not covered by the snapshot
+    pub sequence: u64,
+    pub ops: Vec<ReplicationOp>,
+    /// Cursor of the next chunk, `None` once the snapshot is complete
+    pub next_cursor: Option<u64>,
+}
+
+/// Bounded in-memory log of applied writes, read by replicas
+pub struct ReplicationLog {
+    capacity: usize,
+    records: Mutex<VecDeque<ReplicationRecord>>,
+    next_sequence: AtomicU64,
+    transfers: Mutex<HashMap<u64, SnapshotTransfer>>,
+    next_transfer: AtomicU64,
+}
+
+impl ReplicationLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            records: Mutex::new(VecDeque::new()),
+            next_sequence: AtomicU64::new(0),
+            transfers: Mutex::new(HashMap::new()),
+            next_transfer: AtomicU64::new(1),
+        }
+    }
+
+    /// Sequence the next published record will get
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence.load(Ordering::Acquire)
+    }
+
+    /// Append ops in order, evicting the oldest records beyond capacity
+    pub fn publish(&self, ops: impl IntoIterator<Item = ReplicationOp>) {
+        let mut records = self.records.lock();
+        for op in ops {
+            let sequence = self.next_sequence.load(Ordering::Relaxed);
+            records.push_back(ReplicationRecord { sequence, op });
+            self.next_sequence.store(sequence + 1, Ordering::Release);
+        }
+        while records.len() > self.capacity {
+            records.pop_front();
+        }
+    }
+
+    /// Records with `sequence >= from`, at most `max`.
+    ///
+    /// Returns `Err(oldest_retained)` if records before that were evicted.
+    pub fn read_from(&self, from: u64, max: usize) -> Result<Vec<ReplicationRecord>, u64> {
+        let records = self.records.lock();
+        let oldest = records
+            .front()
+            .map(|r| r.sequence)
+            .unwrap_or_else(|| self.next_sequence());
+        if from < oldest {
+            return Err(oldest);
+        }
+        let skip = (from - oldest) as usize;
+        Ok(records.iter().skip(skip).take(max).cloned().collect())
+    }
+
+    /// Pin `snapshot` for a chunked transfer. `sequence` must have been read
+    /// before the snapshot was taken.
+    pub fn start_transfer(&self, snapshot: Arc<GraphSnapshot>, sequence: u64) -> u64 {
+        let id = self.next_transfer.fetch_add(1, Ordering::Relaxed);
+        let mut transfers = self.transfers.lock();
+        transfers.retain(|_, t| t.last_used.elapsed() < TRANSFER_IDLE_TIMEOUT);
+        while transfers.len() >= MAX_TRANSFERS {
+            let oldest = transfers
+                .iter()
+                .min_by_key(|(_, t)| t.last_used)
+                .map(|(id, _)| *id);
+            match oldest {
+                Some(oldest) => transfers.remove(&oldest),
+                None => break,
+            };
+        }
+        transfers.insert(
+            id,
+            SnapshotTransfer {
+                ids: snapshot.concepts.keys().copied().collect(),
+                snapshot,
+                sequence,
+                last_used: Instant::now(),
+            },
+        );
+        id
+    }
+
+    /// Ops of `transfer_id` from `cursor`, stopping once about `max_bytes`
+    /// have been gathered (always at least one position). `None` if the
+    /// transfer is unknown or expired. The transfer is released with its
+    /// last chunk.
+    pub fn snapshot_chunk(
+        &self,
+        transfer_id: u64,
+        cursor: u64,
+        max_bytes: usize,
+    ) -> Option<SnapshotChunk> {
+        let mut transfers = self.transfers.lock();
+        let transfer = transfers.get_mut(&transfer_id)?;
+        transfer.last_used = Instant::now();
+
+        let concepts = transfer.ids.len() as u64;
+        let mut position = cursor;
+        let mut ops = Vec::new();
+        let mut bytes = 0;
+        while position < concepts * 2 && (ops.is_empty() || bytes < max_bytes) {
+            let index = (position % concepts.max(1)) as usize;
+            if let Some(node) = transfer.snapshot.concepts.get(&transfer.ids[index]) {
+                if position < concepts {
+                    bytes +=
+                        node.content.len() + node.vector.as_ref().map_or(0, |v| v.len() * 4) + 64;
+                    ops.push(concept_op(node));
+                } else {
+                    let edges: Vec<_> = edge_ops(node).collect();
+                    bytes += edges.len() * 48;
+                    ops.extend(edges);
+                }
+            }
+            position += 1;
+        }
+
+        let sequence = transfer.sequence;
+        let next_cursor = (position < concepts * 2).then_some(position);
+        if next_cursor.is_none() {
+            transfers.remove(&transfer_id);
+        }
+        Some(SnapshotChunk {
+            transfer_id,
+            sequence,
+            ops,
+            next_cursor,
+        })
+    }
+}
+
+fn concept_op(node: &ConceptNode) -> ReplicationOp {
+    ReplicationOp::AddConcept {
+        id: node.id,
+        content: node.content.to_vec(),
+        vector: node.vector.as_ref().map(|v| v.to_vec()),
+        strength: node.strength,
+        confidence: node.confidence,
+        timestamp: node.created,
+        attributes: node.attributes.clone(),
+        semantic: node.semantic.clone(),
+    }
+}
+
+/// Edges stored on `node` that it is the source of, so each is sent once
+fn edge_ops(node: &ConceptNode) -> impl Iterator<Item = ReplicationOp> + '_ {
+    node.associations
+        .iter()
+        .filter(|record| record.source_id == node.id)
+        .map(ReplicationOp::from_record)
+}
+
+/// Express a whole snapshot as ops: every concept, then every edge once
+pub fn snapshot_ops(snapshot: &GraphSnapshot) -> Vec<ReplicationOp> {
+    let mut ops: Vec<_> = snapshot.concepts.values().map(concept_op).collect();
+    let edges: Vec<_> = snapshot.concepts.values().flat_map(edge_ops).collect();
+    ops.extend(edges);
+    ops
+}
+
+/// Replica configuration
+#[derive(Debug, Clone)]
+pub struct ReplicaConfig {
+    /// Primary server address
+    pub primary_addr: SocketAddr,
+    /// Namespace to replicate (primary's default namespace if `None`)
+    pub namespace: Option<String>,
+    /// Delay between pulls when caught up
+    pub poll_interval: Duration,
+    /// Maximum records per pull
+    pub batch_size: u32,
+    /// Delay before reconnecting after an error
+    pub retry_interval: Duration,
+    /// Largest response frame accepted from the primary
+    pub max_message_size: usize,
+}
+
+impl ReplicaConfig {
+    pub fn new(primary_addr: SocketAddr) -> Self {
+        Self {
+            primary_addr,
+            namespace: None,
+            poll_interval: Duration::from_millis(50),
+            batch_size: 1000,
+            retry_interval: Duration::from_secs(1),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+}
+
+/// Live replication progress, shared with the replica's server for stats
+#[derive(Debug, Default)]
+pub struct ReplicaStatus {
+    applied_sequence: AtomicU64,
+    primary_sequence: AtomicU64,
+    connected: AtomicBool,
+}
+
+impl ReplicaStatus {
+    /// Next sequence the replica will apply
+    pub fn applied_sequence(&self) -> u64 {
+        self.applied_sequence.load(Ordering::Relaxed)
+    }
+
+    /// Primary's sequence as of the last pull
+    pub fn primary_sequence(&self) -> u64 {
+        self.primary_sequence.load(Ordering::Relaxed)
+    }
+
+    /// Records the primary has published that this replica has not applied
+    pub fn lag(&self) -> u64 {
+        self.primary_sequence()
+            .saturating_sub(self.applied_sequence())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+}
+
+/// Background task keeping a local `ConcurrentMemory` in sync with a primary
+pub struct Replica {
+    status: Arc<ReplicaStatus>,
+    handle: JoinHandle<()>,
+}
+
+impl Replica {
+    /// Start replicating into `storage`. Must be called within a Tokio runtime.
+    pub fn start(config: ReplicaConfig, storage: Arc<ConcurrentMemory>) -> Self {
+        let status = Arc::new(ReplicaStatus::default());
+        let task_status = Arc::clone(&status);
+
+        let handle = tokio::spawn(async move {
+            let mut resume_from = None;
+            loop {
+                if let Err(e) = replicate(&config, &storage, &task_status, &mut resume_from).await {
+                    log::warn!(
+                        "Replication from {} interrupted: {}",
+                        config.primary_addr,
+                        e
+                    );
+                }
+                task_status.connected.store(false, Ordering::Relaxed);
+                tokio::time::sleep(config.retry_interval).await;
+            }
+        });
+
+        Self { status, handle }
+    }
+
+    pub fn status(&self) -> &Arc<ReplicaStatus> {
+        &self.status
+    }
+
+    pub fn stop(&self) {
+        self.handle.abort();
+    }
+}
+
+impl Drop for Replica {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// One replication session: snapshot unless `resume_from` holds the next
+/// sequence to pull, then pull until an error occurs
+async fn replicate(
+    config: &ReplicaConfig,
+    storage: &ConcurrentMemory,
+    status: &ReplicaStatus,
+    resume_from: &mut Option<u64>,
+) -> anyhow::Result<()> {
+    let mut stream = TcpStream::connect(config.primary_addr).await?;
+    stream.set_nodelay(true)?;
+    status.connected.store(true, Ordering::Relaxed);
+
+    let mut next = match *resume_from {
+        Some(next) => next,
+        None => bootstrap(config, storage, &mut stream).await?,
+    };
+    *resume_from = Some(next);
+    status.applied_sequence.store(next, Ordering::Relaxed);
+    status.primary_sequence.store(next, Ordering::Relaxed);
+
+    loop {
+        let response = call(
+            &mut stream,
+            &StorageRequest::ReplicationPull {
+                namespace: config.namespace.clone(),
+                from_sequence: next,
+                max_entries: config.batch_size,
+            },
+            config.max_message_size,
+        )
+        .await?;
+
+        match response {
+            StorageResponse::ReplicationBatchOk {
+                records,
+                primary_sequence,
+            } => {
+                status
+                    .primary_sequence
+                    .store(primary_sequence, Ordering::Relaxed);
+                if primary_sequence < next {
+                    // The primary restarted and its log starts over
+                    *resume_from = None;
+                    anyhow::bail!(
+                        "primary sequence {} is behind applied sequence {}, resyncing",
+                        primary_sequence,
+                        next
+                    );
+                }
+                let caught_up = records.is_empty();
+                for record in records {
+                    storage.apply_replicated(record.op)?;
+                    next = record.sequence + 1;
+                    *resume_from = Some(next);
+                }
+                status.applied_sequence.store(next, Ordering::Relaxed);
+                if caught_up {
+                    tokio::time::sleep(config.poll_interval).await;
+                }
+            }
+            StorageResponse::ReplicationResyncRequired { oldest_sequence } => {
+                *resume_from = None;
+                anyhow::bail!(
+                    "fell behind primary log (need {}, oldest retained {}), resyncing",
+                    next,
+                    oldest_sequence
+                );
+            }
+            other => anyhow::bail!("Unexpected pull response: {:?}", other),
+        }
+    }
+}
+
+/// Transfer a full snapshot and swap it in; returns the first sequence to pull
+async fn bootstrap(
+    config: &ReplicaConfig,
+    storage: &ConcurrentMemory,
+    stream: &mut TcpStream,
+) -> anyhow::Result<u64> {
+    let mut request = StorageRequest::ReplicationSnapshot {
+        namespace: config.namespace.clone(),
+        transfer_id: None,
+        cursor: 0,
+    };
+    let mut buffered = Vec::new();
+    loop {
+        match call(stream, &request, config.max_message_size).await? {
+            StorageResponse::ReplicationSnapshotOk {
+                sequence,
+                ops,
+                transfer_id,
+                next_cursor,
+            } => {
+                buffered.extend(ops);
+                match (transfer_id, next_cursor) {
+                    (Some(transfer_id), Some(cursor)) => {
+                        request = StorageRequest::ReplicationSnapshot {
+                            namespace: config.namespace.clone(),
+                            transfer_id: Some(transfer_id),
+                            cursor,
+                        };
+                    }
+                    _ => {
+                        let applied = buffered.len();
+                        storage.replace_replicated(buffered)?;
+                        log::info!(
+                            "Replica bootstrapped from {} ({} ops at sequence {})",
+                            config.primary_addr,
+                            applied,
+                            sequence
+                        );
+                        return Ok(sequence);
+                    }
+                }
+            }
+            other => anyhow::bail!("Unexpected snapshot response: {:?}", other),
+        }
+    }
+}
+
+/// Get current timestamp in microseconds
+fn current_timestamp_us() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
+
+async fn call(
+    stream: &mut TcpStream,
+    request: &StorageRequest,
+    max_message_size: usize,
+) -> anyhow::Result<StorageResponse> {
+    let bytes = rmp_serde::to_vec_named(request)?;
+    stream.write_u32(bytes.len() as u32).await?;
+    stream.write_all(&bytes).await?;
+    stream.flush().await?;
+
+    let len = stream.read_u32().await?;
+    if len as usize > max_message_size {
+        anyhow::bail!("Frame too large: {} bytes (max: {})", len, max_message_size);
+    }
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await?;
+    Ok(rmp_serde::from_slice(&buf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replication_log_eviction() {
+        let log = ReplicationLog::new(3);
+        log.publish((0..5u8).map(|i| ReplicationOp::DeleteConcept {
+            id: ConceptId([i; 16]),
+        }));
+
+        assert_eq!(log.next_sequence(), 5);
+        assert_eq!(log.read_from(0, 10), Err(2));
+
+        let tail = log.read_from(3, 10).unwrap();
+        assert_eq!(
+            tail.iter().map(|r| r.sequence).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert!(log.read_from(5, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_transfer_chunks_fit_and_order_edges_last() {
+        use crate::types::AssociationType;
+
+        let mut snapshot = GraphSnapshot::new(0);
+        for i in 0..50u8 {
+            let mut node = ConceptNode::new(ConceptId([i; 16]), vec![b'x'; 100], None, 1.0, 0.9, 1);
+            if i > 0 {
+                let record = AssociationRecord::new(
+                    ConceptId([i - 1; 16]),
+                    ConceptId([i; 16]),
+                    AssociationType::Semantic,
+                    0.8,
+                );
+                node.add_edge(ConceptId([i - 1; 16]), record);
+            }
+            snapshot.insert_concept(node);
+        }
+        // Edges live on both endpoints; put each on its source as well
+        for i in 1..50u8 {
+            let mut source = snapshot
+                .concepts
+                .get(&ConceptId([i - 1; 16]))
+                .cloned()
+                .unwrap();
+            let record = snapshot.concepts[&ConceptId([i; 16])].associations[0];
+            source.add_edge(ConceptId([i; 16]), record);
+            snapshot.insert_concept(source);
+        }
+        let expected = snapshot_ops(&snapshot).len();
+
+        let log = ReplicationLog::new(10);
+        let transfer = log.start_transfer(Arc::new(snapshot), 7);
+
+        let mut ops = Vec::new();
+        let mut cursor = 0;
+        let mut chunks = 0;
+        loop {
+            let chunk = log.snapshot_chunk(transfer, cursor, 1_000).unwrap();
+            assert_eq!(chunk.sequence, 7);
+            chunks += 1;
+            ops.extend(chunk.ops);
+            match chunk.next_cursor {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        assert!(chunks > 5, "expected several chunks, got {}", chunks);
+        assert_eq!(ops.len(), expected);
+        let first_edge = ops
+            .iter()
+            .position(|op| matches!(op, ReplicationOp::AddAssociation { .. }))
+            .unwrap();
+        assert_eq!(first_edge, 50);
+        assert_eq!(ops.len() - first_edge, 49);
+
+        // The transfer is released with its last chunk
+        assert!(log.snapshot_chunk(transfer, 0, 1_000).is_none());
+    }
+}
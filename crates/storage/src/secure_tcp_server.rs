@@ -3,18 +3,24 @@
 //! Wraps the storage server with production-grade security:
 //! - HMAC/JWT authentication
 //! - TLS 1.3 encryption
+//! - Optional mutual TLS (client certificates verified against a CA bundle)
 //! - Role-based access control
 //! - Audit logging
 
 use crate::auth::{AuthManager, Claims};
-use crate::tcp_server::{StorageRequest, StorageResponse, StorageServer};
-use crate::tls::{is_tls_enabled, TlsConfigBuilder};
+use crate::tcp_server::{
+    drain_clients, negotiate_wire_format, starts_handshake, ChangeSubscriptions, StorageRequest,
+    StorageResponse, StorageServer, WireFormat,
+};
+use crate::tls::{is_tls_enabled, ClientIdentity, TlsConfigBuilder};
 use anyhow::{anyhow, Result};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::signal;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
 use tokio_rustls::server::TlsStream;
 use tracing::{error, info, warn};
 
@@ -58,6 +64,9 @@ impl SecureStorageServer {
     }
 
     /// Start secure TCP server with a custom shutdown signal (test-friendly)
+    ///
+    /// Shutdown drains connections within the inner server's drain timeout and
+    /// flushes storage, like [`StorageServer::serve_with_shutdown`].
     pub async fn serve_with_shutdown<F>(
         self: Arc<Self>,
         addr: SocketAddr,
@@ -82,14 +91,18 @@ impl SecureStorageServer {
 
         tokio::pin!(shutdown);
 
+        let (drain_tx, drain_rx) = watch::channel(false);
+        let mut clients = JoinSet::new();
+
         loop {
             tokio::select! {
                 result = listener.accept() => {
                     match result {
                         Ok((stream, peer_addr)) => {
                             let server = self.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = server.handle_client(stream, peer_addr).await {
+                            let drain_rx = drain_rx.clone();
+                            clients.spawn(async move {
+                                if let Err(e) = server.handle_client(stream, peer_addr, drain_rx).await {
                                     error!("Client error ({}): {}", peer_addr, e);
                                 }
                             });
@@ -99,18 +112,43 @@ impl SecureStorageServer {
                         }
                     }
                 }
+                Some(_) = clients.join_next(), if !clients.is_empty() => {}
                 _ = &mut shutdown => {
-                    info!("Shutdown signal received");
                     break;
                 }
             }
         }
+        drop(listener);
+
+        let drain_timeout = self.inner.drain_timeout();
+        let in_flight = clients.len();
+        info!(
+            "Shutdown signal received, draining {} connection(s) (timeout {:?})",
+            in_flight, drain_timeout
+        );
+        let _ = drain_tx.send(true);
+
+        let cancelled = drain_clients(&mut clients, drain_timeout).await;
+        info!(
+            "Drained {} connection(s), force-cancelled {}",
+            in_flight - cancelled,
+            cancelled
+        );
+
+        if let Err(e) = self.inner.flush_all() {
+            error!("Flush error: {:?}", e);
+        }
 
         Ok(())
     }
 
     /// Handle single client connection with TLS and authentication
-    async fn handle_client(&self, stream: TcpStream, peer_addr: SocketAddr) -> Result<()> {
+    async fn handle_client(
+        &self,
+        stream: TcpStream,
+        peer_addr: SocketAddr,
+        drain: watch::Receiver<bool>,
+    ) -> Result<()> {
         info!("Client connecting: {}", peer_addr);
 
         // TLS handshake if enabled
@@ -121,10 +159,10 @@ impl SecureStorageServer {
                     .await
                     .map_err(|e| anyhow!("TLS handshake failed: {}", e))?;
                 info!("✅ TLS handshake complete: {}", peer_addr);
-                self.handle_authenticated_client(tls_stream, peer_addr)
+                self.handle_authenticated_client(tls_stream, peer_addr, drain)
                     .await
             }
-            None => self.handle_plain_client(stream, peer_addr).await,
+            None => self.handle_plain_client(stream, peer_addr, drain).await,
         }
     }
 
@@ -133,8 +171,16 @@ impl SecureStorageServer {
         &self,
         mut stream: TlsStream<TcpStream>,
         peer_addr: SocketAddr,
+        drain: watch::Receiver<bool>,
     ) -> Result<()> {
         stream.get_mut().0.set_nodelay(true)?;
+        let client = ClientIdentity::from_connection(stream.get_ref().1);
+        if let Some(ref client) = client {
+            info!(
+                "✅ Client certificate verified: {} ({})",
+                client.common_name, peer_addr
+            );
+        }
 
         // 1. Authentication handshake
         let claims = if let Some(ref _auth) = self.auth_manager {
@@ -147,8 +193,14 @@ impl SecureStorageServer {
         };
 
         // 2. Process authenticated requests
-        self.process_requests(&mut stream, peer_addr, claims.as_ref())
-            .await?;
+        self.process_requests(
+            &mut stream,
+            peer_addr,
+            claims.as_ref(),
+            client.as_ref(),
+            drain,
+        )
+        .await?;
 
         Ok(())
     }
@@ -158,6 +210,7 @@ impl SecureStorageServer {
         &self,
         mut stream: TcpStream,
         peer_addr: SocketAddr,
+        drain: watch::Receiver<bool>,
     ) -> Result<()> {
         stream.set_nodelay(true)?;
 
@@ -172,7 +225,7 @@ impl SecureStorageServer {
         };
 
         // Process authenticated requests
-        self.process_requests(&mut stream, peer_addr, claims.as_ref())
+        self.process_requests(&mut stream, peer_addr, claims.as_ref(), None, drain)
             .await?;
 
         Ok(())
@@ -213,17 +266,56 @@ impl SecureStorageServer {
         Ok(claims)
     }
 
-    /// Process authenticated storage requests
+    /// Process authenticated storage requests until the client leaves or `drain` is set
     async fn process_requests<S>(
         &self,
         stream: &mut S,
         peer_addr: SocketAddr,
         claims: Option<&Claims>,
+        client: Option<&ClientIdentity>,
+        mut drain: watch::Receiver<bool>,
     ) -> Result<()>
     where
         S: AsyncReadExt + AsyncWriteExt + Unpin,
     {
+        // Verified identity the requests count against: the client
+        // certificate, else the token subject
+        let identity = client
+            .map(ClientIdentity::caller)
+            .or_else(|| claims.map(|claims| format!("sub:{}", claims.sub)));
+
+        // Buffered so waiting for the next request can be interrupted by a
+        // notification without losing bytes already read
+        let mut stream = BufReader::new(stream);
+        let stream = &mut stream;
+        let mut subscriptions = ChangeSubscriptions::default();
+        // Frame encoding; a client may switch it with a Content-Type line
+        let mut wire_format = WireFormat::default();
+
         loop {
+            if *drain.borrow() {
+                break;
+            }
+            let filled = tokio::select! {
+                biased;
+                _ = drain.changed() => break,
+                change = subscriptions.next() => {
+                    let notification = ChangeSubscriptions::notification(change);
+                    self.send_response(stream, wire_format, &notification).await?;
+                    continue;
+                }
+                filled = stream.fill_buf() => filled,
+            };
+            let filled = filled?;
+            if filled.is_empty() {
+                info!("Client disconnected: {}", peer_addr);
+                break;
+            }
+            if starts_handshake(filled) {
+                negotiate_wire_format(stream, peer_addr, &mut wire_format).await?;
+                continue;
+            }
+
             // Read request length
             let len = match stream.read_u32().await {
                 Ok(len) => len,
@@ -234,10 +326,20 @@ impl SecureStorageServer {
                 Err(e) => return Err(e.into()),
             };
 
-            // Validate message size
-            const MAX_MESSAGE_SIZE: usize = 100 * 1024 * 1024;
-            if len as usize > MAX_MESSAGE_SIZE {
-                self.send_error(stream, "Message too large").await?;
+            // Validate message size; the payload is skipped without buffering it
+            let max_message_size = self.inner.max_message_size();
+            if len as usize > max_message_size {
+                tokio::io::copy(&mut (&mut *stream).take(len as u64), &mut tokio::io::sink())
+                    .await?;
+                self.send_error(
+                    stream,
+                    wire_format,
+                    &format!(
+                        "Message too large: {} bytes (max: {})",
+                        len, max_message_size
+                    ),
+                )
+                .await?;
                 continue;
             }
 
@@ -246,47 +348,82 @@ impl SecureStorageServer {
             stream.read_exact(&mut buf).await?;
 
             // Deserialize request
-            let request: StorageRequest = rmp_serde::from_slice(&buf)
+            let request = wire_format
+                .decode_request(&buf)
                 .map_err(|e| anyhow!("Deserialization failed: {}", e))?;
 
             // Authorization check
             if let Some(claims) = claims {
                 if let Err(e) = self.authorize_request(claims, &request) {
                     warn!("Authorization failed: {} ({})", e, peer_addr);
-                    self.send_error(stream, &format!("Unauthorized: {}", e))
+                    self.send_error(stream, wire_format, &format!("Unauthorized: {}", e))
                         .await?;
                     continue;
                 }
             }
 
-            // Forward to inner server
-            let response = self.inner.handle_request(request).await;
+            // Forward to inner server; subscriptions belong to this connection
+            let response = match request {
+                StorageRequest::SubscribeChanges { pattern } => {
+                    self.inner.subscribe_changes(&mut subscriptions, pattern)
+                }
+                request => {
+                    self.inner
+                        .handle_request_for(request, peer_addr, identity.as_deref())
+                        .await
+                }
+            };
 
             // Audit log (if needed)
             if matches!(response, StorageResponse::Error { .. }) {
                 warn!("Request failed: {:?} ({})", response, peer_addr);
             }
 
-            // Send response
-            let response_bytes = rmp_serde::to_vec_named(&response)
-                .map_err(|e| anyhow!("Serialization failed: {}", e))?;
-
-            stream.write_u32(response_bytes.len() as u32).await?;
-            stream.write_all(&response_bytes).await?;
-            stream.flush().await?;
+            self.send_response(stream, wire_format, &response).await?;
         }
 
         Ok(())
     }
 
+    /// Send one response frame
+    async fn send_response<S>(
+        &self,
+        stream: &mut S,
+        wire_format: WireFormat,
+        response: &StorageResponse,
+    ) -> Result<()>
+    where
+        S: AsyncWriteExt + Unpin,
+    {
+        let response_bytes = wire_format.encode_response(response);
+
+        stream.write_u32(response_bytes.len() as u32).await?;
+        stream.write_all(&response_bytes).await?;
+        stream.flush().await?;
+
+        Ok(())
+    }
+
     /// Check if claims authorize request
     fn authorize_request(&self, claims: &Claims, request: &StorageRequest) -> Result<()> {
+        let (scope, required) = (claims.scope(), request.required_scope());
+        if !scope.allows(required) {
+            return Err(anyhow!(
+                "insufficient scope: {} has {:?}, request requires {:?}",
+                claims.sub,
+                scope,
+                required
+            ));
+        }
+
         let operation = match request {
             StorageRequest::LearnConceptV2 { .. }
             | StorageRequest::LearnBatch { .. }
             | StorageRequest::LearnWithEmbedding { .. }
             | StorageRequest::LearnConcept { .. }
-            | StorageRequest::LearnAssociation { .. } => "write",
+            | StorageRequest::LearnAssociation { .. }
+            | StorageRequest::UpdateConcept { .. }
+            | StorageRequest::Transaction { .. } => "write",
 
             StorageRequest::QueryConcept { .. }
             | StorageRequest::GetNeighbors { .. }
@@ -297,10 +434,19 @@ impl SecureStorageServer {
             | StorageRequest::FindContradictions { .. }
             | StorageRequest::QueryBySemantic { .. }
             | StorageRequest::VectorSearch { .. }
+            | StorageRequest::VectorSearchBatch { .. }
             | StorageRequest::TextSearch { .. }
             | StorageRequest::ListRecent { .. }
+            | StorageRequest::QueryByMetadata { .. }
             | StorageRequest::GetStats { .. }
+            | StorageRequest::TopAccessed { .. }
+            | StorageRequest::ColdestConcepts { .. }
+            | StorageRequest::GetGaps { .. }
+            | StorageRequest::ReplicationSnapshot { .. }
+            | StorageRequest::ReplicationPull { .. }
             | StorageRequest::HealthCheck
+            | StorageRequest::Handshake { .. }
+            | StorageRequest::SubscribeChanges { .. }
             | StorageRequest::ListSubscriptions
             | StorageRequest::ListGoals { .. }
             | StorageRequest::GetAutonomyStats => "read",
@@ -313,7 +459,8 @@ impl SecureStorageServer {
             StorageRequest::Subscribe { .. }
             | StorageRequest::Unsubscribe { .. }
             | StorageRequest::CreateGoal { .. }
-            | StorageRequest::ProvideFeedback { .. } => "write",
+            | StorageRequest::ProvideFeedback { .. }
+            | StorageRequest::Reindex { .. } => "write",
         };
 
         if !claims.can_perform(operation) {
@@ -328,20 +475,19 @@ impl SecureStorageServer {
     }
 
     /// Send error response
-    async fn send_error<S>(&self, stream: &mut S, message: &str) -> Result<()>
+    async fn send_error<S>(
+        &self,
+        stream: &mut S,
+        wire_format: WireFormat,
+        message: &str,
+    ) -> Result<()>
     where
         S: AsyncWriteExt + Unpin,
     {
         let response = StorageResponse::Error {
             message: message.to_string(),
         };
-
-        let response_bytes = rmp_serde::to_vec_named(&response)?;
-        stream.write_u32(response_bytes.len() as u32).await?;
-        stream.write_all(&response_bytes).await?;
-        stream.flush().await?;
-
-        Ok(())
+        self.send_response(stream, wire_format, &response).await
     }
 }
 
@@ -181,6 +181,10 @@ impl Segment {
 
         writer.write_all(record_bytes)?;
 
+        // Concept records are contiguous, starting wherever the first one lands
+        if self.header.concept_count == 0 {
+            self.header.concept_offset = offset;
+        }
         self.write_pos += record_bytes.len() as u64;
         self.header.concept_count += 1;
 
@@ -199,6 +203,9 @@ impl Segment {
 
         writer.write_all(record_bytes)?;
 
+        if self.header.association_count == 0 {
+            self.header.association_offset = offset;
+        }
         self.write_pos += record_bytes.len() as u64;
         self.header.association_count += 1;
 
@@ -373,6 +380,7 @@ impl Segment {
             writer.write_all(header_bytes)?;
             writer.flush()?;
             writer.get_ref().sync_all()?;
+            writer.seek(SeekFrom::Start(self.write_pos))?;
         }
         Ok(())
     }
@@ -10,7 +10,7 @@ pub mod query;
 pub mod types;
 
 pub use analyzer::SemanticAnalyzer;
-pub use pathfinding::{SemanticPath, SemanticPathFinder};
+pub use pathfinding::{PathSearchResult, SemanticPath, SemanticPathFinder};
 pub use query::{
     queries, CausalFilter, SemanticFilter, SemanticQuery, SortOrder, TemporalConstraint,
 };
@@ -9,6 +9,7 @@ use crate::read_view::GraphSnapshot;
 use crate::types::ConceptId;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Result of semantic pathfinding
 #[derive(Debug, Clone)]
@@ -49,6 +50,19 @@ impl SemanticPath {
     }
 }
 
+/// Outcome of a budgeted path search
+#[derive(Debug, Clone)]
+pub struct PathSearchResult {
+    /// Paths found before the search completed or a budget was hit
+    pub paths: Vec<SemanticPath>,
+
+    /// True if the node budget or timeout stopped the search early
+    pub truncated: bool,
+
+    /// Number of nodes expanded during the search
+    pub nodes_visited: usize,
+}
+
 /// Semantic-aware pathfinding engine
 pub struct SemanticPathFinder {
     /// Maximum path depth
@@ -56,6 +70,12 @@ pub struct SemanticPathFinder {
 
     /// Maximum paths to find
     max_paths: usize,
+
+    /// Maximum nodes expanded before the search gives up (None = unbounded)
+    max_nodes_visited: Option<usize>,
+
+    /// Wall-clock budget for a single search (None = unbounded)
+    timeout: Option<Duration>,
 }
 
 impl SemanticPathFinder {
@@ -63,9 +83,23 @@ impl SemanticPathFinder {
         Self {
             max_depth,
             max_paths,
+            max_nodes_visited: None,
+            timeout: None,
         }
     }
 
+    /// Cap the number of nodes expanded per search
+    pub fn with_max_nodes_visited(mut self, max_nodes: usize) -> Self {
+        self.max_nodes_visited = Some(max_nodes);
+        self
+    }
+
+    /// Cap the wall-clock time spent per search
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     /// Find paths with semantic filtering
     pub fn find_paths_filtered(
         &self,
@@ -74,15 +108,47 @@ impl SemanticPathFinder {
         end: ConceptId,
         filter: &SemanticFilter,
     ) -> Vec<SemanticPath> {
+        self.find_paths_bounded(snapshot, start, end, filter).paths
+    }
+
+    /// Find paths with semantic filtering, honouring the node and time budgets.
+    ///
+    /// When a budget is exhausted the paths found so far are returned with
+    /// `truncated` set, instead of continuing an unbounded traversal.
+    pub fn find_paths_bounded(
+        &self,
+        snapshot: Arc<GraphSnapshot>,
+        start: ConceptId,
+        end: ConceptId,
+        filter: &SemanticFilter,
+    ) -> PathSearchResult {
+        let started = Instant::now();
         let mut paths = Vec::new();
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
+        let mut nodes_visited = 0usize;
+        let mut truncated = false;
 
         // Initialize with start node
         queue.push_back((start, vec![start], 0));
         visited.insert(start);
 
         while let Some((current, path, depth)) = queue.pop_front() {
+            // Enforce work budgets before expanding another node
+            if let Some(max_nodes) = self.max_nodes_visited {
+                if nodes_visited >= max_nodes {
+                    truncated = true;
+                    break;
+                }
+            }
+            if let Some(timeout) = self.timeout {
+                if started.elapsed() >= timeout {
+                    truncated = true;
+                    break;
+                }
+            }
+            nodes_visited += 1;
+
             // Check if we reached the destination
             if current == end {
                 let semantic_path = self.analyze_path(&snapshot, &path);
@@ -128,7 +194,11 @@ impl SemanticPathFinder {
             }
         }
 
-        paths
+        PathSearchResult {
+            paths,
+            truncated,
+            nodes_visited,
+        }
     }
 
     /// Find temporal chain (concepts ordered by time)
@@ -422,4 +492,50 @@ mod tests {
         assert_eq!(paths[0].len(), 3);
         assert!(paths[0].domains.contains(&DomainContext::Medical));
     }
+
+    #[test]
+    fn test_node_budget_truncates_dense_graph() {
+        // Fully connected graph with an unreachable target forces a full sweep
+        let mut snapshot = GraphSnapshot::new(0);
+        let ids: Vec<ConceptId> = (0..200u32)
+            .map(|i| {
+                let mut bytes = [0u8; 16];
+                bytes[..4].copy_from_slice(&i.to_le_bytes());
+                ConceptId::from_bytes(bytes)
+            })
+            .collect();
+
+        for &id in &ids {
+            let mut node = ConceptNode::new(id, b"dense".to_vec(), None, 1.0, 1.0, 1000);
+            node.neighbors = ids.iter().copied().filter(|n| *n != id).collect();
+            snapshot.concepts.insert(id, node);
+        }
+        snapshot.update_stats();
+
+        let snapshot = Arc::new(snapshot);
+        let missing = ConceptId::from_bytes([0xFF; 16]);
+        let filter = SemanticFilter::new();
+
+        let unbounded = SemanticPathFinder::new(10, 10).find_paths_bounded(
+            Arc::clone(&snapshot),
+            ids[0],
+            missing,
+            &filter,
+        );
+        assert!(!unbounded.truncated);
+        assert_eq!(unbounded.nodes_visited, ids.len());
+
+        let bounded = SemanticPathFinder::new(10, 10)
+            .with_max_nodes_visited(25)
+            .find_paths_bounded(Arc::clone(&snapshot), ids[0], missing, &filter);
+        assert!(bounded.truncated);
+        assert_eq!(bounded.nodes_visited, 25);
+        assert!(bounded.paths.is_empty());
+
+        let timed = SemanticPathFinder::new(10, 10)
+            .with_timeout(Duration::ZERO)
+            .find_paths_bounded(snapshot, ids[0], missing, &filter);
+        assert!(timed.truncated);
+        assert_eq!(timed.nodes_visited, 0);
+    }
 }
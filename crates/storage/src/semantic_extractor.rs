@@ -14,6 +14,7 @@
 //! Dependencies: None (uses existing HA embedding service)
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
@@ -21,6 +22,33 @@ use tracing::{debug, info, warn};
 use crate::embedding_provider::EmbeddingProvider;
 use crate::types::AssociationType;
 
+/// Default similarity below which no association is extracted
+pub const DEFAULT_SIMILARITY_FLOOR: f32 = 0.65;
+
+/// How a sentence's similarity to a relation type becomes edge confidence
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum SimilarityMapping {
+    /// Confidence equals the cosine similarity
+    #[default]
+    Linear,
+    /// Logistic curve centred halfway between the floor and 1.0; higher
+    /// steepness pushes confidences towards 0 or 1
+    Sigmoid { steepness: f32 },
+}
+
+impl SimilarityMapping {
+    /// Edge confidence for `similarity` (at or above `floor`)
+    pub fn confidence(&self, similarity: f32, floor: f32) -> f32 {
+        match self {
+            SimilarityMapping::Linear => similarity,
+            SimilarityMapping::Sigmoid { steepness } => {
+                let midpoint = (floor + 1.0) / 2.0;
+                1.0 / (1.0 + (-steepness * (similarity - midpoint)).exp())
+            }
+        }
+    }
+}
+
 /// Semantic association extractor using embeddings
 pub struct SemanticExtractor {
     /// Embedding client (HA service or local provider)
@@ -120,7 +148,7 @@ impl SemanticExtractor {
         Ok(Self {
             embedding_client,
             relation_embeddings,
-            similarity_threshold: 0.65, // Tunable via env var
+            similarity_threshold: DEFAULT_SIMILARITY_FLOOR,
             min_entity_length: 3,
         })
     }
@@ -194,6 +222,20 @@ impl SemanticExtractor {
     /// Returns a vector of associations with confidence scores.
     /// Uses batched embedding generation for efficiency.
     pub async fn extract(&self, text: &str) -> Result<Vec<SemanticAssociation>> {
+        self.extract_with(text, self.similarity_threshold, SimilarityMapping::Linear)
+            .await
+    }
+
+    /// Extract associations with a custom similarity floor and confidence mapping
+    ///
+    /// Sentences less similar than `floor` to every relation type yield no
+    /// associations; the rest get `mapping.confidence(similarity, floor)`.
+    pub async fn extract_with(
+        &self,
+        text: &str,
+        floor: f32,
+        mapping: SimilarityMapping,
+    ) -> Result<Vec<SemanticAssociation>> {
         if text.trim().is_empty() {
             return Ok(Vec::new());
         }
@@ -216,10 +258,11 @@ impl SemanticExtractor {
         for (sentence, emb_opt) in sentences.iter().zip(sentence_embeddings) {
             if let Some(emb) = emb_opt {
                 // Classify relation type by similarity
-                let (assoc_type, confidence) = self.classify_relation(&emb);
+                let (assoc_type, similarity) = self.classify_relation(&emb);
 
                 // Only process if above threshold
-                if confidence >= self.similarity_threshold {
+                if similarity >= floor {
+                    let confidence = mapping.confidence(similarity, floor);
                     // Extract entities from sentence
                     let entities = self.extract_entities(sentence);
 
@@ -376,6 +419,15 @@ mod tests {
         assert!(cosine_similarity(&v5, &v6) < 0.1); // Should be clamped to 0
     }
 
+    #[test]
+    fn test_sigmoid_mapping() {
+        let mapping = SimilarityMapping::Sigmoid { steepness: 20.0 };
+        assert!((mapping.confidence(0.8, 0.6) - 0.5).abs() < 1e-6);
+        assert!(mapping.confidence(0.65, 0.6) < 0.2);
+        assert!(mapping.confidence(0.95, 0.6) > 0.8);
+        assert_eq!(SimilarityMapping::Linear.confidence(0.7, 0.6), 0.7);
+    }
+
     #[test]
     fn test_split_sentences() {
         use crate::embedding_client::HttpEmbeddingClient;
@@ -1,4 +1,6 @@
 use crate::concurrent_memory::{ConcurrentConfig, ConcurrentMemory, ConcurrentStats};
+use crate::read_view::DeadlineExceeded;
+use crate::storage_pool::StoragePool;
 use crate::transaction::{TransactionCoordinator, TxnOperation};
 use crate::types::ConceptId;
 /// Sharded Storage - Horizontal Scaling Beyond 10M Concepts
@@ -20,6 +22,7 @@ use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 const DEFAULT_NUM_SHARDS: u32 = 16;
 
@@ -54,6 +57,8 @@ pub struct ShardedStorage {
     shard_map: Arc<RwLock<ShardMap>>,
     /// 🔥 NEW: 2PC transaction coordinator for atomic cross-shard operations
     txn_coordinator: Arc<TransactionCoordinator>,
+    /// Pool for parallel fan-out across shards
+    pool: StoragePool,
 }
 
 /// Shard map for routing decisions
@@ -109,11 +114,14 @@ impl ShardedStorage {
         // Initialize 2PC transaction coordinator (5 second timeout)
         let txn_coordinator = Arc::new(TransactionCoordinator::new(5));
 
+        let pool = StoragePool::shared(config.shard_config.storage_threads);
+
         Ok(Self {
             config,
             shards,
             shard_map: Arc::new(RwLock::new(shard_map)),
             txn_coordinator,
+            pool,
         })
     }
 
@@ -179,11 +187,13 @@ impl ShardedStorage {
     /// Clear all shards in parallel
     pub fn clear(&self) -> Result<()> {
         use rayon::prelude::*;
-        self.shards.par_iter().try_for_each(|shard| {
-            shard
-                .clear()
-                .map(|_| ())
-                .map_err(|e| anyhow::anyhow!("Shard clear failed: {:?}", e))
+        self.pool.install(|| {
+            self.shards.par_iter().try_for_each(|shard| {
+                shard
+                    .clear()
+                    .map(|_| ())
+                    .map_err(|e| anyhow::anyhow!("Shard clear failed: {:?}", e))
+            })
         })
     }
 
@@ -307,6 +317,17 @@ impl ShardedStorage {
         snapshot.get_neighbors(&id)
     }
 
+    /// BFS path search that follows edges from one shard into another
+    pub fn find_path_until(
+        &self,
+        start: ConceptId,
+        end: ConceptId,
+        max_depth: usize,
+        deadline: Option<Instant>,
+    ) -> Result<Option<CrossShardPath>, DeadlineExceeded> {
+        find_path_across_shards(&self.shards, start, end, max_depth, deadline)
+    }
+
     /// Semantic search across all shards (parallel)
     pub fn semantic_search(&self, query_vector: Vec<f32>, top_k: usize) -> Vec<(ConceptId, f32)> {
         use rayon::prelude::*;
@@ -314,15 +335,16 @@ impl ShardedStorage {
         // Query all shards in parallel
         let per_shard_k = (top_k / self.config.num_shards as usize).max(10);
 
-        let mut all_results: Vec<(ConceptId, f32)> = self
-            .shards
-            .par_iter()
-            .flat_map(|shard| {
-                shard
-                    .semantic_search(query_vector.clone(), per_shard_k)
-                    .unwrap_or_default()
-            })
-            .collect();
+        let mut all_results: Vec<(ConceptId, f32)> = self.pool.install(|| {
+            self.shards
+                .par_iter()
+                .flat_map(|shard| {
+                    shard
+                        .semantic_search(query_vector.clone(), per_shard_k)
+                        .unwrap_or_default()
+                })
+                .collect()
+        });
 
         // Sort by similarity and take top_k
         all_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
@@ -335,10 +357,12 @@ impl ShardedStorage {
     pub fn flush(&self) -> Result<()> {
         use rayon::prelude::*;
 
-        self.shards.par_iter().try_for_each(|shard| {
-            shard
-                .flush()
-                .map_err(|e| anyhow::anyhow!("Shard flush failed: {:?}", e))
+        self.pool.install(|| {
+            self.shards.par_iter().try_for_each(|shard| {
+                shard
+                    .flush()
+                    .map_err(|e| anyhow::anyhow!("Shard flush failed: {:?}", e))
+            })
         })
     }
 
@@ -366,6 +390,118 @@ impl ShardedStorage {
     }
 }
 
+/// Path stitched together from edges in several shards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossShardPath {
+    /// Concepts from start to end
+    pub concepts: Vec<ConceptId>,
+    /// Index of the shard each concept was visited in
+    pub shards: Vec<usize>,
+}
+
+impl CrossShardPath {
+    /// Positions in `concepts` where the path enters a different shard
+    pub fn boundaries(&self) -> Vec<usize> {
+        (1..self.shards.len())
+            .filter(|&i| self.shards[i] != self.shards[i - 1])
+            .collect()
+    }
+}
+
+/// BFS over the union of `shards`' graphs
+///
+/// A concept's edges may be split across shards (an association between
+/// concepts in different shards is stored on each side), so every shard
+/// holding the concept is expanded. One visited set spans all shards, which
+/// keeps cycles that bounce between shards from looping, and `max_depth`
+/// bounds the whole path rather than each shard's part of it.
+pub fn find_path_across_shards(
+    shards: &[Arc<ConcurrentMemory>],
+    start: ConceptId,
+    end: ConceptId,
+    max_depth: usize,
+    deadline: Option<Instant>,
+) -> Result<Option<CrossShardPath>, DeadlineExceeded> {
+    use std::collections::VecDeque;
+
+    let snapshots: Vec<_> = shards.iter().map(|shard| shard.get_snapshot()).collect();
+    // Shard a concept is visited in: the one we reached it from, if it lives there
+    let home = |id: &ConceptId, from: usize| {
+        if snapshots[from].contains(id) {
+            Some(from)
+        } else {
+            snapshots.iter().position(|snapshot| snapshot.contains(id))
+        }
+    };
+
+    let Some(start_shard) = snapshots
+        .iter()
+        .position(|snapshot| snapshot.contains(&start))
+    else {
+        return Ok(None);
+    };
+    if start == end {
+        return Ok(Some(CrossShardPath {
+            concepts: vec![start],
+            shards: vec![start_shard],
+        }));
+    }
+
+    // concept -> (previous concept, shard it was visited in)
+    let mut visited = HashMap::new();
+    let mut queue = VecDeque::new();
+    visited.insert(start, (None, start_shard));
+    queue.push_back((start, 0));
+
+    let mut expanded = 0usize;
+    while let Some((current, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+
+        expanded += 1;
+        if expanded.is_multiple_of(256) && deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(DeadlineExceeded);
+        }
+
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            let Some(node) = snapshot.concepts.get(&current) else {
+                continue;
+            };
+            for &neighbor in &node.neighbors {
+                if visited.contains_key(&neighbor) {
+                    continue;
+                }
+                let Some(shard) = home(&neighbor, index) else {
+                    continue;
+                };
+                visited.insert(neighbor, (Some(current), shard));
+
+                if neighbor == end {
+                    let mut concepts = vec![neighbor];
+                    let mut shards = vec![shard];
+                    let mut backtrack = current;
+                    while let Some(&(prev, shard)) = visited.get(&backtrack) {
+                        concepts.push(backtrack);
+                        shards.push(shard);
+                        match prev {
+                            Some(prev) => backtrack = prev,
+                            None => break,
+                        }
+                    }
+                    concepts.reverse();
+                    shards.reverse();
+                    return Ok(Some(CrossShardPath { concepts, shards }));
+                }
+
+                queue.push_back((neighbor, depth + 1));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 /// Aggregated statistics across all shards
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregatedStats {
@@ -380,6 +516,7 @@ pub struct AggregatedStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::AssociationType;
     use tempfile::TempDir;
 
     #[test]
@@ -476,4 +613,65 @@ mod tests {
         assert_eq!(results.len(), 10);
         assert!(results[0].1 > 0.0); // Should have similarity scores
     }
+
+    #[test]
+    fn test_find_path_crosses_shards() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = ShardedStorage::new(ShardConfig {
+            num_shards: 2,
+            base_path: temp_dir.path().to_path_buf(),
+            shard_config: ConcurrentConfig {
+                storage_path: PathBuf::from("will_be_overridden"),
+                ..Default::default()
+            },
+        })
+        .unwrap();
+
+        // A, B and an isolated E in one shard; C and D in the other
+        let ids_in = |shard: u32, n: usize| -> Vec<ConceptId> {
+            (0..=255u8)
+                .map(|i| ConceptId([i; 16]))
+                .filter(|id| storage.get_shard_id(*id) == shard)
+                .take(n)
+                .collect()
+        };
+        let (first, second) = (ids_in(0, 3), ids_in(1, 2));
+        let [a, b, e] = [first[0], first[1], first[2]];
+        let [c, d] = [second[0], second[1]];
+        for id in [a, b, c, d, e] {
+            storage
+                .learn_concept(id, id.0.to_vec(), None, 1.0, 0.9, HashMap::new())
+                .unwrap();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        for (source, target) in [(a, b), (b, c), (c, d)] {
+            storage
+                .create_association(source, target, AssociationType::Semantic, 0.9)
+                .unwrap();
+        }
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        let path = loop {
+            if let Some(path) = storage.find_path_until(a, d, 6, None).unwrap() {
+                break path;
+            }
+            assert!(Instant::now() < deadline, "path never appeared");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert_eq!(path.concepts, vec![a, b, c, d]);
+        assert_eq!(path.shards, vec![0, 0, 1, 1]);
+        assert_eq!(path.boundaries(), vec![2]);
+        assert_eq!(storage.find_path_until(a, d, 2, None).unwrap(), None);
+
+        // Close the loop D -> A so the graph cycles through both shards; a
+        // search for the unreachable E must still finish
+        storage
+            .create_association(d, a, AssociationType::Semantic, 0.9)
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(storage.find_path_until(b, e, 64, None).unwrap(), None);
+        let path = storage.find_path_until(a, d, 6, None).unwrap().unwrap();
+        assert_eq!(path.concepts, vec![a, d]);
+        assert_eq!(path.boundaries(), vec![1]);
+    }
 }
@@ -0,0 +1,122 @@
+//! Thread pool for CPU-heavy storage work
+//!
+//! Path finding, shard fan-out and HNSW rebuilds run on rayon. By default
+//! that is rayon's global pool; with `ConcurrentConfig::storage_threads` set
+//! they run on a dedicated pool instead, so operators can cap how much CPU
+//! storage takes on a shared machine. Pools are shared per thread count, so
+//! every namespace and shard of a process uses the same one.
+
+use parking_lot::Mutex;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// Handle to the pool storage parallelism runs on
+#[derive(Clone, Default)]
+pub struct StoragePool {
+    /// `None` uses rayon's global pool
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl StoragePool {
+    /// Rayon's global pool
+    pub fn global() -> Self {
+        Self::default()
+    }
+
+    /// Dedicated pool with `num_threads` threads, shared process-wide
+    ///
+    /// `0` selects the global pool, as does failing to start the threads.
+    pub fn shared(num_threads: usize) -> Self {
+        if num_threads == 0 {
+            return Self::global();
+        }
+
+        static POOLS: OnceLock<Mutex<HashMap<usize, Arc<ThreadPool>>>> = OnceLock::new();
+        let mut pools = POOLS.get_or_init(Default::default).lock();
+        if let Some(pool) = pools.get(&num_threads) {
+            return Self {
+                pool: Some(Arc::clone(pool)),
+            };
+        }
+
+        match ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("sutra-storage-{}", i))
+            .build()
+        {
+            Ok(pool) => {
+                let pool = Arc::new(pool);
+                pools.insert(num_threads, Arc::clone(&pool));
+                Self { pool: Some(pool) }
+            }
+            Err(e) => {
+                log::warn!(
+                    "⚠️ Failed to start {}-thread storage pool, using the global pool: {}",
+                    num_threads,
+                    e
+                );
+                Self::global()
+            }
+        }
+    }
+
+    /// Threads available to parallel storage work
+    pub fn num_threads(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    /// Run `op` so that rayon parallelism inside it uses this pool
+    pub fn install<R, F>(&self, op: F) -> R
+    where
+        F: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match &self.pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Run `op` in the background on this pool
+    pub fn spawn<F>(&self, op: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        match &self.pool {
+            Some(pool) => pool.spawn(op),
+            None => rayon::spawn(op),
+        }
+    }
+}
+
+impl std::fmt::Debug for StoragePool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoragePool")
+            .field("dedicated", &self.pool.is_some())
+            .field("num_threads", &self.num_threads())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_pools_are_reused() {
+        let a = StoragePool::shared(2);
+        let b = StoragePool::shared(2);
+        assert_eq!(a.num_threads(), 2);
+        assert!(Arc::ptr_eq(
+            a.pool.as_ref().unwrap(),
+            b.pool.as_ref().unwrap()
+        ));
+
+        assert!(StoragePool::shared(0).pool.is_none());
+        assert_eq!(a.install(rayon::current_num_threads), 2);
+    }
+}
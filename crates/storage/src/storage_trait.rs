@@ -54,6 +54,12 @@ pub trait LearningStorage {
     fn vector_search(&self, vector: &[f32], k: usize, ef_search: usize) -> Vec<(ConceptId, f32)>;
 }
 
+/// Wrap a write log error so callers can still downcast to it
+fn write_log_error(e: crate::write_log::WriteLogError) -> anyhow::Error {
+    let context = format!("WriteLog error: {:?}", e);
+    anyhow::Error::new(e).context(context)
+}
+
 // Implement for ConcurrentMemory
 impl LearningStorage for crate::concurrent_memory::ConcurrentMemory {
     fn learn_concept(
@@ -66,7 +72,7 @@ impl LearningStorage for crate::concurrent_memory::ConcurrentMemory {
         attributes: std::collections::HashMap<String, String>,
     ) -> Result<u64> {
         self.learn_concept(id, content, vector, strength, confidence, attributes)
-            .map_err(|e| anyhow::anyhow!("WriteLog error: {:?}", e))
+            .map_err(write_log_error)
     }
 
     fn learn_association(
@@ -77,7 +83,7 @@ impl LearningStorage for crate::concurrent_memory::ConcurrentMemory {
         confidence: f32,
     ) -> Result<u64> {
         self.learn_association(source, target, assoc_type, confidence)
-            .map_err(|e| anyhow::anyhow!("WriteLog error: {:?}", e))
+            .map_err(write_log_error)
     }
 
     fn learn_concept_with_semantic(
@@ -90,7 +96,7 @@ impl LearningStorage for crate::concurrent_memory::ConcurrentMemory {
         semantic: SemanticMetadata,
     ) -> Result<u64> {
         self.learn_concept_with_semantic(id, content, vector, strength, confidence, semantic)
-            .map_err(|e| anyhow::anyhow!("WriteLog error: {:?}", e))
+            .map_err(write_log_error)
     }
 
     fn vector_search(&self, vector: &[f32], k: usize, ef_search: usize) -> Vec<(ConceptId, f32)> {
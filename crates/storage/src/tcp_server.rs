@@ -3,19 +3,29 @@
 //! Replaces gRPC server while maintaining distributed architecture.
 //! Runs as standalone service - API/Hybrid connect over network.
 
-use crate::autonomy::{AutonomyConfig, AutonomyManager};
+use crate::auth::Scope;
+use crate::autonomy::{AutonomyConfig, AutonomyManager, METRICS_NAMESPACE};
 use crate::concurrent_memory::ConcurrentMemory;
+use crate::idempotency::{IdempotencyCache, KeyReused};
 use crate::learning_pipeline::{LearnOptions, LearningPipeline};
-use crate::namespace_manager::NamespaceManager;
+use crate::namespace_manager::{NamespaceEvictionConfig, NamespaceManager, NamespaceQuota};
 use crate::nl_parser::NlParser; // 🔥 NEW
+use crate::rate_limiter::{PeerRateLimiter, RateLimiterConfig};
+use crate::replication::{Replica, ReplicaConfig, ReplicaStatus, ReplicationOp, ReplicationRecord};
 use crate::semantic::{CausalType, DomainContext, SemanticType};
-use crate::sharded_storage::ShardedStorage;
+use crate::semantic_extractor::SimilarityMapping;
+use crate::sharded_storage::{find_path_across_shards, ShardedStorage};
+use crate::tls::ClientIdentity;
+use crate::write_log::WriteLogError;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use sutra_protocol::{pattern_matches, ChangeKind, ConceptChange};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader}; // BufRead for lines
 use tokio::net::{TcpListener, TcpStream};
 use tokio::signal;
-use tracing::info;
+use tokio::sync::{broadcast, watch, Semaphore};
+use tokio::task::JoinSet;
+use tracing::{info, warn};
 
 // Import protocol from sutra-protocol crate
 // Note: In production, add sutra-protocol as dependency in Cargo.toml
@@ -25,9 +35,31 @@ use serde::{Deserialize, Serialize};
 const MAX_CONTENT_SIZE: usize = 10 * 1024 * 1024; // 10MB max content
 const MAX_EMBEDDING_DIM: usize = 2048; // Max embedding dimension
 const MAX_BATCH_SIZE: usize = 1000; // Max batch size
-const MAX_MESSAGE_SIZE: usize = 100 * 1024 * 1024; // 100MB max TCP message
 const MAX_PATH_DEPTH: u32 = 20; // Max path finding depth
+const MAX_PATH_NODES_VISITED: u32 = 100_000; // Max nodes expanded per semantic path query
+const MAX_PATH_TIMEOUT_MS: u64 = 5_000; // Max wall-clock budget per semantic path query
 const MAX_SEARCH_K: u32 = 1000; // Max k for vector search
+const MAX_EF_SEARCH: u32 = 1000; // Max HNSW search beam width
+const MAX_BLOCKING_SEARCHES: usize = 64; // Max vector searches running on the blocking pool
+const SHORT_SEARCH_BUDGET: std::time::Duration = std::time::Duration::from_millis(50); // Below this, narrow the search
+const SHORT_BUDGET_EF_SEARCH: u32 = 64; // Beam width for searches with a short budget
+const MAX_BATCH_SEARCH_RESULTS: usize = 100_000; // Max queries × k for a search batch
+const MAX_REPLICATION_BATCH: u32 = 10_000; // Max records per replication pull
+const MAX_GAP_SAMPLE: usize = 10_000; // Max concepts analyzed per gap query
+const BACKPRESSURE_RETRY_AFTER_MS: u64 = 100; // Suggested wait after a backpressure refusal
+const MAX_SUBSCRIPTION_PATTERNS: usize = 64; // Max SubscribeChanges patterns per connection
+const CHANGE_FEED_CAPACITY: usize = 1024; // Changes buffered per subscribed connection
+
+/// Default maximum size of one TCP frame (100MB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 100 * 1024 * 1024;
+
+/// Largest maximum message size a deployment may configure (1GB)
+pub const MAX_MESSAGE_SIZE_CEILING: usize = sutra_protocol::MAX_MESSAGE_SIZE_CEILING as usize;
+
+pub use sutra_protocol::validate_max_message_size;
+
+/// Default time to wait for in-flight requests when shutting down
+pub const DEFAULT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 // Re-define protocol messages here for now (will use sutra-protocol crate)
 
@@ -66,6 +98,12 @@ pub struct LearnOptionsMsg {
     pub max_associations_per_concept: usize,
     pub strength: f32,
     pub confidence: f32,
+    /// Similarity below which no association is auto-created (default 0.65)
+    #[serde(default)]
+    pub similarity_floor: Option<f32>,
+    /// Similarity-to-confidence mapping for auto-created associations
+    #[serde(default)]
+    pub similarity_mapping: SimilarityMapping,
 }
 
 impl From<LearnOptionsMsg> for LearnOptions {
@@ -80,8 +118,13 @@ impl From<LearnOptionsMsg> for LearnOptions {
                 .unwrap_or(true),
             min_association_confidence: m.min_association_confidence,
             max_associations_per_concept: m.max_associations_per_concept,
+            similarity_floor: m
+                .similarity_floor
+                .unwrap_or(crate::semantic_extractor::DEFAULT_SIMILARITY_FLOOR),
+            similarity_mapping: m.similarity_mapping,
             strength: m.strength,
             confidence: m.confidence,
+            use_embedding_cache: true,
         }
     }
 }
@@ -98,6 +141,8 @@ impl Default for LearnOptionsMsg {
             max_associations_per_concept: d.max_associations_per_concept,
             strength: d.strength,
             confidence: d.confidence,
+            similarity_floor: None,
+            similarity_mapping: d.similarity_mapping,
         }
     }
 }
@@ -111,12 +156,18 @@ pub enum StorageRequest {
         namespace: Option<String>,
         content: String,
         options: LearnOptionsMsg,
+        /// Retry-safe key: a repeat within the TTL returns the original response
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
     /// Learn a batch of concepts
     LearnBatch {
         namespace: Option<String>,
         contents: Vec<String>,
         options: LearnOptionsMsg,
+        /// Retry-safe key: a repeat within the TTL returns the original response
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
     /// 🔥 NEW: Learn with precomputed embedding (Requested for Sutra)
     LearnWithEmbedding {
@@ -126,6 +177,9 @@ pub enum StorageRequest {
         embedding: Vec<f32>,
         metadata: std::collections::HashMap<String, String>,
         timestamp: Option<i64>,
+        /// Retry-safe key: a repeat within the TTL returns the original response
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
     LearnConcept {
         namespace: Option<String>,
@@ -134,6 +188,9 @@ pub enum StorageRequest {
         embedding: Vec<f32>,
         strength: f32,
         confidence: f32,
+        /// Retry-safe key: a repeat within the TTL returns the original response
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
     /// Learn association between concepts
     LearnAssociation {
@@ -142,17 +199,42 @@ pub enum StorageRequest {
         target_id: String,
         assoc_type: u32,
         confidence: f32,
+        /// Retry-safe key: a repeat within the TTL returns the original response
+        #[serde(default)]
+        idempotency_key: Option<String>,
+    },
+    /// Apply several learn operations all-or-nothing
+    Transaction {
+        namespace: Option<String>,
+        operations: Vec<TxnOperationMsg>,
     },
     /// Get concept by ID
     QueryConcept {
         namespace: Option<String>,
         concept_id: String,
+        /// Also return the stored embedding
+        #[serde(default)]
+        include_vector: bool,
     },
     /// 🔥 NEW: Delete concept by ID (Requested for Sutra)
     DeleteConcept {
         namespace: String,
         id: String,
     },
+    /// Edit strength, confidence or attributes without touching edges or vector
+    UpdateConcept {
+        namespace: Option<String>,
+        id: String,
+        /// `None` keeps the stored value
+        #[serde(default)]
+        strength: Option<f32>,
+        /// `None` keeps the stored value
+        #[serde(default)]
+        confidence: Option<f32>,
+        /// Keys to add or overwrite; other attributes are kept
+        #[serde(default)]
+        metadata_merge: std::collections::HashMap<String, String>,
+    },
     /// 🔥 NEW: Clear an entire collection (Requested for Sutra)
     ClearCollection {
         namespace: String,
@@ -167,17 +249,42 @@ pub enum StorageRequest {
         start_id: String,
         end_id: String,
         max_depth: u32,
+        /// Give up once this many milliseconds have passed (see `deadline_exceeded`)
+        #[serde(default)]
+        deadline_ms: Option<u64>,
+        /// Search these namespaces together instead of `namespace`
+        #[serde(default)]
+        namespaces: Vec<String>,
     },
     VectorSearch {
         namespace: Option<String>,
         query_vector: Vec<f32>,
         k: u32,
         ef_search: u32,
+        /// Give up once this many milliseconds have passed (see `deadline_exceeded`)
+        #[serde(default)]
+        deadline_ms: Option<u64>,
+    },
+    /// Several `VectorSearch` queries in one round-trip, answered in order
+    VectorSearchBatch {
+        namespace: Option<String>,
+        queries: Vec<Vec<f32>>,
+        k: u32,
+        ef_search: u32,
     },
     /// 🔥 NEW: List recent items without vector search (Requested for Sutra)
     ListRecent {
         namespace: String,
         limit: u32,
+        /// `next_cursor` of the previous page; omit for the newest items
+        #[serde(default)]
+        cursor: Option<String>,
+    },
+    /// Concepts whose attributes match every `key = value` pair (newest first)
+    QueryByMetadata {
+        namespace: Option<String>,
+        attributes: std::collections::HashMap<String, String>,
+        limit: u32,
     },
     // 🔥 NEW: Semantic query operations
     FindPathSemantic {
@@ -187,38 +294,117 @@ pub enum StorageRequest {
         filter: SemanticFilterMsg,
         max_depth: u32,
         max_paths: u32,
+        /// Node expansion budget (capped at MAX_PATH_NODES_VISITED)
+        #[serde(default)]
+        max_nodes_visited: Option<u32>,
+        /// Wall-clock budget in milliseconds (capped at MAX_PATH_TIMEOUT_MS)
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// Give up once this many milliseconds have passed (see `deadline_exceeded`)
+        #[serde(default)]
+        deadline_ms: Option<u64>,
+        /// Search these namespaces together instead of `namespace`
+        #[serde(default)]
+        namespaces: Vec<String>,
     },
     FindTemporalChain {
         namespace: Option<String>,
         domain: Option<String>, // "medical", "legal", etc.
         start_time: i64,
         end_time: i64,
+        /// Search these namespaces together instead of `namespace`
+        #[serde(default)]
+        namespaces: Vec<String>,
     },
     FindCausalChain {
         namespace: Option<String>,
         start_id: String,
         causal_type: String, // "direct", "indirect", "enabling", etc.
         max_depth: u32,
+        /// Search these namespaces together instead of `namespace`
+        #[serde(default)]
+        namespaces: Vec<String>,
     },
     FindContradictions {
         namespace: Option<String>,
         domain: String,
+        /// Search these namespaces together instead of `namespace`
+        #[serde(default)]
+        namespaces: Vec<String>,
     },
     QueryBySemantic {
         namespace: Option<String>,
         filter: SemanticFilterMsg,
         limit: Option<usize>,
+        /// Search these namespaces together instead of `namespace`
+        #[serde(default)]
+        namespaces: Vec<String>,
     },
     TextSearch {
         namespace: Option<String>,
         query: String,
         limit: u32,
+        /// Snippet length in bytes for `highlights` (default 160, at most 4096)
+        #[serde(default)]
+        snippet_len: Option<u32>,
+        /// Blend BM25 keyword scores in with this weight (0.0-1.0, anything
+        /// else is rejected); unset searches by embedding only
+        #[serde(default)]
+        alpha: Option<f32>,
     },
     GetStats {
         namespace: Option<String>,
     },
+    /// Most-accessed concepts (working set)
+    TopAccessed {
+        namespace: Option<String>,
+        limit: u32,
+    },
+    /// Least-accessed concepts (decay candidates)
+    ColdestConcepts {
+        namespace: Option<String>,
+        limit: u32,
+    },
+    /// Knowledge gaps found by the gap detector's analysis
+    GetGaps {
+        namespace: Option<String>,
+        /// "isolated", "near_miss", "incomplete_chain"; empty means all
+        #[serde(default)]
+        kinds: Vec<String>,
+        limit: u32,
+    },
+    /// Snapshot for bootstrapping a read replica, one chunk per request.
+    /// The first request starts a transfer; later ones pass its id and the
+    /// cursor from the previous chunk.
+    ReplicationSnapshot {
+        namespace: Option<String>,
+        #[serde(default)]
+        transfer_id: Option<u64>,
+        #[serde(default)]
+        cursor: u64,
+    },
+    /// Replication log records starting at `from_sequence`
+    ReplicationPull {
+        namespace: Option<String>,
+        from_sequence: u64,
+        max_entries: u32,
+    },
     Flush,
+    /// Rebuild the namespace's HNSW index without deleted vectors
+    Reindex {
+        namespace: Option<String>,
+    },
     HealthCheck,
+    /// Agree on a protocol version and learn this server's limits
+    Handshake {
+        protocol_version: u32,
+    },
+    /// Push a `Notification` on this connection for every change to a
+    /// concept whose ID matches `pattern` (`*`, `prefix*` or an exact ID),
+    /// in any namespace. Only answered on a client connection.
+    SubscribeChanges {
+        pattern: String,
+    },
     // Autonomy: Subscriptions
     Subscribe {
         filter: SemanticFilterMsg,
@@ -273,6 +459,168 @@ pub struct ConceptWithSemanticMsg {
     pub confidence: f32,
 }
 
+impl StorageRequest {
+    /// Change announced to subscribers if this request succeeds, for
+    /// requests that name the concept they change
+    fn named_change(&self) -> Option<ConceptChange> {
+        let (concept_id, change_kind) = match self {
+            StorageRequest::LearnConcept { concept_id, .. } => (concept_id, ChangeKind::Learned),
+            StorageRequest::LearnAssociation { source_id, .. } => {
+                (source_id, ChangeKind::Associated)
+            }
+            _ => return None,
+        };
+        Some(ConceptChange {
+            concept_id: concept_id.clone(),
+            change_kind,
+        })
+    }
+
+    /// Cache key for requests carrying an idempotency key, scoped by
+    /// request kind and namespace
+    fn idempotency_scope(&self) -> Option<String> {
+        let (kind, namespace, key) = match self {
+            StorageRequest::LearnConceptV2 {
+                namespace,
+                idempotency_key,
+                ..
+            } => ("LearnConceptV2", namespace.as_deref(), idempotency_key),
+            StorageRequest::LearnBatch {
+                namespace,
+                idempotency_key,
+                ..
+            } => ("LearnBatch", namespace.as_deref(), idempotency_key),
+            StorageRequest::LearnConcept {
+                namespace,
+                idempotency_key,
+                ..
+            } => ("LearnConcept", namespace.as_deref(), idempotency_key),
+            StorageRequest::LearnAssociation {
+                namespace,
+                idempotency_key,
+                ..
+            } => ("LearnAssociation", namespace.as_deref(), idempotency_key),
+            StorageRequest::LearnWithEmbedding {
+                namespace,
+                idempotency_key,
+                ..
+            } => (
+                "LearnWithEmbedding",
+                Some(namespace.as_str()),
+                idempotency_key,
+            ),
+            _ => return None,
+        };
+        Some(format!(
+            "{}\0{}\0{}",
+            kind,
+            namespace.unwrap_or("default"),
+            key.as_deref()?
+        ))
+    }
+
+    /// Whether the request changes stored data (rejected on read replicas)
+    pub fn is_mutation(&self) -> bool {
+        match self {
+            StorageRequest::LearnConceptV2 { .. }
+            | StorageRequest::LearnBatch { .. }
+            | StorageRequest::LearnWithEmbedding { .. }
+            | StorageRequest::LearnConcept { .. }
+            | StorageRequest::LearnAssociation { .. }
+            | StorageRequest::Transaction { .. }
+            | StorageRequest::DeleteConcept { .. }
+            | StorageRequest::UpdateConcept { .. }
+            | StorageRequest::ClearCollection { .. }
+            | StorageRequest::CreateGoal { .. }
+            | StorageRequest::CancelGoal { .. }
+            | StorageRequest::ProvideFeedback { .. } => true,
+
+            StorageRequest::QueryConcept { .. }
+            | StorageRequest::GetNeighbors { .. }
+            | StorageRequest::FindPath { .. }
+            | StorageRequest::FindPathSemantic { .. }
+            | StorageRequest::FindTemporalChain { .. }
+            | StorageRequest::FindCausalChain { .. }
+            | StorageRequest::FindContradictions { .. }
+            | StorageRequest::QueryBySemantic { .. }
+            | StorageRequest::VectorSearch { .. }
+            | StorageRequest::VectorSearchBatch { .. }
+            | StorageRequest::TextSearch { .. }
+            | StorageRequest::ListRecent { .. }
+            | StorageRequest::QueryByMetadata { .. }
+            | StorageRequest::GetStats { .. }
+            | StorageRequest::TopAccessed { .. }
+            | StorageRequest::GetGaps { .. }
+            | StorageRequest::ColdestConcepts { .. }
+            | StorageRequest::ReplicationSnapshot { .. }
+            | StorageRequest::ReplicationPull { .. }
+            | StorageRequest::Flush
+            | StorageRequest::Reindex { .. }
+            | StorageRequest::HealthCheck
+            | StorageRequest::Handshake { .. }
+            | StorageRequest::SubscribeChanges { .. }
+            | StorageRequest::Subscribe { .. }
+            | StorageRequest::Unsubscribe { .. }
+            | StorageRequest::ListSubscriptions
+            | StorageRequest::ListGoals { .. }
+            | StorageRequest::GetAutonomyStats => false,
+        }
+    }
+
+    /// Least API key scope allowed to make this request
+    ///
+    /// Replication streams export whole namespaces, so they need `Admin`
+    /// like maintenance. Subscriptions need `ReadWrite`: they register state
+    /// on the server and make it connect out to the callback address.
+    pub fn required_scope(&self) -> Scope {
+        match self {
+            StorageRequest::Flush
+            | StorageRequest::Reindex { .. }
+            | StorageRequest::ReplicationSnapshot { .. }
+            | StorageRequest::ReplicationPull { .. } => Scope::Admin,
+
+            StorageRequest::LearnConceptV2 { .. }
+            | StorageRequest::LearnBatch { .. }
+            | StorageRequest::LearnWithEmbedding { .. }
+            | StorageRequest::LearnConcept { .. }
+            | StorageRequest::LearnAssociation { .. }
+            | StorageRequest::Transaction { .. }
+            | StorageRequest::DeleteConcept { .. }
+            | StorageRequest::UpdateConcept { .. }
+            | StorageRequest::ClearCollection { .. }
+            | StorageRequest::CreateGoal { .. }
+            | StorageRequest::CancelGoal { .. }
+            | StorageRequest::ProvideFeedback { .. }
+            | StorageRequest::Subscribe { .. }
+            | StorageRequest::Unsubscribe { .. } => Scope::ReadWrite,
+
+            StorageRequest::QueryConcept { .. }
+            | StorageRequest::GetNeighbors { .. }
+            | StorageRequest::FindPath { .. }
+            | StorageRequest::FindPathSemantic { .. }
+            | StorageRequest::FindTemporalChain { .. }
+            | StorageRequest::FindCausalChain { .. }
+            | StorageRequest::FindContradictions { .. }
+            | StorageRequest::QueryBySemantic { .. }
+            | StorageRequest::VectorSearch { .. }
+            | StorageRequest::VectorSearchBatch { .. }
+            | StorageRequest::TextSearch { .. }
+            | StorageRequest::ListRecent { .. }
+            | StorageRequest::QueryByMetadata { .. }
+            | StorageRequest::GetStats { .. }
+            | StorageRequest::TopAccessed { .. }
+            | StorageRequest::GetGaps { .. }
+            | StorageRequest::ColdestConcepts { .. }
+            | StorageRequest::HealthCheck
+            | StorageRequest::Handshake { .. }
+            | StorageRequest::SubscribeChanges { .. }
+            | StorageRequest::ListSubscriptions
+            | StorageRequest::ListGoals { .. }
+            | StorageRequest::GetAutonomyStats => Scope::ReadOnly,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StorageResponse {
     LearnConceptV2Ok {
@@ -287,9 +635,18 @@ pub enum StorageResponse {
     LearnAssociationOk {
         sequence: u64,
     },
+    TransactionOk {
+        /// IDs of the concepts learned, in operation order
+        concept_ids: Vec<String>,
+        sequence: u64,
+    },
     DeleteConceptOk {
         id: String,
     },
+    UpdateConceptOk {
+        id: String,
+        sequence: u64,
+    },
     ClearCollectionOk {
         namespace: String,
     },
@@ -300,6 +657,9 @@ pub enum StorageResponse {
         strength: f32,
         confidence: f32,
         attributes: std::collections::HashMap<String, String>,
+        /// Stored embedding, when requested with `include_vector` and present
+        #[serde(default)]
+        vector: Option<Vec<f32>>,
     },
     GetNeighborsOk {
         neighbor_ids: Vec<String>,
@@ -307,22 +667,56 @@ pub enum StorageResponse {
     FindPathOk {
         found: bool,
         path: Vec<String>,
+        /// True if the request's `deadline_ms` passed before the work finished
+        #[serde(default)]
+        deadline_exceeded: bool,
+        /// Namespace each path concept was visited in, for paths searched
+        /// across namespaces (sharded mode without a namespace)
+        #[serde(default)]
+        shards: Vec<String>,
     },
     VectorSearchOk {
         results: Vec<(String, f32)>,
+        /// True if the request's `deadline_ms` passed before the work finished
+        #[serde(default)]
+        deadline_exceeded: bool,
+    },
+    VectorSearchBatchOk {
+        /// One result list per query, in request order
+        results: Vec<Vec<(String, f32)>>,
     },
     ListRecentOk {
         items: Vec<RecentItemMsg>,
+        /// Pass back as `cursor` to fetch the next page; `None` on the last page
+        #[serde(default)]
+        next_cursor: Option<String>,
+    },
+    QueryByMetadataOk {
+        concepts: Vec<RecentItemMsg>,
     },
     // 🔥 NEW: Semantic query responses
     FindPathSemanticOk {
         paths: Vec<SemanticPathMsg>,
+        /// True if a node or time budget cut the search short
+        #[serde(default)]
+        truncated: bool,
+        /// True if the request's `deadline_ms` passed before the work finished
+        #[serde(default)]
+        deadline_exceeded: bool,
+        /// True if start and end are only found in different shards; paths
+        /// across shards are not searched
+        #[serde(default)]
+        cross_shard: bool,
     },
     FindTemporalChainOk {
         paths: Vec<SemanticPathMsg>,
     },
     FindCausalChainOk {
         paths: Vec<SemanticPathMsg>,
+        /// True if a chain ends at a concept that another shard links onward;
+        /// chains are not followed across shards
+        #[serde(default)]
+        cross_shard: bool,
     },
     FindContradictionsOk {
         contradictions: Vec<(String, String, String)>, // (id1, id2, reason)
@@ -332,6 +726,9 @@ pub enum StorageResponse {
     },
     TextSearchOk {
         results: Vec<(String, f32)>, // (concept_id, score)
+        /// Query-term matches for each result, in the same order
+        #[serde(default)]
+        highlights: Vec<TextHighlightMsg>,
     },
     StatsOk {
         concepts: u64,
@@ -342,12 +739,82 @@ pub enum StorageResponse {
         pending: u64,
         reconciliations: u64,
         uptime_seconds: u64,
+        #[serde(default)]
+        embedding_cache_hits: u64,
+        #[serde(default)]
+        embedding_cache_misses: u64,
+        /// Records behind the primary (0 unless this server is a replica)
+        #[serde(default)]
+        replication_lag: u64,
+        /// Entries in the attribute index used by QueryByMetadata
+        #[serde(default)]
+        attribute_index_entries: u64,
+        /// Namespaces currently open
+        #[serde(default)]
+        namespaces_open: u64,
+        /// Namespaces closed by the eviction policy since startup
+        #[serde(default)]
+        namespaces_evicted: u64,
+        /// Share of HNSW vectors belonging to deleted concepts
+        #[serde(default)]
+        hnsw_tombstone_ratio: f32,
+        /// Completion time of the last reindex (Unix µs, 0 if never)
+        #[serde(default)]
+        last_reindex_us: u64,
+        /// Threads available to parallel storage work
+        #[serde(default)]
+        storage_threads: u64,
+        /// Requests refused by the per-client rate limits since startup
+        #[serde(default)]
+        rate_limited_requests: u64,
+        /// Clients currently out of read or write tokens
+        #[serde(default)]
+        rate_limited_peers: u64,
+        /// Namespace quota limits (0 = unlimited or no quota)
+        #[serde(default)]
+        quota_max_concepts: u64,
+        #[serde(default)]
+        quota_max_bytes: u64,
+        /// Concepts and bytes counted against the quota (0 without a quota)
+        #[serde(default)]
+        quota_used_concepts: u64,
+        #[serde(default)]
+        quota_used_bytes: u64,
+        /// Concepts deleted to stay under an LRU quota
+        #[serde(default)]
+        quota_evicted: u64,
+    },
+    AccessRankingOk {
+        concepts: Vec<AccessRankMsg>,
+    },
+    GapsOk {
+        /// Most severe first
+        gaps: Vec<GapReportMsg>,
     },
     FlushOk,
+    ReindexOk {
+        /// Vectors in the rebuilt index
+        indexed: u64,
+        tombstones_removed: u64,
+    },
     HealthCheckOk {
         healthy: bool,
         status: String,
         uptime_seconds: u64,
+        /// Largest request frame this server accepts, in bytes
+        #[serde(default)]
+        max_message_size: u64,
+    },
+    HandshakeOk {
+        /// Version both sides speak
+        protocol_version: u32,
+        /// Largest request frame this server accepts, in bytes
+        max_message_size: u32,
+    },
+    /// Change pushed to a `SubscribeChanges` connection; never a reply
+    Notification {
+        concept_id: String,
+        change_kind: ChangeKind,
     },
     // Autonomy responses
     SubscribeOk {
@@ -370,9 +837,35 @@ pub enum StorageResponse {
     AutonomyStatsOk {
         stats: String,
     },
+    ReplicationSnapshotOk {
+        /// First log sequence not covered by the snapshot
+        sequence: u64,
+        ops: Vec<ReplicationOp>,
+        #[serde(default)]
+        transfer_id: Option<u64>,
+        /// Cursor of the next chunk; `None` once the snapshot is complete
+        #[serde(default)]
+        next_cursor: Option<u64>,
+    },
+    ReplicationBatchOk {
+        records: Vec<ReplicationRecord>,
+        /// Primary's next log sequence
+        primary_sequence: u64,
+    },
+    /// The requested records were evicted; bootstrap again from a snapshot
+    ReplicationResyncRequired {
+        oldest_sequence: u64,
+    },
     Error {
         message: String,
     },
+    /// Transient refusal: the same request may succeed after `retry_after_ms`
+    RetryableError {
+        /// Machine-readable reason ("backpressure")
+        code: String,
+        message: String,
+        retry_after_ms: u64,
+    },
 }
 
 /// Subscription info for protocol messages
@@ -392,6 +885,121 @@ pub struct GoalSummaryMsg {
     pub priority: u8,
 }
 
+/// Concept access statistics for protocol messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRankMsg {
+    pub concept_id: String,
+    pub access_count: u32,
+    pub last_accessed: u64,
+}
+
+impl From<crate::concurrent_memory::AccessRank> for AccessRankMsg {
+    fn from(r: crate::concurrent_memory::AccessRank) -> Self {
+        Self {
+            concept_id: r.id.to_hex(),
+            access_count: r.access_count,
+            last_accessed: r.last_accessed,
+        }
+    }
+}
+
+/// Knowledge gap for protocol messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapReportMsg {
+    /// "isolated", "near_miss" or "incomplete_chain"
+    pub kind: String,
+    pub concept_ids: Vec<String>,
+    pub severity: f32,
+}
+
+impl From<crate::autonomy::GapReport> for GapReportMsg {
+    fn from(gap: crate::autonomy::GapReport) -> Self {
+        Self {
+            kind: gap.kind.as_str().to_string(),
+            concept_ids: gap.concept_ids.iter().map(|id| id.to_hex()).collect(),
+            severity: gap.severity,
+        }
+    }
+}
+
+/// Reply to a `Handshake`: the version both sides speak and this server's frame limit
+fn handshake_response(client_version: u32, max_message_size: usize) -> StorageResponse {
+    if client_version < sutra_protocol::MIN_PROTOCOL_VERSION {
+        return StorageResponse::Error {
+            message: format!(
+                "Unsupported protocol version {} (minimum {})",
+                client_version,
+                sutra_protocol::MIN_PROTOCOL_VERSION
+            ),
+        };
+    }
+    StorageResponse::HandshakeOk {
+        protocol_version: client_version.min(sutra_protocol::PROTOCOL_VERSION),
+        max_message_size: max_message_size as u32,
+    }
+}
+
+/// Retryable answer for a write refused under backpressure or with the
+/// write log full; both clear once the reconciler catches up
+fn backpressure_response(error: &WriteLogError) -> StorageResponse {
+    StorageResponse::RetryableError {
+        code: "backpressure".to_string(),
+        message: error.to_string(),
+        retry_after_ms: BACKPRESSURE_RETRY_AFTER_MS,
+    }
+}
+
+/// The write log error behind a learning pipeline error, if it is retryable
+fn retryable_write_error(error: &anyhow::Error) -> Option<&WriteLogError> {
+    error
+        .downcast_ref::<WriteLogError>()
+        .filter(|e| matches!(e, WriteLogError::Backpressure | WriteLogError::Full))
+}
+
+/// Run the gap detector's analysis over one storage, off the async runtime
+///
+/// Thresholds come from `config`; the sample is widened to `MAX_GAP_SAMPLE`
+/// since a query should see more than one background cycle does.
+async fn get_gaps_response(
+    storage: Arc<ConcurrentMemory>,
+    config: crate::autonomy::GapDetectorConfig,
+    kinds: Vec<String>,
+    limit: u32,
+) -> StorageResponse {
+    let mut parsed = Vec::with_capacity(kinds.len());
+    for kind in &kinds {
+        match crate::autonomy::GapKind::parse(kind) {
+            Some(kind) => parsed.push(kind),
+            None => {
+                return StorageResponse::Error {
+                    message: format!(
+                        "Unknown gap kind '{}' (expected isolated, near_miss or incomplete_chain)",
+                        kind
+                    ),
+                }
+            }
+        }
+    }
+
+    let config = crate::autonomy::GapDetectorConfig {
+        sample_size: MAX_GAP_SAMPLE,
+        ..config
+    };
+    let limit = limit.min(MAX_SEARCH_K) as usize;
+    match tokio::task::spawn_blocking(move || {
+        crate::autonomy::detect_gaps(&storage, &config, &parsed, limit)
+    })
+    .await
+    {
+        Ok(gaps) => StorageResponse::GapsOk {
+            gaps: gaps.into_iter().map(GapReportMsg::from).collect(),
+        },
+        Err(e) => StorageResponse::Error {
+            message: format!("Gap detection task failed: {}", e),
+        },
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecentItemMsg {
     pub id: String,
@@ -400,12 +1008,400 @@ pub struct RecentItemMsg {
     pub attributes: std::collections::HashMap<String, String>,
 }
 
+impl RecentItemMsg {
+    fn from_node(node: &crate::read_view::ConceptNode) -> Self {
+        Self {
+            id: node.id.to_hex(),
+            content_preview: String::from_utf8_lossy(&node.content)
+                .chars()
+                .take(200)
+                .collect(),
+            created: node.created,
+            attributes: node.attributes.clone(),
+        }
+    }
+}
+
+/// One operation of a `Transaction` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TxnOperationMsg {
+    LearnConcept {
+        /// Defaults to an ID derived from `content`
+        #[serde(default)]
+        concept_id: Option<String>,
+        content: String,
+        #[serde(default)]
+        embedding: Vec<f32>,
+        strength: f32,
+        confidence: f32,
+        #[serde(default)]
+        attributes: std::collections::HashMap<String, String>,
+    },
+    LearnAssociation {
+        source_id: String,
+        target_id: String,
+        assoc_type: u32,
+        confidence: f32,
+    },
+}
+
+/// Where the query matched in one `TextSearch` result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TextHighlightMsg {
+    pub concept_id: String,
+    /// `[start, end)` byte offsets of keyword matches in the content
+    pub spans: Vec<(u32, u32)>,
+    /// Byte offset of `snippet` in the content
+    pub snippet_start: u32,
+    pub snippet: String,
+}
+
+impl TextHighlightMsg {
+    /// Highlights for `results`; concepts without readable content get empty spans
+    fn for_results(
+        storage: &ConcurrentMemory,
+        results: &[(crate::types::ConceptId, f32)],
+        query: &str,
+        snippet_len: Option<u32>,
+    ) -> Vec<Self> {
+        let ids: Vec<crate::types::ConceptId> = results.iter().map(|(id, _)| *id).collect();
+        let snippet_len = snippet_len.map_or(crate::highlight::DEFAULT_SNIPPET_LEN, |n| n as usize);
+        ids.iter()
+            .zip(storage.highlight_matches(&ids, query, snippet_len))
+            .map(|(id, h)| {
+                let h = h.unwrap_or_else(|| crate::highlight::TextHighlight {
+                    spans: Vec::new(),
+                    snippet_start: 0,
+                    snippet: String::new(),
+                });
+                Self {
+                    concept_id: id.to_hex(),
+                    spans: h
+                        .spans
+                        .into_iter()
+                        .map(|(s, e)| (s as u32, e as u32))
+                        .collect(),
+                    snippet_start: h.snippet_start as u32,
+                    snippet: h.snippet,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Payload encoding for length-prefixed binary frames, chosen per connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    /// MessagePack (production default)
+    #[default]
+    MessagePack,
+    /// JSON, for debugging a client by eye
+    Json,
+}
+
+impl WireFormat {
+    /// Parse a `Content-Type: <format>` handshake line
+    ///
+    /// Accepts `json`/`application/json` and `msgpack`/`application/msgpack`.
+    pub fn from_handshake(line: &str) -> Option<Self> {
+        let (key, value) = line.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("content-type") {
+            return None;
+        }
+        match value.trim().to_lowercase().as_str() {
+            "json" | "application/json" => Some(Self::Json),
+            "msgpack" | "application/msgpack" | "application/x-msgpack" => Some(Self::MessagePack),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MessagePack => "msgpack",
+            Self::Json => "json",
+        }
+    }
+
+    /// Encode a response frame payload
+    pub fn encode_response(&self, response: &StorageResponse) -> Vec<u8> {
+        match self {
+            Self::MessagePack => rmp_serde::to_vec_named(response).unwrap(),
+            Self::Json => serde_json::to_vec(response).unwrap(),
+        }
+    }
+
+    /// Decode a request frame payload
+    pub fn decode_request(&self, buf: &[u8]) -> Result<StorageRequest, String> {
+        match self {
+            Self::MessagePack => rmp_serde::from_slice(buf).map_err(|e| e.to_string()),
+            Self::Json => serde_json::from_slice(buf).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Longest `Content-Type` line a frame-only connection reads
+const MAX_HANDSHAKE_LINE: u64 = 256;
+
+/// Whether buffered input starts a `Content-Type` line rather than a frame
+///
+/// Frame lengths never exceed `MAX_MESSAGE_SIZE_CEILING` (1 GiB), so no length
+/// prefix starts with `C` or `c`.
+pub(crate) fn starts_handshake(buf: &[u8]) -> bool {
+    matches!(buf.first(), Some(b'C' | b'c'))
+}
+
+/// Answer a `Content-Type` line on a connection that only speaks frames,
+/// switching `wire_format` if the line names a known format
+pub(crate) async fn negotiate_wire_format<S>(
+    stream: &mut S,
+    peer_addr: SocketAddr,
+    wire_format: &mut WireFormat,
+) -> std::io::Result<()>
+where
+    S: AsyncBufReadExt + AsyncWriteExt + Unpin,
+{
+    let mut line = String::new();
+    (&mut *stream)
+        .take(MAX_HANDSHAKE_LINE)
+        .read_line(&mut line)
+        .await?;
+    match WireFormat::from_handshake(line.trim()) {
+        Some(negotiated) => {
+            *wire_format = negotiated;
+            info!(
+                "🔀 {} switched wire format to {}",
+                peer_addr,
+                wire_format.as_str()
+            );
+            stream
+                .write_all(format!("OK {}\n", wire_format.as_str()).as_bytes())
+                .await?;
+        }
+        None => {
+            stream
+                .write_all(b"Error: expected 'Content-Type: json' or 'Content-Type: msgpack'\n")
+                .await?;
+        }
+    }
+    stream.flush().await
+}
+
 /// Storage server state
 pub struct StorageServer {
     namespaces: Arc<NamespaceManager>,
     start_time: std::time::Instant,
     pipeline: LearningPipeline,
     autonomy: Arc<parking_lot::RwLock<AutonomyManager>>,
+    drain_timeout: std::time::Duration,
+    /// Set when this server is a read replica: writes are rejected
+    replica: Option<Replica>,
+    /// Responses of recent keyed learn requests
+    idempotency: IdempotencyCache<StorageResponse>,
+    max_message_size: usize,
+    /// Per-client request limits (unlimited when `None`)
+    rate_limits: Option<PeerRateLimiter>,
+    /// Concept changes fanned out to `SubscribeChanges` connections
+    changes: broadcast::Sender<ConceptChange>,
+    /// Vector searches allowed on the blocking pool at once
+    search_permits: Arc<Semaphore>,
+}
+
+/// Changes `response` reports, given the change its request named (see
+/// `StorageRequest::named_change`); responses carry the IDs of concepts
+/// whose ID the server derived
+fn announced_changes(
+    named: Option<ConceptChange>,
+    response: &StorageResponse,
+) -> Vec<ConceptChange> {
+    let learned = |concept_id: &String| ConceptChange {
+        concept_id: concept_id.clone(),
+        change_kind: ChangeKind::Learned,
+    };
+    match response {
+        StorageResponse::LearnConceptOk { .. } | StorageResponse::LearnAssociationOk { .. } => {
+            named.into_iter().collect()
+        }
+        StorageResponse::LearnConceptV2Ok { concept_id } => vec![learned(concept_id)],
+        StorageResponse::UpdateConceptOk { id, .. } => vec![learned(id)],
+        StorageResponse::LearnBatchOk { concept_ids }
+        | StorageResponse::TransactionOk { concept_ids, .. } => {
+            concept_ids.iter().map(learned).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// A connection's `SubscribeChanges` patterns and the change feed they filter
+#[derive(Default)]
+pub(crate) struct ChangeSubscriptions {
+    patterns: Vec<String>,
+    feed: Option<broadcast::Receiver<ConceptChange>>,
+}
+
+impl ChangeSubscriptions {
+    /// Next change matching a subscribed pattern; never resolves before the
+    /// first subscription
+    ///
+    /// A connection that falls `CHANGE_FEED_CAPACITY` changes behind skips
+    /// the oldest ones.
+    pub(crate) async fn next(&mut self) -> ConceptChange {
+        if let Some(feed) = self.feed.as_mut() {
+            loop {
+                match feed.recv().await {
+                    Ok(change) => {
+                        if self
+                            .patterns
+                            .iter()
+                            .any(|pattern| pattern_matches(pattern, &change.concept_id))
+                        {
+                            return change;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Subscriber fell behind, skipped {} change(s)", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+        std::future::pending().await
+    }
+
+    /// Frame pushed to the client for `change`
+    pub(crate) fn notification(change: ConceptChange) -> StorageResponse {
+        StorageResponse::Notification {
+            concept_id: change.concept_id,
+            change_kind: change.change_kind,
+        }
+    }
+}
+
+/// Rewrite `storage`'s `storage.dat` off the async workers once enough of it is deleted
+fn compact_in_background(storage: &Arc<ConcurrentMemory>) {
+    if !storage.compaction_due() {
+        return;
+    }
+    let storage = Arc::clone(storage);
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = storage.compact_if_needed() {
+            eprintln!("⚠️ Compaction failed: {:?}", e);
+        }
+    });
+}
+
+/// Give `clients` up to `timeout` to finish, then cancel the rest
+///
+/// Returns the number of connections that were cancelled.
+pub(crate) async fn drain_clients(
+    clients: &mut JoinSet<()>,
+    timeout: std::time::Duration,
+) -> usize {
+    let _ = tokio::time::timeout(timeout, async {
+        while clients.join_next().await.is_some() {}
+    })
+    .await;
+
+    let cancelled = clients.len();
+    clients.shutdown().await;
+    cancelled
+}
+
+/// Key a peer's feedback is tracked under when it has no verified identity
+fn peer_caller(peer: SocketAddr) -> String {
+    format!("ip:{}", peer.ip())
+}
+
+/// The error to answer with if the caller is over its rate limit for `request`
+///
+/// Callers with a verified `identity` are limited by it, others by `peer` address.
+fn rate_limited(
+    limits: Option<&PeerRateLimiter>,
+    peer: SocketAddr,
+    identity: Option<&str>,
+    request: &StorageRequest,
+) -> Option<StorageResponse> {
+    let limits = limits?;
+    let write = request.is_mutation();
+    match identity {
+        Some(identity) => limits.check_subject(identity, write),
+        None => limits.check(peer.ip(), write),
+    }
+    .err()?;
+    Some(StorageResponse::Error {
+        message: "rate limited".to_string(),
+    })
+}
+
+/// Run `request` through `cache` if it carries an idempotency key.
+/// Error responses are not remembered so a failed attempt can be retried.
+///
+/// Keys are scoped to `caller`, so two clients picking the same key never
+/// see each other's responses.
+async fn run_idempotent<F, Fut>(
+    cache: &IdempotencyCache<StorageResponse>,
+    caller: &str,
+    request: StorageRequest,
+    handle: F,
+) -> StorageResponse
+where
+    F: FnOnce(StorageRequest) -> Fut,
+    Fut: std::future::Future<Output = StorageResponse>,
+{
+    let Some(scope) = request.idempotency_scope() else {
+        return handle(request).await;
+    };
+    let fingerprint = request_fingerprint(&request);
+    let result =
+        cache
+            .get_or_run(format!("{}\0{}", caller, scope), fingerprint, || async {
+                match handle(request).await {
+                    error @ (StorageResponse::Error { .. }
+                    | StorageResponse::RetryableError { .. }) => Err(error),
+                    response => Ok(response),
+                }
+            })
+            .await;
+    result.unwrap_or_else(|e: KeyReused| StorageResponse::Error {
+        message: e.to_string(),
+    })
+}
+
+/// Hash of a request's body, to tell a retry from a different request
+///
+/// Hashed in a canonical form: a retry's maps (metadata, attributes) are
+/// decoded into new `HashMap`s that iterate in a different order.
+fn request_fingerprint(request: &StorageRequest) -> u64 {
+    use std::hash::Hasher;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    if let Ok(value) = serde_json::to_value(request) {
+        hash_canonical(&value, &mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hash `value` with object entries in key order
+fn hash_canonical(value: &serde_json::Value, hasher: &mut impl std::hash::Hasher) {
+    use std::hash::Hash;
+
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            (b'{', entries.len()).hash(hasher);
+            for (key, value) in entries {
+                key.hash(hasher);
+                hash_canonical(value, hasher);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            (b'[', items.len()).hash(hasher);
+            for item in items {
+                hash_canonical(item, hasher);
+            }
+        }
+        scalar => scalar.to_string().hash(hasher),
+    }
 }
 
 impl StorageServer {
@@ -438,7 +1434,14 @@ impl StorageServer {
             .await
             .expect("Failed to init learning pipeline");
 
+        // The metrics namespace creates its own directory and WAL, so it is
+        // only opened when there are samples to put in it
+        let monitoring = autonomy_config.enabled && autonomy_config.self_monitor.enabled;
         let mut autonomy_manager = AutonomyManager::new(autonomy_config, Arc::clone(&storage));
+        if monitoring {
+            autonomy_manager =
+                autonomy_manager.with_metrics_storage(manager.get_namespace(METRICS_NAMESPACE));
+        }
         autonomy_manager.start();
 
         Self {
@@ -446,6 +1449,13 @@ impl StorageServer {
             start_time: std::time::Instant::now(),
             pipeline,
             autonomy: Arc::new(parking_lot::RwLock::new(autonomy_manager)),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            replica: None,
+            idempotency: IdempotencyCache::default(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            rate_limits: None,
+            changes: broadcast::channel(CHANGE_FEED_CAPACITY).0,
+            search_permits: Arc::new(Semaphore::new(MAX_BLOCKING_SEARCHES)),
         }
     }
 
@@ -473,9 +1483,76 @@ impl StorageServer {
             start_time: std::time::Instant::now(),
             pipeline,
             autonomy: Arc::new(parking_lot::RwLock::new(autonomy_manager)),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            replica: None,
+            idempotency: IdempotencyCache::default(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            rate_limits: None,
+            changes: broadcast::channel(CHANGE_FEED_CAPACITY).0,
+            search_permits: Arc::new(Semaphore::new(MAX_BLOCKING_SEARCHES)),
         }
     }
 
+    /// Set how long shutdown waits for in-flight requests before cancelling them
+    pub fn with_drain_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Close idle namespaces according to `config` (default: never)
+    pub fn with_namespace_eviction(self, config: NamespaceEvictionConfig) -> Self {
+        self.namespaces.set_eviction(config);
+        self
+    }
+
+    /// Limit the size of every namespace without its own quota (default: unlimited)
+    pub fn with_namespace_quota(self, quota: NamespaceQuota) -> Self {
+        self.namespaces.set_default_quota(Some(quota));
+        self
+    }
+
+    /// Limit the size of one namespace
+    pub fn set_namespace_quota(&self, namespace: &str, quota: Option<NamespaceQuota>) {
+        self.namespaces.set_quota(namespace, quota);
+    }
+
+    /// Reject request frames larger than `bytes` (capped at `MAX_MESSAGE_SIZE_CEILING`)
+    pub fn with_max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes.clamp(1, MAX_MESSAGE_SIZE_CEILING);
+        self
+    }
+
+    /// Limit each client's request rate, with `writes` applied to mutations
+    ///
+    /// Requests over the limit get `Error { message: "rate limited" }` and
+    /// the connection stays open.
+    pub fn with_rate_limits(mut self, reads: RateLimiterConfig, writes: RateLimiterConfig) -> Self {
+        self.rate_limits = Some(PeerRateLimiter::new(reads, writes));
+        self
+    }
+
+    /// Largest request frame accepted, in bytes
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Serve as a read-only replica of `config.primary_addr`
+    ///
+    /// The default namespace is bootstrapped from the primary's snapshot and
+    /// kept up to date from its replication log; write requests are rejected.
+    /// Frames from the primary are held to this server's `max_message_size`,
+    /// so set that first.
+    pub fn with_replica(mut self, mut config: ReplicaConfig) -> Self {
+        config.max_message_size = self.max_message_size;
+        self.replica = Some(Replica::start(config, self.get_storage(None)));
+        self
+    }
+
+    /// Replication progress, if this server is a replica
+    pub fn replica_status(&self) -> Option<&Arc<ReplicaStatus>> {
+        self.replica.as_ref().map(|r| r.status())
+    }
+
     /// Get storage for a namespace (falls back to "default")
     fn get_storage(&self, ns: Option<String>) -> Arc<ConcurrentMemory> {
         self.namespaces
@@ -491,6 +1568,10 @@ impl StorageServer {
     }
 
     /// Start TCP server with a custom shutdown signal (test-friendly)
+    ///
+    /// On shutdown the listener stops accepting connections, idle connections
+    /// are closed, and requests already being read or processed are given up to
+    /// `drain_timeout` to complete before their tasks are cancelled.
     pub async fn serve_with_shutdown<F>(
         self: Arc<Self>,
         addr: SocketAddr,
@@ -504,14 +1585,18 @@ impl StorageServer {
 
         tokio::pin!(shutdown);
 
+        let (drain_tx, drain_rx) = watch::channel(false);
+        let mut clients = JoinSet::new();
+
         loop {
             tokio::select! {
                 result = listener.accept() => {
                     match result {
                         Ok((stream, peer_addr)) => {
                             let server = self.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = server.handle_client(stream, peer_addr).await {
+                            let drain_rx = drain_rx.clone();
+                            clients.spawn(async move {
+                                if let Err(e) = server.handle_client(stream, peer_addr, drain_rx).await {
                                     eprintln!("Client error ({}): {}", peer_addr, e);
                                 }
                             });
@@ -521,36 +1606,96 @@ impl StorageServer {
                         }
                     }
                 }
+                // Reap finished connections so the set only holds live ones
+                Some(_) = clients.join_next(), if !clients.is_empty() => {}
                 _ = &mut shutdown => {
-                    eprintln!("Shutdown signal received, flushing storage...");
-                    if let Err(e) = self.namespaces.flush_all() {
-                        eprintln!("Flush error: {:?}", e);
-                    }
                     break;
                 }
             }
         }
+        drop(listener);
+
+        let in_flight = clients.len();
+        eprintln!(
+            "Shutdown signal received, draining {} connection(s) (timeout {:?})...",
+            in_flight, self.drain_timeout
+        );
+        let _ = drain_tx.send(true);
+
+        let cancelled = drain_clients(&mut clients, self.drain_timeout).await;
+        eprintln!(
+            "Drained {} connection(s), force-cancelled {}",
+            in_flight - cancelled,
+            cancelled
+        );
+
+        eprintln!("Flushing storage...");
+        if let Err(e) = self.flush_all() {
+            eprintln!("Flush error: {:?}", e);
+        }
 
         Ok(())
     }
 
-    /// Handle single client connection
-    async fn handle_client(&self, stream: TcpStream, peer_addr: SocketAddr) -> std::io::Result<()> {
-        eprintln!("Client connected: {}", peer_addr);
+    /// How long shutdown waits for in-flight requests
+    pub fn drain_timeout(&self) -> std::time::Duration {
+        self.drain_timeout
+    }
 
-        // Configure for low latency and better throughput
-        stream.set_nodelay(true)?;
+    /// Flush every open namespace to disk
+    pub fn flush_all(&self) -> anyhow::Result<()> {
+        self.namespaces.flush_all()
+    }
+
+    /// Handle single client connection
+    ///
+    /// `drain` flips to `true` on shutdown: the connection finishes the request
+    /// it is currently serving and then closes instead of waiting for another.
+    async fn handle_client(
+        &self,
+        stream: TcpStream,
+        peer_addr: SocketAddr,
+        mut drain: watch::Receiver<bool>,
+    ) -> std::io::Result<()> {
+        eprintln!("Client connected: {}", peer_addr);
+
+        // Configure for low latency and better throughput
+        stream.set_nodelay(true)?;
 
         let mut request_count = 0u64;
 
+        // Binary frame encoding; a client may switch it with a Content-Type line
+        let mut wire_format = WireFormat::default();
+
+        // Changes pushed between replies once the client subscribes
+        let mut subscriptions = ChangeSubscriptions::default();
+
         // Wrap stream in BufReader for line-based reading support
         let mut reader = BufReader::new(stream);
 
         loop {
             let _request_start = std::time::Instant::now();
 
-            // Peek first byte to sniff protocol
-            let start_byte = match reader.fill_buf().await {
+            if *drain.borrow() {
+                break;
+            }
+
+            // Peek first byte to sniff protocol (or stop if draining while idle).
+            // Notifications only go out between requests, never inside a reply.
+            let filled = tokio::select! {
+                biased;
+                _ = drain.changed() => break,
+                change = subscriptions.next() => {
+                    let notification = ChangeSubscriptions::notification(change);
+                    let frame = wire_format.encode_response(&notification);
+                    reader.write_u32(frame.len() as u32).await?;
+                    reader.write_all(&frame).await?;
+                    reader.flush().await?;
+                    continue;
+                }
+                filled = reader.fill_buf() => filled,
+            };
+            let start_byte = match filled {
                 Ok(buf) => {
                     if buf.is_empty() {
                         // Client disconnected
@@ -572,11 +1717,17 @@ impl StorageServer {
                     Err(_) => break,
                 };
 
-                if len as usize > MAX_MESSAGE_SIZE {
+                if len as usize > self.max_message_size {
+                    // Skip the payload without buffering it so the stream stays in sync
+                    tokio::io::copy(&mut (&mut reader).take(len as u64), &mut tokio::io::sink())
+                        .await?;
                     let error = StorageResponse::Error {
-                        message: format!("Message too large: {}", len),
+                        message: format!(
+                            "Message too large: {} bytes (max: {})",
+                            len, self.max_message_size
+                        ),
                     };
-                    let response_bytes = rmp_serde::to_vec_named(&error).unwrap();
+                    let response_bytes = wire_format.encode_response(&error);
                     reader.write_u32(response_bytes.len() as u32).await?;
                     reader.write_all(&response_bytes).await?;
                     reader.flush().await?;
@@ -586,17 +1737,29 @@ impl StorageServer {
                 let mut buf = vec![0u8; len as usize];
                 reader.read_exact(&mut buf).await?;
 
-                let request: StorageRequest = match rmp_serde::from_slice(&buf) {
-                    Ok(req) => req,
-                    Err(e) => {
-                        eprintln!("Deserialization error: {}", e);
-                        continue;
+                // An undecodable frame still gets a reply so the client is not
+                // left waiting for one
+                let response = match wire_format.decode_request(&buf) {
+                    Ok(request) => {
+                        match rate_limited(self.rate_limits.as_ref(), peer_addr, None, &request) {
+                            Some(error) => error,
+                            None => match request {
+                                StorageRequest::SubscribeChanges { pattern } => {
+                                    self.subscribe_changes(&mut subscriptions, pattern)
+                                }
+                                request => {
+                                    self.handle_request_as(request, peer_caller(peer_addr))
+                                        .await
+                                }
+                            },
+                        }
                     }
+                    Err(e) => StorageResponse::Error {
+                        message: format!("Invalid {} request: {}", wire_format.as_str(), e),
+                    },
                 };
 
-                let response = self.handle_request(request).await;
-
-                let response_bytes = rmp_serde::to_vec_named(&response).unwrap();
+                let response_bytes = wire_format.encode_response(&response);
                 reader.write_u32(response_bytes.len() as u32).await?;
                 reader.write_all(&response_bytes).await?;
                 reader.flush().await?;
@@ -608,10 +1771,32 @@ impl StorageServer {
                     Ok(0) => break, // EOF
                     Ok(_) => {
                         let line = line.trim();
-                        if !line.is_empty() {
+                        if let Some(negotiated) = WireFormat::from_handshake(line) {
+                            // Handshake: switch binary frame encoding for this connection
+                            wire_format = negotiated;
+                            info!(
+                                "🔀 {} switched wire format to {}",
+                                peer_addr,
+                                wire_format.as_str()
+                            );
+                            reader
+                                .write_all(format!("OK {}\n", wire_format.as_str()).as_bytes())
+                                .await?;
+                            reader.flush().await?;
+                        } else if !line.is_empty() {
                             info!("🗣️ NL Command: '{}'", line);
                             if let Some(req) = NlParser::parse(line) {
-                                let response = self.handle_request(req).await;
+                                let response = match rate_limited(
+                                    self.rate_limits.as_ref(),
+                                    peer_addr,
+                                    None,
+                                    &req,
+                                ) {
+                                    Some(error) => error,
+                                    None => {
+                                        self.handle_request_as(req, peer_caller(peer_addr)).await
+                                    }
+                                };
 
                                 // Serialize response as JSON/Text for the human
                                 let json =
@@ -636,8 +1821,107 @@ impl StorageServer {
         Ok(())
     }
 
-    /// Handle storage request
+    /// Handle a request from `peer`, applying the per-client rate limits
+    ///
+    /// A client with a verified certificate identity is limited by its common
+    /// name, so its budget follows it across addresses; others by IP address.
+    /// Feedback is attributed the same way.
+    pub async fn handle_request_from(
+        &self,
+        request: StorageRequest,
+        peer: SocketAddr,
+        client: Option<&ClientIdentity>,
+    ) -> StorageResponse {
+        let identity = client.map(ClientIdentity::caller);
+        self.handle_request_for(request, peer, identity.as_deref())
+            .await
+    }
+
+    /// Handle a request from `peer` on behalf of a verified `identity`
+    ///
+    /// Rate limits and feedback are keyed by `identity` (such as
+    /// [`ClientIdentity::caller`] or an authenticated token subject), falling
+    /// back to the peer's IP address without one.
+    pub async fn handle_request_for(
+        &self,
+        request: StorageRequest,
+        peer: SocketAddr,
+        identity: Option<&str>,
+    ) -> StorageResponse {
+        if let Some(error) = rate_limited(self.rate_limits.as_ref(), peer, identity, &request) {
+            return error;
+        }
+        let caller = identity.map_or_else(|| peer_caller(peer), str::to_string);
+        self.handle_request_as(request, caller).await
+    }
+
+    /// Handle storage request from an unidentified caller
     pub async fn handle_request(&self, request: StorageRequest) -> StorageResponse {
+        self.handle_request_as(
+            request,
+            crate::autonomy::feedback::ANONYMOUS_SOURCE.to_string(),
+        )
+        .await
+    }
+
+    /// Handle a request on behalf of `caller`, the key its feedback is tracked under
+    async fn handle_request_as(&self, request: StorageRequest, caller: String) -> StorageResponse {
+        if self.replica.is_some() && request.is_mutation() {
+            return StorageResponse::Error {
+                message: "ReadOnly: this server is a read replica".to_string(),
+            };
+        }
+
+        run_idempotent(&self.idempotency, &caller, request, |request| async {
+            let named = request.named_change();
+            let response = self.execute_request(request, &caller).await;
+            self.announce(named, &response);
+            response
+        })
+        .await
+    }
+
+    /// Send the changes `response` reports to subscribed connections
+    fn announce(&self, named: Option<ConceptChange>, response: &StorageResponse) {
+        if self.changes.receiver_count() == 0 {
+            return;
+        }
+        for change in announced_changes(named, response) {
+            let _ = self.changes.send(change);
+        }
+    }
+
+    /// Answer `SubscribeChanges` by adding `pattern` to a connection's subscriptions
+    pub(crate) fn subscribe_changes(
+        &self,
+        subscriptions: &mut ChangeSubscriptions,
+        pattern: String,
+    ) -> StorageResponse {
+        if pattern.is_empty() {
+            return StorageResponse::Error {
+                message: "Subscription pattern must not be empty".to_string(),
+            };
+        }
+        if !subscriptions.patterns.contains(&pattern) {
+            if subscriptions.patterns.len() >= MAX_SUBSCRIPTION_PATTERNS {
+                return StorageResponse::Error {
+                    message: format!(
+                        "Too many subscriptions on this connection (max: {})",
+                        MAX_SUBSCRIPTION_PATTERNS
+                    ),
+                };
+            }
+            subscriptions.patterns.push(pattern.clone());
+        }
+        subscriptions
+            .feed
+            .get_or_insert_with(|| self.changes.subscribe());
+        StorageResponse::SubscribeOk {
+            subscription_id: pattern,
+        }
+    }
+
+    async fn execute_request(&self, request: StorageRequest, caller: &str) -> StorageResponse {
         use crate::types::{AssociationType, ConceptId};
 
         match request {
@@ -645,7 +1929,12 @@ impl StorageServer {
                 namespace,
                 content,
                 options,
+                idempotency_key: _,
             } => {
+                let mut learn_opts: LearnOptions = options.into();
+                learn_opts.use_embedding_cache = self
+                    .pipeline
+                    .embedding_cache_enabled_for(namespace.as_deref().unwrap_or("default"));
                 let storage = self.get_storage(namespace);
                 // ✅ PRODUCTION: Validate content size
                 if content.len() > MAX_CONTENT_SIZE {
@@ -660,12 +1949,15 @@ impl StorageServer {
 
                 match self
                     .pipeline
-                    .learn_concept(&storage, &content, &options.into())
+                    .learn_concept(&storage, &content, &learn_opts)
                     .await
                 {
                     Ok(concept_id) => StorageResponse::LearnConceptV2Ok { concept_id },
-                    Err(e) => StorageResponse::Error {
-                        message: format!("LearnConceptV2 failed: {}", e),
+                    Err(e) => match retryable_write_error(&e) {
+                        Some(error) => backpressure_response(error),
+                        None => StorageResponse::Error {
+                            message: format!("LearnConceptV2 failed: {}", e),
+                        },
                     },
                 }
             }
@@ -673,7 +1965,12 @@ impl StorageServer {
                 namespace,
                 contents,
                 options,
+                idempotency_key: _,
             } => {
+                let mut learn_opts: LearnOptions = options.into();
+                learn_opts.use_embedding_cache = self
+                    .pipeline
+                    .embedding_cache_enabled_for(namespace.as_deref().unwrap_or("default"));
                 let storage = self.get_storage(namespace);
                 // ✅ PRODUCTION: Validate batch size
                 if contents.len() > MAX_BATCH_SIZE {
@@ -702,12 +1999,15 @@ impl StorageServer {
 
                 match self
                     .pipeline
-                    .learn_batch(&storage, &contents, &options.into())
+                    .learn_batch(&storage, &contents, &learn_opts)
                     .await
                 {
                     Ok(concept_ids) => StorageResponse::LearnBatchOk { concept_ids },
-                    Err(e) => StorageResponse::Error {
-                        message: format!("LearnBatch failed: {}", e),
+                    Err(e) => match retryable_write_error(&e) {
+                        Some(error) => backpressure_response(error),
+                        None => StorageResponse::Error {
+                            message: format!("LearnBatch failed: {}", e),
+                        },
                     },
                 }
             }
@@ -718,6 +2018,7 @@ impl StorageServer {
                 embedding,
                 metadata,
                 timestamp: _,
+                idempotency_key: _,
             } => {
                 let storage = self.get_storage(Some(namespace));
                 let concept_id = id
@@ -735,6 +2036,9 @@ impl StorageServer {
                     Ok(_) => StorageResponse::LearnConceptV2Ok {
                         concept_id: concept_id.to_hex(),
                     },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => {
+                        backpressure_response(&e)
+                    }
                     Err(e) => StorageResponse::Error {
                         message: format!("LearnWithEmbedding failed: {:?}", e),
                     },
@@ -747,6 +2051,7 @@ impl StorageServer {
                 embedding,
                 strength,
                 confidence,
+                idempotency_key: _,
             } => {
                 let storage = self.get_storage(namespace);
                 // ✅ PRODUCTION: Validate content size
@@ -788,6 +2093,9 @@ impl StorageServer {
                     std::collections::HashMap::new(),
                 ) {
                     Ok(sequence) => StorageResponse::LearnConceptOk { sequence },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => {
+                        backpressure_response(&e)
+                    }
                     Err(e) => StorageResponse::Error {
                         message: format!("Learn concept failed: {:?}", e),
                     },
@@ -800,6 +2108,7 @@ impl StorageServer {
                 target_id,
                 assoc_type,
                 confidence,
+                idempotency_key: _,
             } => {
                 let storage = self.get_storage(namespace);
                 let source = ConceptId::from_string(&source_id);
@@ -809,20 +2118,34 @@ impl StorageServer {
 
                 match storage.learn_association(source, target, atype, confidence) {
                     Ok(sequence) => StorageResponse::LearnAssociationOk { sequence },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => {
+                        backpressure_response(&e)
+                    }
                     Err(e) => StorageResponse::Error {
                         message: format!("Learn association failed: {:?}", e),
                     },
                 }
             }
 
+            StorageRequest::Transaction {
+                namespace,
+                operations,
+            } => {
+                let storage = self.get_storage(namespace);
+                transaction_response(&storage, operations)
+            }
+
             StorageRequest::QueryConcept {
                 namespace,
                 concept_id,
+                include_vector,
             } => {
                 let storage = self.get_storage(namespace);
                 let id = ConceptId::from_string(&concept_id);
 
                 if let Some(node) = storage.query_concept(&id) {
+                    // Feed access analytics and decay reinforcement
+                    storage.record_access(id);
                     StorageResponse::QueryConceptOk {
                         found: true,
                         concept_id: id.to_hex(),
@@ -830,6 +2153,7 @@ impl StorageServer {
                         strength: node.strength,
                         confidence: node.confidence,
                         attributes: node.attributes.clone(),
+                        vector: include_vector.then(|| response_vector(&node)).flatten(),
                     }
                 } else {
                     StorageResponse::QueryConceptOk {
@@ -839,6 +2163,7 @@ impl StorageServer {
                         strength: 0.0,
                         confidence: 0.0,
                         attributes: std::collections::HashMap::new(),
+                        vector: None,
                     }
                 }
             }
@@ -847,19 +2172,42 @@ impl StorageServer {
                 let storage = self.get_storage(Some(namespace));
                 let concept_id = ConceptId::from_string(&id);
                 match storage.delete_concept(concept_id) {
-                    Ok(_) => StorageResponse::DeleteConceptOk { id: id.to_string() },
+                    Ok(_) => {
+                        compact_in_background(&storage);
+                        StorageResponse::DeleteConceptOk { id: id.to_string() }
+                    }
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => {
+                        backpressure_response(&e)
+                    }
                     Err(e) => StorageResponse::Error {
                         message: format!("Delete failed: {:?}", e),
                     },
                 }
             }
 
+            StorageRequest::UpdateConcept {
+                namespace,
+                id,
+                strength,
+                confidence,
+                metadata_merge,
+            } => update_concept_response(
+                &self.get_storage(namespace),
+                id,
+                strength,
+                confidence,
+                metadata_merge,
+            ),
+
             StorageRequest::ClearCollection { namespace } => {
                 let storage = self.get_storage(Some(namespace.clone()));
                 match storage.clear() {
                     Ok(_) => StorageResponse::ClearCollectionOk {
                         namespace: namespace.to_string(),
                     },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => {
+                        backpressure_response(&e)
+                    }
                     Err(e) => StorageResponse::Error {
                         message: format!("Clear failed: {:?}", e),
                     },
@@ -883,8 +2231,10 @@ impl StorageServer {
                 start_id,
                 end_id,
                 max_depth,
+                deadline_ms,
+                namespaces,
             } => {
-                let storage = self.get_storage(namespace);
+                let deadline = deadline_from(deadline_ms);
                 // ✅ PRODUCTION: Validate path depth to prevent expensive queries
                 if max_depth > MAX_PATH_DEPTH {
                     return StorageResponse::Error {
@@ -894,22 +2244,15 @@ impl StorageServer {
                         ),
                     };
                 }
+                let shards = match query_shards(&self.namespaces, namespace, namespaces) {
+                    Ok(shards) => shards,
+                    Err(message) => return StorageResponse::Error { message },
+                };
 
                 let start = ConceptId::from_string(&start_id);
                 let end = ConceptId::from_string(&end_id);
 
-                if let Some(path) = storage.find_path(start, end, max_depth as usize) {
-                    let path_ids = path.iter().map(|id| id.to_hex()).collect();
-                    StorageResponse::FindPathOk {
-                        found: true,
-                        path: path_ids,
-                    }
-                } else {
-                    StorageResponse::FindPathOk {
-                        found: false,
-                        path: vec![],
-                    }
-                }
+                find_path_in(&shards, start, end, max_depth as usize, deadline)
             }
 
             StorageRequest::VectorSearch {
@@ -917,7 +2260,9 @@ impl StorageServer {
                 query_vector,
                 k,
                 ef_search,
+                deadline_ms,
             } => {
+                let deadline = deadline_from(deadline_ms);
                 let storage = self.get_storage(namespace);
                 // ✅ PRODUCTION: Validate query vector dimension
                 if query_vector.len() > MAX_EMBEDDING_DIM {
@@ -937,46 +2282,48 @@ impl StorageServer {
                     };
                 }
 
-                let results = storage.vector_search(&query_vector, k as usize, ef_search as usize);
-                let results_vec = results
-                    .into_iter()
-                    .map(|(id, sim)| (id.to_hex(), sim))
-                    .collect();
-
-                StorageResponse::VectorSearchOk {
-                    results: results_vec,
-                }
+                let permits = Arc::clone(&self.search_permits);
+                vector_search_response(storage, permits, query_vector, k, ef_search, deadline).await
             }
 
-            StorageRequest::ListRecent { namespace, limit } => {
-                let storage = self.get_storage(Some(namespace));
-                let snapshot = storage.get_snapshot();
-                let mut items: Vec<RecentItemMsg> = snapshot
-                    .concepts
-                    .values()
-                    .map(|node| RecentItemMsg {
-                        id: node.id.to_hex(),
-                        content_preview: String::from_utf8_lossy(&node.content)
-                            .chars()
-                            .take(200)
-                            .collect(),
-                        created: node.created,
-                        attributes: node.attributes.clone(),
-                    })
-                    .collect();
+            StorageRequest::VectorSearchBatch {
+                namespace,
+                queries,
+                k,
+                ef_search,
+            } => {
+                let permits = Arc::clone(&self.search_permits);
+                vector_search_batch_response(
+                    self.get_storage(namespace),
+                    permits,
+                    queries,
+                    k,
+                    ef_search,
+                )
+                .await
+            }
 
-                // Sort by created timestamp descending
-                items.sort_by(|a, b| b.created.cmp(&a.created));
-                items.truncate(limit as usize);
+            StorageRequest::ListRecent {
+                namespace,
+                limit,
+                cursor,
+            } => list_recent_response(&self.get_storage(Some(namespace)), limit, cursor.as_deref()),
 
-                StorageResponse::ListRecentOk { items }
-            }
+            StorageRequest::QueryByMetadata {
+                namespace,
+                attributes,
+                limit,
+            } => query_by_metadata_response(&self.get_storage(namespace), &attributes, limit),
 
             StorageRequest::GetStats { namespace } => {
                 let storage = self.get_storage(namespace);
                 let stats = storage.stats();
                 let hnsw_stats = storage.hnsw_stats();
+                let namespace_stats = self.namespaces.stats();
                 let uptime = self.start_time.elapsed().as_secs();
+                let cache_stats = self.pipeline.embedding_cache().map(|c| c.stats());
+                let rate_stats = self.rate_limits.as_ref().map(|r| r.stats());
+                let quota = storage.quota_usage();
 
                 StorageResponse::StatsOk {
                     concepts: stats.snapshot.concept_count as u64,
@@ -987,6 +2334,114 @@ impl StorageServer {
                     pending: stats.write_log.pending as u64,
                     reconciliations: stats.reconciler.reconciliations,
                     uptime_seconds: uptime,
+                    embedding_cache_hits: cache_stats.map_or(0, |c| c.hits),
+                    embedding_cache_misses: cache_stats.map_or(0, |c| c.misses),
+                    replication_lag: self.replica_status().map_or(0, |r| r.lag()),
+                    attribute_index_entries: stats.snapshot.attribute_index_entries as u64,
+                    namespaces_open: namespace_stats.open as u64,
+                    namespaces_evicted: namespace_stats.evicted,
+                    hnsw_tombstone_ratio: hnsw_stats.tombstone_ratio(),
+                    last_reindex_us: hnsw_stats.last_reindex_us.unwrap_or(0),
+                    storage_threads: stats.storage_threads as u64,
+                    rate_limited_requests: rate_stats.as_ref().map_or(0, |r| r.rejected),
+                    rate_limited_peers: rate_stats.as_ref().map_or(0, |r| {
+                        (r.reads.throttled_subjects + r.writes.throttled_subjects) as u64
+                    }),
+                    quota_max_concepts: quota.map_or(0, |q| q.quota.max_concepts as u64),
+                    quota_max_bytes: quota.map_or(0, |q| q.quota.max_bytes),
+                    quota_used_concepts: quota.map_or(0, |q| q.concepts as u64),
+                    quota_used_bytes: quota.map_or(0, |q| q.bytes),
+                    quota_evicted: quota.map_or(0, |q| q.evicted),
+                }
+            }
+
+            StorageRequest::TopAccessed { namespace, limit } => {
+                let storage = self.get_storage(namespace);
+                StorageResponse::AccessRankingOk {
+                    concepts: storage
+                        .top_accessed(limit.min(MAX_SEARCH_K) as usize)
+                        .into_iter()
+                        .map(AccessRankMsg::from)
+                        .collect(),
+                }
+            }
+
+            StorageRequest::ColdestConcepts { namespace, limit } => {
+                let storage = self.get_storage(namespace);
+                StorageResponse::AccessRankingOk {
+                    concepts: storage
+                        .coldest_concepts(limit.min(MAX_SEARCH_K) as usize)
+                        .into_iter()
+                        .map(AccessRankMsg::from)
+                        .collect(),
+                }
+            }
+
+            StorageRequest::GetGaps {
+                namespace,
+                kinds,
+                limit,
+            } => {
+                let config = self.autonomy.read().gap_detector_config().clone();
+                get_gaps_response(self.get_storage(namespace), config, kinds, limit).await
+            }
+
+            StorageRequest::ReplicationSnapshot {
+                namespace,
+                transfer_id,
+                cursor,
+            } => {
+                let storage = self.get_storage(namespace);
+                let Some(log) = storage.replication_log() else {
+                    return StorageResponse::Error {
+                        message: "Replication log disabled on this server".to_string(),
+                    };
+                };
+                let transfer_id = transfer_id.unwrap_or_else(|| {
+                    // Read the sequence before the snapshot: everything below
+                    // it is already visible, anything newer is replayed
+                    let sequence = log.next_sequence();
+                    log.start_transfer(storage.get_snapshot(), sequence)
+                });
+                // Half the frame limit leaves room for encoding overhead
+                match log.snapshot_chunk(transfer_id, cursor, self.max_message_size / 2) {
+                    Some(chunk) => StorageResponse::ReplicationSnapshotOk {
+                        sequence: chunk.sequence,
+                        ops: chunk.ops,
+                        transfer_id: Some(chunk.transfer_id),
+                        next_cursor: chunk.next_cursor,
+                    },
+                    None => StorageResponse::Error {
+                        message: format!("Unknown or expired snapshot transfer {}", transfer_id),
+                    },
+                }
+            }
+
+            StorageRequest::ReplicationPull {
+                namespace,
+                from_sequence,
+                max_entries,
+            } => {
+                let storage = self.get_storage(namespace);
+                match storage.replication_log() {
+                    Some(log) => {
+                        let primary_sequence = log.next_sequence();
+                        match log.read_from(
+                            from_sequence,
+                            max_entries.min(MAX_REPLICATION_BATCH) as usize,
+                        ) {
+                            Ok(records) => StorageResponse::ReplicationBatchOk {
+                                records,
+                                primary_sequence,
+                            },
+                            Err(oldest_sequence) => {
+                                StorageResponse::ReplicationResyncRequired { oldest_sequence }
+                            }
+                        }
+                    }
+                    None => StorageResponse::Error {
+                        message: "Replication log disabled on this server".to_string(),
+                    },
                 }
             }
 
@@ -997,6 +2452,10 @@ impl StorageServer {
                 },
             },
 
+            StorageRequest::Reindex { namespace } => {
+                reindex_response(self.get_storage(namespace)).await
+            }
+
             StorageRequest::HealthCheck => {
                 let uptime = self.start_time.elapsed().as_secs();
                 StorageResponse::HealthCheckOk {
@@ -1006,52 +2465,53 @@ impl StorageServer {
                         self.namespaces.list_namespaces().len()
                     ),
                     uptime_seconds: uptime,
+                    max_message_size: self.max_message_size as u64,
                 }
             }
 
-            // 🔥 NEW: Semantic query handlers
-            StorageRequest::FindPathSemantic {
-                namespace,
-                start_id,
-                end_id,
-                filter,
-                max_depth,
-                max_paths,
-            } => self.handle_find_path_semantic(
-                namespace, start_id, end_id, filter, max_depth, max_paths,
-            ),
-
-            StorageRequest::FindTemporalChain {
-                namespace,
-                domain,
-                start_time,
-                end_time,
-            } => self.handle_find_temporal_chain(namespace, domain, start_time, end_time),
+            StorageRequest::Handshake { protocol_version } => {
+                handshake_response(protocol_version, self.max_message_size)
+            }
 
-            StorageRequest::FindCausalChain {
-                namespace,
-                start_id,
-                causal_type,
-                max_depth,
-            } => self.handle_find_causal_chain(namespace, start_id, causal_type, max_depth),
+            // Connections answer this themselves; there is none to push to here
+            StorageRequest::SubscribeChanges { .. } => StorageResponse::Error {
+                message: "SubscribeChanges needs a client connection".to_string(),
+            },
 
-            StorageRequest::FindContradictions { namespace, domain } => {
-                self.handle_find_contradictions(namespace, domain)
+            // 🔥 NEW: Semantic query handlers
+            request @ (StorageRequest::FindPathSemantic { .. }
+            | StorageRequest::FindTemporalChain { .. }
+            | StorageRequest::FindCausalChain { .. }
+            | StorageRequest::FindContradictions { .. }
+            | StorageRequest::QueryBySemantic { .. }) => {
+                semantic_query_response(&self.namespaces, request)
             }
 
-            StorageRequest::QueryBySemantic {
-                namespace,
-                filter,
-                limit,
-            } => self.handle_query_by_semantic(namespace, filter, limit),
             StorageRequest::TextSearch {
                 namespace,
                 query,
                 limit,
+                snippet_len,
+                alpha,
             } => {
                 let storage = self.get_storage(namespace);
-                match self.pipeline.search(&storage, &query, limit as usize).await {
+                let limit = limit.min(MAX_SEARCH_K);
+                let results = match alpha {
+                    Some(alpha) => {
+                        self.pipeline
+                            .hybrid_search(&storage, &query, limit as usize, alpha)
+                            .await
+                    }
+                    None => self.pipeline.search(&storage, &query, limit as usize).await,
+                };
+                match results {
                     Ok(results) => StorageResponse::TextSearchOk {
+                        highlights: TextHighlightMsg::for_results(
+                            &storage,
+                            &results,
+                            &query,
+                            snippet_len,
+                        ),
                         results: results
                             .into_iter()
                             .map(|(id, score)| (id.to_hex(), score))
@@ -1167,6 +2627,7 @@ impl StorageServer {
                 let autonomy = self.autonomy.read();
                 let adjustments = autonomy.feedback_processor().process(
                     autonomy.storage(),
+                    caller,
                     &result_concept_ids,
                     &accepted,
                     ranking.as_deref(),
@@ -1183,267 +2644,793 @@ impl StorageServer {
             }
         }
     }
+}
 
-    // 🔥 NEW: Semantic query implementation methods
-    fn handle_find_path_semantic(
-        &self,
-        namespace: Option<String>,
-        start_id: String,
-        end_id: String,
-        filter_msg: SemanticFilterMsg,
-        max_depth: u32,
-        max_paths: u32,
-    ) -> StorageResponse {
-        let storage = self.get_storage(namespace);
-        use crate::semantic::{
-            CausalFilter, SemanticFilter, SemanticPathFinder, TemporalConstraint,
-        };
-
-        let start = ConceptId::from_string(&start_id);
-        let end = ConceptId::from_string(&end_id);
-
-        // Convert message filter to internal filter
-        let mut filter = SemanticFilter::new();
-
-        if let Some(ref st) = filter_msg.semantic_type {
-            if let Some(semantic_type) = parse_semantic_type(st) {
-                filter = filter.with_type(semantic_type);
-            }
-        }
-
-        if let Some(ref dc) = filter_msg.domain_context {
-            if let Some(domain) = parse_domain_context(dc) {
-                filter = filter.with_domain(domain);
-            }
-        }
-
-        if let Some(after) = filter_msg.temporal_after {
-            filter = filter.with_temporal(TemporalConstraint::After(after));
-        }
-
-        if let Some(before) = filter_msg.temporal_before {
-            filter = filter.with_temporal(TemporalConstraint::Before(before));
-        }
+// Helper functions for parsing semantic types from strings
+use crate::types::{AssociationType, ConceptId};
 
-        if filter_msg.has_causal_relation {
-            filter = filter.with_causal(CausalFilter::HasCausalRelation);
+impl SemanticPathMsg {
+    fn from_path(path: crate::semantic::SemanticPath) -> Self {
+        Self {
+            concepts: path.concepts.iter().map(|id| id.to_hex()).collect(),
+            confidence: path.confidence,
+            type_distribution: path
+                .type_distribution
+                .into_iter()
+                .map(|(t, c)| (t.as_str().to_string(), c))
+                .collect(),
+            domains: path
+                .domains
+                .into_iter()
+                .map(|d| d.as_str().to_string())
+                .collect(),
+            is_temporally_ordered: path.is_temporally_ordered,
         }
+    }
+}
 
-        filter = filter.with_min_confidence(filter_msg.min_confidence);
-
-        for term in filter_msg.required_terms {
-            filter = filter.with_term(term);
-        }
+fn semantic_filter(msg: SemanticFilterMsg) -> crate::semantic::SemanticFilter {
+    use crate::semantic::{CausalFilter, SemanticFilter, TemporalConstraint};
 
-        // Create pathfinder
-        let pathfinder = SemanticPathFinder::new(max_depth as usize, max_paths as usize);
-        let snapshot = storage.get_snapshot();
+    let mut filter = SemanticFilter::new();
 
-        // Find paths
-        let paths = pathfinder.find_paths_filtered(snapshot, start, end, &filter);
+    if let Some(semantic_type) = msg.semantic_type.as_deref().and_then(parse_semantic_type) {
+        filter = filter.with_type(semantic_type);
+    }
 
-        // Convert to message format
-        let path_msgs: Vec<SemanticPathMsg> = paths
-            .into_iter()
-            .map(|p| SemanticPathMsg {
-                concepts: p.concepts.iter().map(|id| id.to_hex()).collect(),
-                confidence: p.confidence,
-                type_distribution: p
-                    .type_distribution
-                    .into_iter()
-                    .map(|(t, c)| (t.as_str().to_string(), c))
-                    .collect(),
-                domains: p
-                    .domains
-                    .into_iter()
-                    .map(|d| d.as_str().to_string())
-                    .collect(),
-                is_temporally_ordered: p.is_temporally_ordered,
-            })
-            .collect();
+    if let Some(domain) = msg.domain_context.as_deref().and_then(parse_domain_context) {
+        filter = filter.with_domain(domain);
+    }
 
-        StorageResponse::FindPathSemanticOk { paths: path_msgs }
+    if let Some(after) = msg.temporal_after {
+        filter = filter.with_temporal(TemporalConstraint::After(after));
     }
 
-    fn handle_find_temporal_chain(
-        &self,
-        namespace: Option<String>,
-        domain: Option<String>,
-        start_time: i64,
-        end_time: i64,
-    ) -> StorageResponse {
-        let storage = self.get_storage(namespace);
-        use crate::semantic::SemanticPathFinder;
-
-        let domain_ctx = domain.and_then(|d| parse_domain_context(&d));
-        let pathfinder = SemanticPathFinder::default();
-        let snapshot = storage.get_snapshot();
-
-        let paths = pathfinder.find_temporal_chain(snapshot, domain_ctx, start_time, end_time);
-
-        let path_msgs: Vec<SemanticPathMsg> = paths
-            .into_iter()
-            .map(|p| SemanticPathMsg {
-                concepts: p.concepts.iter().map(|id| id.to_hex()).collect(),
-                confidence: p.confidence,
-                type_distribution: p
-                    .type_distribution
-                    .into_iter()
-                    .map(|(t, c)| (t.as_str().to_string(), c))
-                    .collect(),
-                domains: p
-                    .domains
-                    .into_iter()
-                    .map(|d| d.as_str().to_string())
-                    .collect(),
-                is_temporally_ordered: p.is_temporally_ordered,
-            })
-            .collect();
+    if let Some(before) = msg.temporal_before {
+        filter = filter.with_temporal(TemporalConstraint::Before(before));
+    }
 
-        StorageResponse::FindTemporalChainOk { paths: path_msgs }
+    if msg.has_causal_relation {
+        filter = filter.with_causal(CausalFilter::HasCausalRelation);
     }
 
-    fn handle_find_causal_chain(
-        &self,
-        namespace: Option<String>,
-        start_id: String,
-        causal_type: String,
-        max_depth: u32,
-    ) -> StorageResponse {
-        let storage = self.get_storage(namespace);
-        use crate::semantic::SemanticPathFinder;
-
-        let start = ConceptId::from_string(&start_id);
-        let causal = parse_causal_type(&causal_type).unwrap_or(CausalType::Direct);
-
-        let pathfinder = SemanticPathFinder::new(max_depth as usize, 100);
-        let snapshot = storage.get_snapshot();
-
-        let paths = pathfinder.find_causal_chain(snapshot, start, causal);
-
-        let path_msgs: Vec<SemanticPathMsg> = paths
-            .into_iter()
-            .map(|p| SemanticPathMsg {
-                concepts: p.concepts.iter().map(|id| id.to_hex()).collect(),
-                confidence: p.confidence,
-                type_distribution: p
-                    .type_distribution
-                    .into_iter()
-                    .map(|(t, c)| (t.as_str().to_string(), c))
-                    .collect(),
-                domains: p
-                    .domains
-                    .into_iter()
-                    .map(|d| d.as_str().to_string())
-                    .collect(),
-                is_temporally_ordered: p.is_temporally_ordered,
-            })
-            .collect();
+    filter = filter.with_min_confidence(msg.min_confidence);
 
-        StorageResponse::FindCausalChainOk { paths: path_msgs }
+    for term in msg.required_terms {
+        filter = filter.with_term(term);
     }
 
-    fn handle_find_contradictions(
-        &self,
-        namespace: Option<String>,
-        domain: String,
-    ) -> StorageResponse {
-        let storage = self.get_storage(namespace);
-        use crate::semantic::SemanticPathFinder;
+    filter
+}
 
-        let domain_ctx = parse_domain_context(&domain).unwrap_or(DomainContext::General);
-        let pathfinder = SemanticPathFinder::default();
-        let snapshot = storage.get_snapshot();
+/// Combine per-shard paths: duplicates dropped, most confident first
+fn merge_semantic_paths(
+    paths: impl IntoIterator<Item = crate::semantic::SemanticPath>,
+    max_paths: usize,
+) -> Vec<SemanticPathMsg> {
+    let mut seen = std::collections::HashSet::new();
+    let mut merged: Vec<SemanticPathMsg> = paths
+        .into_iter()
+        .filter(|path| seen.insert(path.concepts.clone()))
+        .map(SemanticPathMsg::from_path)
+        .collect();
+    merged.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    merged.truncate(max_paths);
+    merged
+}
 
-        let contradictions = pathfinder.find_contradictions(snapshot, domain_ctx);
+// Semantic queries run on each shard's snapshot independently and merge the
+// results. A query reads one namespace unless it lists several in `namespaces`.
 
-        let contradiction_msgs: Vec<(String, String, String)> = contradictions
-            .into_iter()
-            .map(|(id1, id2, reason)| (id1.to_hex(), id2.to_hex(), reason))
-            .collect();
+/// Most namespaces one query may list in `namespaces`
+const MAX_QUERY_NAMESPACES: usize = 64;
 
-        StorageResponse::FindContradictionsOk {
-            contradictions: contradiction_msgs,
-        }
+/// Namespaces a read query covers, opened
+struct QueryShards {
+    names: Vec<String>,
+    stores: Vec<Arc<ConcurrentMemory>>,
+}
+
+/// Resolve a query's `namespace` and `namespaces`
+///
+/// Listing `namespaces` opts in to searching them together; otherwise the
+/// query reads `namespace`, or the default namespace. Naming both is an error.
+fn query_shards(
+    manager: &NamespaceManager,
+    namespace: Option<String>,
+    namespaces: Vec<String>,
+) -> Result<QueryShards, String> {
+    let mut names = match (namespace, namespaces.is_empty()) {
+        (Some(_), false) => return Err("Pass either namespace or namespaces, not both".to_string()),
+        (Some(ns), true) => vec![ns],
+        (None, true) => vec!["default".to_string()],
+        (None, false) => namespaces,
+    };
+    if names.len() > MAX_QUERY_NAMESPACES {
+        return Err(format!(
+            "Too many namespaces: {} (max: {})",
+            names.len(),
+            MAX_QUERY_NAMESPACES
+        ));
     }
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
 
-    fn handle_query_by_semantic(
-        &self,
-        namespace: Option<String>,
-        filter_msg: SemanticFilterMsg,
-        limit: Option<usize>,
-    ) -> StorageResponse {
-        let storage = self.get_storage(namespace);
-        use crate::semantic::{CausalFilter, SemanticFilter, TemporalConstraint};
+    let stores = names.iter().map(|ns| manager.get_namespace(ns)).collect();
+    Ok(QueryShards { names, stores })
+}
 
-        // Convert message filter to internal filter
-        let mut filter = SemanticFilter::new();
+/// `FindPath` within one namespace, or across several through the concepts
+/// they share
+fn find_path_in(
+    shards: &QueryShards,
+    start: ConceptId,
+    end: ConceptId,
+    max_depth: usize,
+    deadline: Option<std::time::Instant>,
+) -> StorageResponse {
+    if let [storage] = shards.stores.as_slice() {
+        return find_path_response(storage.find_path_until(start, end, max_depth, deadline));
+    }
+    match find_path_across_shards(&shards.stores, start, end, max_depth, deadline) {
+        Ok(Some(path)) => StorageResponse::FindPathOk {
+            found: true,
+            path: path.concepts.iter().map(|id| id.to_hex()).collect(),
+            deadline_exceeded: false,
+            shards: path
+                .shards
+                .iter()
+                .map(|&i| shards.names[i].clone())
+                .collect(),
+        },
+        Ok(None) => find_path_response(Ok(None)),
+        Err(e) => find_path_response(Err(e)),
+    }
+}
 
-        if let Some(ref st) = filter_msg.semantic_type {
-            if let Some(semantic_type) = parse_semantic_type(st) {
-                filter = filter.with_type(semantic_type);
-            }
+/// Answer a semantic query on the namespaces it names
+fn semantic_query_response(manager: &NamespaceManager, request: StorageRequest) -> StorageResponse {
+    let (namespace, namespaces) = match &request {
+        StorageRequest::FindPathSemantic {
+            namespace,
+            namespaces,
+            ..
         }
-
-        if let Some(ref dc) = filter_msg.domain_context {
-            if let Some(domain) = parse_domain_context(dc) {
-                filter = filter.with_domain(domain);
+        | StorageRequest::FindTemporalChain {
+            namespace,
+            namespaces,
+            ..
+        }
+        | StorageRequest::FindCausalChain {
+            namespace,
+            namespaces,
+            ..
+        }
+        | StorageRequest::FindContradictions {
+            namespace,
+            namespaces,
+            ..
+        }
+        | StorageRequest::QueryBySemantic {
+            namespace,
+            namespaces,
+            ..
+        } => (namespace.clone(), namespaces.clone()),
+        other => {
+            return StorageResponse::Error {
+                message: format!("Not a semantic query: {:?}", other),
             }
         }
-
-        if let Some(after) = filter_msg.temporal_after {
-            filter = filter.with_temporal(TemporalConstraint::After(after));
+    };
+    let shards = match query_shards(manager, namespace, namespaces) {
+        Ok(shards) => shards.stores,
+        Err(message) => return StorageResponse::Error { message },
+    };
+
+    match request {
+        StorageRequest::FindPathSemantic {
+            start_id,
+            end_id,
+            filter,
+            max_depth,
+            max_paths,
+            max_nodes_visited,
+            timeout_ms,
+            deadline_ms,
+            ..
+        } => find_path_semantic_response(
+            &shards,
+            &start_id,
+            &end_id,
+            filter,
+            max_depth,
+            max_paths,
+            max_nodes_visited,
+            timeout_ms,
+            deadline_ms,
+        ),
+        StorageRequest::FindTemporalChain {
+            domain,
+            start_time,
+            end_time,
+            ..
+        } => find_temporal_chain_response(&shards, domain, start_time, end_time),
+        StorageRequest::FindCausalChain {
+            start_id,
+            causal_type,
+            max_depth,
+            ..
+        } => find_causal_chain_response(&shards, &start_id, &causal_type, max_depth),
+        StorageRequest::FindContradictions { domain, .. } => {
+            find_contradictions_response(&shards, &domain)
         }
-
-        if let Some(before) = filter_msg.temporal_before {
-            filter = filter.with_temporal(TemporalConstraint::Before(before));
+        StorageRequest::QueryBySemantic { filter, limit, .. } => {
+            query_by_semantic_response(&shards, filter, limit)
         }
+        _ => unreachable!("checked above"),
+    }
+}
 
-        if filter_msg.has_causal_relation {
-            filter = filter.with_causal(CausalFilter::HasCausalRelation);
+#[allow(clippy::too_many_arguments)]
+fn find_path_semantic_response(
+    shards: &[Arc<ConcurrentMemory>],
+    start_id: &str,
+    end_id: &str,
+    filter: SemanticFilterMsg,
+    max_depth: u32,
+    max_paths: u32,
+    max_nodes_visited: Option<u32>,
+    timeout_ms: Option<u64>,
+    deadline_ms: Option<u64>,
+) -> StorageResponse {
+    use crate::semantic::SemanticPathFinder;
+
+    let start = ConceptId::from_string(start_id);
+    let end = ConceptId::from_string(end_id);
+    let filter = semantic_filter(filter);
+
+    // ✅ PRODUCTION: Always bound the traversal so dense graphs can't pin a worker
+    let max_nodes = max_nodes_visited
+        .unwrap_or(MAX_PATH_NODES_VISITED)
+        .min(MAX_PATH_NODES_VISITED);
+    let timeout = timeout_ms
+        .unwrap_or(MAX_PATH_TIMEOUT_MS)
+        .min(MAX_PATH_TIMEOUT_MS)
+        .min(deadline_ms.unwrap_or(u64::MAX));
+    // One budget for the whole request, however many shards it spans
+    let budget_end = std::time::Instant::now() + std::time::Duration::from_millis(timeout);
+    let deadline = deadline_from(deadline_ms);
+
+    let mut paths = Vec::new();
+    let mut truncated = false;
+    let (mut has_start, mut has_end, mut has_both) = (false, false, false);
+    for shard in shards {
+        let snapshot = shard.get_snapshot();
+        let (start_here, end_here) = (snapshot.contains(&start), snapshot.contains(&end));
+        has_start |= start_here;
+        has_end |= end_here;
+        if !(start_here && end_here) {
+            continue;
         }
+        has_both = true;
 
-        filter = filter.with_min_confidence(filter_msg.min_confidence);
-
-        for term in filter_msg.required_terms {
-            filter = filter.with_term(term);
+        let remaining = budget_end.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            truncated = true;
+            break;
         }
+        let pathfinder = SemanticPathFinder::new(max_depth as usize, max_paths as usize)
+            .with_max_nodes_visited(max_nodes as usize)
+            .with_timeout(remaining);
+        let result = pathfinder.find_paths_bounded(snapshot, start, end, &filter);
+        truncated |= result.truncated;
+        paths.extend(result.paths);
+    }
 
-        // Query concepts
-        let snapshot = storage.get_snapshot();
-        let mut concepts = Vec::new();
+    StorageResponse::FindPathSemanticOk {
+        paths: merge_semantic_paths(paths, max_paths as usize),
+        truncated,
+        deadline_exceeded: truncated && deadline_passed(deadline),
+        cross_shard: has_start && has_end && !has_both,
+    }
+}
 
-        for concept in snapshot.all_concepts() {
-            if let Some(ref semantic) = concept.semantic {
-                let content = String::from_utf8_lossy(&concept.content);
-                if filter.matches(semantic, &content, &concept.id) {
-                    concepts.push(ConceptWithSemanticMsg {
-                        concept_id: concept.id.to_hex(),
-                        content: content.to_string(),
-                        semantic_type: semantic.semantic_type.as_str().to_string(),
-                        domain: semantic.domain_context.as_str().to_string(),
-                        confidence: semantic.classification_confidence,
-                    });
-
-                    if let Some(lim) = limit {
-                        if concepts.len() >= lim {
-                            break;
-                        }
-                    }
-                }
-            }
+fn find_temporal_chain_response(
+    shards: &[Arc<ConcurrentMemory>],
+    domain: Option<String>,
+    start_time: i64,
+    end_time: i64,
+) -> StorageResponse {
+    use crate::semantic::SemanticPathFinder;
+
+    let domain = domain.and_then(|d| parse_domain_context(&d));
+    let pathfinder = SemanticPathFinder::default();
+    let paths = shards.iter().flat_map(|shard| {
+        pathfinder.find_temporal_chain(shard.get_snapshot(), domain, start_time, end_time)
+    });
+
+    StorageResponse::FindTemporalChainOk {
+        paths: merge_semantic_paths(paths, usize::MAX),
+    }
+}
+
+fn find_causal_chain_response(
+    shards: &[Arc<ConcurrentMemory>],
+    start_id: &str,
+    causal_type: &str,
+    max_depth: u32,
+) -> StorageResponse {
+    use crate::semantic::SemanticPathFinder;
+
+    let start = ConceptId::from_string(start_id);
+    let causal = parse_causal_type(causal_type).unwrap_or(CausalType::Direct);
+    let pathfinder = SemanticPathFinder::new(max_depth as usize, 100);
+
+    let mut paths = Vec::new();
+    let mut cross_shard = false;
+    for (index, shard) in shards.iter().enumerate() {
+        for path in pathfinder.find_causal_chain(shard.get_snapshot(), start, causal) {
+            // The chain stops here, but another shard may link its last concept onward
+            let last = path.concepts.last().copied().unwrap_or(start);
+            cross_shard |= shards.iter().enumerate().any(|(other, shard)| {
+                other != index
+                    && shard
+                        .query_concept(&last)
+                        .is_some_and(|node| !node.neighbors.is_empty())
+            });
+            paths.push(path);
         }
+    }
 
-        StorageResponse::QueryBySemanticOk { concepts }
+    StorageResponse::FindCausalChainOk {
+        paths: merge_semantic_paths(paths, 100),
+        cross_shard,
     }
 }
 
-// Helper functions for parsing semantic types from strings
-use crate::types::ConceptId;
+fn find_contradictions_response(shards: &[Arc<ConcurrentMemory>], domain: &str) -> StorageResponse {
+    use crate::semantic::SemanticPathFinder;
 
-fn parse_semantic_type(s: &str) -> Option<SemanticType> {
-    match s.to_lowercase().as_str() {
-        "entity" => Some(SemanticType::Entity),
+    let domain = parse_domain_context(domain).unwrap_or(DomainContext::General);
+    let pathfinder = SemanticPathFinder::default();
+
+    let mut seen = std::collections::HashSet::new();
+    let contradictions = shards
+        .iter()
+        .flat_map(|shard| pathfinder.find_contradictions(shard.get_snapshot(), domain))
+        .filter(|(id1, id2, _)| seen.insert((*id1, *id2)))
+        .map(|(id1, id2, reason)| (id1.to_hex(), id2.to_hex(), reason))
+        .collect();
+
+    StorageResponse::FindContradictionsOk { contradictions }
+}
+
+fn query_by_semantic_response(
+    shards: &[Arc<ConcurrentMemory>],
+    filter: SemanticFilterMsg,
+    limit: Option<usize>,
+) -> StorageResponse {
+    let filter = semantic_filter(filter);
+    let limit = limit.unwrap_or(usize::MAX);
+
+    let mut seen = std::collections::HashSet::new();
+    let mut concepts = Vec::new();
+    'shards: for shard in shards {
+        let snapshot = shard.get_snapshot();
+        for concept in snapshot.all_concepts() {
+            let Some(ref semantic) = concept.semantic else {
+                continue;
+            };
+            let content = String::from_utf8_lossy(&concept.content);
+            if !filter.matches(semantic, &content, &concept.id) || !seen.insert(concept.id) {
+                continue;
+            }
+            concepts.push(ConceptWithSemanticMsg {
+                concept_id: concept.id.to_hex(),
+                content: content.to_string(),
+                semantic_type: semantic.semantic_type.as_str().to_string(),
+                domain: semantic.domain_context.as_str().to_string(),
+                confidence: semantic.classification_confidence,
+            });
+            if concepts.len() >= limit {
+                break 'shards;
+            }
+        }
+    }
+
+    StorageResponse::QueryBySemanticOk { concepts }
+}
+
+/// Stored embedding of `node` for a response, omitted beyond `MAX_EMBEDDING_DIM`
+fn response_vector(node: &crate::read_view::ConceptNode) -> Option<Vec<f32>> {
+    node.vector
+        .as_ref()
+        .filter(|v| v.len() <= MAX_EMBEDDING_DIM)
+        .map(|v| v.to_vec())
+}
+
+/// Validate and apply a `Transaction` request; nothing is applied on error
+fn transaction_response(
+    storage: &ConcurrentMemory,
+    operations: Vec<TxnOperationMsg>,
+) -> StorageResponse {
+    if operations.len() > MAX_BATCH_SIZE {
+        return StorageResponse::Error {
+            message: format!(
+                "Transaction too large: {} operations (max: {})",
+                operations.len(),
+                MAX_BATCH_SIZE
+            ),
+        };
+    }
+
+    let mut concept_ids = Vec::new();
+    let mut writes = Vec::with_capacity(operations.len());
+    for (index, op) in operations.into_iter().enumerate() {
+        let aborted = |reason: String| StorageResponse::Error {
+            message: format!("Transaction aborted: operation {}: {}", index, reason),
+        };
+        match op {
+            TxnOperationMsg::LearnConcept {
+                concept_id,
+                content,
+                embedding,
+                strength,
+                confidence,
+                attributes,
+            } => {
+                if content.len() > MAX_CONTENT_SIZE {
+                    return aborted(format!(
+                        "content too large: {} bytes (max: {})",
+                        content.len(),
+                        MAX_CONTENT_SIZE
+                    ));
+                }
+                if embedding.len() > MAX_EMBEDDING_DIM {
+                    return aborted(format!(
+                        "embedding dimension too large: {} (max: {})",
+                        embedding.len(),
+                        MAX_EMBEDDING_DIM
+                    ));
+                }
+                let id = ConceptId::from_string(concept_id.as_deref().unwrap_or(&content));
+                concept_ids.push(id.to_hex());
+                writes.push(crate::concurrent_memory::AtomicWrite::Concept {
+                    id,
+                    content: content.into_bytes(),
+                    vector: (!embedding.is_empty()).then_some(embedding),
+                    strength,
+                    confidence,
+                    attributes,
+                });
+            }
+            TxnOperationMsg::LearnAssociation {
+                source_id,
+                target_id,
+                assoc_type,
+                confidence,
+            } => writes.push(crate::concurrent_memory::AtomicWrite::Association {
+                source: ConceptId::from_string(&source_id),
+                target: ConceptId::from_string(&target_id),
+                assoc_type: AssociationType::from_u8(assoc_type as u8)
+                    .unwrap_or(AssociationType::Semantic),
+                confidence,
+            }),
+        }
+    }
+
+    match storage.learn_atomic(writes) {
+        Ok(sequence) => StorageResponse::TransactionOk {
+            concept_ids,
+            sequence,
+        },
+        Err(crate::transaction::TxnError::WriteLogBusy(e)) => backpressure_response(&e),
+        Err(e) => StorageResponse::Error {
+            message: format!("Transaction aborted: {}", e),
+        },
+    }
+}
+
+/// Apply an `UpdateConcept` request to an existing concept
+fn update_concept_response(
+    storage: &ConcurrentMemory,
+    id: String,
+    strength: Option<f32>,
+    confidence: Option<f32>,
+    metadata_merge: std::collections::HashMap<String, String>,
+) -> StorageResponse {
+    for (field, value) in [("strength", strength), ("confidence", confidence)] {
+        if let Some(value) = value.filter(|v| !(0.0..=1.0).contains(v)) {
+            return StorageResponse::Error {
+                message: format!("{} must be between 0.0 and 1.0, got {}", field, value),
+            };
+        }
+    }
+
+    // Learned concepts may still be waiting for the reconciler
+    let concept_id = ConceptId::from_string(&id);
+    if !storage.contains_or_pending(&concept_id) {
+        return StorageResponse::Error {
+            message: format!("Concept not found: {}", id),
+        };
+    }
+
+    match storage.update_concept(concept_id, strength, confidence, metadata_merge) {
+        Ok(sequence) => StorageResponse::UpdateConceptOk { id, sequence },
+        Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => backpressure_response(&e),
+        Err(e) => StorageResponse::Error {
+            message: format!("Update failed: {:?}", e),
+        },
+    }
+}
+
+/// Rebuild the HNSW index off the async runtime
+async fn reindex_response(storage: Arc<ConcurrentMemory>) -> StorageResponse {
+    match tokio::task::spawn_blocking(move || storage.reindex()).await {
+        Ok(Ok(Some(report))) => StorageResponse::ReindexOk {
+            indexed: report.indexed as u64,
+            tombstones_removed: report.tombstones_removed as u64,
+        },
+        Ok(Ok(None)) => StorageResponse::Error {
+            message: "Reindex already in progress".to_string(),
+        },
+        Ok(Err(e)) => StorageResponse::Error {
+            message: format!("Reindex failed: {}", e),
+        },
+        Err(e) => StorageResponse::Error {
+            message: format!("Reindex task failed: {}", e),
+        },
+    }
+}
+
+/// Validate and run a `VectorSearchBatch` request
+///
+/// Limits apply to the batch as a whole: at most `MAX_BATCH_SIZE` queries
+/// and `MAX_BATCH_SEARCH_RESULTS` requested results in total. `ef_search` is
+/// capped at `MAX_EF_SEARCH`.
+async fn vector_search_batch_response(
+    storage: Arc<ConcurrentMemory>,
+    permits: Arc<Semaphore>,
+    queries: Vec<Vec<f32>>,
+    k: u32,
+    ef_search: u32,
+) -> StorageResponse {
+    if queries.len() > MAX_BATCH_SIZE {
+        return StorageResponse::Error {
+            message: format!(
+                "Batch too large: {} queries (max: {})",
+                queries.len(),
+                MAX_BATCH_SIZE
+            ),
+        };
+    }
+    if k > MAX_SEARCH_K {
+        return StorageResponse::Error {
+            message: format!("k too large: {} (max: {})", k, MAX_SEARCH_K),
+        };
+    }
+    let total_results = queries.len() * k as usize;
+    if total_results > MAX_BATCH_SEARCH_RESULTS {
+        return StorageResponse::Error {
+            message: format!(
+                "Batch requests too many results: {} queries × k {} (max: {})",
+                queries.len(),
+                k,
+                MAX_BATCH_SEARCH_RESULTS
+            ),
+        };
+    }
+    if let Some((index, query)) = queries
+        .iter()
+        .enumerate()
+        .find(|(_, q)| q.len() > MAX_EMBEDDING_DIM)
+    {
+        return StorageResponse::Error {
+            message: format!(
+                "Query {} vector dimension too large: {} (max: {})",
+                index,
+                query.len(),
+                MAX_EMBEDDING_DIM
+            ),
+        };
+    }
+
+    let ef_search = ef_search.min(MAX_EF_SEARCH);
+    let search = move || storage.vector_search_batch(&queries, k as usize, ef_search as usize);
+    // Keep a large batch from stalling the async worker serving other clients
+    match run_blocking_until(&permits, None, search).await {
+        Ok(Ok(results)) => StorageResponse::VectorSearchBatchOk {
+            results: results
+                .into_iter()
+                .map(|hits| {
+                    hits.into_iter()
+                        .map(|(id, sim)| (id.to_hex(), sim))
+                        .collect()
+                })
+                .collect(),
+        },
+        Ok(Err(e)) => StorageResponse::Error {
+            message: format!("Vector search task failed: {}", e),
+        },
+        Err(_) => StorageResponse::Error {
+            message: "Vector search batch deadline exceeded".to_string(),
+        },
+    }
+}
+
+/// One page of `ListRecent`, resuming after `cursor` if given
+///
+/// The cursor is `created:id` of the last item of the previous page. It is
+/// only returned when the page came back full.
+fn list_recent_response(
+    storage: &ConcurrentMemory,
+    limit: u32,
+    cursor: Option<&str>,
+) -> StorageResponse {
+    let before = match cursor.map(parse_recent_cursor) {
+        Some(None) => {
+            return StorageResponse::Error {
+                message: format!("Invalid ListRecent cursor: {}", cursor.unwrap_or_default()),
+            }
+        }
+        Some(before) => before,
+        None => None,
+    };
+
+    let limit = limit.min(MAX_SEARCH_K);
+    let nodes = storage.list_recent(limit as usize, before);
+    let next_cursor = nodes
+        .last()
+        .filter(|_| nodes.len() == limit as usize)
+        .map(|node| format!("{}:{}", node.created, node.id.to_hex()));
+    StorageResponse::ListRecentOk {
+        items: nodes.iter().map(RecentItemMsg::from_node).collect(),
+        next_cursor,
+    }
+}
+
+/// Newest concepts matching every attribute filter, at most `MAX_SEARCH_K`
+///
+/// An empty filter would match the whole namespace, so it is refused;
+/// `ListRecent` pages through everything instead.
+fn query_by_metadata_response(
+    storage: &ConcurrentMemory,
+    attributes: &std::collections::HashMap<String, String>,
+    limit: u32,
+) -> StorageResponse {
+    if attributes.is_empty() {
+        return StorageResponse::Error {
+            message: "QueryByMetadata needs at least one attribute filter".to_string(),
+        };
+    }
+    let limit = limit.min(MAX_SEARCH_K);
+    let concepts = storage
+        .query_by_attributes(attributes, limit as usize)
+        .iter()
+        .map(RecentItemMsg::from_node)
+        .collect();
+    StorageResponse::QueryByMetadataOk { concepts }
+}
+
+fn parse_recent_cursor(cursor: &str) -> Option<(u64, ConceptId)> {
+    let (created, id) = cursor.split_once(':')?;
+    let bytes: [u8; 16] = hex::decode(id).ok()?.try_into().ok()?;
+    Some((created.parse().ok()?, ConceptId(bytes)))
+}
+
+/// Absolute deadline for a request's `deadline_ms`, measured from now
+fn deadline_from(deadline_ms: Option<u64>) -> Option<std::time::Instant> {
+    deadline_ms.map(|ms| std::time::Instant::now() + std::time::Duration::from_millis(ms))
+}
+
+fn deadline_passed(deadline: Option<std::time::Instant>) -> bool {
+    deadline.is_some_and(|d| std::time::Instant::now() >= d)
+}
+
+/// Run blocking `work` off the async runtime, waiting no later than `deadline`
+///
+/// The work can't be interrupted: past the deadline it finishes on the
+/// blocking pool and its result is dropped. It holds one of `permits` until
+/// then, so abandoned work can't pile up on the pool.
+async fn run_blocking_until<T: Send + 'static>(
+    permits: &Arc<Semaphore>,
+    deadline: Option<std::time::Instant>,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Result<Result<T, tokio::task::JoinError>, crate::read_view::DeadlineExceeded> {
+    if deadline_passed(deadline) {
+        return Err(crate::read_view::DeadlineExceeded);
+    }
+    let acquire = Arc::clone(permits).acquire_owned();
+    let permit = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), acquire)
+            .await
+            .map_err(|_| crate::read_view::DeadlineExceeded)?,
+        None => acquire.await,
+    }
+    .expect("search permits are never closed");
+    let task = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        work()
+    });
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), task)
+            .await
+            .map_err(|_| crate::read_view::DeadlineExceeded),
+        None => Ok(task.await),
+    }
+}
+
+/// Beam width for a search of `k` results that has until `deadline`
+///
+/// Capped at `MAX_EF_SEARCH`, and narrowed to `SHORT_BUDGET_EF_SEARCH` (or `k`
+/// if larger) when less than `SHORT_SEARCH_BUDGET` is left.
+fn ef_search_for_budget(ef_search: u32, k: u32, deadline: Option<std::time::Instant>) -> u32 {
+    let ef_search = ef_search.min(MAX_EF_SEARCH);
+    match deadline {
+        Some(d) if d.saturating_duration_since(std::time::Instant::now()) < SHORT_SEARCH_BUDGET => {
+            ef_search.min(SHORT_BUDGET_EF_SEARCH.max(k))
+        }
+        _ => ef_search,
+    }
+}
+
+/// HNSW search that answers with `deadline_exceeded` once `deadline` passes
+async fn vector_search_response(
+    storage: Arc<ConcurrentMemory>,
+    permits: Arc<Semaphore>,
+    query_vector: Vec<f32>,
+    k: u32,
+    ef_search: u32,
+    deadline: Option<std::time::Instant>,
+) -> StorageResponse {
+    let ef_search = ef_search_for_budget(ef_search, k, deadline);
+    let search = move || storage.vector_search(&query_vector, k as usize, ef_search as usize);
+    match run_blocking_until(&permits, deadline, search).await {
+        Ok(Ok(results)) => StorageResponse::VectorSearchOk {
+            results: results
+                .into_iter()
+                .map(|(id, sim)| (id.to_hex(), sim))
+                .collect(),
+            deadline_exceeded: false,
+        },
+        Ok(Err(e)) => StorageResponse::Error {
+            message: format!("Vector search task failed: {}", e),
+        },
+        Err(_) => StorageResponse::VectorSearchOk {
+            results: vec![],
+            deadline_exceeded: true,
+        },
+    }
+}
+
+fn find_path_response(
+    result: Result<Option<Vec<crate::types::ConceptId>>, crate::read_view::DeadlineExceeded>,
+) -> StorageResponse {
+    match result {
+        Ok(Some(path)) => StorageResponse::FindPathOk {
+            found: true,
+            path: path.iter().map(|id| id.to_hex()).collect(),
+            deadline_exceeded: false,
+            shards: vec![],
+        },
+        Ok(None) => StorageResponse::FindPathOk {
+            found: false,
+            path: vec![],
+            deadline_exceeded: false,
+            shards: vec![],
+        },
+        Err(_) => StorageResponse::FindPathOk {
+            found: false,
+            path: vec![],
+            deadline_exceeded: true,
+            shards: vec![],
+        },
+    }
+}
+
+fn parse_semantic_type(s: &str) -> Option<SemanticType> {
+    match s.to_lowercase().as_str() {
+        "entity" => Some(SemanticType::Entity),
         "event" => Some(SemanticType::Event),
         "rule" => Some(SemanticType::Rule),
         "temporal" => Some(SemanticType::Temporal),
@@ -1485,6 +3472,11 @@ pub struct ShardedStorageServer {
     namespaces: Arc<NamespaceManager>,
     start_time: std::time::Instant,
     pipeline: LearningPipeline,
+    idempotency: IdempotencyCache<StorageResponse>,
+    max_message_size: usize,
+    rate_limits: Option<PeerRateLimiter>,
+    drain_timeout: std::time::Duration,
+    search_permits: Arc<Semaphore>,
 }
 
 impl ShardedStorageServer {
@@ -1512,9 +3504,49 @@ impl ShardedStorageServer {
             namespaces: Arc::new(manager),
             start_time: std::time::Instant::now(),
             pipeline,
+            idempotency: IdempotencyCache::default(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            rate_limits: None,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            search_permits: Arc::new(Semaphore::new(MAX_BLOCKING_SEARCHES)),
         }
     }
 
+    /// Set how long shutdown waits for in-flight requests before cancelling them
+    pub fn with_drain_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Close idle namespaces according to `config` (default: never)
+    pub fn with_namespace_eviction(self, config: NamespaceEvictionConfig) -> Self {
+        self.namespaces.set_eviction(config);
+        self
+    }
+
+    /// Limit the size of every namespace without its own quota (default: unlimited)
+    pub fn with_namespace_quota(self, quota: NamespaceQuota) -> Self {
+        self.namespaces.set_default_quota(Some(quota));
+        self
+    }
+
+    /// Limit the size of one namespace
+    pub fn set_namespace_quota(&self, namespace: &str, quota: Option<NamespaceQuota>) {
+        self.namespaces.set_quota(namespace, quota);
+    }
+
+    /// Reject request frames larger than `bytes` (capped at `MAX_MESSAGE_SIZE_CEILING`)
+    pub fn with_max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes.clamp(1, MAX_MESSAGE_SIZE_CEILING);
+        self
+    }
+
+    /// Limit each client's request rate, with `writes` applied to mutations
+    pub fn with_rate_limits(mut self, reads: RateLimiterConfig, writes: RateLimiterConfig) -> Self {
+        self.rate_limits = Some(PeerRateLimiter::new(reads, writes));
+        self
+    }
+
     /// Helper to get storage for a namespace
     fn get_storage(&self, namespace: Option<String>) -> Arc<ConcurrentMemory> {
         let ns = namespace.unwrap_or_else(|| "default".to_string());
@@ -1523,21 +3555,39 @@ impl ShardedStorageServer {
 
     /// Start TCP server (same interface as StorageServer)
     pub async fn serve(self: Arc<Self>, addr: SocketAddr) -> std::io::Result<()> {
+        self.serve_with_shutdown(addr, async {
+            let _ = signal::ctrl_c().await;
+        })
+        .await
+    }
+
+    /// Start TCP server with a custom shutdown signal, draining connections
+    /// like [`StorageServer::serve_with_shutdown`]
+    pub async fn serve_with_shutdown<F>(
+        self: Arc<Self>,
+        addr: SocketAddr,
+        shutdown: F,
+    ) -> std::io::Result<()>
+    where
+        F: std::future::Future<Output = ()> + Send,
+    {
         let listener = TcpListener::bind(addr).await?;
         eprintln!("Sharded storage server listening on {}", addr);
 
-        // Graceful shutdown handler
-        let shutdown = signal::ctrl_c();
         tokio::pin!(shutdown);
 
+        let (drain_tx, drain_rx) = watch::channel(false);
+        let mut clients = JoinSet::new();
+
         loop {
             tokio::select! {
                 result = listener.accept() => {
                     match result {
                         Ok((stream, peer_addr)) => {
                             let server = self.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = server.handle_client(stream, peer_addr).await {
+                            let drain_rx = drain_rx.clone();
+                            clients.spawn(async move {
+                                if let Err(e) = server.handle_client(stream, peer_addr, drain_rx).await {
                                     eprintln!("Client error ({}): {}", peer_addr, e);
                                 }
                             });
@@ -1547,31 +3597,69 @@ impl ShardedStorageServer {
                         }
                     }
                 }
+                Some(_) = clients.join_next(), if !clients.is_empty() => {}
                 _ = &mut shutdown => {
-                    eprintln!("Shutdown signal received, flushing all namespaces...");
-                    if let Err(e) = self.namespaces.flush_all() {
-                        eprintln!("Flush error: {:?}", e);
-                    }
                     break;
                 }
             }
         }
+        drop(listener);
+
+        let in_flight = clients.len();
+        eprintln!(
+            "Shutdown signal received, draining {} connection(s) (timeout {:?})...",
+            in_flight, self.drain_timeout
+        );
+        let _ = drain_tx.send(true);
+
+        let cancelled = drain_clients(&mut clients, self.drain_timeout).await;
+        eprintln!(
+            "Drained {} connection(s), force-cancelled {}",
+            in_flight - cancelled,
+            cancelled
+        );
+
+        eprintln!("Flushing all namespaces...");
+        if let Err(e) = self.namespaces.flush_all() {
+            eprintln!("Flush error: {:?}", e);
+        }
 
         Ok(())
     }
 
-    /// Handle single client connection
+    /// Handle single client connection, closing it between requests once `drain` is set
     async fn handle_client(
         &self,
-        mut stream: TcpStream,
+        stream: TcpStream,
         peer_addr: SocketAddr,
+        mut drain: watch::Receiver<bool>,
     ) -> std::io::Result<()> {
         eprintln!("Client connected: {}", peer_addr);
 
         // Configure for low latency
         stream.set_nodelay(true)?;
+        // Buffered so waiting for a request can be interrupted by a drain
+        let mut stream = BufReader::new(stream);
+        // Frame encoding; a client may switch it with a Content-Type line
+        let mut wire_format = WireFormat::default();
 
         loop {
+            if *drain.borrow() {
+                break;
+            }
+            let filled = tokio::select! {
+                biased;
+                _ = drain.changed() => break,
+                filled = stream.fill_buf() => filled?,
+            };
+            if filled.is_empty() {
+                break;
+            }
+            if starts_handshake(filled) {
+                negotiate_wire_format(&mut stream, peer_addr, &mut wire_format).await?;
+                continue;
+            }
+
             // Read message length (4 bytes)
             let len = match stream.read_u32().await {
                 Ok(len) => len,
@@ -1582,20 +3670,40 @@ impl ShardedStorageServer {
                 Err(e) => return Err(e),
             };
 
+            if len as usize > self.max_message_size {
+                tokio::io::copy(&mut (&mut stream).take(len as u64), &mut tokio::io::sink())
+                    .await?;
+                let error = StorageResponse::Error {
+                    message: format!(
+                        "Message too large: {} bytes (max: {})",
+                        len, self.max_message_size
+                    ),
+                };
+                let response_bytes = wire_format.encode_response(&error);
+                stream.write_u32(response_bytes.len() as u32).await?;
+                stream.write_all(&response_bytes).await?;
+                stream.flush().await?;
+                continue;
+            }
+
             // Read message payload
             let mut buf = vec![0u8; len as usize];
             stream.read_exact(&mut buf).await?;
 
             // Deserialize request (msgpack for Python clients)
-            let request: StorageRequest = rmp_serde::from_slice(&buf)
+            let request = wire_format
+                .decode_request(&buf)
                 .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
             // Handle request
-            let response = self.handle_request(request).await;
+            let response = match rate_limited(self.rate_limits.as_ref(), peer_addr, None, &request)
+            {
+                Some(error) => error,
+                None => self.handle_request(request, &peer_caller(peer_addr)).await,
+            };
 
             // Serialize response (msgpack for Python clients)
-            let response_bytes = rmp_serde::to_vec_named(&response)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            let response_bytes = wire_format.encode_response(&response);
 
             // Write response
             stream.write_u32(response_bytes.len() as u32).await?;
@@ -1607,30 +3715,45 @@ impl ShardedStorageServer {
         Ok(())
     }
 
-    /// Handle storage request (sharded version)
-    async fn handle_request(&self, request: StorageRequest) -> StorageResponse {
+    /// Handle storage request (sharded version) on behalf of `caller`
+    async fn handle_request(&self, request: StorageRequest, caller: &str) -> StorageResponse {
+        run_idempotent(&self.idempotency, caller, request, |request| {
+            self.execute_request(request)
+        })
+        .await
+    }
+
+    async fn execute_request(&self, request: StorageRequest) -> StorageResponse {
         use crate::types::{AssociationType, ConceptId};
 
         match request {
-            StorageRequest::LearnConceptV2 { namespace, content, options } => {
+            StorageRequest::LearnConceptV2 { namespace, content, options, idempotency_key: _ } => {
+                let mut learn_opts: LearnOptions = options.into();
+                learn_opts.use_embedding_cache = self.pipeline.embedding_cache_enabled_for(namespace.as_deref().unwrap_or("default"));
                 let storage = self.get_storage(namespace);
-                let learn_opts: LearnOptions = options.into();
 
                 match self.pipeline.learn_concept(&storage, &content, &learn_opts).await {
                     Ok(concept_id) => StorageResponse::LearnConceptV2Ok { concept_id },
-                    Err(e) => StorageResponse::Error {
-                        message: format!("Learning pipeline failed: {}", e),
+                    Err(e) => match retryable_write_error(&e) {
+                        Some(error) => backpressure_response(error),
+                        None => StorageResponse::Error {
+                            message: format!("Learning pipeline failed: {}", e),
+                        },
                     },
                 }
             }
-            StorageRequest::LearnBatch { namespace, contents, options } => {
+            StorageRequest::LearnBatch { namespace, contents, options, idempotency_key: _ } => {
+                let mut learn_opts: LearnOptions = options.into();
+                learn_opts.use_embedding_cache = self.pipeline.embedding_cache_enabled_for(namespace.as_deref().unwrap_or("default"));
                 let storage = self.get_storage(namespace);
-                let learn_opts: LearnOptions = options.into();
 
                 match self.pipeline.learn_batch(&storage, &contents, &learn_opts).await {
                     Ok(concept_ids) => StorageResponse::LearnBatchOk { concept_ids },
-                    Err(e) => StorageResponse::Error {
-                        message: format!("Batch learning failed: {}", e),
+                    Err(e) => match retryable_write_error(&e) {
+                        Some(error) => backpressure_response(error),
+                        None => StorageResponse::Error {
+                            message: format!("Batch learning failed: {}", e),
+                        },
                     },
                 }
             }
@@ -1641,6 +3764,7 @@ impl ShardedStorageServer {
                 embedding,
                 strength,
                 confidence,
+                idempotency_key: _,
             } => {
                 let storage = self.get_storage(namespace);
                 let id = ConceptId::from_string(&concept_id);
@@ -1649,6 +3773,7 @@ impl ShardedStorageServer {
 
                 match storage.learn_concept(id, content_bytes, vector, strength, confidence, std::collections::HashMap::new()) {
                     Ok(sequence) => StorageResponse::LearnConceptOk { sequence },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => backpressure_response(&e),
                     Err(e) => StorageResponse::Error {
                         message: format!("Learn concept failed: {:?}", e),
                     },
@@ -1661,6 +3786,7 @@ impl ShardedStorageServer {
                 target_id,
                 assoc_type,
                 confidence,
+                idempotency_key: _,
             } => {
                 let storage = self.get_storage(namespace);
                 let source = ConceptId::from_string(&source_id);
@@ -1670,17 +3796,28 @@ impl ShardedStorageServer {
 
                 match storage.learn_association(source, target, atype, confidence) {
                     Ok(sequence) => StorageResponse::LearnAssociationOk { sequence },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => backpressure_response(&e),
                     Err(e) => StorageResponse::Error {
                         message: format!("Learn association failed: {:?}", e),
                     },
                 }
             }
 
-            StorageRequest::QueryConcept { namespace, concept_id } => {
+            StorageRequest::Transaction {
+                namespace,
+                operations,
+            } => {
+                let storage = self.get_storage(namespace);
+                transaction_response(&storage, operations)
+            }
+
+            StorageRequest::QueryConcept { namespace, concept_id, include_vector } => {
                 let storage = self.get_storage(namespace);
                 let id = ConceptId::from_string(&concept_id);
 
                 if let Some(node) = storage.query_concept(&id) {
+                    // Feed access analytics and decay reinforcement
+                    storage.record_access(id);
                     StorageResponse::QueryConceptOk {
                         found: true,
                         concept_id: id.to_hex(),
@@ -1688,6 +3825,7 @@ impl ShardedStorageServer {
                         strength: node.strength,
                         confidence: node.confidence,
                         attributes: node.attributes.clone(),
+                        vector: include_vector.then(|| response_vector(&node)).flatten(),
                     }
                 } else {
                     StorageResponse::QueryConceptOk {
@@ -1697,6 +3835,7 @@ impl ShardedStorageServer {
                         strength: 0.0,
                         confidence: 0.0,
                         attributes: std::collections::HashMap::new(),
+                        vector: None,
                     }
                 }
             }
@@ -1715,19 +3854,20 @@ impl ShardedStorageServer {
                 start_id,
                 end_id,
                 max_depth,
+                deadline_ms,
+                namespaces,
             } => {
-                let storage = self.get_storage(namespace);
-                if let Some(path) = storage.find_path(ConceptId::from_string(&start_id), ConceptId::from_string(&end_id), max_depth as usize) {
-                    StorageResponse::FindPathOk {
-                        found: true,
-                        path: path.iter().map(|id: &ConceptId| id.to_hex()).collect(),
-                    }
-                } else {
-                    StorageResponse::FindPathOk {
-                        found: false,
-                        path: vec![],
-                    }
+                if max_depth > MAX_PATH_DEPTH {
+                    return StorageResponse::Error {
+                        message: format!("Path depth too large: {} (max: {})", max_depth, MAX_PATH_DEPTH),
+                    };
                 }
+                let shards = match query_shards(&self.namespaces, namespace, namespaces) {
+                    Ok(shards) => shards,
+                    Err(message) => return StorageResponse::Error { message },
+                };
+                let (start, end) = (ConceptId::from_string(&start_id), ConceptId::from_string(&end_id));
+                find_path_in(&shards, start, end, max_depth as usize, deadline_from(deadline_ms))
             }
 
             StorageRequest::VectorSearch {
@@ -1735,22 +3875,21 @@ impl ShardedStorageServer {
                 query_vector,
                 k,
                 ef_search,
+                deadline_ms,
             } => {
-                let storage = self.get_storage(namespace);
-                let results = storage.vector_search(&query_vector, k as usize, ef_search as usize);
-                let results_vec = results
-                    .into_iter()
-                    .map(|(id, sim)| (id.to_hex(), sim))
-                    .collect();
-
-                StorageResponse::VectorSearchOk { results: results_vec }
+                let (storage, permits) = (self.get_storage(namespace), Arc::clone(&self.search_permits));
+                vector_search_response(storage, permits, query_vector, k, ef_search, deadline_from(deadline_ms)).await
             }
 
             StorageRequest::GetStats { namespace } => {
                 let storage = self.get_storage(namespace);
                 let stats = storage.stats();
                 let hnsw_stats = storage.hnsw_stats();
+                let namespace_stats = self.namespaces.stats();
                 let uptime = self.start_time.elapsed().as_secs();
+                let cache_stats = self.pipeline.embedding_cache().map(|c| c.stats());
+                let rate_stats = self.rate_limits.as_ref().map(|r| r.stats());
+                let quota = storage.quota_usage();
 
                 StorageResponse::StatsOk {
                     concepts: stats.snapshot.concept_count as u64,
@@ -1761,9 +3900,44 @@ impl ShardedStorageServer {
                     pending: stats.write_log.pending as u64,
                     reconciliations: stats.reconciler.reconciliations,
                     uptime_seconds: uptime,
+                    embedding_cache_hits: cache_stats.map_or(0, |c| c.hits),
+                    embedding_cache_misses: cache_stats.map_or(0, |c| c.misses),
+                    replication_lag: 0,
+                    attribute_index_entries: stats.snapshot.attribute_index_entries as u64,
+                    namespaces_open: namespace_stats.open as u64,
+                    namespaces_evicted: namespace_stats.evicted,
+                    hnsw_tombstone_ratio: hnsw_stats.tombstone_ratio(),
+                    last_reindex_us: hnsw_stats.last_reindex_us.unwrap_or(0),
+                    storage_threads: stats.storage_threads as u64,
+                    rate_limited_requests: rate_stats.as_ref().map_or(0, |r| r.rejected),
+                    rate_limited_peers: rate_stats.as_ref().map_or(0, |r| {
+                        (r.reads.throttled_subjects + r.writes.throttled_subjects) as u64
+                    }),
+                    quota_max_concepts: quota.map_or(0, |q| q.quota.max_concepts as u64),
+                    quota_max_bytes: quota.map_or(0, |q| q.quota.max_bytes),
+                    quota_used_concepts: quota.map_or(0, |q| q.concepts as u64),
+                    quota_used_bytes: quota.map_or(0, |q| q.bytes),
+                    quota_evicted: quota.map_or(0, |q| q.evicted),
                 }
             }
 
+            StorageRequest::TopAccessed { namespace, limit } => {
+                let storage = self.get_storage(namespace);
+                StorageResponse::AccessRankingOk {
+                    concepts: storage.top_accessed(limit.min(MAX_SEARCH_K) as usize).into_iter().map(AccessRankMsg::from).collect(),
+                }
+            }
+
+            StorageRequest::ColdestConcepts { namespace, limit } => {
+                let storage = self.get_storage(namespace);
+                StorageResponse::AccessRankingOk {
+                    concepts: storage.coldest_concepts(limit.min(MAX_SEARCH_K) as usize).into_iter().map(AccessRankMsg::from).collect(),
+                }
+            }
+
+            // No autonomy engine runs here, so the detector's default thresholds apply
+            StorageRequest::GetGaps { namespace, kinds, limit } => get_gaps_response(self.get_storage(namespace), Default::default(), kinds, limit).await,
+
             StorageRequest::Flush => match self.namespaces.flush_all() {
                 Ok(_) => StorageResponse::FlushOk,
                 Err(e) => StorageResponse::Error {
@@ -1771,51 +3945,68 @@ impl ShardedStorageServer {
                 },
             },
 
+            StorageRequest::Reindex { namespace } => {
+                reindex_response(self.get_storage(namespace)).await
+            }
+
             StorageRequest::HealthCheck => {
                 let uptime = self.start_time.elapsed().as_secs();
                 StorageResponse::HealthCheckOk {
                     healthy: true,
                     status: format!("sharded-namespaces ({} active)", self.namespaces.list_namespaces().len()),
                     uptime_seconds: uptime,
+                    max_message_size: self.max_message_size as u64,
                 }
             }
 
+            StorageRequest::Handshake { protocol_version } => {
+                handshake_response(protocol_version, self.max_message_size)
+            }
+
+            StorageRequest::SubscribeChanges { .. } => StorageResponse::Error {
+                message: "Change subscriptions not yet implemented for sharded storage. Use single-shard mode.".to_string(),
+            },
+
             StorageRequest::DeleteConcept { namespace, id } => {
                 let storage = self.get_storage(Some(namespace));
                 let concept_id = ConceptId::from_string(&id);
                 match storage.delete_concept(concept_id) {
-                    Ok(_) => StorageResponse::DeleteConceptOk { id: id.to_string() },
+                    Ok(_) => {
+                        compact_in_background(&storage);
+                        StorageResponse::DeleteConceptOk { id: id.to_string() }
+                    }
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => backpressure_response(&e),
                     Err(e) => StorageResponse::Error { message: format!("Delete failed: {:?}", e) },
                 }
             }
 
+            StorageRequest::UpdateConcept { namespace, id, strength, confidence, metadata_merge } => {
+                update_concept_response(&self.get_storage(namespace), id, strength, confidence, metadata_merge)
+            }
+
             StorageRequest::ClearCollection { namespace } => {
                 let storage = self.get_storage(Some(namespace.clone()));
                 match storage.clear() {
                     Ok(_) => StorageResponse::ClearCollectionOk { namespace: namespace.to_string() },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => backpressure_response(&e),
                     Err(e) => StorageResponse::Error { message: format!("Clear failed: {:?}", e) },
                 }
             }
 
-            StorageRequest::ListRecent { namespace, limit } => {
-                let storage = self.get_storage(Some(namespace));
-                let snapshot = storage.get_snapshot();
-                let mut items: Vec<RecentItemMsg> = snapshot.concepts.values().map(|node| {
-                    RecentItemMsg {
-                        id: node.id.to_hex(),
-                        content_preview: String::from_utf8_lossy(&node.content).chars().take(200).collect(),
-                        created: node.created,
-                        attributes: node.attributes.clone(),
-                    }
-                }).collect();
+            StorageRequest::VectorSearchBatch { namespace, queries, k, ef_search } => {
+                let permits = Arc::clone(&self.search_permits);
+                vector_search_batch_response(self.get_storage(namespace), permits, queries, k, ef_search).await
+            }
 
-                items.sort_by(|a, b| b.created.cmp(&a.created));
-                items.truncate(limit as usize);
+            StorageRequest::ListRecent { namespace, limit, cursor } => {
+                list_recent_response(&self.get_storage(Some(namespace)), limit, cursor.as_deref())
+            }
 
-                StorageResponse::ListRecentOk { items }
+            StorageRequest::QueryByMetadata { namespace, attributes, limit } => {
+                query_by_metadata_response(&self.get_storage(namespace), &attributes, limit)
             }
 
-            StorageRequest::LearnWithEmbedding { id, namespace, content, embedding, metadata, timestamp: _ } => {
+            StorageRequest::LearnWithEmbedding { id, namespace, content, embedding, metadata, timestamp: _, idempotency_key: _ } => {
                 let storage = self.get_storage(Some(namespace));
                 let concept_id = id.map(|s| ConceptId::from_string(&s))
                     .unwrap_or_else(|| ConceptId::from_string(&content));
@@ -1828,50 +4019,40 @@ impl ShardedStorageServer {
                     metadata
                 ) {
                     Ok(_) => StorageResponse::LearnConceptV2Ok { concept_id: concept_id.to_hex() },
+                    Err(e @ (WriteLogError::Backpressure | WriteLogError::Full)) => backpressure_response(&e),
                     Err(e) => StorageResponse::Error { message: format!("LearnWithEmbedding failed: {:?}", e) },
                 }
             }
 
-            // Semantic query handlers (limited support in sharded mode)
-            StorageRequest::FindPathSemantic { .. } => {
-                StorageResponse::Error {
-                    message: "Semantic pathfinding not yet implemented for sharded storage. Use single-shard mode.".to_string(),
-                }
-            }
-
-            StorageRequest::FindTemporalChain { .. } => {
-                StorageResponse::Error {
-                    message: "Temporal chain queries not yet implemented for sharded storage. Use single-shard mode.".to_string(),
-                }
-            }
-
-            StorageRequest::FindCausalChain { .. } => {
-                StorageResponse::Error {
-                    message: "Causal chain queries not yet implemented for sharded storage. Use single-shard mode.".to_string(),
-                }
-            }
+            // Semantic queries fan out over the listed namespaces and merge
+            request @ (StorageRequest::FindPathSemantic { .. }
+            | StorageRequest::FindTemporalChain { .. }
+            | StorageRequest::FindCausalChain { .. }
+            | StorageRequest::FindContradictions { .. }
+            | StorageRequest::QueryBySemantic { .. }) => semantic_query_response(&self.namespaces, request),
 
-            StorageRequest::FindContradictions { .. } => {
-                StorageResponse::Error {
-                    message: "Contradiction detection not yet implemented for sharded storage. Use single-shard mode.".to_string(),
-                }
-            }
-
-            StorageRequest::QueryBySemantic { .. } => {
-                StorageResponse::Error {
-                    message: "Semantic queries not yet implemented for sharded storage. Use single-shard mode.".to_string(),
-                }
-            }
-            StorageRequest::TextSearch { namespace, query, limit } => {
+            StorageRequest::TextSearch { namespace, query, limit, snippet_len, alpha } => {
                 let storage = self.get_storage(namespace);
-                match self.pipeline.search(&storage, &query, limit as usize).await {
+                let limit = limit.min(MAX_SEARCH_K);
+                let results = match alpha {
+                    Some(alpha) => self.pipeline.hybrid_search(&storage, &query, limit as usize, alpha).await,
+                    None => self.pipeline.search(&storage, &query, limit as usize).await,
+                };
+                match results {
                     Ok(results) => StorageResponse::TextSearchOk {
+                        highlights: TextHighlightMsg::for_results(&storage, &results, &query, snippet_len),
                         results: results.into_iter().map(|(id, score)| (id.to_hex(), score)).collect()
                     },
                     Err(e) => StorageResponse::Error { message: format!("Sharded TextSearch failed: {}", e) },
                 }
             }
 
+            StorageRequest::ReplicationSnapshot { .. } | StorageRequest::ReplicationPull { .. } => {
+                StorageResponse::Error {
+                    message: "Replication not yet implemented for sharded storage. Use single-shard mode.".to_string(),
+                }
+            }
+
             // Autonomy features not supported in sharded mode
             StorageRequest::Subscribe { .. }
             | StorageRequest::Unsubscribe { .. }
@@ -1888,3 +4069,662 @@ impl ShardedStorageServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent_memory::ConcurrentConfig;
+    use crate::semantic::{CausalRelation, SemanticMetadata};
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn shard(dir: &TempDir) -> Arc<ConcurrentMemory> {
+        Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        }))
+    }
+
+    fn learn_cause(shard: &ConcurrentMemory, id: ConceptId) {
+        let mut semantic = SemanticMetadata::new(SemanticType::Causal);
+        semantic.causal_relations.push(CausalRelation {
+            confidence: 0.9,
+            relation_type: CausalType::Direct,
+            strength: 0.9,
+        });
+        shard
+            .learn_concept_with_semantic(id, id.to_hex().into_bytes(), None, 1.0, 0.9, semantic)
+            .unwrap();
+    }
+
+    fn wait_for_edges(shard: &ConcurrentMemory, id: ConceptId) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while shard
+            .query_concept(&id)
+            .is_none_or(|node| node.neighbors.is_empty())
+        {
+            assert!(Instant::now() < deadline, "edges never reconciled");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_semantic_queries_search_only_listed_namespaces() {
+        let dir = TempDir::new().unwrap();
+        let manager =
+            NamespaceManager::new(dir.path().to_path_buf(), ConcurrentConfig::default()).unwrap();
+        learn_cause(&manager.get_namespace("a"), ConceptId([1; 16]));
+        learn_cause(&manager.get_namespace("b"), ConceptId([2; 16]));
+
+        let query = |namespace: Option<&str>, namespaces: &[&str]| {
+            semantic_query_response(
+                &manager,
+                StorageRequest::QueryBySemantic {
+                    namespace: namespace.map(str::to_string),
+                    filter: SemanticFilterMsg::default(),
+                    limit: None,
+                    namespaces: namespaces.iter().map(|ns| ns.to_string()).collect(),
+                },
+            )
+        };
+        let found = |response| match response {
+            StorageResponse::QueryBySemanticOk { concepts } => concepts.len(),
+            other => panic!("Unexpected response: {:?}", other),
+        };
+
+        // Without an opt-in the query stays in the default namespace
+        assert_eq!(found(query(None, &[])), 0);
+        assert_eq!(found(query(Some("a"), &[])), 1);
+        assert_eq!(found(query(None, &["a", "b", "a"])), 2);
+
+        assert!(matches!(
+            query(Some("a"), &["b"]),
+            StorageResponse::Error { .. }
+        ));
+        let too_many: Vec<String> = (0..=MAX_QUERY_NAMESPACES).map(|i| i.to_string()).collect();
+        let too_many: Vec<&str> = too_many.iter().map(String::as_str).collect();
+        assert!(matches!(
+            query(None, &too_many),
+            StorageResponse::Error { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_gaps_uses_detector_thresholds() {
+        let dir = TempDir::new().unwrap();
+        let storage = shard(&dir);
+        let (a, b) = (ConceptId([1; 16]), ConceptId([2; 16]));
+        for id in [a, b] {
+            storage
+                .learn_concept(
+                    id,
+                    id.to_hex().into_bytes(),
+                    None,
+                    1.0,
+                    0.9,
+                    Default::default(),
+                )
+                .unwrap();
+        }
+        storage
+            .learn_association(a, b, AssociationType::Semantic, 0.9)
+            .unwrap();
+        wait_for_edges(&storage, a);
+        wait_for_edges(&storage, b);
+
+        let isolated = |isolation_threshold| {
+            let config = crate::autonomy::GapDetectorConfig {
+                isolation_threshold,
+                ..Default::default()
+            };
+            get_gaps_response(
+                Arc::clone(&storage),
+                config,
+                vec!["isolated".to_string()],
+                10,
+            )
+        };
+        match isolated(1).await {
+            StorageResponse::GapsOk { gaps } => assert!(gaps.is_empty(), "{:?}", gaps),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+        // A stricter detector counts one neighbor as half isolated
+        match isolated(2).await {
+            StorageResponse::GapsOk { gaps } => {
+                assert_eq!(gaps.len(), 2);
+                assert!(gaps.iter().all(|gap| gap.severity == 0.5));
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_required_scope_per_request() {
+        use StorageRequest::*;
+
+        let id = || "0".repeat(32);
+        let cases = [
+            (
+                LearnConceptV2 {
+                    namespace: None,
+                    content: String::new(),
+                    options: LearnOptionsMsg::default(),
+                    idempotency_key: None,
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                LearnBatch {
+                    namespace: None,
+                    contents: vec![],
+                    options: LearnOptionsMsg::default(),
+                    idempotency_key: None,
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                LearnWithEmbedding {
+                    id: None,
+                    namespace: "default".to_string(),
+                    content: String::new(),
+                    embedding: vec![],
+                    metadata: Default::default(),
+                    timestamp: None,
+                    idempotency_key: None,
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                LearnConcept {
+                    namespace: None,
+                    concept_id: id(),
+                    content: String::new(),
+                    embedding: vec![],
+                    strength: 1.0,
+                    confidence: 1.0,
+                    idempotency_key: None,
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                LearnAssociation {
+                    namespace: None,
+                    source_id: id(),
+                    target_id: id(),
+                    assoc_type: 0,
+                    confidence: 1.0,
+                    idempotency_key: None,
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                Transaction {
+                    namespace: None,
+                    operations: vec![],
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                QueryConcept {
+                    namespace: None,
+                    concept_id: id(),
+                    include_vector: false,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                DeleteConcept {
+                    namespace: "default".to_string(),
+                    id: id(),
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                UpdateConcept {
+                    namespace: None,
+                    id: id(),
+                    strength: None,
+                    confidence: None,
+                    metadata_merge: Default::default(),
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                ClearCollection {
+                    namespace: "default".to_string(),
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                GetNeighbors {
+                    namespace: None,
+                    concept_id: id(),
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                FindPath {
+                    namespace: None,
+                    start_id: id(),
+                    end_id: id(),
+                    max_depth: 1,
+                    deadline_ms: None,
+                    namespaces: vec![],
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                VectorSearch {
+                    namespace: None,
+                    query_vector: vec![],
+                    k: 1,
+                    ef_search: 1,
+                    deadline_ms: None,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                VectorSearchBatch {
+                    namespace: None,
+                    queries: vec![],
+                    k: 1,
+                    ef_search: 1,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                ListRecent {
+                    namespace: "default".to_string(),
+                    limit: 1,
+                    cursor: None,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                QueryByMetadata {
+                    namespace: None,
+                    attributes: Default::default(),
+                    limit: 1,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                FindPathSemantic {
+                    namespace: None,
+                    start_id: id(),
+                    end_id: id(),
+                    filter: SemanticFilterMsg::default(),
+                    max_depth: 1,
+                    max_paths: 1,
+                    max_nodes_visited: None,
+                    timeout_ms: None,
+                    deadline_ms: None,
+                    namespaces: vec![],
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                FindTemporalChain {
+                    namespace: None,
+                    domain: None,
+                    start_time: 0,
+                    end_time: 0,
+                    namespaces: vec![],
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                FindCausalChain {
+                    namespace: None,
+                    start_id: id(),
+                    causal_type: "direct".to_string(),
+                    max_depth: 1,
+                    namespaces: vec![],
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                FindContradictions {
+                    namespace: None,
+                    domain: String::new(),
+                    namespaces: vec![],
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                QueryBySemantic {
+                    namespace: None,
+                    filter: SemanticFilterMsg::default(),
+                    limit: None,
+                    namespaces: vec![],
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                TextSearch {
+                    namespace: None,
+                    query: String::new(),
+                    limit: 1,
+                    snippet_len: None,
+                    alpha: None,
+                },
+                Scope::ReadOnly,
+            ),
+            (GetStats { namespace: None }, Scope::ReadOnly),
+            (
+                TopAccessed {
+                    namespace: None,
+                    limit: 1,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                ColdestConcepts {
+                    namespace: None,
+                    limit: 1,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                GetGaps {
+                    namespace: None,
+                    kinds: vec![],
+                    limit: 1,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                ReplicationSnapshot {
+                    namespace: None,
+                    transfer_id: None,
+                    cursor: 0,
+                },
+                Scope::Admin,
+            ),
+            (
+                ReplicationPull {
+                    namespace: None,
+                    from_sequence: 0,
+                    max_entries: 1,
+                },
+                Scope::Admin,
+            ),
+            (Flush, Scope::Admin),
+            (Reindex { namespace: None }, Scope::Admin),
+            (HealthCheck, Scope::ReadOnly),
+            (
+                Handshake {
+                    protocol_version: 1,
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                SubscribeChanges {
+                    pattern: "*".to_string(),
+                },
+                Scope::ReadOnly,
+            ),
+            (
+                Subscribe {
+                    filter: SemanticFilterMsg::default(),
+                    callback_addr: "127.0.0.1:1".to_string(),
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                Unsubscribe {
+                    subscription_id: String::new(),
+                },
+                Scope::ReadWrite,
+            ),
+            (ListSubscriptions, Scope::ReadOnly),
+            (
+                CreateGoal {
+                    namespace: None,
+                    description: String::new(),
+                    condition: String::new(),
+                    action: String::new(),
+                    priority: 0,
+                },
+                Scope::ReadWrite,
+            ),
+            (ListGoals { namespace: None }, Scope::ReadOnly),
+            (
+                CancelGoal {
+                    namespace: None,
+                    goal_id: String::new(),
+                },
+                Scope::ReadWrite,
+            ),
+            (
+                ProvideFeedback {
+                    namespace: None,
+                    query_id: String::new(),
+                    result_concept_ids: vec![],
+                    accepted: vec![],
+                    ranking: None,
+                },
+                Scope::ReadWrite,
+            ),
+            (GetAutonomyStats, Scope::ReadOnly),
+        ];
+
+        for (request, scope) in cases {
+            assert_eq!(request.required_scope(), scope, "{:?}", request);
+            // Anything that changes data needs at least ReadWrite
+            if request.is_mutation() {
+                assert!(scope >= Scope::ReadWrite, "{:?}", request);
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_write_log_is_retryable() {
+        for error in [WriteLogError::Full, WriteLogError::Backpressure] {
+            assert!(matches!(
+                backpressure_response(&error),
+                StorageResponse::RetryableError { code, .. } if code == "backpressure"
+            ));
+            let wrapped = anyhow::Error::new(error.clone());
+            assert_eq!(retryable_write_error(&wrapped), Some(&error));
+        }
+        let quota = anyhow::Error::new(WriteLogError::QuotaExceeded("full".to_string()));
+        assert_eq!(retryable_write_error(&quota), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_until_gives_up_at_deadline() {
+        let permits = Arc::new(Semaphore::new(MAX_BLOCKING_SEARCHES));
+        // The work can only finish once the caller has given up on it
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+        let late = run_blocking_until(
+            &permits,
+            Some(Instant::now() + Duration::from_millis(20)),
+            move || blocked.recv(),
+        )
+        .await;
+        assert!(late.is_err());
+        release.send(()).unwrap();
+
+        let on_time = run_blocking_until(
+            &permits,
+            Some(Instant::now() + Duration::from_secs(5)),
+            || 7,
+        )
+        .await;
+        assert_eq!(on_time.unwrap().unwrap(), 7);
+        assert_eq!(
+            run_blocking_until(&permits, None, || 7)
+                .await
+                .unwrap()
+                .unwrap(),
+            7
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_blocking_work_is_bounded() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let permits = Arc::new(Semaphore::new(2));
+        let started = Arc::new(AtomicUsize::new(0));
+        let released = Arc::new(AtomicBool::new(false));
+        for _ in 0..6 {
+            let (started, released) = (Arc::clone(&started), Arc::clone(&released));
+            let result = run_blocking_until(
+                &permits,
+                Some(Instant::now() + Duration::from_millis(20)),
+                move || {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    while !released.load(Ordering::SeqCst) {
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                },
+            )
+            .await;
+            assert!(result.is_err());
+        }
+
+        // Work past its deadline keeps its permit, so later calls never started
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+        assert_eq!(permits.available_permits(), 0);
+
+        released.store(true, Ordering::SeqCst);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while permits.available_permits() < 2 {
+            assert!(Instant::now() < deadline, "permits were not returned");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[test]
+    fn test_ef_search_narrows_for_short_budgets() {
+        let far = Some(Instant::now() + Duration::from_secs(5));
+        let near = Some(Instant::now() + Duration::from_millis(5));
+        assert_eq!(ef_search_for_budget(200, 10, None), 200);
+        assert_eq!(ef_search_for_budget(u32::MAX, 10, far), MAX_EF_SEARCH);
+        assert_eq!(ef_search_for_budget(200, 10, near), SHORT_BUDGET_EF_SEARCH);
+        assert_eq!(ef_search_for_budget(200, 100, near), 100);
+        assert_eq!(ef_search_for_budget(20, 10, near), 20);
+    }
+
+    #[test]
+    fn test_find_path_crosses_only_listed_namespaces() {
+        let dir = TempDir::new().unwrap();
+        let manager =
+            NamespaceManager::new(dir.path().to_path_buf(), ConcurrentConfig::default()).unwrap();
+        let (first, shared, last) = (ConceptId([1; 16]), ConceptId([2; 16]), ConceptId([3; 16]));
+        for (ns, from, to) in [("a", first, shared), ("b", shared, last)] {
+            let store = manager.get_namespace(ns);
+            for id in [from, to] {
+                store
+                    .learn_concept(
+                        id,
+                        id.to_hex().into_bytes(),
+                        None,
+                        1.0,
+                        0.9,
+                        Default::default(),
+                    )
+                    .unwrap();
+            }
+            store
+                .learn_association(from, to, AssociationType::Semantic, 0.9)
+                .unwrap();
+            wait_for_edges(&store, from);
+        }
+
+        let find = |namespace: Option<&str>, namespaces: &[&str]| {
+            let shards = query_shards(
+                &manager,
+                namespace.map(str::to_string),
+                namespaces.iter().map(|ns| ns.to_string()).collect(),
+            )
+            .unwrap();
+            match find_path_in(&shards, first, last, 10, None) {
+                StorageResponse::FindPathOk { found, shards, .. } => (found, shards),
+                other => panic!("Unexpected response: {:?}", other),
+            }
+        };
+
+        assert!(!find(None, &[]).0);
+        assert!(!find(Some("a"), &[]).0);
+        let (found, shards) = find(None, &["a", "b"]);
+        assert!(found);
+        assert_eq!(shards, ["a", "a", "b"]);
+    }
+
+    #[test]
+    fn test_causal_chain_merges_shards_and_flags_boundary() {
+        let (dir_a, dir_b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let (a, b) = (shard(&dir_a), shard(&dir_b));
+        let [c1, c2, c3] = [1u8, 2, 3].map(|i| ConceptId([i; 16]));
+
+        // c1 -> c2 lives in shard A; c2 -> c3 in shard B
+        for id in [c1, c2] {
+            learn_cause(&a, id);
+        }
+        for id in [c2, c3] {
+            learn_cause(&b, id);
+        }
+        a.learn_association(c1, c2, AssociationType::Causal, 0.9)
+            .unwrap();
+        b.learn_association(c2, c3, AssociationType::Causal, 0.9)
+            .unwrap();
+        wait_for_edges(&a, c1);
+        wait_for_edges(&b, c2);
+
+        let shards = [a, b];
+        let StorageResponse::FindCausalChainOk { paths, cross_shard } =
+            find_causal_chain_response(&shards, &c1.to_hex(), "direct", 5)
+        else {
+            panic!("expected FindCausalChainOk");
+        };
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].concepts, vec![c1.to_hex(), c2.to_hex()]);
+        assert!(cross_shard);
+
+        // From c2 each shard has its own chain, and neither leads elsewhere
+        let StorageResponse::FindCausalChainOk { paths, cross_shard } =
+            find_causal_chain_response(&shards, &c2.to_hex(), "direct", 5)
+        else {
+            panic!("expected FindCausalChainOk");
+        };
+        assert_eq!(paths.len(), 2);
+        assert!(paths
+            .iter()
+            .any(|p| p.concepts == vec![c2.to_hex(), c3.to_hex()]));
+        assert!(!cross_shard);
+
+        let StorageResponse::FindPathSemanticOk {
+            paths, cross_shard, ..
+        } = find_path_semantic_response(
+            &shards,
+            &c1.to_hex(),
+            &c3.to_hex(),
+            SemanticFilterMsg::default(),
+            5,
+            10,
+            None,
+            None,
+            None,
+        )
+        else {
+            panic!("expected FindPathSemanticOk");
+        };
+        assert!(paths.is_empty());
+        assert!(cross_shard);
+
+        let StorageResponse::QueryBySemanticOk { concepts } = query_by_semantic_response(
+            &shards,
+            SemanticFilterMsg {
+                semantic_type: Some("causal".to_string()),
+                ..Default::default()
+            },
+            None,
+        ) else {
+            panic!("expected QueryBySemanticOk");
+        };
+        assert_eq!(concepts.len(), 3);
+    }
+}
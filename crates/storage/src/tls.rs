@@ -1,22 +1,29 @@
 //! TLS configuration for secure TCP connections
 //!
 //! Provides certificate loading, validation, and TLS acceptor creation.
+//! With client authentication enabled (mTLS) the acceptor only completes
+//! handshakes with clients whose certificate chains to the configured CA
+//! bundle; the certificate's common name becomes the client's identity.
 
 #![allow(unexpected_cfgs)] // dev-tools feature is optional
 
 use anyhow::{anyhow, Result};
-use rustls::{Certificate, PrivateKey, ServerConfig};
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig, ServerConnection};
 use rustls_pemfile::{certs, pkcs8_private_keys};
 use std::fs::File;
 use std::io::BufReader;
 use std::sync::Arc;
 use tokio_rustls::TlsAcceptor;
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Result, BERReader, Tag};
 
 /// TLS configuration builder
 pub struct TlsConfigBuilder {
     cert_path: Option<String>,
     key_path: Option<String>,
     client_auth_required: bool,
+    client_ca_path: Option<String>,
 }
 
 impl TlsConfigBuilder {
@@ -25,6 +32,7 @@ impl TlsConfigBuilder {
             cert_path: None,
             key_path: None,
             client_auth_required: false,
+            client_ca_path: None,
         }
     }
 
@@ -46,6 +54,12 @@ impl TlsConfigBuilder {
         self
     }
 
+    /// Set the CA bundle client certificates must chain to
+    pub fn client_ca_path(mut self, path: String) -> Self {
+        self.client_ca_path = Some(path);
+        self
+    }
+
     /// Load TLS configuration from environment variables
     pub fn from_env() -> Result<Self> {
         let cert_path = std::env::var("SUTRA_TLS_CERT")
@@ -63,6 +77,7 @@ impl TlsConfigBuilder {
             cert_path: Some(cert_path),
             key_path: Some(key_path),
             client_auth_required: client_auth,
+            client_ca_path: std::env::var("SUTRA_TLS_CLIENT_CA").ok(),
         })
     }
 
@@ -80,9 +95,30 @@ impl TlsConfigBuilder {
         let key = load_private_key(&key_path)?;
 
         // Build server config
-        let config = ServerConfig::builder()
-            .with_safe_defaults()
-            .with_no_client_auth()
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = if self.client_auth_required {
+            // No fallback to unauthenticated clients: a server asked to verify
+            // client certificates refuses to start rather than accept anyone
+            let ca_path = self.client_ca_path.ok_or_else(|| {
+                anyhow!(
+                    "Client authentication requires a CA bundle: set SUTRA_TLS_CLIENT_CA \
+                     or unset SUTRA_TLS_CLIENT_AUTH"
+                )
+            })?;
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(&ca_path)? {
+                roots
+                    .add(&cert)
+                    .map_err(|e| anyhow!("Invalid client CA certificate in {}: {}", ca_path, e))?;
+            }
+            if roots.is_empty() {
+                return Err(anyhow!("No CA certificates found in {}", ca_path));
+            }
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+        } else {
+            builder.with_no_client_auth()
+        };
+        let config = builder
             .with_single_cert(certs, key)
             .map_err(|e| anyhow!("TLS config error: {}", e))?;
 
@@ -131,6 +167,75 @@ fn load_private_key(path: &str) -> Result<PrivateKey> {
     Ok(PrivateKey(keys[0].clone()))
 }
 
+/// Identity of a client that presented a verified certificate (mTLS)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    /// Subject common name (CN) of the client certificate
+    pub common_name: String,
+}
+
+impl ClientIdentity {
+    /// Key the client's requests are tracked and rate limited under
+    pub fn caller(&self) -> String {
+        format!("cn:{}", self.common_name)
+    }
+
+    /// Identity from the verified certificate of a completed handshake
+    ///
+    /// `None` without client authentication or if the certificate has no CN.
+    pub fn from_connection(conn: &ServerConnection) -> Option<Self> {
+        let cert = conn.peer_certificates()?.first()?;
+        certificate_common_name(&cert.0).map(|common_name| Self { common_name })
+    }
+}
+
+/// id-at-commonName (2.5.4.3)
+const COMMON_NAME_OID: &[u64] = &[2, 5, 4, 3];
+
+/// Subject common name of a DER-encoded X.509 certificate
+fn certificate_common_name(der: &[u8]) -> Option<String> {
+    yasna::parse_der(der, |reader| {
+        reader.read_sequence(|reader| {
+            let common_name = reader.next().read_sequence(|tbs| {
+                // Optional version, then serial, signature algorithm, issuer and validity
+                tbs.read_optional(|r| r.read_tagged(Tag::context(0), |r| r.read_i64()))?;
+                tbs.next().read_bigint_bytes()?;
+                for _ in 0..3 {
+                    tbs.next().read_der()?;
+                }
+                let common_name = subject_common_name(tbs.next())?;
+                // Key, unique IDs and extensions
+                while tbs.read_optional(|r| r.read_der())?.is_some() {}
+                Ok(common_name)
+            })?;
+            reader.next().read_der()?; // signature algorithm
+            reader.next().read_der()?; // signature
+            Ok(common_name)
+        })
+    })
+    .ok()
+    .flatten()
+}
+
+/// First common name in a distinguished name (SEQUENCE OF SET OF attribute)
+fn subject_common_name(reader: BERReader) -> ASN1Result<Option<String>> {
+    let common_name_oid = ObjectIdentifier::from_slice(COMMON_NAME_OID);
+    let mut common_name = None;
+    reader.read_sequence_of(|names| {
+        names.read_set_of(|attribute| {
+            attribute.read_sequence(|attribute| {
+                let oid = attribute.next().read_oid()?;
+                let value = attribute.next().read_tagged_der()?;
+                if common_name.is_none() && oid == common_name_oid {
+                    common_name = value.as_str().map(str::to_string);
+                }
+                Ok(())
+            })
+        })
+    })?;
+    Ok(common_name)
+}
+
 /// Check if TLS is enabled via environment
 pub fn is_tls_enabled() -> bool {
     std::env::var("SUTRA_TLS_ENABLED")
@@ -213,4 +318,44 @@ MIIEvQIBADANBgkqhkiG9w0BAQEFAASCBKcwggSjAgEAAoIBAQC8zE+YfS1lsWwv
         let builder = TlsConfigBuilder::from_env();
         assert!(builder.is_ok());
     }
+
+    #[test]
+    fn test_certificate_common_name() {
+        let mut params = rcgen::CertificateParams::new(vec!["localhost".to_string()]);
+        params
+            .distinguished_name
+            .push(rcgen::DnType::OrganizationName, "Sutra");
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "ingest-worker-7");
+        let cert = rcgen::Certificate::from_params(params).unwrap();
+
+        assert_eq!(
+            certificate_common_name(&cert.serialize_der().unwrap()).as_deref(),
+            Some("ingest-worker-7")
+        );
+        assert_eq!(certificate_common_name(b"not a certificate"), None);
+    }
+
+    #[test]
+    fn test_client_auth_requires_ca_bundle() {
+        let dir = tempdir().unwrap();
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+        std::fs::write(&cert_path, cert.serialize_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+
+        let builder = || {
+            TlsConfigBuilder::new()
+                .cert_path(cert_path.to_str().unwrap().to_string())
+                .key_path(key_path.to_str().unwrap().to_string())
+                .require_client_auth(true)
+        };
+        assert!(builder().build().is_err());
+        assert!(builder()
+            .client_ca_path(cert_path.to_str().unwrap().to_string())
+            .build()
+            .is_ok());
+    }
 }
@@ -68,6 +68,8 @@ pub enum TxnOperation {
         assoc_type: AssociationType,
         strength: f32,
     },
+    /// Single-shard batch of writes applied all-or-nothing
+    LocalBatch { shard_id: u32, writes: usize },
 }
 
 /// Transaction coordinator (manages 2PC protocol)
@@ -114,6 +116,11 @@ impl TransactionCoordinator {
 
                 parts
             }
+            TxnOperation::LocalBatch { shard_id, .. } => vec![Participant {
+                shard_id: *shard_id,
+                state: TxnState::Preparing,
+                prepared_at: None,
+            }],
         };
 
         let txn = Transaction {
@@ -326,6 +333,13 @@ pub enum TxnError {
         expected: TxnState,
         actual: TxnState,
     },
+    /// An operation failed validation; nothing was applied
+    Rejected { index: usize, reason: String },
+    /// Writing the transaction failed; nothing was applied
+    WriteFailed(String),
+    /// The write log refused the batch (full or under backpressure);
+    /// nothing was applied and the same batch can be retried
+    WriteLogBusy(crate::write_log::WriteLogError),
 }
 
 impl std::fmt::Display for TxnError {
@@ -345,6 +359,11 @@ impl std::fmt::Display for TxnError {
                 "Transaction {} invalid state: expected {:?}, got {:?}",
                 txn_id, expected, actual
             ),
+            TxnError::Rejected { index, reason } => {
+                write!(f, "operation {}: {}", index, reason)
+            }
+            TxnError::WriteFailed(reason) => write!(f, "write failed: {}", reason),
+            TxnError::WriteLogBusy(error) => write!(f, "{}", error),
         }
     }
 }
@@ -1,6 +1,7 @@
 /// Dense vector storage with compression
 ///
-/// Stores float32 vectors efficiently with optional Product Quantization.
+/// Stores float32 vectors efficiently with optional Product Quantization
+/// or per-vector int8 scalar quantization.
 /// Supports incremental updates and persistence.
 use anyhow::{Context, Result};
 use parking_lot::RwLock;
@@ -14,17 +15,53 @@ use std::sync::Arc;
 use crate::quantization::ProductQuantizer;
 use crate::types::ConceptId;
 
+/// On-disk / in-memory encoding of stored vectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VectorEncoding {
+    /// Full-precision float32 only
+    Float32,
+    /// int8 codes with one f32 scale per vector (~4x smaller, no training)
+    Int8Scaled,
+    /// float32 plus Product Quantization codes once the quantizer is trained
+    #[default]
+    ProductQuantized,
+}
+
+/// Similarity used to rank search results
+///
+/// On L2-normalized vectors the two metrics produce identical rankings, since
+/// cosine similarity is then just the dot product; dot product skips the norm
+/// computation per candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VectorMetric {
+    /// `dot(a, b) / (|a| * |b|)`
+    #[default]
+    Cosine,
+    /// Raw `dot(a, b)`; only meaningful as cosine on normalized vectors
+    DotProduct,
+}
+
 /// Vector storage configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorConfig {
     /// Vector dimensionality
     pub dimension: usize,
-    /// Whether to use compression
+    /// Whether to use compression (Product Quantization encoding only)
     pub use_compression: bool,
     /// Number of subvectors for Product Quantization
     pub num_subvectors: usize,
     /// Number of centroids per subvector
     pub num_centroids: usize,
+    /// How vectors are encoded in the store
+    #[serde(default)]
+    pub encoding: VectorEncoding,
+    /// Similarity used by `search` and `distance`
+    #[serde(default)]
+    pub metric: VectorMetric,
+    /// L2-normalize vectors before they are stored and queries before they
+    /// are scored, so stored vectors have unit length under either metric
+    #[serde(default)]
+    pub normalize_on_insert: bool,
 }
 
 impl Default for VectorConfig {
@@ -34,10 +71,41 @@ impl Default for VectorConfig {
             use_compression: true,
             num_subvectors: 48, // 384 / 8
             num_centroids: 256,
+            encoding: VectorEncoding::ProductQuantized,
+            metric: VectorMetric::Cosine,
+            normalize_on_insert: false,
         }
     }
 }
 
+/// int8 scalar-quantized vector: `value ≈ code as f32 * scale`
+#[derive(Debug, Clone)]
+struct Int8Vector {
+    scale: f32,
+    codes: Vec<i8>,
+}
+
+impl Int8Vector {
+    fn encode(vector: &[f32]) -> Self {
+        let max_abs = vector.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+        let scale = if max_abs > 0.0 { max_abs / 127.0 } else { 1.0 };
+        let codes = vector
+            .iter()
+            .map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+            .collect();
+        Self { scale, codes }
+    }
+
+    fn decode(&self) -> Vec<f32> {
+        self.codes.iter().map(|&c| c as f32 * self.scale).collect()
+    }
+
+    /// Encoded size in bytes (codes + scale)
+    fn size_bytes(&self) -> usize {
+        self.codes.len() + std::mem::size_of::<f32>()
+    }
+}
+
 /// Vector metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorMetadata {
@@ -63,6 +131,8 @@ pub struct VectorStore {
     quantizer: Arc<RwLock<Option<ProductQuantizer>>>,
     /// Compressed vectors (concept_id -> codes)
     compressed_vectors: Arc<RwLock<HashMap<ConceptId, Vec<u8>>>>,
+    /// int8 scalar-quantized vectors (Int8Scaled encoding only)
+    int8_vectors: Arc<RwLock<HashMap<ConceptId, Int8Vector>>>,
     /// Metadata
     metadata: Arc<RwLock<HashMap<ConceptId, VectorMetadata>>>,
 }
@@ -79,6 +149,7 @@ impl VectorStore {
             raw_vectors: Arc::new(RwLock::new(HashMap::new())),
             quantizer: Arc::new(RwLock::new(None)),
             compressed_vectors: Arc::new(RwLock::new(HashMap::new())),
+            int8_vectors: Arc::new(RwLock::new(HashMap::new())),
             metadata: Arc::new(RwLock::new(HashMap::new())),
         })
     }
@@ -111,6 +182,11 @@ impl VectorStore {
             store.load_vectors(&vectors_path)?;
         }
 
+        let int8_path = path.join("vectors_int8.bin");
+        if int8_path.exists() {
+            store.load_int8_vectors(&int8_path)?;
+        }
+
         Ok(store)
     }
 
@@ -123,28 +199,50 @@ impl VectorStore {
                 vector.len()
             );
         }
+        let vector = if self.config.normalize_on_insert {
+            l2_normalized(&vector)
+        } else {
+            vector
+        };
 
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_micros() as u64;
 
-        // Store raw vector
-        self.raw_vectors.write().insert(concept_id, vector.clone());
-
-        // Compress if enabled and quantizer is trained
-        if self.config.use_compression {
-            if let Some(quantizer) = self.quantizer.read().as_ref() {
-                let codes = quantizer.encode(&vector)?;
-                self.compressed_vectors.write().insert(concept_id, codes);
+        let dimension = vector.len();
+        let compressed = match self.config.encoding {
+            VectorEncoding::Int8Scaled => {
+                // Only the int8 form is kept; reads dequantize on the fly
+                self.int8_vectors
+                    .write()
+                    .insert(concept_id, Int8Vector::encode(&vector));
+                true
             }
-        }
+            VectorEncoding::Float32 => {
+                self.raw_vectors.write().insert(concept_id, vector);
+                false
+            }
+            VectorEncoding::ProductQuantized => {
+                // Store raw vector
+                self.raw_vectors.write().insert(concept_id, vector.clone());
+
+                // Compress if enabled and quantizer is trained
+                if self.config.use_compression {
+                    if let Some(quantizer) = self.quantizer.read().as_ref() {
+                        let codes = quantizer.encode(&vector)?;
+                        self.compressed_vectors.write().insert(concept_id, codes);
+                    }
+                }
+                self.config.use_compression && self.quantizer.read().is_some()
+            }
+        };
 
         // Store metadata
         let metadata = VectorMetadata {
             concept_id,
-            dimension: vector.len(),
-            compressed: self.config.use_compression && self.quantizer.read().is_some(),
+            dimension,
+            compressed,
             timestamp,
         };
         self.metadata.write().insert(concept_id, metadata);
@@ -152,9 +250,16 @@ impl VectorStore {
         Ok(())
     }
 
-    /// Get a vector (returns raw vector)
+    /// Get a vector (raw, or dequantized for Int8Scaled)
     pub fn get_vector(&self, concept_id: ConceptId) -> Option<Vec<f32>> {
-        self.raw_vectors.read().get(&concept_id).cloned()
+        match self.config.encoding {
+            VectorEncoding::Int8Scaled => self
+                .int8_vectors
+                .read()
+                .get(&concept_id)
+                .map(Int8Vector::decode),
+            _ => self.raw_vectors.read().get(&concept_id).cloned(),
+        }
     }
 
     /// Get compressed codes
@@ -166,13 +271,15 @@ impl VectorStore {
     pub fn remove_vector(&self, concept_id: ConceptId) -> Result<()> {
         self.raw_vectors.write().remove(&concept_id);
         self.compressed_vectors.write().remove(&concept_id);
+        self.int8_vectors.write().remove(&concept_id);
         self.metadata.write().remove(&concept_id);
         Ok(())
     }
 
     /// Train the quantizer on existing vectors
     pub fn train_quantizer(&self, training_vectors: Option<Vec<Vec<f32>>>) -> Result<()> {
-        if !self.config.use_compression {
+        if !self.config.use_compression || self.config.encoding != VectorEncoding::ProductQuantized
+        {
             return Ok(());
         }
 
@@ -221,21 +328,63 @@ impl VectorStore {
 
     /// Compute distance between two concepts
     pub fn distance(&self, id1: ConceptId, id2: ConceptId) -> Result<f32> {
-        // Use raw vectors for accurate distance
-        let v1 = self
-            .raw_vectors
-            .read()
-            .get(&id1)
-            .context("Vector 1 not found")?
-            .clone();
-        let v2 = self
-            .raw_vectors
-            .read()
-            .get(&id2)
-            .context("Vector 2 not found")?
-            .clone();
+        // Use the most precise stored form for distance
+        let v1 = self.get_vector(id1).context("Vector 1 not found")?;
+        let v2 = self.get_vector(id2).context("Vector 2 not found")?;
 
-        Ok(Self::cosine_distance(&v1, &v2))
+        Ok(1.0 - self.similarity(&v1, &v2))
+    }
+
+    /// Brute-force k-nearest search by the configured metric over the stored encoding.
+    ///
+    /// Float32 scans raw vectors, Int8Scaled dequantizes each candidate, and
+    /// ProductQuantized scores PQ-decoded vectors once the quantizer is trained
+    /// (falling back to raw vectors before training).
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<(ConceptId, f32)>> {
+        if query.len() != self.config.dimension {
+            anyhow::bail!(
+                "Query dimension mismatch: expected {}, got {}",
+                self.config.dimension,
+                query.len()
+            );
+        }
+        let normalized;
+        let query = if self.config.normalize_on_insert {
+            normalized = l2_normalized(query);
+            &normalized[..]
+        } else {
+            query
+        };
+
+        let mut scored: Vec<(ConceptId, f32)> = match self.config.encoding {
+            VectorEncoding::Int8Scaled => self
+                .int8_vectors
+                .read()
+                .iter()
+                .map(|(id, v)| (*id, self.similarity(query, &v.decode())))
+                .collect(),
+            VectorEncoding::ProductQuantized if self.quantizer.read().is_some() => {
+                let quantizer = self.quantizer.read();
+                let quantizer = quantizer.as_ref().context("Quantizer not trained")?;
+                let compressed = self.compressed_vectors.read();
+                let mut scored = Vec::with_capacity(compressed.len());
+                for (id, codes) in compressed.iter() {
+                    let decoded = quantizer.decode(codes)?;
+                    scored.push((*id, self.similarity(query, &decoded)));
+                }
+                scored
+            }
+            _ => self
+                .raw_vectors
+                .read()
+                .iter()
+                .map(|(id, v)| (*id, self.similarity(query, v)))
+                .collect(),
+        };
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        Ok(scored)
     }
 
     /// Compute approximate distance using compressed vectors
@@ -259,6 +408,14 @@ impl VectorStore {
         quantizer.compute_distance(&codes1, &codes2)
     }
 
+    /// Similarity under the configured metric (higher is closer)
+    fn similarity(&self, v1: &[f32], v2: &[f32]) -> f32 {
+        match self.config.metric {
+            VectorMetric::Cosine => 1.0 - Self::cosine_distance(v1, v2),
+            VectorMetric::DotProduct => v1.iter().zip(v2.iter()).map(|(a, b)| a * b).sum(),
+        }
+    }
+
     /// Cosine distance between two vectors
     fn cosine_distance(v1: &[f32], v2: &[f32]) -> f32 {
         let dot: f32 = v1.iter().zip(v2.iter()).map(|(a, b)| a * b).sum();
@@ -289,6 +446,89 @@ impl VectorStore {
         let vectors_path = self.path.join("vectors.bin");
         self.save_vectors(&vectors_path)?;
 
+        if self.config.encoding == VectorEncoding::Int8Scaled {
+            let int8_path = self.path.join("vectors_int8.bin");
+            self.save_int8_vectors(&int8_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Save int8 vectors to binary file
+    ///
+    /// Layout: count, then per vector: id, metadata (len + json), scale, len, codes
+    fn save_int8_vectors(&self, path: &Path) -> Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        let mut writer = BufWriter::new(file);
+
+        let int8 = self.int8_vectors.read();
+        let metadata_map = self.metadata.read();
+
+        writer.write_all(&(int8.len() as u32).to_le_bytes())?;
+
+        for (id, vector) in int8.iter() {
+            writer.write_all(&id.0)?;
+
+            if let Some(meta) = metadata_map.get(id) {
+                let meta_json = serde_json::to_string(meta)?;
+                writer.write_all(&(meta_json.len() as u32).to_le_bytes())?;
+                writer.write_all(meta_json.as_bytes())?;
+            } else {
+                writer.write_all(&0u32.to_le_bytes())?;
+            }
+
+            writer.write_all(&vector.scale.to_le_bytes())?;
+            writer.write_all(&(vector.codes.len() as u32).to_le_bytes())?;
+            let bytes: Vec<u8> = vector.codes.iter().map(|&c| c as u8).collect();
+            writer.write_all(&bytes)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load int8 vectors from binary file
+    fn load_int8_vectors(&self, path: &Path) -> Result<()> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+
+        let mut u32_bytes = [0u8; 4];
+        reader.read_exact(&mut u32_bytes)?;
+        let count = u32::from_le_bytes(u32_bytes);
+
+        let mut int8 = self.int8_vectors.write();
+        let mut metadata_map = self.metadata.write();
+
+        for _ in 0..count {
+            let mut id_bytes = [0u8; 16];
+            reader.read_exact(&mut id_bytes)?;
+            let concept_id = ConceptId(id_bytes);
+
+            reader.read_exact(&mut u32_bytes)?;
+            let meta_len = u32::from_le_bytes(u32_bytes);
+            if meta_len > 0 {
+                let mut meta_bytes = vec![0u8; meta_len as usize];
+                reader.read_exact(&mut meta_bytes)?;
+                let meta: VectorMetadata = serde_json::from_slice(&meta_bytes)?;
+                metadata_map.insert(concept_id, meta);
+            }
+
+            reader.read_exact(&mut u32_bytes)?;
+            let scale = f32::from_le_bytes(u32_bytes);
+
+            reader.read_exact(&mut u32_bytes)?;
+            let len = u32::from_le_bytes(u32_bytes);
+            let mut bytes = vec![0u8; len as usize];
+            reader.read_exact(&mut bytes)?;
+            let codes = bytes.into_iter().map(|b| b as i8).collect();
+
+            int8.insert(concept_id, Int8Vector { scale, codes });
+        }
+
         Ok(())
     }
 
@@ -410,13 +650,24 @@ impl VectorStore {
     pub fn stats(&self) -> VectorStats {
         let raw = self.raw_vectors.read();
         let compressed = self.compressed_vectors.read();
-
-        let raw_count = raw.len();
-        let compressed_count = compressed.len();
-
-        // Estimate memory usage
-        let raw_size = raw.values().map(|v| v.len() * 4).sum::<usize>();
-        let compressed_size = compressed.values().map(|c| c.len()).sum::<usize>();
+        let int8 = self.int8_vectors.read();
+
+        let (raw_count, compressed_count, raw_size, compressed_size) = match self.config.encoding {
+            VectorEncoding::Int8Scaled => (
+                int8.len(),
+                int8.len(),
+                // Equivalent float32 footprint, for the compression ratio
+                int8.values().map(|v| v.codes.len() * 4).sum::<usize>(),
+                int8.values().map(Int8Vector::size_bytes).sum::<usize>(),
+            ),
+            _ => (
+                raw.len(),
+                compressed.len(),
+                // Estimate memory usage
+                raw.values().map(|v| v.len() * 4).sum::<usize>(),
+                compressed.values().map(|c| c.len()).sum::<usize>(),
+            ),
+        };
 
         let compression_ratio = if compressed_size > 0 {
             raw_size as f32 / compressed_size as f32
@@ -432,6 +683,7 @@ impl VectorStore {
             compressed_size_bytes: compressed_size,
             compression_ratio,
             quantizer_trained: self.quantizer.read().is_some(),
+            encoding: self.config.encoding,
         }
     }
 
@@ -439,6 +691,7 @@ impl VectorStore {
     pub fn clear(&self) {
         self.raw_vectors.write().clear();
         self.compressed_vectors.write().clear();
+        self.int8_vectors.write().clear();
         self.metadata.write().clear();
     }
 }
@@ -453,6 +706,16 @@ pub struct VectorStats {
     pub compressed_size_bytes: usize,
     pub compression_ratio: f32,
     pub quantizer_trained: bool,
+    pub encoding: VectorEncoding,
+}
+
+/// Scale `vector` to unit L2 norm; zero vectors are returned unchanged
+pub(crate) fn l2_normalized(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|x| x / norm).collect()
 }
 
 #[cfg(test)]
@@ -608,6 +871,7 @@ mod tests {
             use_compression: true,
             num_subvectors: 8,
             num_centroids: 16,
+            ..Default::default()
         };
 
         let store = VectorStore::new(dir.path(), config).unwrap();
@@ -638,4 +902,161 @@ mod tests {
         assert!(exact_dist >= 0.0);
         assert!(approx_dist >= 0.0);
     }
+
+    fn pseudo_random_vector(seed: u64, dim: usize) -> Vec<f32> {
+        let mut state = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (0..dim)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_int8_encoding_recall_and_size() {
+        let dim = 64;
+        let corpus: Vec<Vec<f32>> = (0..300).map(|i| pseudo_random_vector(i, dim)).collect();
+        let queries: Vec<Vec<f32>> = (0..20)
+            .map(|i| pseudo_random_vector(10_000 + i, dim))
+            .collect();
+
+        let build = |encoding: VectorEncoding| {
+            let dir = TempDir::new().unwrap();
+            let store = VectorStore::new(
+                dir.path(),
+                VectorConfig {
+                    dimension: dim,
+                    num_subvectors: 16,
+                    num_centroids: 16,
+                    encoding,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            for (i, v) in corpus.iter().enumerate() {
+                store
+                    .add_vector(test_concept_id(i as u64), v.clone())
+                    .unwrap();
+            }
+            store.train_quantizer(None).unwrap();
+            (dir, store)
+        };
+
+        let (_d1, exact) = build(VectorEncoding::Float32);
+        let (_d2, int8) = build(VectorEncoding::Int8Scaled);
+        let (_d3, pq) = build(VectorEncoding::ProductQuantized);
+
+        let recall_at_10 = |store: &VectorStore| {
+            let mut hits = 0;
+            for q in &queries {
+                let truth: std::collections::HashSet<ConceptId> = exact
+                    .search(q, 10)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .collect();
+                hits += store
+                    .search(q, 10)
+                    .unwrap()
+                    .into_iter()
+                    .filter(|(id, _)| truth.contains(id))
+                    .count();
+            }
+            hits as f32 / (queries.len() * 10) as f32
+        };
+
+        let int8_recall = recall_at_10(&int8);
+        let pq_recall = recall_at_10(&pq);
+        assert!(int8_recall >= 0.9, "int8 recall@10 = {}", int8_recall);
+        assert!(
+            int8_recall >= pq_recall,
+            "int8 {} < pq {}",
+            int8_recall,
+            pq_recall
+        );
+
+        // int8 codes + one scale per vector: ~4x smaller than float32
+        let stats = int8.stats();
+        assert_eq!(stats.encoding, VectorEncoding::Int8Scaled);
+        assert_eq!(stats.total_vectors, corpus.len());
+        assert_eq!(stats.raw_size_bytes, corpus.len() * dim * 4);
+        assert_eq!(stats.compressed_size_bytes, corpus.len() * (dim + 4));
+        assert!(stats.compression_ratio > 3.5 && stats.compression_ratio <= 4.0);
+
+        // Dequantized vectors stay close to the originals and survive save/load
+        let original = &corpus[0];
+        let restored = int8.get_vector(test_concept_id(0)).unwrap();
+        let max_err = original
+            .iter()
+            .zip(&restored)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+        assert!(max_err < 0.01, "max dequant error {}", max_err);
+
+        int8.save().unwrap();
+        let reloaded = VectorStore::load(&int8.path).unwrap();
+        assert_eq!(reloaded.get_vector(test_concept_id(0)).unwrap(), restored);
+    }
+
+    #[test]
+    fn test_normalized_cosine_and_dot_product_rank_identically() {
+        let dim = 32;
+        // Varying magnitudes: unnormalized dot product would favour long vectors
+        let corpus: Vec<Vec<f32>> = (0..200)
+            .map(|i| {
+                let scale = 1.0 + (i % 7) as f32 * 3.0;
+                pseudo_random_vector(i, dim)
+                    .into_iter()
+                    .map(|x| x * scale)
+                    .collect()
+            })
+            .collect();
+
+        let build = |metric: VectorMetric| {
+            let dir = TempDir::new().unwrap();
+            let store = VectorStore::new(
+                dir.path(),
+                VectorConfig {
+                    dimension: dim,
+                    encoding: VectorEncoding::Float32,
+                    metric,
+                    normalize_on_insert: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            for (i, v) in corpus.iter().enumerate() {
+                store
+                    .add_vector(test_concept_id(i as u64), v.clone())
+                    .unwrap();
+            }
+            (dir, store)
+        };
+        let (_d1, cosine) = build(VectorMetric::Cosine);
+        let (_d2, dot) = build(VectorMetric::DotProduct);
+
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let stored = dot.get_vector(test_concept_id(3)).unwrap();
+        assert!((norm(&stored) - 1.0).abs() < 1e-5);
+
+        for q in 0..20 {
+            let query: Vec<f32> = pseudo_random_vector(5_000 + q, dim)
+                .into_iter()
+                .map(|x| x * 10.0)
+                .collect();
+            let by_cosine = cosine.search(&query, 10).unwrap();
+            let by_dot = dot.search(&query, 10).unwrap();
+
+            let ids = |r: &[(ConceptId, f32)]| r.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+            assert_eq!(ids(&by_cosine), ids(&by_dot));
+            for ((_, a), (_, b)) in by_cosine.iter().zip(&by_dot) {
+                assert!((a - b).abs() < 1e-5);
+            }
+        }
+    }
 }
@@ -5,7 +5,7 @@
 ///
 /// Features:
 /// - Append-only log format
-/// - Fsync control for durability
+/// - Configurable sync policy (per-append, interval, or OS-buffered)
 /// - Transaction support (begin/commit/rollback)
 /// - Atomic batch writes
 /// - Automatic log rotation
@@ -17,6 +17,54 @@ use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// When the WAL forces appended entries to stable storage.
+///
+/// - `Always`: flush and fsync per append. Every acknowledged entry is
+///   durable.
+/// - `EverySync(interval)`: each append is handed to the OS, so a process
+///   crash loses nothing; fsync happens on the first append after `interval`
+///   has elapsed since the last sync, or from the timer started by
+///   [`spawn_sync_timer`] when appends stop. A machine crash can lose roughly
+///   one interval's worth of entries.
+/// - `Never`: appends stay in the userspace buffer until it fills or the log
+///   is flushed, synced or dropped, and there is no fsync. Even a process
+///   crash can lose buffered entries. Fastest, least durable.
+///
+/// In all cases recovery sees a prefix of the log: a torn final entry is
+/// detected by its length prefix and ignored on replay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SyncPolicy {
+    #[default]
+    Always,
+    EverySync(Duration),
+    Never,
+}
+
+impl std::str::FromStr for SyncPolicy {
+    type Err = anyhow::Error;
+
+    /// Parse `always`, `never`, or an interval in milliseconds (`every:100`)
+    ///
+    /// A zero interval is refused: the sync timer would never sleep.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "always" => Ok(SyncPolicy::Always),
+            "never" => Ok(SyncPolicy::Never),
+            other => {
+                let ms = other
+                    .strip_prefix("every:")
+                    .and_then(|ms| ms.parse::<u64>().ok())
+                    .ok_or_else(|| anyhow::anyhow!("Invalid WAL sync policy: {}", s))?;
+                if ms == 0 {
+                    anyhow::bail!("WAL sync interval must be > 0: {}", s);
+                }
+                Ok(SyncPolicy::EverySync(Duration::from_millis(ms)))
+            }
+        }
+    }
+}
 
 /// WAL operation type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -39,6 +87,11 @@ pub enum Operation {
     },
     /// Delete a concept
     DeleteConcept { concept_id: ConceptId },
+    /// Edit a concept's strength, confidence or attributes in place
+    UpdateConcept {
+        concept_id: ConceptId,
+        modified: u64,
+    },
     /// Delete an association
     DeleteAssociation { association_id: AssociationId },
     /// Begin transaction
@@ -87,8 +140,14 @@ pub struct WriteAheadLog {
     writer: BufWriter<File>,
     /// Next sequence number
     next_sequence: Arc<AtomicU64>,
-    /// Whether to fsync after each write
-    fsync: bool,
+    /// When appends are fsynced
+    sync_policy: SyncPolicy,
+    /// Time of the last fsync (for `SyncPolicy::EverySync`)
+    last_sync: Instant,
+    /// Number of fsyncs issued
+    sync_count: u64,
+    /// Entries appended since the last fsync
+    unsynced: bool,
     /// Current transaction ID (if in transaction)
     current_transaction: Option<u64>,
     /// Next transaction ID
@@ -96,8 +155,26 @@ pub struct WriteAheadLog {
 }
 
 impl WriteAheadLog {
-    /// Create a new WAL
+    /// Create a new WAL, fsyncing every append if `fsync` is set
     pub fn create<P: AsRef<Path>>(path: P, fsync: bool) -> Result<Self> {
+        Self::create_with_policy(path, Self::policy_for(fsync))
+    }
+
+    /// Open existing WAL, fsyncing every append if `fsync` is set
+    pub fn open<P: AsRef<Path>>(path: P, fsync: bool) -> Result<Self> {
+        Self::open_with_policy(path, Self::policy_for(fsync))
+    }
+
+    fn policy_for(fsync: bool) -> SyncPolicy {
+        if fsync {
+            SyncPolicy::Always
+        } else {
+            SyncPolicy::Never
+        }
+    }
+
+    /// Create a new WAL with the given sync policy
+    pub fn create_with_policy<P: AsRef<Path>>(path: P, sync_policy: SyncPolicy) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
 
         let file = OpenOptions::new()
@@ -112,14 +189,17 @@ impl WriteAheadLog {
             path,
             writer,
             next_sequence: Arc::new(AtomicU64::new(0)),
-            fsync,
+            sync_policy,
+            last_sync: Instant::now(),
+            sync_count: 0,
+            unsynced: false,
             current_transaction: None,
             next_transaction_id: Arc::new(AtomicU64::new(1)),
         })
     }
 
-    /// Open existing WAL
-    pub fn open<P: AsRef<Path>>(path: P, fsync: bool) -> Result<Self> {
+    /// Open existing WAL with the given sync policy
+    pub fn open_with_policy<P: AsRef<Path>>(path: P, sync_policy: SyncPolicy) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
 
         // Read existing entries to determine next sequence
@@ -138,7 +218,10 @@ impl WriteAheadLog {
             path,
             writer,
             next_sequence: Arc::new(AtomicU64::new(next_sequence)),
-            fsync,
+            sync_policy,
+            last_sync: Instant::now(),
+            sync_count: 0,
+            unsynced: false,
             current_transaction: None,
             next_transaction_id: Arc::new(AtomicU64::new(1)),
         })
@@ -163,17 +246,33 @@ impl WriteAheadLog {
             .write_all(&bytes)
             .context("Failed to write entry")?;
 
-        if self.fsync {
-            self.writer.flush().context("Failed to flush")?;
-            self.writer
-                .get_ref()
-                .sync_all()
-                .context("Failed to fsync")?;
+        self.unsynced = true;
+        match self.sync_policy {
+            SyncPolicy::Always => self.sync()?,
+            SyncPolicy::EverySync(_) => {
+                self.writer.flush().context("Failed to flush")?;
+                self.sync_if_due()?;
+            }
+            SyncPolicy::Never => {}
         }
 
         Ok(sequence)
     }
 
+    /// Fsync if the `EverySync` interval has elapsed with entries still
+    /// unsynced; returns whether it synced
+    pub fn sync_if_due(&mut self) -> Result<bool> {
+        match self.sync_policy {
+            SyncPolicy::EverySync(interval)
+                if self.unsynced && self.last_sync.elapsed() >= interval =>
+            {
+                self.sync()?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     /// Begin a transaction
     pub fn begin_transaction(&mut self) -> Result<u64> {
         if self.current_transaction.is_some() {
@@ -225,9 +324,22 @@ impl WriteAheadLog {
             .get_ref()
             .sync_all()
             .context("Failed to sync WAL")?;
+        self.last_sync = Instant::now();
+        self.sync_count += 1;
+        self.unsynced = false;
         Ok(())
     }
 
+    /// Get the sync policy
+    pub fn sync_policy(&self) -> SyncPolicy {
+        self.sync_policy
+    }
+
+    /// Number of fsyncs issued since the WAL was opened
+    pub fn sync_count(&self) -> u64 {
+        self.sync_count
+    }
+
     /// Read all entries from the log
     pub fn read_entries<P: AsRef<Path>>(path: P) -> Result<Vec<LogEntry>> {
         use std::io::Read;
@@ -312,10 +424,13 @@ impl WriteAheadLog {
 
     /// Truncate the log (remove all entries)
     pub fn truncate(&mut self) -> Result<()> {
-        drop(std::mem::replace(
+        let old = std::mem::replace(
             &mut self.writer,
             BufWriter::new(File::create(&self.path).context("Failed to truncate WAL")?),
-        ));
+        );
+        // Discard anything still buffered rather than flushing it into the
+        // truncated file
+        let _ = old.into_parts();
 
         self.next_sequence.store(0, Ordering::SeqCst);
 
@@ -333,6 +448,30 @@ impl WriteAheadLog {
     }
 }
 
+/// Fsync `wal` from a background thread once `interval` has passed with
+/// entries unsynced, so the tail of an `EverySync` log is not left waiting
+/// for another append. The thread exits once the log is dropped.
+pub fn spawn_sync_timer(
+    wal: std::sync::Weak<std::sync::Mutex<WriteAheadLog>>,
+    interval: Duration,
+) -> std::thread::JoinHandle<()> {
+    std::thread::Builder::new()
+        .name("wal-sync".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(interval);
+            let Some(wal) = wal.upgrade() else {
+                return;
+            };
+            let Ok(mut wal) = wal.lock() else {
+                return;
+            };
+            if let Err(e) = wal.sync_if_due() {
+                log::warn!("WAL timer sync failed: {}", e);
+            }
+        })
+        .expect("Failed to spawn WAL sync thread")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -594,4 +733,103 @@ mod tests {
         let entries = WriteAheadLog::read_entries(&path).unwrap();
         assert_eq!(entries.len(), 1);
     }
+
+    #[test]
+    fn test_sync_policy_fsync_counts() {
+        let dir = TempDir::new().unwrap();
+        let appends = 200;
+
+        let mut syncs = Vec::new();
+        for (name, policy) in [
+            ("always", SyncPolicy::Always),
+            ("every", SyncPolicy::EverySync(Duration::from_millis(50))),
+            ("never", SyncPolicy::Never),
+        ] {
+            let path = dir.path().join(format!("{}.wal", name));
+            let mut wal = WriteAheadLog::create_with_policy(&path, policy).unwrap();
+
+            for i in 0..appends {
+                wal.append(Operation::WriteConcept {
+                    concept_id: test_concept_id(i as u64),
+                    content_len: 100,
+                    vector_len: 384,
+                    created: 1000,
+                    modified: 1000,
+                })
+                .unwrap();
+            }
+            syncs.push(wal.sync_count());
+
+            let on_disk = WriteAheadLog::read_entries(&path).unwrap().len();
+            if policy == SyncPolicy::Never {
+                // Buffered until an explicit flush
+                assert!(on_disk < appends);
+                wal.flush().unwrap();
+                assert_eq!(WriteAheadLog::read_entries(&path).unwrap().len(), appends);
+            } else {
+                assert_eq!(on_disk, appends);
+            }
+        }
+
+        // Throughput is dominated by fsync cost, so compare fsyncs issued
+        assert_eq!(syncs[0], appends as u64);
+        assert!(syncs[1] < syncs[0]);
+        assert_eq!(syncs[2], 0);
+    }
+
+    #[test]
+    fn test_every_sync_timer_syncs_idle_tail() {
+        let dir = TempDir::new().unwrap();
+        let interval = Duration::from_millis(100);
+        let wal = Arc::new(std::sync::Mutex::new(
+            WriteAheadLog::create_with_policy(
+                dir.path().join("idle.wal"),
+                SyncPolicy::EverySync(interval),
+            )
+            .unwrap(),
+        ));
+        let timer = spawn_sync_timer(Arc::downgrade(&wal), interval);
+
+        // The first append lands inside the interval, so it is not synced
+        wal.lock()
+            .unwrap()
+            .append(Operation::WriteConcept {
+                concept_id: test_concept_id(1),
+                content_len: 100,
+                vector_len: 384,
+                created: 1000,
+                modified: 1000,
+            })
+            .unwrap();
+        assert_eq!(wal.lock().unwrap().sync_count(), 0);
+
+        // No further appends: the timer syncs the tail
+        let start = Instant::now();
+        while wal.lock().unwrap().sync_count() == 0 {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "tail never synced"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        // Nothing new to sync: the count stays put
+        std::thread::sleep(interval * 3);
+        assert_eq!(wal.lock().unwrap().sync_count(), 1);
+
+        drop(wal);
+        timer.join().unwrap();
+    }
+
+    #[test]
+    fn test_sync_policy_parse() {
+        assert_eq!("always".parse::<SyncPolicy>().unwrap(), SyncPolicy::Always);
+        assert_eq!("Never".parse::<SyncPolicy>().unwrap(), SyncPolicy::Never);
+        assert_eq!(
+            "every:250".parse::<SyncPolicy>().unwrap(),
+            SyncPolicy::EverySync(Duration::from_millis(250))
+        );
+        assert!("sometimes".parse::<SyncPolicy>().is_err());
+        assert!("every:0".parse::<SyncPolicy>().is_err());
+    }
 }
@@ -11,6 +11,7 @@
 use crate::semantic::SemanticMetadata;
 use crate::types::{AssociationRecord, ConceptId};
 use crossbeam::channel::{bounded, Receiver, Sender, TrySendError};
+use dashmap::DashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
@@ -38,6 +39,17 @@ pub enum WriteEntry {
     /// Update concept strength (from temporal decay)
     UpdateStrength { id: ConceptId, strength: f32 },
 
+    /// Edit an existing concept in place, keeping its content, vector and edges
+    ///
+    /// Ignored if the concept doesn't exist when the entry is applied.
+    UpdateConcept {
+        id: ConceptId,
+        strength: Option<f32>,
+        confidence: Option<f32>,
+        /// Merged into the existing attributes
+        attributes: std::collections::HashMap<String, String>,
+    },
+
     /// Record access (for heat tracking)
     RecordAccess { id: ConceptId, timestamp: u64 },
 
@@ -49,6 +61,33 @@ pub enum WriteEntry {
 
     /// Batch marker (for checkpointing)
     BatchMarker { sequence: u64 },
+
+    /// Entries that become visible in the same snapshot (transactions)
+    Atomic { entries: Vec<WriteEntry> },
+}
+
+impl WriteEntry {
+    /// The individual entries to apply (an `Atomic` entry's contents, else itself)
+    pub fn entries(&self) -> &[WriteEntry] {
+        match self {
+            WriteEntry::Atomic { entries } => entries,
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    fn delete_count(&self) -> u64 {
+        self.entries()
+            .iter()
+            .filter(|entry| matches!(entry, WriteEntry::DeleteConcept { .. }))
+            .count() as u64
+    }
+
+    fn added_concepts(&self) -> impl Iterator<Item = ConceptId> + '_ {
+        self.entries().iter().filter_map(|entry| match entry {
+            WriteEntry::AddConcept { id, .. } => Some(*id),
+            _ => None,
+        })
+    }
 }
 
 /// Lock-free write log
@@ -67,6 +106,15 @@ pub struct WriteLog {
 
     /// Total written
     written: Arc<AtomicU64>,
+
+    /// Concepts added by entries not yet applied, with how many each
+    pending_concepts: DashMap<ConceptId, usize>,
+
+    /// Concept deletes ever appended
+    appended_deletes: AtomicU64,
+
+    /// Concept deletes ever applied to a snapshot or dropped unapplied
+    settled_deletes: AtomicU64,
 }
 
 impl WriteLog {
@@ -80,6 +128,9 @@ impl WriteLog {
             sequence: Arc::new(AtomicU64::new(0)),
             dropped: Arc::new(AtomicU64::new(0)),
             written: Arc::new(AtomicU64::new(0)),
+            pending_concepts: DashMap::new(),
+            appended_deletes: AtomicU64::new(0),
+            settled_deletes: AtomicU64::new(0),
         }
     }
 
@@ -87,7 +138,21 @@ impl WriteLog {
     /// CRITICAL: On overflow, drops OLDEST entry and accepts newest (as documented)
     pub fn append(&self, entry: WriteEntry) -> Result<u64, WriteLogError> {
         let seq = self.sequence.fetch_add(1, Ordering::Relaxed);
+        // Tracked before sending so the reconciler can't apply it first
+        let added: Vec<ConceptId> = entry.added_concepts().collect();
+        self.track_pending(added.iter().copied());
+        let deletes = entry.delete_count();
+        self.appended_deletes.fetch_add(deletes, Ordering::Relaxed);
+
+        let result = self.send(entry, seq);
+        if result.is_err() {
+            self.untrack_pending(added);
+            self.settled_deletes.fetch_add(deletes, Ordering::Relaxed);
+        }
+        result
+    }
 
+    fn send(&self, entry: WriteEntry, seq: u64) -> Result<u64, WriteLogError> {
         match self.sender.try_send(entry) {
             Ok(()) => {
                 self.written.fetch_add(1, Ordering::Relaxed);
@@ -96,7 +161,10 @@ impl WriteLog {
             Err(TrySendError::Full(entry)) => {
                 // Backpressure: evict oldest entry, then retry with newest
                 match self.receiver.try_recv() {
-                    Ok(_evicted) => {
+                    Ok(evicted) => {
+                        self.untrack_pending(evicted.added_concepts());
+                        self.settled_deletes
+                            .fetch_add(evicted.delete_count(), Ordering::Relaxed);
                         // Successfully evicted oldest, now retry send
                         match self.sender.try_send(entry) {
                             Ok(()) => {
@@ -195,6 +263,50 @@ impl WriteLog {
         self.drain_batch(MAX_WRITE_LOG_SIZE)
     }
 
+    /// Whether an entry adding `id` is still waiting to be applied
+    pub fn has_pending_concept(&self, id: &ConceptId) -> bool {
+        self.pending_concepts.contains_key(id)
+    }
+
+    /// Record that drained `entries` are now visible to readers
+    pub fn mark_applied(&self, entries: &[WriteEntry]) {
+        for entry in entries {
+            self.untrack_pending(entry.added_concepts());
+            self.settled_deletes
+                .fetch_add(entry.delete_count(), Ordering::Relaxed);
+        }
+    }
+
+    /// Concept deletes applied to a snapshot or dropped so far
+    ///
+    /// Read before loading a snapshot, every delete counted here is already
+    /// reflected in it.
+    pub fn settled_deletes(&self) -> u64 {
+        self.settled_deletes.load(Ordering::Relaxed)
+    }
+
+    /// Concept deletes appended but not yet applied
+    pub fn pending_deletes(&self) -> u64 {
+        self.appended_deletes
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.settled_deletes())
+    }
+
+    fn track_pending(&self, ids: impl IntoIterator<Item = ConceptId>) {
+        for id in ids {
+            *self.pending_concepts.entry(id).or_insert(0) += 1;
+        }
+    }
+
+    fn untrack_pending(&self, ids: impl IntoIterator<Item = ConceptId>) {
+        for id in ids {
+            self.pending_concepts.remove_if_mut(&id, |_, count| {
+                *count -= 1;
+                *count == 0
+            });
+        }
+    }
+
     /// Get current sequence number
     pub fn sequence(&self) -> u64 {
         self.sequence.load(Ordering::Relaxed)
@@ -238,18 +350,24 @@ pub struct WriteLogStats {
 pub enum WriteLogError {
     /// Log is full (backpressure)
     Full,
+    /// Write refused because the reconciler is behind; retry later
+    Backpressure,
     /// Channel disconnected
     Disconnected,
     /// System error (e.g., WAL failure)
     SystemError(String),
+    /// Write refused because the namespace quota is used up
+    QuotaExceeded(String),
 }
 
 impl std::fmt::Display for WriteLogError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Full => write!(f, "Write log full (backpressure)"),
+            Self::Backpressure => write!(f, "Write refused under backpressure, retry later"),
             Self::Disconnected => write!(f, "Write log disconnected"),
             Self::SystemError(msg) => write!(f, "System error: {}", msg),
+            Self::QuotaExceeded(msg) => write!(f, "Quota exceeded: {}", msg),
         }
     }
 }
@@ -293,6 +411,60 @@ mod tests {
         assert_eq!(stats.pending, 1);
     }
 
+    #[test]
+    fn test_added_concepts_pending_until_applied() {
+        let log = WriteLog::new();
+        let (single, batched) = (ConceptId([1; 16]), ConceptId([2; 16]));
+        let add = |id| WriteEntry::AddConcept {
+            id,
+            content: Box::new([]),
+            vector: None,
+            strength: 1.0,
+            confidence: 0.9,
+            timestamp: 0,
+            attributes: std::collections::HashMap::new(),
+            semantic: None,
+        };
+        log.append(add(single)).unwrap();
+        log.append(add(single)).unwrap();
+        log.append(WriteEntry::Atomic {
+            entries: vec![add(batched)],
+        })
+        .unwrap();
+        assert!(log.has_pending_concept(&single));
+        assert!(log.has_pending_concept(&batched));
+
+        // Pending until every entry adding the concept has been applied
+        let first = log.drain_batch(1);
+        log.mark_applied(&first);
+        assert!(log.has_pending_concept(&single));
+        let rest = log.drain_all();
+        log.mark_applied(&rest);
+        assert!(!log.has_pending_concept(&single));
+        assert!(!log.has_pending_concept(&batched));
+    }
+
+    #[test]
+    fn test_deletes_pending_until_applied() {
+        let log = WriteLog::new();
+        let delete = |i| WriteEntry::DeleteConcept {
+            id: ConceptId([i; 16]),
+            timestamp: 0,
+        };
+        log.append(delete(1)).unwrap();
+        log.append(WriteEntry::Atomic {
+            entries: vec![delete(2), delete(3)],
+        })
+        .unwrap();
+        assert_eq!((log.pending_deletes(), log.settled_deletes()), (3, 0));
+
+        // Drained is not applied: the snapshot may not hold them yet
+        let batch = log.drain_all();
+        assert_eq!(log.pending_deletes(), 3);
+        log.mark_applied(&batch);
+        assert_eq!((log.pending_deletes(), log.settled_deletes()), (0, 3));
+    }
+
     #[test]
     fn test_drain_batch() {
         let log = WriteLog::new();
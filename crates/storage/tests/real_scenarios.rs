@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tempfile::TempDir;
 
+use sutra_storage::embedding_cache::{EmbeddingCache, EmbeddingCacheConfig};
 use sutra_storage::embedding_provider::EmbeddingProvider;
 use sutra_storage::learning_pipeline::{LearnOptions, LearningPipeline};
 use sutra_storage::semantic::SemanticType;
@@ -89,10 +90,12 @@ async fn test_natural_language_semantic_pipeline() {
     let provider = Arc::new(MockEmbeddingProvider::new(8));
     let pipeline = LearningPipeline::new_with_provider(provider).await.unwrap();
 
-    let mut options = LearnOptions::default();
-    options.generate_embedding = true;
-    options.extract_associations = false;
-    options.analyze_semantics = true;
+    let options = LearnOptions {
+        generate_embedding: true,
+        extract_associations: false,
+        analyze_semantics: true,
+        ..Default::default()
+    };
 
     let text = "High blood pressure causes cardiovascular disease.";
     let concept_hex = pipeline
@@ -193,3 +196,464 @@ async fn test_concurrent_read_write_realistic() {
 
     assert!(found > 0);
 }
+
+/// Counts embedding calls per text so tests can assert on cache behaviour
+struct CountingEmbeddingProvider {
+    inner: MockEmbeddingProvider,
+    calls: parking_lot::Mutex<HashMap<String, usize>>,
+}
+
+impl CountingEmbeddingProvider {
+    fn calls_for(&self, text: &str) -> usize {
+        self.calls.lock().get(text).copied().unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CountingEmbeddingProvider {
+    async fn generate(&self, text: &str, normalize: bool) -> anyhow::Result<Vec<f32>> {
+        *self.calls.lock().entry(text.to_string()).or_default() += 1;
+        // Keep the call in flight long enough for concurrent learners to overlap
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        Ok(self.inner.embed(text, normalize))
+    }
+
+    async fn generate_batch(&self, texts: &[String], normalize: bool) -> Vec<Option<Vec<f32>>> {
+        let mut out = Vec::with_capacity(texts.len());
+        for text in texts {
+            out.push(self.generate(text, normalize).await.ok());
+        }
+        out
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_embedding_cache_shared_across_namespaces() {
+    let temp_dir = TempDir::new().unwrap();
+    let namespace = |name: &str| {
+        Arc::new(ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: temp_dir.path().join(name),
+            vector_dimension: 8,
+            memory_threshold: 1000,
+            ..Default::default()
+        }))
+    };
+    let tenant_a = namespace("tenant_a");
+    let tenant_b = namespace("tenant_b");
+    let isolated = namespace("isolated");
+
+    let provider = Arc::new(CountingEmbeddingProvider {
+        inner: MockEmbeddingProvider::new(8),
+        calls: parking_lot::Mutex::new(HashMap::new()),
+    });
+    let cache = Arc::new(EmbeddingCache::new(EmbeddingCacheConfig {
+        isolated_namespaces: ["isolated".to_string()].into_iter().collect(),
+        ..Default::default()
+    }));
+
+    // Two pipelines (e.g. per-namespace) sharing one cache
+    let pipeline_a = Arc::new(
+        LearningPipeline::new_with_provider(provider.clone())
+            .await
+            .unwrap()
+            .with_embedding_cache(cache.clone()),
+    );
+    let pipeline_b = Arc::new(
+        LearningPipeline::new_with_provider(provider.clone())
+            .await
+            .unwrap()
+            .with_embedding_cache(cache.clone()),
+    );
+
+    let text = "Shared documents appear in many tenants.";
+    let options = LearnOptions {
+        extract_associations: false,
+        ..Default::default()
+    };
+
+    let mut tasks = Vec::new();
+    for (pipeline, storage) in [
+        (pipeline_a.clone(), tenant_a.clone()),
+        (pipeline_b.clone(), tenant_b.clone()),
+        (pipeline_a.clone(), tenant_b.clone()),
+        (pipeline_b.clone(), tenant_a.clone()),
+    ] {
+        let options = options.clone();
+        tasks.push(tokio::spawn(async move {
+            pipeline
+                .learn_concept(storage.as_ref(), text, &options)
+                .await
+                .unwrap()
+        }));
+    }
+    let mut concept_hex = String::new();
+    for task in tasks {
+        concept_hex = task.await.unwrap();
+    }
+
+    assert_eq!(provider.calls_for(text), 1);
+    let stats = cache.stats();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 3);
+
+    // Both namespaces got the embedding
+    let id = ConceptId::from_string(&concept_hex);
+    wait_for_concept(&tenant_a, &id, true).await;
+    wait_for_concept(&tenant_b, &id, true).await;
+    assert!(tenant_a.query_concept(&id).unwrap().vector.is_some());
+    assert!(tenant_b.query_concept(&id).unwrap().vector.is_some());
+
+    // An isolated namespace always embeds on its own
+    assert!(!pipeline_a.embedding_cache_enabled_for("isolated"));
+    let isolated_options = LearnOptions {
+        use_embedding_cache: pipeline_a.embedding_cache_enabled_for("isolated"),
+        ..options
+    };
+    pipeline_a
+        .learn_concept(isolated.as_ref(), text, &isolated_options)
+        .await
+        .unwrap();
+    assert_eq!(provider.calls_for(text), 2);
+    assert_eq!(cache.stats().misses, 1);
+}
+
+#[tokio::test]
+async fn test_metadata_index_matches_full_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = ConcurrentMemory::new(ConcurrentConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        vector_dimension: 8,
+        memory_threshold: 50_000,
+        ..Default::default()
+    });
+
+    let total = 10_000u64;
+    for i in 0..total {
+        let mut attributes = HashMap::new();
+        attributes.insert("topic".to_string(), format!("topic-{}", i % 50));
+        attributes.insert(
+            "lang".to_string(),
+            ["en", "de", "fr"][(i % 3) as usize].to_string(),
+        );
+        if i % 1429 == 0 {
+            attributes.insert("tag".to_string(), "rare".to_string());
+        }
+        storage
+            .learn_concept(
+                ConceptId::from_string(&format!("tagged-{}", i)),
+                format!("Tagged concept {}", i).into_bytes(),
+                None,
+                1.0,
+                0.9,
+                attributes,
+            )
+            .unwrap();
+    }
+
+    let start = std::time::Instant::now();
+    while storage.stats().snapshot.concept_count < total as usize {
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(10),
+            "reconciler did not catch up"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let brute_force = |filters: &HashMap<String, String>| {
+        let mut ids: Vec<ConceptId> = storage
+            .get_snapshot()
+            .concepts
+            .values()
+            .filter(|node| {
+                filters
+                    .iter()
+                    .all(|(k, v)| node.attributes.get(k) == Some(v))
+            })
+            .map(|node| node.id)
+            .collect();
+        ids.sort_by_key(|id| id.to_hex());
+        ids
+    };
+    let indexed = |filters: &HashMap<String, String>| {
+        let mut ids: Vec<ConceptId> = storage
+            .query_by_attributes(filters, usize::MAX)
+            .iter()
+            .map(|node| node.id)
+            .collect();
+        ids.sort_by_key(|id| id.to_hex());
+        ids
+    };
+
+    let rare: HashMap<String, String> = [("tag".to_string(), "rare".to_string())].into();
+    let rare_matches = indexed(&rare);
+    assert_eq!(rare_matches.len(), 7);
+    assert_eq!(rare_matches, brute_force(&rare));
+
+    // A limit keeps the newest matches, newest first
+    let newest = storage.query_by_attributes(&rare, 3);
+    assert_eq!(newest.len(), 3);
+    assert!(newest.windows(2).all(|w| w[0].created >= w[1].created));
+    let oldest_kept = newest[2].created;
+    let snapshot = storage.get_snapshot();
+    let newer_elsewhere = rare_matches
+        .iter()
+        .filter(|id| !newest.iter().any(|node| node.id == **id))
+        .any(|id| snapshot.concepts[id].created > oldest_kept);
+    assert!(!newer_elsewhere, "limit dropped a newer match");
+
+    // Multi-attribute filters intersect posting lists
+    let multi: HashMap<String, String> = [
+        ("topic".to_string(), "topic-7".to_string()),
+        ("lang".to_string(), "de".to_string()),
+    ]
+    .into();
+    assert!(!indexed(&multi).is_empty());
+    assert_eq!(indexed(&multi), brute_force(&multi));
+    let none: HashMap<String, String> = [("tag".to_string(), "missing".to_string())].into();
+    assert!(indexed(&none).is_empty());
+
+    // Updates and deletes keep the index in sync
+    let retagged = ConceptId::from_string("tagged-0");
+    storage
+        .learn_concept(
+            retagged,
+            b"Tagged concept 0".to_vec(),
+            None,
+            1.0,
+            0.9,
+            HashMap::new(),
+        )
+        .unwrap();
+    storage
+        .delete_concept(ConceptId::from_string("tagged-1429"))
+        .unwrap();
+    let start = std::time::Instant::now();
+    while indexed(&rare).len() != 5 {
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(5),
+            "index not updated"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(indexed(&rare), brute_force(&rare));
+    // Two attributes per remaining concept (tagged-0 lost all of its own)
+    assert_eq!(
+        storage.stats().snapshot.attribute_index_entries,
+        2 * (total as usize - 2) + 5
+    );
+}
+
+#[tokio::test]
+async fn test_similarity_floor_limits_auto_created_edges() {
+    let text = "Paris is the capital of France. Berlin hosts the Bundestag. \
+                Tokyo borders Yokohama. Rome surrounds Vatican City. \
+                Madrid lies in Spain. Lisbon faces the Atlantic Ocean. \
+                Vienna sits on the Danube. Cairo stands beside the Nile.";
+
+    let mut edge_counts = Vec::new();
+    for floor in [0.0, 0.8, 1.01] {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = ConcurrentMemory::new(ConcurrentConfig {
+            storage_path: temp_dir.path().to_path_buf(),
+            vector_dimension: 8,
+            ..Default::default()
+        });
+        let provider = Arc::new(MockEmbeddingProvider::new(8));
+        let pipeline = LearningPipeline::new_with_provider(provider).await.unwrap();
+
+        let options = LearnOptions {
+            analyze_semantics: false,
+            min_association_confidence: 0.0,
+            max_associations_per_concept: 100,
+            similarity_floor: floor,
+            ..Default::default()
+        };
+        let concept_hex = pipeline
+            .learn_concept(&storage, text, &options)
+            .await
+            .unwrap();
+
+        // The write log is FIFO: once the sentinel is visible, so are the edges
+        let sentinel = ConceptId::from_string("sentinel");
+        storage
+            .learn_concept(
+                sentinel,
+                b"sentinel".to_vec(),
+                None,
+                1.0,
+                1.0,
+                HashMap::new(),
+            )
+            .unwrap();
+        wait_for_concept(&storage, &sentinel, true).await;
+
+        let id = ConceptId::from_string(&concept_hex);
+        edge_counts.push(storage.query_neighbors(&id).len());
+    }
+
+    assert!(edge_counts[0] > 0, "no edges at floor 0.0");
+    assert!(
+        edge_counts.windows(2).all(|w| w[0] >= w[1]),
+        "raising the floor added edges: {:?}",
+        edge_counts
+    );
+    assert_eq!(edge_counts[2], 0);
+}
+
+#[tokio::test]
+async fn test_reindex_after_bulk_delete_restores_recall() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConcurrentConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        vector_dimension: 8,
+        memory_threshold: 1000,
+        // Reindex manually so the before/after states are deterministic
+        reindex_tombstone_ratio: 0.0,
+        ..Default::default()
+    };
+    let storage = ConcurrentMemory::new(config);
+
+    // Two clusters: the one around the query gets deleted
+    let vector_for = |i: u64, axis: usize| {
+        let mut v = vec![0.05; 8];
+        v[axis] = 1.0;
+        v[(i % 6) as usize + 2] += 0.01 * i as f32;
+        v
+    };
+    let mut doomed = Vec::new();
+    let mut survivors = Vec::new();
+    for i in 0u64..40 {
+        let id = ConceptId::from_string(&format!("reindex-{}", i));
+        let axis = if i < 30 { 0 } else { 1 };
+        storage
+            .learn_concept(
+                id,
+                format!("concept {}", i).into_bytes(),
+                Some(vector_for(i, axis)),
+                1.0,
+                0.9,
+                HashMap::new(),
+            )
+            .unwrap();
+        if i < 30 {
+            doomed.push(id);
+        } else {
+            survivors.push(id);
+        }
+    }
+    wait_for_concept(&storage, &survivors[9], true).await;
+
+    for id in &doomed {
+        storage.delete_concept(*id).unwrap();
+    }
+    let stats = storage.hnsw_stats();
+    assert_eq!(stats.tombstones, 30);
+    assert!((stats.tombstone_ratio() - 0.75).abs() < f32::EPSILON);
+
+    // Tombstones crowd the live vectors out of the top k
+    let query = vector_for(0, 0);
+    let before = storage.vector_search(&query, 10, 50);
+    assert!(before.len() < survivors.len());
+
+    let report = storage.reindex().unwrap().expect("no reindex in flight");
+    assert_eq!(report.indexed, 10);
+    assert_eq!(report.tombstones_removed, 30);
+
+    let stats = storage.hnsw_stats();
+    assert_eq!(stats.tombstones, 0);
+    assert!(stats.last_reindex_us.is_some());
+
+    let mut after: Vec<ConceptId> = storage
+        .vector_search(&query, 10, 50)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    after.sort_by_key(|id| id.to_hex());
+    survivors.sort_by_key(|id| id.to_hex());
+    assert_eq!(after, survivors);
+}
+
+#[tokio::test]
+async fn test_duplicate_report_finds_seeded_duplicates() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConcurrentConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        vector_dimension: 8,
+        memory_threshold: 1000,
+        ..Default::default()
+    };
+    let storage = ConcurrentMemory::new(config);
+
+    let axis = |i: usize, nudge: f32| {
+        let mut v = vec![0.0; 8];
+        v[i] = 1.0;
+        v[(i + 1) % 8] += nudge;
+        v
+    };
+    let learn = |key: &str, content: &str, vector: Vec<f32>| {
+        let id = ConceptId::from_string(key);
+        storage
+            .learn_concept(
+                id,
+                content.as_bytes().to_vec(),
+                Some(vector),
+                1.0,
+                0.9,
+                HashMap::new(),
+            )
+            .unwrap();
+        id
+    };
+
+    // Same bytes under three IDs
+    let copies: Vec<ConceptId> = (0..3)
+        .map(|i| {
+            learn(
+                &format!("copy-{}", i),
+                "Paris is the capital of France.",
+                axis(0, 0.0),
+            )
+        })
+        .collect();
+    // Reworded, nearly identical embeddings
+    let reworded = [
+        learn("reworded-a", "Water boils at 100 C.", axis(2, 0.0)),
+        learn("reworded-b", "Water boils at 100 degrees C.", axis(2, 0.02)),
+    ];
+    // Unrelated
+    let mut last = reworded[1];
+    for i in 4..8 {
+        last = learn(
+            &format!("unique-{}", i),
+            &format!("fact {}", i),
+            axis(i, 0.0),
+        );
+    }
+    wait_for_concept(&storage, &last, true).await;
+    let before = storage.snapshot_info();
+
+    let report = storage.duplicate_report(0.95);
+
+    assert_eq!(report.exact.len(), 1);
+    let mut exact_ids = report.exact[0].concept_ids.clone();
+    exact_ids.sort_by_key(|id| id.to_hex());
+    let mut expected = copies.clone();
+    expected.sort_by_key(|id| id.to_hex());
+    assert_eq!(exact_ids, expected);
+    assert_eq!(report.exact[0].similarity, 1.0);
+
+    assert_eq!(report.near.len(), 1);
+    let mut near_ids = report.near[0].concept_ids.clone();
+    near_ids.sort_by_key(|id| id.to_hex());
+    let mut expected = reworded.to_vec();
+    expected.sort_by_key(|id| id.to_hex());
+    assert_eq!(near_ids, expected);
+    assert!(report.near[0].similarity >= 0.95);
+
+    assert_eq!(report.reclaimable, 3);
+
+    // Dry run: nothing changed
+    let after = storage.snapshot_info();
+    assert_eq!(after.sequence, before.sequence);
+    assert_eq!(after.concept_count, 9);
+}
@@ -1,20 +1,23 @@
+#![allow(clippy::await_holding_lock)]
+
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tempfile::TempDir;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 use tokio::time::sleep;
 
-use sutra_storage::auth::{AuthManager, Role};
+use sutra_storage::auth::{AuthManager, Role, Scope};
 use sutra_storage::embedding_provider::EmbeddingProvider;
 use sutra_storage::learning_pipeline::LearningPipeline;
 use sutra_storage::secure_tcp_server::SecureStorageServer;
 use sutra_storage::tcp_server::{StorageRequest, StorageResponse, StorageServer};
-use sutra_storage::{ConcurrentConfig, ConcurrentMemory};
+use sutra_storage::{ConcurrentConfig, ConcurrentMemory, RateLimiterConfig};
 
+// Held across awaits on purpose: each test owns the process env for its duration
 static ENV_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
 fn lock_env() -> std::sync::MutexGuard<'static, ()> {
@@ -54,17 +57,30 @@ impl EmbeddingProvider for MockEmbeddingProvider {
     }
 }
 
+type SecureServerHandle = (
+    SocketAddr,
+    tokio::sync::oneshot::Sender<()>,
+    tokio::task::JoinHandle<()>,
+    TempDir,
+);
+
 async fn start_secure_server(
     auth: Option<AuthManager>,
     tls: bool,
     cert_path: Option<&str>,
     key_path: Option<&str>,
-) -> (
-    SocketAddr,
-    tokio::sync::oneshot::Sender<()>,
-    tokio::task::JoinHandle<()>,
-    TempDir,
-) {
+) -> SecureServerHandle {
+    start_secure_server_with(auth, tls, cert_path, key_path, |server| server).await
+}
+
+/// Start a secure server around a storage server adjusted by `configure`
+async fn start_secure_server_with(
+    auth: Option<AuthManager>,
+    tls: bool,
+    cert_path: Option<&str>,
+    key_path: Option<&str>,
+    configure: impl FnOnce(StorageServer) -> StorageServer,
+) -> SecureServerHandle {
     if tls {
         std::env::set_var("SUTRA_TLS_ENABLED", "true");
         if let Some(cert) = cert_path {
@@ -89,7 +105,7 @@ async fn start_secure_server(
     let storage = ConcurrentMemory::new(config);
     let provider = Arc::new(MockEmbeddingProvider::new(8));
     let pipeline = LearningPipeline::new_with_provider(provider).await.unwrap();
-    let server = StorageServer::new_with_pipeline(storage, pipeline);
+    let server = configure(StorageServer::new_with_pipeline(storage, pipeline));
     let secure = SecureStorageServer::new(server, auth).await.unwrap();
 
     let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
@@ -187,6 +203,45 @@ async fn test_hmac_auth_plaintext_healthcheck() {
     let _ = handle.await;
 }
 
+#[tokio::test]
+async fn test_secure_server_json_wire_format() {
+    let _guard = lock_env();
+
+    let auth = AuthManager::new_hmac("test-secret-key-32-chars-long-here".to_string(), 3600);
+    let token = auth
+        .generate_token("test-client", vec![Role::Reader])
+        .unwrap();
+
+    let (addr, shutdown_tx, handle, _temp_dir) =
+        start_secure_server(Some(auth), false, None, None).await;
+
+    let mut stream = BufReader::new(connect_with_retry(addr).await.unwrap());
+    auth_handshake(&mut stream, &token).await.unwrap();
+
+    // Switch to JSON frames once authenticated
+    stream.write_all(b"Content-Type: json\n").await.unwrap();
+    stream.flush().await.unwrap();
+    let mut ack = String::new();
+    stream.read_line(&mut ack).await.unwrap();
+    assert_eq!(ack.trim(), "OK json");
+
+    let bytes = serde_json::to_vec(&StorageRequest::HealthCheck).unwrap();
+    stream.write_u32(bytes.len() as u32).await.unwrap();
+    stream.write_all(&bytes).await.unwrap();
+    stream.flush().await.unwrap();
+
+    let len = stream.read_u32().await.unwrap();
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+    match serde_json::from_slice::<StorageResponse>(&buf).unwrap() {
+        StorageResponse::HealthCheckOk { healthy, .. } => assert!(healthy),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let _ = shutdown_tx.send(());
+    let _ = handle.await;
+}
+
 #[tokio::test]
 async fn test_hmac_auth_rejects_invalid_token() {
     let _guard = lock_env();
@@ -255,3 +310,275 @@ async fn test_tls_auth_handshake() {
     let _ = shutdown_tx.send(());
     let _ = handle.await;
 }
+
+#[tokio::test]
+async fn test_read_only_scope_can_query_but_not_learn() {
+    let _guard = lock_env();
+
+    let auth = AuthManager::new_hmac("test-secret-key-32-chars-long-here".to_string(), 3600);
+    // The Writer role alone would allow learning; the scope narrows it
+    let token = auth
+        .generate_scoped_token("dashboard", vec![Role::Writer], Scope::ReadOnly)
+        .unwrap();
+    let (addr, shutdown_tx, handle, _temp_dir) =
+        start_secure_server(Some(auth), false, None, None).await;
+
+    let mut stream = connect_with_retry(addr).await.unwrap();
+    auth_handshake(&mut stream, &token).await.unwrap();
+
+    let query = StorageRequest::QueryConcept {
+        namespace: None,
+        concept_id: format!("{:032x}", 7),
+        include_vector: false,
+    };
+    match send_request(&mut stream, &query).await.unwrap() {
+        StorageResponse::QueryConceptOk { .. } => {}
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let learn = StorageRequest::LearnConcept {
+        namespace: None,
+        concept_id: format!("{:032x}", 7),
+        content: "read-only keys cannot write".to_string(),
+        embedding: vec![],
+        strength: 1.0,
+        confidence: 0.9,
+        idempotency_key: None,
+    };
+    match send_request(&mut stream, &learn).await.unwrap() {
+        StorageResponse::Error { message } => {
+            assert!(message.contains("insufficient scope"), "{}", message)
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let _ = shutdown_tx.send(());
+    let _ = handle.await;
+}
+
+fn ca_certificate(name: &str) -> rcgen::Certificate {
+    let mut params = rcgen::CertificateParams::new(Vec::new());
+    params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, name);
+    rcgen::Certificate::from_params(params).unwrap()
+}
+
+fn client_certificate(common_name: &str) -> rcgen::Certificate {
+    let mut params = rcgen::CertificateParams::new(Vec::new());
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, common_name);
+    rcgen::Certificate::from_params(params).unwrap()
+}
+
+/// Connect over TLS, optionally presenting `client` signed by its CA, and send a HealthCheck
+async fn mtls_health_check(
+    addr: SocketAddr,
+    server_cert: &rcgen::Certificate,
+    client: Option<(&rcgen::Certificate, &rcgen::Certificate)>,
+) -> anyhow::Result<StorageResponse> {
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.add(&rustls::Certificate(server_cert.serialize_der()?))?;
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store);
+    let config = match client {
+        Some((cert, ca)) => builder.with_client_auth_cert(
+            vec![rustls::Certificate(cert.serialize_der_with_signer(ca)?)],
+            rustls::PrivateKey(cert.serialize_private_key_der()),
+        )?,
+        None => builder.with_no_client_auth(),
+    };
+
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+    let stream = connect_with_retry(addr).await?;
+    let domain = rustls::ServerName::try_from("localhost")?;
+    let mut tls_stream = connector.connect(domain, stream).await?;
+    send_request(&mut tls_stream, &StorageRequest::HealthCheck).await
+}
+
+#[tokio::test]
+async fn test_mtls_rejects_missing_and_untrusted_client_certs() {
+    let _guard = lock_env();
+
+    let cert_dir = TempDir::new().unwrap();
+    let cert_path = cert_dir.path().join("cert.pem");
+    let key_path = cert_dir.path().join("key.pem");
+    let ca_path = cert_dir.path().join("clients-ca.pem");
+
+    let server_cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    std::fs::write(&cert_path, server_cert.serialize_pem().unwrap()).unwrap();
+    std::fs::write(&key_path, server_cert.serialize_private_key_pem()).unwrap();
+    let client_ca = ca_certificate("Sutra Clients CA");
+    std::fs::write(&ca_path, client_ca.serialize_pem().unwrap()).unwrap();
+    let rogue_ca = ca_certificate("Rogue CA");
+
+    std::env::set_var("SUTRA_TLS_CLIENT_AUTH", "true");
+    std::env::set_var("SUTRA_TLS_CLIENT_CA", &ca_path);
+    let (addr, shutdown_tx, handle, _temp_dir) =
+        start_secure_server(None, true, cert_path.to_str(), key_path.to_str()).await;
+    std::env::remove_var("SUTRA_TLS_CLIENT_AUTH");
+    std::env::remove_var("SUTRA_TLS_CLIENT_CA");
+
+    let trusted = client_certificate("ingest-worker");
+    match mtls_health_check(addr, &server_cert, Some((&trusted, &client_ca))).await {
+        Ok(StorageResponse::HealthCheckOk { healthy, .. }) => assert!(healthy),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let untrusted = client_certificate("intruder");
+    assert!(
+        mtls_health_check(addr, &server_cert, Some((&untrusted, &rogue_ca)))
+            .await
+            .is_err(),
+        "certificate from an unknown CA must fail the handshake"
+    );
+    assert!(
+        mtls_health_check(addr, &server_cert, None).await.is_err(),
+        "connection without a client certificate must fail the handshake"
+    );
+
+    let _ = shutdown_tx.send(());
+    let _ = handle.await;
+}
+
+/// Limits allowing `burst` requests of each kind before refilling slowly
+fn tight_limits(burst: u32) -> (RateLimiterConfig, RateLimiterConfig) {
+    let config = RateLimiterConfig {
+        requests_per_second: 1,
+        burst_capacity: burst,
+        ..Default::default()
+    };
+    (config.clone(), config)
+}
+
+#[tokio::test]
+async fn test_secure_server_applies_peer_rate_limits() {
+    let _guard = lock_env();
+
+    let (reads, writes) = tight_limits(2);
+    let (addr, shutdown_tx, handle, _temp_dir) =
+        start_secure_server_with(None, false, None, None, |server| {
+            server.with_rate_limits(reads, writes)
+        })
+        .await;
+
+    let mut stream = connect_with_retry(addr).await.unwrap();
+    for _ in 0..2 {
+        let response = send_request(&mut stream, &StorageRequest::HealthCheck)
+            .await
+            .unwrap();
+        assert!(matches!(response, StorageResponse::HealthCheckOk { .. }));
+    }
+    match send_request(&mut stream, &StorageRequest::HealthCheck)
+        .await
+        .unwrap()
+    {
+        StorageResponse::Error { message } => assert_eq!(message, "rate limited"),
+        other => panic!("Expected rate limit, got {:?}", other),
+    }
+
+    let _ = shutdown_tx.send(());
+    let _ = handle.await;
+}
+
+#[tokio::test]
+async fn test_mtls_connections_share_identity_rate_limit() {
+    let _guard = lock_env();
+
+    let cert_dir = TempDir::new().unwrap();
+    let cert_path = cert_dir.path().join("cert.pem");
+    let key_path = cert_dir.path().join("key.pem");
+    let ca_path = cert_dir.path().join("clients-ca.pem");
+
+    let server_cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    std::fs::write(&cert_path, server_cert.serialize_pem().unwrap()).unwrap();
+    std::fs::write(&key_path, server_cert.serialize_private_key_pem()).unwrap();
+    let client_ca = ca_certificate("Sutra Clients CA");
+    std::fs::write(&ca_path, client_ca.serialize_pem().unwrap()).unwrap();
+
+    std::env::set_var("SUTRA_TLS_CLIENT_AUTH", "true");
+    std::env::set_var("SUTRA_TLS_CLIENT_CA", &ca_path);
+    let (reads, writes) = tight_limits(2);
+    let (addr, shutdown_tx, handle, _temp_dir) = start_secure_server_with(
+        None,
+        true,
+        cert_path.to_str(),
+        key_path.to_str(),
+        |server| server.with_rate_limits(reads, writes),
+    )
+    .await;
+    std::env::remove_var("SUTRA_TLS_CLIENT_AUTH");
+    std::env::remove_var("SUTRA_TLS_CLIENT_CA");
+
+    // Each check is a separate connection; all of them present the same identity
+    let worker = client_certificate("ingest-worker");
+    for _ in 0..2 {
+        match mtls_health_check(addr, &server_cert, Some((&worker, &client_ca))).await {
+            Ok(StorageResponse::HealthCheckOk { healthy, .. }) => assert!(healthy),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+    match mtls_health_check(addr, &server_cert, Some((&worker, &client_ca))).await {
+        Ok(StorageResponse::Error { message }) => assert_eq!(message, "rate limited"),
+        other => panic!("Expected shared bucket to be exhausted, got {:?}", other),
+    }
+
+    // Same address, different identity: a bucket of its own
+    let other = client_certificate("report-worker");
+    match mtls_health_check(addr, &server_cert, Some((&other, &client_ca))).await {
+        Ok(StorageResponse::HealthCheckOk { healthy, .. }) => assert!(healthy),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let _ = shutdown_tx.send(());
+    let _ = handle.await;
+}
+
+#[tokio::test]
+async fn test_secure_shutdown_drains_in_flight_request() {
+    let _guard = lock_env();
+
+    let (addr, shutdown_tx, handle, _temp_dir) =
+        start_secure_server_with(None, false, None, None, |server| {
+            server.with_drain_timeout(Duration::from_secs(5))
+        })
+        .await;
+
+    let mut idle = connect_with_retry(addr).await.unwrap();
+    let mut stream = connect_with_retry(addr).await.unwrap();
+    let bytes = rmp_serde::to_vec_named(&StorageRequest::HealthCheck).unwrap();
+
+    // Send the frame header and half the body so the request is mid-read
+    let (head, tail) = bytes.split_at(bytes.len() / 2);
+    stream.write_u32(bytes.len() as u32).await.unwrap();
+    stream.write_all(head).await.unwrap();
+    stream.flush().await.unwrap();
+    sleep(Duration::from_millis(50)).await;
+
+    let _ = shutdown_tx.send(());
+    sleep(Duration::from_millis(50)).await;
+    assert!(!handle.is_finished(), "server exited before draining");
+
+    // The idle connection is closed straight away
+    let mut rest = Vec::new();
+    assert_eq!(idle.read_to_end(&mut rest).await.unwrap(), 0);
+
+    stream.write_all(tail).await.unwrap();
+    stream.flush().await.unwrap();
+    let len = stream.read_u32().await.unwrap();
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+    match rmp_serde::from_slice::<StorageResponse>(&buf).unwrap() {
+        StorageResponse::HealthCheckOk { healthy, .. } => assert!(healthy),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    assert_eq!(stream.read_to_end(&mut rest).await.unwrap(), 0);
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("server did not finish draining")
+        .unwrap();
+}
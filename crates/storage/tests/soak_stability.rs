@@ -89,13 +89,13 @@ async fn soak_multi_hour_stability() {
                     )
                     .unwrap();
 
-                if idx % 10 == 0 {
+                if idx.is_multiple_of(10) {
                     let _ = storage.query_concept(&id);
                 }
-                if idx % 25 == 0 {
+                if idx.is_multiple_of(25) {
                     let _ = storage.vector_search(&embedding, 1, 32);
                 }
-                if idx % flush_every == 0 {
+                if idx.is_multiple_of(flush_every) {
                     let _ = storage.flush();
                 }
 
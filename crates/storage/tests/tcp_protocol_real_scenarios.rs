@@ -1,14 +1,20 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tempfile::TempDir;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 
 use sutra_storage::embedding_provider::EmbeddingProvider;
 use sutra_storage::learning_pipeline::LearningPipeline;
-use sutra_storage::tcp_server::{StorageRequest, StorageResponse, StorageServer};
-use sutra_storage::{ConcurrentConfig, ConcurrentMemory};
+use sutra_storage::replication::ReplicaConfig;
+use sutra_storage::tcp_server::{
+    ShardedStorageServer, StorageRequest, StorageResponse, StorageServer,
+};
+use sutra_storage::{ConcurrentConfig, ConcurrentMemory, ShardConfig, ShardedStorage};
 
 struct MockEmbeddingProvider {
     dim: usize,
@@ -75,36 +81,111 @@ async fn send_request(
     Ok(response)
 }
 
-#[tokio::test]
-async fn test_tcp_learn_query_roundtrip() {
-    let temp_dir = TempDir::new().unwrap();
-    let config = ConcurrentConfig {
-        storage_path: temp_dir.path().to_path_buf(),
-        vector_dimension: 8,
+/// Repeat `request` until `ready` accepts the response (writes apply asynchronously)
+async fn wait_for(
+    stream: &mut TcpStream,
+    request: &StorageRequest,
+    ready: impl Fn(&StorageResponse) -> bool,
+) -> StorageResponse {
+    let start = std::time::Instant::now();
+    loop {
+        let response = send_request(stream, request).await.unwrap();
+        if ready(&response) {
+            return response;
+        }
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(5),
+            "timed out waiting, last response: {:?}",
+            response
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
+
+fn config(dim: usize) -> ConcurrentConfig {
+    ConcurrentConfig {
+        vector_dimension: dim,
         memory_threshold: 1000,
         ..Default::default()
-    };
+    }
+}
 
-    let storage = ConcurrentMemory::new(config);
-    let provider = Arc::new(MockEmbeddingProvider::new(8));
-    let pipeline = LearningPipeline::new_with_provider(provider).await.unwrap();
+/// Storage in its own temporary directory
+struct TestStore {
+    dir: TempDir,
+    storage: ConcurrentMemory,
+    dim: usize,
+}
 
-    let server = Arc::new(StorageServer::new_with_pipeline(storage, pipeline));
+fn temp_store(config: ConcurrentConfig) -> TestStore {
+    let dir = TempDir::new().unwrap();
+    let dim = config.vector_dimension;
+    let storage = ConcurrentMemory::new(ConcurrentConfig {
+        storage_path: dir.path().to_path_buf(),
+        ..config
+    });
+    TestStore { dir, storage, dim }
+}
+
+impl TestStore {
+    /// Server over this store using the mock embedding provider; the
+    /// directory is handed back so it outlives the server
+    async fn into_server(self) -> (StorageServer, TempDir) {
+        let provider = Arc::new(MockEmbeddingProvider::new(self.dim));
+        let pipeline = LearningPipeline::new_with_provider(provider).await.unwrap();
+        (
+            StorageServer::new_with_pipeline(self.storage, pipeline),
+            self.dir,
+        )
+    }
+}
+
+/// Server listening on a free local port until stopped
+struct TestServer {
+    server: Arc<StorageServer>,
+    addr: SocketAddr,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    task: JoinHandle<std::io::Result<()>>,
+    _dir: TempDir,
+}
+
+async fn start_server() -> TestServer {
+    start_server_with(temp_store(config(8)), |server| server).await
+}
+
+/// Serve `store`, with `configure` applied to the server before it starts
+async fn start_server_with(
+    store: TestStore,
+    configure: impl FnOnce(StorageServer) -> StorageServer,
+) -> TestServer {
+    let (server, dir) = store.into_server().await;
+    let server = Arc::new(configure(server));
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
     drop(listener);
 
-    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
-    let server_task = tokio::spawn(server.clone().serve_with_shutdown(addr, async {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let task = tokio::spawn(server.clone().serve_with_shutdown(addr, async {
         let _ = shutdown_rx.await;
     }));
 
-    let mut stream = {
+    TestServer {
+        server,
+        addr,
+        shutdown_tx: Some(shutdown_tx),
+        task,
+        _dir: dir,
+    }
+}
+
+impl TestServer {
+    /// Connect, retrying until the listener is up
+    async fn connect(&self) -> TcpStream {
         let start = std::time::Instant::now();
         loop {
-            match TcpStream::connect(addr).await {
-                Ok(stream) => break stream,
+            match TcpStream::connect(self.addr).await {
+                Ok(stream) => return stream,
                 Err(_) => {
                     if start.elapsed() > std::time::Duration::from_secs(1) {
                         panic!("timeout waiting for tcp server to accept connections");
@@ -113,7 +194,26 @@ async fn test_tcp_learn_query_roundtrip() {
                 }
             }
         }
-    };
+    }
+
+    /// Signal shutdown without waiting for the server to exit
+    fn shutdown(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    async fn stop(mut self) {
+        self.shutdown();
+        let _ = self.task.await;
+    }
+}
+
+#[tokio::test]
+async fn test_tcp_learn_query_roundtrip() {
+    let server = start_server().await;
+
+    let mut stream = server.connect().await;
 
     let mut metadata = HashMap::new();
     metadata.insert("source".to_string(), "tcp_test".to_string());
@@ -125,6 +225,7 @@ async fn test_tcp_learn_query_roundtrip() {
         embedding: vec![0.1; 8],
         metadata,
         timestamp: None,
+        idempotency_key: None,
     };
 
     let response = send_request(&mut stream, &request).await.unwrap();
@@ -136,6 +237,7 @@ async fn test_tcp_learn_query_roundtrip() {
     let query = StorageRequest::QueryConcept {
         namespace: Some("default".to_string()),
         concept_id: concept_id.clone(),
+        include_vector: true,
     };
 
     let start = std::time::Instant::now();
@@ -157,15 +259,1483 @@ async fn test_tcp_learn_query_roundtrip() {
             found,
             content,
             attributes,
+            vector,
             ..
         } => {
             assert!(found);
             assert!(content.contains("Natural language systems"));
             assert_eq!(attributes.get("source").unwrap(), "tcp_test");
+            assert_eq!(vector, Some(vec![0.1; 8]));
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_json_wire_format_roundtrip() {
+    let server = start_server().await;
+
+    let stream = server.connect().await;
+    let mut stream = BufReader::new(stream);
+
+    // Handshake: switch this connection to JSON frames
+    stream.write_all(b"Content-Type: json\n").await.unwrap();
+    stream.flush().await.unwrap();
+    let mut ack = String::new();
+    stream.read_line(&mut ack).await.unwrap();
+    assert_eq!(ack.trim(), "OK json");
+
+    let request = StorageRequest::LearnWithEmbedding {
+        id: Some("json-debug".to_string()),
+        namespace: "default".to_string(),
+        content: "JSON framing is readable by eye.".to_string(),
+        embedding: vec![0.2; 8],
+        metadata: HashMap::new(),
+        timestamp: None,
+        idempotency_key: None,
+    };
+    let bytes = serde_json::to_vec(&request).unwrap();
+    stream.write_u32(bytes.len() as u32).await.unwrap();
+    stream.write_all(&bytes).await.unwrap();
+    stream.flush().await.unwrap();
+
+    let len = stream.read_u32().await.unwrap();
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+
+    // The payload is plain JSON text, not MessagePack
+    let text = std::str::from_utf8(&buf).unwrap();
+    assert!(text.starts_with("{\"LearnConceptV2Ok\""), "{}", text);
+    match serde_json::from_slice::<StorageResponse>(&buf).unwrap() {
+        StorageResponse::LearnConceptV2Ok { concept_id } => assert_eq!(concept_id.len(), 32),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // A frame that is not a request is answered with an error, not dropped
+    let bytes = b"{\"NoSuchRequest\": {}}";
+    stream.write_u32(bytes.len() as u32).await.unwrap();
+    stream.write_all(bytes).await.unwrap();
+    stream.flush().await.unwrap();
+
+    let len = stream.read_u32().await.unwrap();
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+    match serde_json::from_slice::<StorageResponse>(&buf).unwrap() {
+        StorageResponse::Error { message } => {
+            assert!(message.starts_with("Invalid json request"), "{}", message)
         }
         other => panic!("Unexpected response: {:?}", other),
     }
 
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_sharded_server_json_wire_format() {
+    let dir = TempDir::new().unwrap();
+    let storage = ShardedStorage::new(ShardConfig {
+        num_shards: 2,
+        base_path: dir.path().to_path_buf(),
+        shard_config: config(8),
+    })
+    .unwrap();
+    let server = Arc::new(ShardedStorageServer::new(storage).await);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let task = tokio::spawn(server.serve_with_shutdown(addr, async {
+        let _ = shutdown_rx.await;
+    }));
+
+    let start = std::time::Instant::now();
+    let stream = loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => {
+                assert!(start.elapsed() < std::time::Duration::from_secs(1));
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        }
+    };
+    let mut stream = BufReader::new(stream);
+
+    stream.write_all(b"Content-Type: json\n").await.unwrap();
+    stream.flush().await.unwrap();
+    let mut ack = String::new();
+    stream.read_line(&mut ack).await.unwrap();
+    assert_eq!(ack.trim(), "OK json");
+
+    let bytes = serde_json::to_vec(&StorageRequest::HealthCheck).unwrap();
+    stream.write_u32(bytes.len() as u32).await.unwrap();
+    stream.write_all(&bytes).await.unwrap();
+    stream.flush().await.unwrap();
+
+    let len = stream.read_u32().await.unwrap();
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+    match serde_json::from_slice::<StorageResponse>(&buf).unwrap() {
+        StorageResponse::HealthCheckOk { healthy, .. } => assert!(healthy),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
     let _ = shutdown_tx.send(());
-    let _ = server_task.await;
+    let _ = task.await;
+}
+
+#[tokio::test]
+async fn test_tcp_shutdown_drains_in_flight_request() {
+    let store = temp_store(config(8));
+    let mut server = start_server_with(store, |server| {
+        server.with_drain_timeout(std::time::Duration::from_secs(5))
+    })
+    .await;
+
+    let mut stream = server.connect().await;
+
+    let request = StorageRequest::LearnWithEmbedding {
+        id: Some("drained-write".to_string()),
+        namespace: "default".to_string(),
+        content: "Written while the server shuts down.".to_string(),
+        embedding: vec![0.3; 8],
+        metadata: HashMap::new(),
+        timestamp: None,
+        idempotency_key: None,
+    };
+    let bytes = rmp_serde::to_vec_named(&request).unwrap();
+
+    // Send the frame header and half the body so the request is mid-read
+    let (head, tail) = bytes.split_at(bytes.len() / 2);
+    stream.write_u32(bytes.len() as u32).await.unwrap();
+    stream.write_all(head).await.unwrap();
+    stream.flush().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    server.shutdown();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!server.task.is_finished(), "server exited before draining");
+
+    stream.write_all(tail).await.unwrap();
+    stream.flush().await.unwrap();
+
+    let len = stream.read_u32().await.unwrap();
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+    match rmp_serde::from_slice::<StorageResponse>(&buf).unwrap() {
+        StorageResponse::LearnConceptV2Ok { concept_id } => assert_eq!(concept_id.len(), 32),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // Once the request is answered the connection closes and the server exits
+    let mut rest = Vec::new();
+    assert_eq!(stream.read_to_end(&mut rest).await.unwrap(), 0);
+    tokio::time::timeout(std::time::Duration::from_secs(5), server.task)
+        .await
+        .expect("server did not finish draining")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_read_replica_converges_with_primary() {
+    let store = temp_store(ConcurrentConfig {
+        replication_log_capacity: 10_000,
+        ..config(8)
+    });
+    let primary = start_server_with(store, |server| server).await;
+
+    // Written before the replica exists: arrives through the snapshot
+    let mut ids = Vec::new();
+    for i in 0..20 {
+        let response = primary
+            .server
+            .handle_request(StorageRequest::LearnWithEmbedding {
+                id: None,
+                namespace: "default".to_string(),
+                content: format!("Snapshot concept {}", i),
+                embedding: vec![0.1 + i as f32 / 100.0; 8],
+                metadata: HashMap::new(),
+                timestamp: None,
+                idempotency_key: None,
+            })
+            .await;
+        match response {
+            StorageResponse::LearnConceptV2Ok { concept_id } => ids.push(concept_id),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    let (replica, _replica_dir) = temp_store(config(8)).into_server().await;
+    let mut replica_config = ReplicaConfig::new(primary.addr);
+    replica_config.poll_interval = std::time::Duration::from_millis(10);
+    replica_config.retry_interval = std::time::Duration::from_millis(20);
+    let replica = replica.with_replica(replica_config);
+
+    // Burst written while the replica is streaming from the log
+    for i in 0..200 {
+        let response = primary
+            .server
+            .handle_request(StorageRequest::LearnWithEmbedding {
+                id: None,
+                namespace: "default".to_string(),
+                content: format!("Streamed concept {}", i),
+                embedding: vec![0.5 + i as f32 / 1000.0; 8],
+                metadata: HashMap::new(),
+                timestamp: None,
+                idempotency_key: None,
+            })
+            .await;
+        match response {
+            StorageResponse::LearnConceptV2Ok { concept_id } => ids.push(concept_id),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    let start = std::time::Instant::now();
+    loop {
+        let mut missing = 0;
+        for id in &ids {
+            let response = replica
+                .handle_request(StorageRequest::QueryConcept {
+                    namespace: None,
+                    concept_id: id.clone(),
+                    include_vector: false,
+                })
+                .await;
+            match response {
+                StorageResponse::QueryConceptOk { found: true, .. } => {}
+                _ => missing += 1,
+            }
+        }
+        let lag = match replica
+            .handle_request(StorageRequest::GetStats { namespace: None })
+            .await
+        {
+            StorageResponse::StatsOk {
+                replication_lag, ..
+            } => replication_lag,
+            other => panic!("Unexpected response: {:?}", other),
+        };
+        if missing == 0 && lag == 0 {
+            break;
+        }
+        if start.elapsed() > std::time::Duration::from_secs(10) {
+            panic!("replica did not converge: {} missing, lag {}", missing, lag);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(replica.replica_status().unwrap().is_connected());
+
+    // Writes are rejected on the replica
+    let response = replica
+        .handle_request(StorageRequest::LearnWithEmbedding {
+            id: None,
+            namespace: "default".to_string(),
+            content: "Should not be accepted".to_string(),
+            embedding: vec![0.9; 8],
+            metadata: HashMap::new(),
+            timestamp: None,
+            idempotency_key: None,
+        })
+        .await;
+    match response {
+        StorageResponse::Error { message } => assert!(message.starts_with("ReadOnly")),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(replica);
+    primary.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_idempotent_retry_applies_once() {
+    let server = start_server().await;
+
+    let mut stream = server.connect().await;
+
+    async fn written(stream: &mut TcpStream) -> u64 {
+        match send_request(stream, &StorageRequest::GetStats { namespace: None })
+            .await
+            .unwrap()
+        {
+            StorageResponse::StatsOk { written, .. } => written,
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    let keyed = StorageRequest::LearnAssociation {
+        namespace: None,
+        source_id: "retry-source".to_string(),
+        target_id: "retry-target".to_string(),
+        assoc_type: 0,
+        confidence: 0.8,
+        idempotency_key: Some("assoc-1".to_string()),
+    };
+
+    let before = written(&mut stream).await;
+    let first = send_request(&mut stream, &keyed).await.unwrap();
+    let retry = send_request(&mut stream, &keyed).await.unwrap();
+    assert!(matches!(first, StorageResponse::LearnAssociationOk { .. }));
+    assert_eq!(
+        rmp_serde::to_vec_named(&first).unwrap(),
+        rmp_serde::to_vec_named(&retry).unwrap()
+    );
+    assert_eq!(written(&mut stream).await, before + 1);
+
+    // A different key is a different request
+    let mut other = keyed.clone();
+    if let StorageRequest::LearnAssociation {
+        idempotency_key, ..
+    } = &mut other
+    {
+        *idempotency_key = Some("assoc-2".to_string());
+    }
+    send_request(&mut stream, &other).await.unwrap();
+    assert_eq!(written(&mut stream).await, before + 2);
+
+    // Reusing a key for a different request is refused, not replayed
+    let mut changed = keyed.clone();
+    if let StorageRequest::LearnAssociation { confidence, .. } = &mut changed {
+        *confidence = 0.3;
+    }
+    match send_request(&mut stream, &changed).await.unwrap() {
+        StorageResponse::Error { message } => assert!(message.contains("reused"), "{}", message),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+    assert_eq!(written(&mut stream).await, before + 2);
+
+    // Keys are per caller: another client's "assoc-1" is its own request
+    let response = server
+        .server
+        .handle_request_from(changed, "10.0.0.9:1000".parse().unwrap(), None)
+        .await;
+    assert!(
+        matches!(response, StorageResponse::LearnAssociationOk { .. }),
+        "{:?}",
+        response
+    );
+    assert_eq!(written(&mut stream).await, before + 3);
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_idempotent_retry_with_metadata_map() {
+    let server = start_server().await;
+    let mut stream = server.connect().await;
+
+    // Every attempt builds its own map, so its entries iterate in a new order
+    let keyed = || StorageRequest::LearnWithEmbedding {
+        id: None,
+        namespace: "default".to_string(),
+        content: "Retried with metadata.".to_string(),
+        embedding: vec![0.4; 8],
+        metadata: (0..8)
+            .map(|i| (format!("key-{}", i), format!("value-{}", i)))
+            .collect(),
+        timestamp: None,
+        idempotency_key: Some("learn-1".to_string()),
+    };
+
+    let first = send_request(&mut stream, &keyed()).await.unwrap();
+    assert!(
+        matches!(first, StorageResponse::LearnConceptV2Ok { .. }),
+        "{:?}",
+        first
+    );
+    for _ in 0..5 {
+        let retry = send_request(&mut stream, &keyed()).await.unwrap();
+        assert_eq!(
+            rmp_serde::to_vec_named(&first).unwrap(),
+            rmp_serde::to_vec_named(&retry).unwrap(),
+            "{:?}",
+            retry
+        );
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_deadline_stops_expensive_queries() {
+    use sutra_storage::{AssociationType, ConceptId};
+
+    let store = temp_store(config(8));
+
+    // Binary tree: every node is reachable from the root within 13 hops
+    let total = 5_000u64;
+    let id = |i: u64| ConceptId::from_string(&format!("tree-{}", i));
+    for i in 0..total {
+        store
+            .storage
+            .learn_concept(
+                id(i),
+                format!("Tree node {}", i).into_bytes(),
+                Some(vec![(i % 97) as f32 / 97.0; 8]),
+                1.0,
+                0.9,
+                HashMap::new(),
+            )
+            .unwrap();
+    }
+    for i in 1..total {
+        store
+            .storage
+            .learn_association(id((i - 1) / 2), id(i), AssociationType::Semantic, 0.9)
+            .unwrap();
+    }
+    let start = std::time::Instant::now();
+    while store.storage.find_path(id(0), id(total - 1), 20).is_none() {
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let server = start_server_with(store, |server| server).await;
+
+    let mut stream = server.connect().await;
+
+    // Unreachable target: without a deadline the whole tree is explored
+    let find_missing = |deadline_ms| StorageRequest::FindPath {
+        namespace: None,
+        namespaces: vec![],
+        start_id: id(0).to_hex(),
+        end_id: "does-not-exist".to_string(),
+        max_depth: 20,
+        deadline_ms,
+    };
+    match send_request(&mut stream, &find_missing(None))
+        .await
+        .unwrap()
+    {
+        StorageResponse::FindPathOk {
+            found,
+            deadline_exceeded,
+            ..
+        } => assert!(!found && !deadline_exceeded),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let start = std::time::Instant::now();
+    let response = send_request(&mut stream, &find_missing(Some(0)))
+        .await
+        .unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    match response {
+        StorageResponse::FindPathOk {
+            found,
+            path,
+            deadline_exceeded,
+            ..
+        } => assert!(!found && path.is_empty() && deadline_exceeded),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // A generous deadline changes nothing
+    let request = StorageRequest::FindPath {
+        namespace: None,
+        namespaces: vec![],
+        start_id: id(0).to_hex(),
+        end_id: id(total - 1).to_hex(),
+        max_depth: 20,
+        deadline_ms: Some(60_000),
+    };
+    match send_request(&mut stream, &request).await.unwrap() {
+        StorageResponse::FindPathOk {
+            found,
+            deadline_exceeded,
+            ..
+        } => assert!(found && !deadline_exceeded),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let request = StorageRequest::VectorSearch {
+        namespace: None,
+        query_vector: vec![0.5; 8],
+        k: 10,
+        ef_search: 64,
+        deadline_ms: Some(0),
+    };
+    match send_request(&mut stream, &request).await.unwrap() {
+        StorageResponse::VectorSearchOk {
+            results,
+            deadline_exceeded,
+        } => assert!(results.is_empty() && deadline_exceeded),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_transaction_is_all_or_nothing() {
+    use sutra_storage::tcp_server::TxnOperationMsg;
+
+    let server = start_server().await;
+
+    let mut stream = server.connect().await;
+
+    let concept = |id: &str, content: &str| TxnOperationMsg::LearnConcept {
+        concept_id: Some(id.to_string()),
+        content: content.to_string(),
+        embedding: vec![0.5; 8],
+        strength: 1.0,
+        confidence: 0.9,
+        attributes: HashMap::new(),
+    };
+    let edge = |source: &str, target: &str| TxnOperationMsg::LearnAssociation {
+        source_id: source.to_string(),
+        target_id: target.to_string(),
+        assoc_type: 0,
+        confidence: 0.9,
+    };
+    let query = |id: &str| StorageRequest::QueryConcept {
+        namespace: None,
+        concept_id: id.to_string(),
+        include_vector: false,
+    };
+
+    // The last operation references a missing concept: nothing is applied
+    let request = StorageRequest::Transaction {
+        namespace: None,
+        operations: vec![
+            concept("order-1", "Order 1 placed"),
+            concept("invoice-1", "Invoice 1 issued"),
+            edge("order-1", "invoice-1"),
+            edge("invoice-1", "payment-1"),
+        ],
+    };
+    match send_request(&mut stream, &request).await.unwrap() {
+        StorageResponse::Error { message } => {
+            assert!(message.contains("operation 3"), "{}", message)
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    for id in ["order-1", "invoice-1"] {
+        match send_request(&mut stream, &query(id)).await.unwrap() {
+            StorageResponse::QueryConceptOk { found, .. } => assert!(!found, "{} leaked", id),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    // A valid transaction becomes visible as a whole
+    let request = StorageRequest::Transaction {
+        namespace: None,
+        operations: vec![
+            concept("order-1", "Order 1 placed"),
+            concept("invoice-1", "Invoice 1 issued"),
+            edge("order-1", "invoice-1"),
+        ],
+    };
+    let concept_ids = match send_request(&mut stream, &request).await.unwrap() {
+        StorageResponse::TransactionOk { concept_ids, .. } => concept_ids,
+        other => panic!("Unexpected response: {:?}", other),
+    };
+    assert_eq!(concept_ids.len(), 2);
+
+    let start = std::time::Instant::now();
+    let neighbors = loop {
+        let request = StorageRequest::GetNeighbors {
+            namespace: None,
+            concept_id: concept_ids[0].clone(),
+        };
+        match send_request(&mut stream, &request).await.unwrap() {
+            StorageResponse::GetNeighborsOk { neighbor_ids } if !neighbor_ids.is_empty() => {
+                break neighbor_ids
+            }
+            StorageResponse::GetNeighborsOk { .. } => {
+                assert!(start.elapsed() < std::time::Duration::from_secs(5));
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    };
+    assert_eq!(neighbors, vec![concept_ids[1].clone()]);
+    match send_request(&mut stream, &query(&concept_ids[1]))
+        .await
+        .unwrap()
+    {
+        StorageResponse::QueryConceptOk { found, content, .. } => {
+            assert!(found);
+            assert_eq!(content, "Invoice 1 issued");
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_configured_max_message_size() {
+    let store = temp_store(config(8));
+    let server = start_server_with(store, |server| server.with_max_message_size(4096)).await;
+
+    let learn = |content: String| StorageRequest::LearnConcept {
+        namespace: None,
+        concept_id: "sized".to_string(),
+        content,
+        embedding: vec![],
+        strength: 1.0,
+        confidence: 0.9,
+        idempotency_key: None,
+    };
+
+    // The limit is negotiated in the handshake
+    let mut stream = server.connect().await;
+    let handshake = StorageRequest::Handshake {
+        protocol_version: sutra_protocol::PROTOCOL_VERSION,
+    };
+    match send_request(&mut stream, &handshake).await.unwrap() {
+        StorageResponse::HandshakeOk {
+            protocol_version,
+            max_message_size,
+        } => {
+            assert_eq!(protocol_version, sutra_protocol::PROTOCOL_VERSION);
+            assert_eq!(max_message_size, 4096);
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // Above the limit: rejected, and the connection stays usable
+    match send_request(&mut stream, &learn("x".repeat(8192)))
+        .await
+        .unwrap()
+    {
+        StorageResponse::Error { message } => {
+            assert!(message.contains("Message too large"), "{}", message)
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // Below the limit: accepted
+    match send_request(&mut stream, &learn("x".repeat(1024)))
+        .await
+        .unwrap()
+    {
+        StorageResponse::LearnConceptOk { .. } => {}
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_list_recent_pages_with_cursor() {
+    let server = start_server().await;
+
+    let mut stream = server.connect().await;
+
+    let learn = |i: usize| StorageRequest::LearnConcept {
+        namespace: None,
+        concept_id: format!("{:032x}", i + 1),
+        content: format!("recent concept {}", i),
+        embedding: vec![0.5; 8],
+        strength: 1.0,
+        confidence: 0.9,
+        idempotency_key: None,
+    };
+    for i in 0..35 {
+        send_request(&mut stream, &learn(i)).await.unwrap();
+    }
+    let everything = StorageRequest::ListRecent {
+        namespace: "default".to_string(),
+        limit: 100,
+        cursor: None,
+    };
+    wait_for(&mut stream, &everything, |response| {
+        matches!(response, StorageResponse::ListRecentOk { items, .. } if items.len() == 35)
+    })
+    .await;
+
+    // Page through in chunks of 10, writing more concepts between pages
+    let mut seen = std::collections::HashSet::new();
+    let mut last_created = u64::MAX;
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let request = StorageRequest::ListRecent {
+            namespace: "default".to_string(),
+            limit: 10,
+            cursor: cursor.take(),
+        };
+        let (items, next_cursor) = match send_request(&mut stream, &request).await.unwrap() {
+            StorageResponse::ListRecentOk { items, next_cursor } => (items, next_cursor),
+            other => panic!("Unexpected response: {:?}", other),
+        };
+        for item in &items {
+            assert!(item.created <= last_created, "pages must stay newest first");
+            last_created = item.created;
+            assert!(seen.insert(item.id.clone()), "{} listed twice", item.id);
+        }
+        pages += 1;
+
+        send_request(&mut stream, &learn(100 + pages))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        match next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, 4);
+    assert_eq!(seen.len(), 35);
+    for i in 0..35 {
+        assert!(seen.contains(&format!("{:032x}", i + 1)));
+    }
+
+    // An oversized limit is capped rather than sizing buffers by it
+    let oversized = StorageRequest::ListRecent {
+        namespace: "default".to_string(),
+        limit: u32::MAX,
+        cursor: None,
+    };
+    wait_for(&mut stream, &oversized, |response| {
+        matches!(response, StorageResponse::ListRecentOk { items, next_cursor: None } if items.len() == 35 + pages)
+    })
+    .await;
+
+    let bad_cursor = StorageRequest::ListRecent {
+        namespace: "default".to_string(),
+        limit: 10,
+        cursor: Some("not-a-cursor".to_string()),
+    };
+    match send_request(&mut stream, &bad_cursor).await.unwrap() {
+        StorageResponse::Error { message } => assert!(message.contains("cursor"), "{}", message),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_update_concept_keeps_unset_fields_and_edges() {
+    use sutra_storage::tcp_server::TxnOperationMsg;
+
+    let server = start_server().await;
+
+    let mut stream = server.connect().await;
+
+    let order = format!("{:032x}", 1);
+    let invoice = format!("{:032x}", 2);
+    let concept = |id: &str, content: &str| TxnOperationMsg::LearnConcept {
+        concept_id: Some(id.to_string()),
+        content: content.to_string(),
+        embedding: vec![0.5; 8],
+        strength: 0.8,
+        confidence: 0.9,
+        attributes: HashMap::from([
+            ("status".to_string(), "open".to_string()),
+            ("owner".to_string(), "billing".to_string()),
+        ]),
+    };
+    let request = StorageRequest::Transaction {
+        namespace: None,
+        operations: vec![
+            concept(&order, "Order 1 placed"),
+            concept(&invoice, "Invoice 1 issued"),
+            TxnOperationMsg::LearnAssociation {
+                source_id: order.clone(),
+                target_id: invoice.clone(),
+                assoc_type: 0,
+                confidence: 0.9,
+            },
+        ],
+    };
+    match send_request(&mut stream, &request).await.unwrap() {
+        StorageResponse::TransactionOk { .. } => {}
+        other => panic!("Unexpected response: {:?}", other),
+    }
+    let query = StorageRequest::QueryConcept {
+        namespace: None,
+        concept_id: order.clone(),
+        include_vector: true,
+    };
+    wait_for(&mut stream, &query, |response| {
+        matches!(
+            response,
+            StorageResponse::QueryConceptOk { found: true, .. }
+        )
+    })
+    .await;
+
+    // Only strength is given: confidence stays, attributes are merged
+    let update = StorageRequest::UpdateConcept {
+        namespace: None,
+        id: order.clone(),
+        strength: Some(0.3),
+        confidence: None,
+        metadata_merge: HashMap::from([
+            ("status".to_string(), "paid".to_string()),
+            ("region".to_string(), "eu".to_string()),
+        ]),
+    };
+    match send_request(&mut stream, &update).await.unwrap() {
+        StorageResponse::UpdateConceptOk { id, .. } => assert_eq!(id, order),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let updated = wait_for(&mut stream, &query, |response| {
+        matches!(response, StorageResponse::QueryConceptOk { strength, .. } if *strength != 0.8)
+    })
+    .await;
+    match updated {
+        StorageResponse::QueryConceptOk {
+            found,
+            content,
+            strength,
+            confidence,
+            attributes,
+            vector,
+            ..
+        } => {
+            assert!(found);
+            assert_eq!(content, "Order 1 placed");
+            assert_eq!(strength, 0.3);
+            assert_eq!(confidence, 0.9);
+            assert_eq!(attributes.len(), 3);
+            assert_eq!(attributes["status"], "paid");
+            assert_eq!(attributes["owner"], "billing");
+            assert_eq!(attributes["region"], "eu");
+            assert_eq!(vector, Some(vec![0.5; 8]));
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let neighbors = StorageRequest::GetNeighbors {
+        namespace: None,
+        concept_id: order.clone(),
+    };
+    match send_request(&mut stream, &neighbors).await.unwrap() {
+        StorageResponse::GetNeighborsOk { neighbor_ids } => {
+            assert_eq!(neighbor_ids, vec![invoice.clone()])
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // The attribute index follows the merged metadata
+    let by_status = |status: &str| StorageRequest::QueryByMetadata {
+        namespace: None,
+        attributes: HashMap::from([("status".to_string(), status.to_string())]),
+        limit: 10,
+    };
+    match send_request(&mut stream, &by_status("paid")).await.unwrap() {
+        StorageResponse::QueryByMetadataOk { concepts } => {
+            assert_eq!(concepts.len(), 1);
+            assert_eq!(concepts[0].id, order);
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+    match send_request(&mut stream, &by_status("open")).await.unwrap() {
+        StorageResponse::QueryByMetadataOk { concepts } => {
+            assert_eq!(concepts.len(), 1);
+            assert_eq!(concepts[0].id, invoice);
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let unfiltered = StorageRequest::QueryByMetadata {
+        namespace: None,
+        attributes: HashMap::new(),
+        limit: 10,
+    };
+    match send_request(&mut stream, &unfiltered).await.unwrap() {
+        StorageResponse::Error { message } => {
+            assert!(message.contains("at least one attribute"), "{}", message)
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let missing = StorageRequest::UpdateConcept {
+        namespace: None,
+        id: format!("{:032x}", 99),
+        strength: None,
+        confidence: Some(0.1),
+        metadata_merge: HashMap::new(),
+    };
+    match send_request(&mut stream, &missing).await.unwrap() {
+        StorageResponse::Error { message } => assert!(message.contains("not found"), "{}", message),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    for confidence in [1.5, f32::NAN] {
+        let invalid = StorageRequest::UpdateConcept {
+            namespace: None,
+            id: invoice.clone(),
+            strength: None,
+            confidence: Some(confidence),
+            metadata_merge: HashMap::new(),
+        };
+        match send_request(&mut stream, &invalid).await.unwrap() {
+            StorageResponse::Error { message } => {
+                assert!(message.contains("between 0.0 and 1.0"), "{}", message)
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_vector_search_batch_matches_sequential() {
+    const DIM: usize = 32;
+
+    let provider = MockEmbeddingProvider::new(DIM);
+    let store = temp_store(config(DIM));
+    let server = start_server_with(store, |server| server).await;
+
+    let mut stream = server.connect().await;
+
+    stream.set_nodelay(true).unwrap();
+
+    for i in 0..500 {
+        let request = StorageRequest::LearnConcept {
+            namespace: None,
+            concept_id: format!("{:032x}", i + 1),
+            content: format!("document {}", i),
+            embedding: provider.embed(&format!("document {}", i), true),
+            strength: 1.0,
+            confidence: 0.9,
+            idempotency_key: None,
+        };
+        send_request(&mut stream, &request).await.unwrap();
+    }
+
+    let queries: Vec<Vec<f32>> = (0..50)
+        .map(|i| provider.embed(&format!("document {}", i * 7), true))
+        .collect();
+    let search = |query: &Vec<f32>| StorageRequest::VectorSearch {
+        namespace: None,
+        query_vector: query.clone(),
+        k: 5,
+        ef_search: 50,
+        deadline_ms: None,
+    };
+    wait_for(&mut stream, &search(&queries[49]), |response| {
+        matches!(response, StorageResponse::VectorSearchOk { results, .. } if results.len() == 5)
+    })
+    .await;
+
+    let batch = StorageRequest::VectorSearchBatch {
+        namespace: None,
+        queries: queries.clone(),
+        k: 5,
+        ef_search: 50,
+    };
+
+    let mut sequential = Vec::new();
+    for query in &queries {
+        match send_request(&mut stream, &search(query)).await.unwrap() {
+            StorageResponse::VectorSearchOk { results, .. } => sequential.push(results),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+    let batched = match send_request(&mut stream, &batch).await.unwrap() {
+        StorageResponse::VectorSearchBatchOk { results } => results,
+        other => panic!("Unexpected response: {:?}", other),
+    };
+
+    // One answer per query, in query order, identical to searching one by one
+    assert_eq!(batched.len(), 50);
+    for (i, (batch_hits, single_hits)) in batched.iter().zip(&sequential).enumerate() {
+        let batch_ids: Vec<&String> = batch_hits.iter().map(|(id, _)| id).collect();
+        let single_ids: Vec<&String> = single_hits.iter().map(|(id, _)| id).collect();
+        assert_eq!(batch_ids, single_ids, "query {} differs", i);
+        let own = format!("{:032x}", i * 7 + 1);
+        assert!(
+            batch_hits
+                .iter()
+                .any(|(id, sim)| *id == own && *sim > 0.999),
+            "query {} misses its own document",
+            i
+        );
+    }
+
+    // An oversized beam width is capped rather than sizing the search
+    let wide_beam = StorageRequest::VectorSearchBatch {
+        namespace: None,
+        queries: queries[..2].to_vec(),
+        k: 5,
+        ef_search: u32::MAX,
+    };
+    match send_request(&mut stream, &wide_beam).await.unwrap() {
+        StorageResponse::VectorSearchBatchOk { results } => {
+            assert!(results.iter().all(|hits| hits.len() == 5))
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // Limits apply to the batch as a whole
+    let too_much_work = StorageRequest::VectorSearchBatch {
+        namespace: None,
+        queries: vec![queries[0].clone(); 200],
+        k: 1000,
+        ef_search: 50,
+    };
+    match send_request(&mut stream, &too_much_work).await.unwrap() {
+        StorageResponse::Error { message } => {
+            assert!(message.contains("too many results"), "{}", message)
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_per_client_rate_limits_recover() {
+    use sutra_storage::RateLimiterConfig;
+
+    let store = temp_store(config(8));
+    let bucket = |rps, burst| RateLimiterConfig {
+        requests_per_second: rps,
+        burst_capacity: burst,
+        ..Default::default()
+    };
+    let server = start_server_with(store, |server| {
+        server.with_rate_limits(bucket(20, 5), bucket(5, 2))
+    })
+    .await;
+
+    let mut stream = server.connect().await;
+
+    stream.set_nodelay(true).unwrap();
+
+    let learn = |i: u32| StorageRequest::LearnConcept {
+        namespace: None,
+        concept_id: format!("{:032x}", i),
+        content: format!("rate limited {}", i),
+        embedding: vec![0.5; 8],
+        strength: 1.0,
+        confidence: 0.9,
+        idempotency_key: None,
+    };
+    let is_rate_limited = |response: &StorageResponse| matches!(response, StorageResponse::Error { message } if message == "rate limited");
+
+    // Writes have the tighter bucket: the third write in a burst is refused
+    for i in 0..2 {
+        let response = send_request(&mut stream, &learn(i)).await.unwrap();
+        assert!(
+            matches!(response, StorageResponse::LearnConceptOk { .. }),
+            "{:?}",
+            response
+        );
+    }
+    let response = send_request(&mut stream, &learn(2)).await.unwrap();
+    assert!(is_rate_limited(&response), "{:?}", response);
+
+    // Reads are limited separately, and the connection survives refusals
+    let mut read_refusals = 0;
+    for _ in 0..10 {
+        let response = send_request(&mut stream, &StorageRequest::HealthCheck)
+            .await
+            .unwrap();
+        if is_rate_limited(&response) {
+            read_refusals += 1;
+        } else {
+            assert!(
+                matches!(response, StorageResponse::HealthCheckOk { .. }),
+                "{:?}",
+                response
+            );
+        }
+    }
+    assert!(read_refusals >= 4, "only {} reads refused", read_refusals);
+
+    // Both buckets refill after the window
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let response = send_request(&mut stream, &learn(3)).await.unwrap();
+    assert!(
+        matches!(response, StorageResponse::LearnConceptOk { .. }),
+        "{:?}",
+        response
+    );
+
+    let stats = StorageRequest::GetStats { namespace: None };
+    match send_request(&mut stream, &stats).await.unwrap() {
+        StorageResponse::StatsOk {
+            rate_limited_requests,
+            ..
+        } => assert_eq!(rate_limited_requests, 1 + read_refusals),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_get_gaps_reports_isolated_concepts() {
+    let server = start_server().await;
+
+    let mut stream = server.connect().await;
+
+    let lonely = format!("{:032x}", 1);
+    let linked = [format!("{:032x}", 2), format!("{:032x}", 3)];
+    for id in std::iter::once(&lonely).chain(&linked) {
+        let learn = StorageRequest::LearnConcept {
+            namespace: None,
+            concept_id: id.clone(),
+            content: format!("gap concept {}", id),
+            embedding: vec![],
+            strength: 1.0,
+            confidence: 0.9,
+            idempotency_key: None,
+        };
+        send_request(&mut stream, &learn).await.unwrap();
+    }
+    let associate = StorageRequest::LearnAssociation {
+        namespace: None,
+        source_id: linked[0].clone(),
+        target_id: linked[1].clone(),
+        assoc_type: 0,
+        confidence: 0.9,
+        idempotency_key: None,
+    };
+    send_request(&mut stream, &associate).await.unwrap();
+
+    let request = StorageRequest::GetGaps {
+        namespace: None,
+        kinds: vec!["isolated".to_string()],
+        limit: 10,
+    };
+    let ids_of = |response: &StorageResponse| match response {
+        StorageResponse::GapsOk { gaps } => gaps
+            .iter()
+            .flat_map(|gap| gap.concept_ids.clone())
+            .collect::<Vec<_>>(),
+        other => panic!("Unexpected response: {:?}", other),
+    };
+    // Wait until the association has landed and only the lonely concept remains
+    let response = wait_for(&mut stream, &request, |response| {
+        ids_of(response) == vec![lonely.clone()]
+    })
+    .await;
+    match response {
+        StorageResponse::GapsOk { gaps } => {
+            assert_eq!(gaps[0].kind, "isolated");
+            assert_eq!(gaps[0].severity, 1.0);
+        }
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    let bad_kind = StorageRequest::GetGaps {
+        namespace: None,
+        kinds: vec!["orphaned".to_string()],
+        limit: 10,
+    };
+    match send_request(&mut stream, &bad_kind).await.unwrap() {
+        StorageResponse::Error { message } => assert!(message.contains("orphaned"), "{}", message),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_protocol_client_against_tcp_server() {
+    use sutra_protocol::{Client, ClientConfig, StorageMessage};
+
+    let server = start_server().await;
+    drop(server.connect().await);
+    let config = ClientConfig::new(server.addr.to_string()).with_handshake();
+    let mut client = Client::connect(config).await.unwrap();
+
+    assert!(client.health_check().await.unwrap());
+    assert_eq!(
+        client.max_message_size() as usize,
+        sutra_storage::tcp_server::DEFAULT_MAX_MESSAGE_SIZE
+    );
+
+    let a = "0123456789abcdef0123456789abcdef";
+    let b = "fedcba9876543210fedcba9876543210";
+    client
+        .learn_concept(a, "Rivers carry sediment.", vec![1.0; 8], 1.0, 0.9)
+        .await
+        .unwrap();
+    client
+        .learn_concept(b, "Deltas form at river mouths.", vec![0.5; 8], 1.0, 0.8)
+        .await
+        .unwrap();
+    client.learn_association(a, b, 0, 0.7).await.unwrap();
+
+    // Writes apply asynchronously
+    let start = std::time::Instant::now();
+    let neighbors = loop {
+        let neighbors = client.get_neighbors(a).await.unwrap();
+        if !neighbors.is_empty() || start.elapsed() > std::time::Duration::from_secs(5) {
+            break neighbors;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    };
+    assert_eq!(neighbors, vec![b.to_string()]);
+
+    let record = client.query_concept(a).await.unwrap().unwrap();
+    assert_eq!(record.concept_id, a);
+    assert_eq!(record.content, "Rivers carry sediment.");
+    assert!(client
+        .query_concept("00000000000000000000000000000001")
+        .await
+        .unwrap()
+        .is_none());
+
+    let matches = client.vector_search(vec![1.0; 8], 2, 50).await.unwrap();
+    assert!(!matches.is_empty());
+    assert!(matches.iter().any(|m| m.concept_id == a));
+
+    // The server answers a repeated idempotency key with the original response
+    let keyed = StorageMessage::LearnAssociation {
+        namespace: None,
+        source_id: b.to_string(),
+        target_id: a.to_string(),
+        assoc_type: 0,
+        confidence: 0.6,
+        idempotency_key: Some("retry-1".to_string()),
+    };
+    let first = client.call(&keyed).await.unwrap();
+    let second = client.call(&keyed).await.unwrap();
+    assert_eq!(format!("{:?}", first), format!("{:?}", second));
+
+    drop(client);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_feedback_is_tracked_per_peer() {
+    let server = start_server().await;
+    let mut stream = server.connect().await;
+
+    let concept_id = format!("{:032x}", 1);
+    let learn = StorageRequest::LearnConcept {
+        namespace: None,
+        concept_id: concept_id.clone(),
+        content: "feedback target".to_string(),
+        embedding: vec![],
+        strength: 0.2,
+        confidence: 0.9,
+        idempotency_key: None,
+    };
+    send_request(&mut stream, &learn).await.unwrap();
+
+    let query = StorageRequest::QueryConcept {
+        namespace: None,
+        concept_id: concept_id.clone(),
+        include_vector: false,
+    };
+    let strength_of = |response: &StorageResponse| match response {
+        StorageResponse::QueryConceptOk {
+            found, strength, ..
+        } => found.then_some(*strength),
+        other => panic!("Unexpected response: {:?}", other),
+    };
+    let mut current = 0.2;
+    wait_for(&mut stream, &query, |r| strength_of(r).is_some()).await;
+
+    // Accept twice from one peer, then once from another; the feedback
+    // request itself carries no source, so only the peer tells them apart
+    let mut gains = Vec::new();
+    for peer in ["10.0.0.1:1000", "10.0.0.1:2000", "10.0.0.2:1000"] {
+        let feedback = StorageRequest::ProvideFeedback {
+            namespace: None,
+            query_id: "q".to_string(),
+            result_concept_ids: vec![concept_id.clone()],
+            accepted: vec![true],
+            ranking: None,
+        };
+        let response = server
+            .server
+            .handle_request_from(feedback, peer.parse().unwrap(), None)
+            .await;
+        assert!(
+            matches!(
+                response,
+                StorageResponse::ProvideFeedbackOk { adjustments: 1 }
+            ),
+            "{:?}",
+            response
+        );
+        let response = wait_for(&mut stream, &query, |r| {
+            strength_of(r).is_some_and(|s| s > current + 0.01)
+        })
+        .await;
+        let next = strength_of(&response).unwrap();
+        gains.push(next - current);
+        current = next;
+    }
+
+    // The same peer's repeat diminishes; a new peer counts in full and more
+    assert!(gains[1] < gains[0], "{:?}", gains);
+    assert!(gains[2] > gains[0], "{:?}", gains);
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_text_search_rejects_invalid_alpha() {
+    let server = start_server().await;
+    let mut stream = server.connect().await;
+
+    for alpha in [f32::NAN, f32::INFINITY, -0.1, 1.5] {
+        let request = StorageRequest::TextSearch {
+            namespace: None,
+            query: "anything".to_string(),
+            limit: 5,
+            snippet_len: None,
+            alpha: Some(alpha),
+        };
+        let response = send_request(&mut stream, &request).await.unwrap();
+        assert!(
+            matches!(response, StorageResponse::Error { ref message } if message.contains("alpha")),
+            "{}: {:?}",
+            alpha,
+            response
+        );
+    }
+
+    // Pure keyword search is accepted without an embedding
+    let request = StorageRequest::TextSearch {
+        namespace: None,
+        query: "anything".to_string(),
+        limit: u32::MAX,
+        snippet_len: None,
+        alpha: Some(1.0),
+    };
+    let response = send_request(&mut stream, &request).await.unwrap();
+    assert!(
+        matches!(response, StorageResponse::TextSearchOk { .. }),
+        "{:?}",
+        response
+    );
+
+    drop(stream);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_rate_limits_follow_verified_identity() {
+    use sutra_storage::RateLimiterConfig;
+
+    let store = temp_store(config(8));
+    let bucket = |rps, burst| RateLimiterConfig {
+        requests_per_second: rps,
+        burst_capacity: burst,
+        ..Default::default()
+    };
+    let server = start_server_with(store, |server| {
+        server.with_rate_limits(bucket(1, 2), bucket(1, 2))
+    })
+    .await;
+
+    let health = |peer: &str, identity: Option<&'static str>| {
+        server.server.handle_request_for(
+            StorageRequest::HealthCheck,
+            peer.parse().unwrap(),
+            identity,
+        )
+    };
+    let is_rate_limited = |response: &StorageResponse| matches!(response, StorageResponse::Error { message } if message == "rate limited");
+
+    // One token subject shares its budget across addresses...
+    assert!(!is_rate_limited(
+        &health("10.0.0.1:1000", Some("sub:svc")).await
+    ));
+    assert!(!is_rate_limited(
+        &health("10.0.0.2:1000", Some("sub:svc")).await
+    ));
+    assert!(is_rate_limited(
+        &health("10.0.0.3:1000", Some("sub:svc")).await
+    ));
+
+    // ...while other identities and anonymous peers have their own
+    assert!(!is_rate_limited(
+        &health("10.0.0.3:1000", Some("cn:worker")).await
+    ));
+    assert!(!is_rate_limited(&health("10.0.0.3:1000", None).await));
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_tcp_learn_notifies_subscribed_connection() {
+    use sutra_protocol::{ChangeKind, Client, ClientConfig, ConceptChange};
+
+    let server = start_server().await;
+    drop(server.connect().await);
+    let mut subscriber = Client::connect(ClientConfig::new(server.addr.to_string()))
+        .await
+        .unwrap();
+    subscriber.subscribe("user:*").await.unwrap();
+
+    let mut writer = server.connect().await;
+    for id in ["user:1", "team:1", "user:2"] {
+        let learn = StorageRequest::LearnConcept {
+            namespace: None,
+            concept_id: id.to_string(),
+            content: format!("Concept {}", id),
+            embedding: vec![],
+            strength: 1.0,
+            confidence: 0.9,
+            idempotency_key: None,
+        };
+        match send_request(&mut writer, &learn).await.unwrap() {
+            StorageResponse::LearnConceptOk { .. } => {}
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+    let associate = StorageRequest::LearnAssociation {
+        namespace: None,
+        source_id: "user:1".to_string(),
+        target_id: "user:2".to_string(),
+        assoc_type: 0,
+        confidence: 0.8,
+        idempotency_key: None,
+    };
+    send_request(&mut writer, &associate).await.unwrap();
+
+    // Notifications arriving before a reply are queued, not taken for it
+    assert!(subscriber.health_check().await.unwrap());
+
+    let change = |id: &str, change_kind| ConceptChange {
+        concept_id: id.to_string(),
+        change_kind,
+    };
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let next = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            subscriber.next_notification(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        received.push(next);
+    }
+    assert_eq!(
+        received,
+        [
+            change("user:1", ChangeKind::Learned),
+            change("user:2", ChangeKind::Learned),
+            change("user:1", ChangeKind::Associated),
+        ]
+    );
+
+    drop(subscriber);
+    drop(writer);
+    server.stop().await;
 }
@@ -185,3 +185,47 @@ fn test_empty_index_handling() {
 
     println!("✅ Empty index handled gracefully");
 }
+
+#[test]
+fn test_concurrent_memory_reloads_persisted_index() {
+    use sutra_storage::{ConcurrentConfig, ConcurrentMemory};
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConcurrentConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        vector_dimension: 16,
+        ..Default::default()
+    };
+    let query: Vec<f32> = (0..16).map(|j| (j % 5) as f32 / 5.0).collect();
+
+    let before = {
+        let memory = ConcurrentMemory::new(config.clone());
+        for i in 0u64..200 {
+            let mut id_bytes = [0u8; 16];
+            id_bytes[0..8].copy_from_slice(&i.to_le_bytes());
+            let vector = (0..16).map(|j| ((i * 7 + j) % 23) as f32 / 23.0).collect();
+            memory
+                .learn_concept(
+                    ConceptId(id_bytes),
+                    format!("concept {}", i).into_bytes(),
+                    Some(vector),
+                    1.0,
+                    0.9,
+                    HashMap::new(),
+                )
+                .unwrap();
+        }
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while memory.stats().snapshot.concept_count < 200 {
+            assert!(Instant::now() < deadline, "concepts never reconciled");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        memory.flush().unwrap();
+        memory.vector_search(&query, 10, 50)
+    };
+
+    // The restarted store loads the saved index and answers identically
+    let memory = ConcurrentMemory::new(config);
+    assert_eq!(memory.hnsw_stats().indexed_vectors, 200);
+    assert_eq!(memory.vector_search(&query, 10, 50), before);
+}